@@ -12,16 +12,36 @@ pub enum Action {
     JumpToHead,
     NextBranch,
     PrevBranch,
+    NextMerge,
+    PrevMerge,
+    NextTag,
+    PrevTag,
     BranchLeft,
     BranchRight,
 
     // Git operations
     Checkout,
+    CheckoutPrevious,
     CreateBranch,
     DeleteBranch,
+    CleanupBranches,
+    CreateTag,
     Fetch,
+    /// Fetch the current branch's upstream, then fast-forward, merge or
+    /// rebase onto it (`pull.rebase`)
+    Pull,
     Merge,
     Rebase,
+    CherryPick,
+    Revert,
+    /// Show the git config keifu relies on and offer to set the identity
+    GitIdentity,
+    /// Commit as another `Name <email>` for the rest of the session
+    SessionAuthor,
+    StashSave,
+    /// Pop the latest stash (the selected one in the stash list)
+    StashPop,
+    StashList,
 
     // Staging / commit / push
     StageToggle,
@@ -29,6 +49,7 @@ pub enum Action {
     UnstageAll,
     CommitDialog,
     Push,
+    ForcePush,
 
     // Clipboard
     CopyHash,
@@ -38,14 +59,46 @@ pub enum Action {
     FocusNext,
     ToggleHelp,
     Search,
+    /// Next/previous search match (help popup)
+    NextMatch,
+    PrevMatch,
     Refresh,
+    ReloadConfig,
     ToggleRemoteBranches,
+    ToggleMinimap,
+    ToggleFocusMode,
+    /// Hide the detail pane, giving the graph the full height
+    ToggleDetail,
+    /// Draw the graph left to right instead of top to bottom
+    ToggleOrientation,
+    /// Plain commit list without lane art
+    ToggleGraph,
+    ToggleRawCommit,
+    ToggleFullMessage,
+    ToggleRewrittenMarker,
+    ToggleParentBadge,
+    ToggleDateHeaders,
+    ToggleAgeHeat,
+    /// Full 40-character hashes instead of short ones
+    ToggleHashLength,
+    ToggleTimers,
+    PathScope,
+    GotoRevision,
+    GotoBranch,
+    UpstreamDiff,
+    MarkRange,
+    ToggleGraphDebug,
+    OpenTree,
+    CompareBranches,
+    CommandPalette,
     Quit,
 
     // Dialogs
     Confirm,
     Cancel,
     InputChar(char),
+    /// Flip the dialog's option (create vs create and switch)
+    InputToggle,
     InputBackspace,
 
     // Search dropdown
@@ -72,4 +125,77 @@ pub enum Action {
     PrevFile,
     NextHunk,
     PrevHunk,
+    ToggleWordDiff,
+    CycleWhitespace,
+    BlameLine,
+
+    // Tree browser
+    TreeExpand,
+    TreeCollapse,
+}
+
+impl Action {
+    /// Needs a working tree, so it's unavailable in bare repositories
+    pub fn needs_work_tree(&self) -> bool {
+        matches!(
+            self,
+            Action::Checkout
+                | Action::CheckoutPrevious
+                | Action::CommitDialog
+                | Action::Merge
+                | Action::Rebase
+                | Action::Pull
+                | Action::CherryPick
+                | Action::Revert
+                | Action::StashSave
+                | Action::StashPop
+                | Action::StashList
+                | Action::StageToggle
+                | Action::StageAll
+                | Action::UnstageAll
+        )
+    }
+
+    /// Changes the repository or talks to a remote; refused in read-only mode
+    pub fn mutates(&self) -> bool {
+        matches!(
+            self,
+            Action::Checkout
+                | Action::CheckoutPrevious
+                | Action::CreateBranch
+                | Action::DeleteBranch
+                | Action::CleanupBranches
+                | Action::CreateTag
+                | Action::Fetch
+                | Action::Pull
+                | Action::Merge
+                | Action::Rebase
+                | Action::CherryPick
+                | Action::Revert
+                | Action::GitIdentity
+                | Action::StashSave
+                | Action::StashPop
+                | Action::StashList
+                | Action::StageToggle
+                | Action::StageAll
+                | Action::UnstageAll
+                | Action::CommitDialog
+                | Action::Push
+                | Action::ForcePush
+        )
+    }
+
+    /// Writes a commit, so it needs `user.name` / `user.email`
+    pub fn writes_commit(&self) -> bool {
+        matches!(
+            self,
+            Action::CommitDialog
+                | Action::Merge
+                | Action::Rebase
+                | Action::CherryPick
+                | Action::Revert
+                | Action::StashSave
+                | Action::Pull
+        )
+    }
 }
@@ -1,12 +1,15 @@
 //! Application state management
 
-use std::collections::HashMap;
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::ops::Range;
 use std::path::PathBuf;
 use std::sync::mpsc::{self, Receiver};
 use std::thread;
 use std::time::{Duration, Instant};
 
-use anyhow::Result;
+use anyhow::{anyhow, bail, Result};
+use chrono::Local;
 use ratatui::layout::Rect;
 use ratatui::widgets::ListState;
 
@@ -14,46 +17,85 @@ use git2::Oid;
 
 use crate::{
     action::Action,
-    config::Config,
+    config::{BranchesConfig, Column, Config, UiState},
     git::{
+        branch::{BranchEnter, BranchSort, MergedBranches},
         build_graph,
-        graph::GraphLayout,
+        capabilities::GitCapabilities,
+        compare::{BranchCompare, CompareSide},
+        diff_cache::DiffCache,
+        graph::{GraphLayout, GraphNode, RefLabel},
         operations::{
-            checkout_branch, checkout_commit, checkout_remote_branch, create_branch, create_commit,
-            delete_branch, fetch_origin, merge_branch, push_branch, rebase_branch, stage_all,
-            stage_path, unstage_all, unstage_path,
+            checkout_branch, checkout_commit, checkout_previous, checkout_remote_branch,
+            cherry_pick_commit, create_branch, create_commit, create_tag, delete_branch,
+            fetch_remote, merge_branch, pull_current_branch, push_branch, rebase_branch,
+            revert_commit, set_identity, split_remote_branch, split_remote_name, stage_all,
+            stage_path, stash_apply, stash_pop, stash_save, unstage_all, unstage_path, Identity,
+            PullOutcome, PushMode, TransferProgress,
         },
+        path_scope::PathScope,
         BranchInfo, CommitDiffInfo, CommitInfo, FileDiffContent, FileDiffInfo, GitRepository,
-        StageState, WorkingTreeStatus,
+        RepoSummary, StageState, StashInfo, TreeBrowser, WhitespaceMode, WorkingTreeStatus,
     },
+    graph::chars::GraphChars,
+    keybindings::BINDINGS,
     perf::PerfStats,
     search::{fuzzy_search_branches, FuzzySearchResult},
+    ui::{
+        file_diff_view::{blame_line_from, build_highlighted_lines},
+        graph_view::{truncate_to_width, AgeRange, DateHeaders},
+        help_popup::HelpPopup,
+    },
 };
 
-/// Filter branch names to exclude remote branches that have matching local branches
+/// Branch names listed in the cleanup confirmation before "… and N more"
+const CLEANUP_LIST_LIMIT: usize = 10;
+
+/// How long the triggered-action indicator stays on screen
+const ACTION_INDICATOR_DURATION: Duration = Duration::from_millis(500);
+
+/// Rows left below the cursor at which the next batch of commits is loaded
+const LOAD_AHEAD_ROWS: usize = 50;
+
+/// Filter branch labels to exclude remote branches that have matching local branches
 /// Returns branches in order: local branches first, then remote-only branches
-fn filter_remote_duplicates(branch_names: &[String]) -> Vec<&str> {
+fn filter_remote_duplicates<'a>(labels: &'a [RefLabel], remotes: &[String]) -> Vec<&'a str> {
     use std::collections::HashSet;
 
-    let local_branches: HashSet<&str> = branch_names
+    let local_branches: HashSet<&str> = labels
         .iter()
-        .filter(|n| !n.starts_with("origin/"))
-        .map(|s| s.as_str())
+        .filter(|l| !l.is_remote)
+        .map(|l| l.name.as_str())
         .collect();
 
-    branch_names
+    labels
         .iter()
-        .filter(|name| {
-            if let Some(local_name) = name.strip_prefix("origin/") {
-                !local_branches.contains(local_name)
-            } else {
-                true
-            }
+        .filter(|label| match split_remote_name(remotes, &label.name) {
+            Some((_, local_name)) if label.is_remote => !local_branches.contains(local_name),
+            _ => true,
         })
-        .map(|s| s.as_str())
+        .map(|l| l.name.as_str())
         .collect()
 }
 
+/// Title of the new-tag dialog, naming the kind of tag Tab switches to
+fn create_tag_title(annotated: bool) -> String {
+    if annotated {
+        "New Annotated Tag Name (Tab: lightweight)".to_string()
+    } else {
+        "New Tag Name (Tab: annotated)".to_string()
+    }
+}
+
+/// Title of the new-branch dialog, naming what Tab switches to
+fn create_branch_title(switch: bool) -> String {
+    if switch {
+        "New Branch + Switch (Tab: create only)".to_string()
+    } else {
+        "New Branch (Tab: also switch)".to_string()
+    }
+}
+
 /// Application modes
 #[derive(Debug, Clone)]
 pub enum AppMode {
@@ -67,6 +109,9 @@ pub enum AppMode {
     Confirm {
         message: String,
         action: ConfirmAction,
+        /// Message lines of a list that scrolls on its own, keeping the
+        /// question and warnings around it in view
+        list: Option<Range<usize>>,
     },
     Error {
         message: String,
@@ -86,14 +131,74 @@ pub enum AppMode {
         max_line_width: usize,
         total_lines: usize,
     },
+    TreeBrowser {
+        browser: TreeBrowser,
+    },
+    /// Two branches side by side
+    BranchCompare {
+        compare: BranchCompare,
+    },
+    StashList {
+        stashes: Vec<StashInfo>,
+        selected: usize,
+    },
 }
 
 /// Input action kinds
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum InputAction {
-    CreateBranch,
+    /// `switch`: check the new branch out too (`git checkout -b`)
+    CreateBranch {
+        switch: bool,
+    },
     Search,
     CommitMessage,
+    /// `annotated`: ask for a message next (`git tag -a`)
+    CreateTag {
+        annotated: bool,
+    },
+    TagMessage {
+        name: String,
+    },
+    PathScope,
+    GotoRevision,
+    GotoBranch,
+    CommandPalette,
+    /// Choose the remote for a fetch or push
+    PickRemote(RemoteOp),
+    /// Text to find in the help popup
+    HelpSearch,
+    /// `user.name` for the repository config; `resume` runs once the
+    /// identity is saved
+    IdentityName {
+        resume: Option<IdentityResume>,
+    },
+    IdentityEmail {
+        name: String,
+        resume: Option<IdentityResume>,
+    },
+    /// `Name <email>` to commit as for the rest of the session
+    SessionAuthor,
+}
+
+impl InputAction {
+    /// Whether the input drives the fuzzy dropdown
+    pub fn is_picker(&self) -> bool {
+        matches!(
+            self,
+            InputAction::Search
+                | InputAction::GotoBranch
+                | InputAction::CommandPalette
+                | InputAction::PickRemote(_)
+        )
+    }
+}
+
+/// Operation waiting for a remote to be chosen
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RemoteOp {
+    Fetch,
+    Push { branch: String, force: bool },
 }
 
 /// Focusable panes in Normal mode
@@ -104,22 +209,69 @@ pub enum FocusedPane {
     Detail,
 }
 
+/// Direction the commit graph is drawn in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GraphOrientation {
+    /// Newest commit at the top, one row per commit
+    #[default]
+    Vertical,
+    /// Time flows left to right, one column per commit, lanes as rows
+    Horizontal,
+}
+
 /// Screen regions of the main panes, recorded during render for mouse routing
 #[derive(Debug, Clone, Copy, Default)]
 pub struct LayoutMap {
     pub graph: Rect,
+    /// Zero-sized when the minimap is hidden
+    pub minimap: Rect,
     pub commit_detail: Rect,
     pub files: Rect,
     pub status_bar: Rect,
 }
 
+/// Where to continue once a missing identity has been saved
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IdentityResume {
+    /// Rerun the action that needed the identity
+    Action(Action),
+    /// Ask for the message of annotated tag `name`, keeping the typed name
+    TagMessage { name: String },
+}
+
 /// Confirmation action kinds
 #[derive(Debug, Clone)]
 pub enum ConfirmAction {
     DeleteBranch(String),
+    /// Delete local branches merged into the main branch
+    CleanupBranches(Vec<String>),
     Merge(String),
     Rebase(String),
-    Push(String),
+    CherryPick(Oid),
+    Revert(Oid),
+    /// Ask for `user.name` / `user.email`, then resume if there is anything
+    /// to resume
+    EditIdentity(Option<IdentityResume>),
+    Push {
+        remote: String,
+        branch: String,
+    },
+    /// Force-push with a lease on the last-seen remote tip
+    ForcePush {
+        remote: String,
+        branch: String,
+        expected: Option<Oid>,
+    },
+    GotoCommit(Oid),
+    /// Open the pending range diff in the file list
+    OpenRange,
+}
+
+/// Message from a background fetch or push
+enum TransferUpdate {
+    Progress(TransferProgress),
+    /// Ok carries the remote name
+    Done(Result<String, String>),
 }
 
 /// Result of async diff computation
@@ -128,11 +280,70 @@ struct DiffResult {
     diff: Result<CommitDiffInfo, String>,
 }
 
+/// Commit diff for the diff worker to compute; the result goes to `reply`
+struct DiffRequest {
+    oid: Oid,
+    whitespace: WhitespaceMode,
+    max_files: usize,
+    reply: mpsc::Sender<DiffResult>,
+}
+
+/// Start the thread computing commit diffs. It opens the repository once
+/// and keeps the handle. A request queued behind a newer one is skipped,
+/// since only the newest receiver is still listening.
+fn spawn_diff_worker(repo_path: String) -> mpsc::Sender<DiffRequest> {
+    let (tx, rx) = mpsc::channel::<DiffRequest>();
+    thread::spawn(move || {
+        let repo = git2::Repository::open(&repo_path).map_err(|e| e.to_string());
+        while let Ok(mut request) = rx.recv() {
+            while let Ok(newer) = rx.try_recv() {
+                request = newer;
+            }
+            let diff = repo.as_ref().map_err(Clone::clone).and_then(|repo| {
+                CommitDiffInfo::from_commit_with_whitespace(
+                    repo,
+                    request.oid,
+                    request.whitespace,
+                    request.max_files,
+                )
+                .map_err(|e| e.to_string())
+            });
+            let _ = request.reply.send(DiffResult {
+                oid: request.oid,
+                diff,
+            });
+        }
+    });
+    tx
+}
+
 /// Identifies the currently selected node for diff loading and caching.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum DiffTarget {
     Commit(Oid),
     Uncommitted,
+    /// Cumulative diff between two commits (see [`RangeDiff`])
+    Range {
+        base: Oid,
+        head: Oid,
+    },
+}
+
+/// Cumulative diff between two commits, reviewed in the file list: the
+/// upstream tip to HEAD before pushing, or a range marked with `x`
+pub struct RangeDiff {
+    pub base: Oid,
+    pub head: Oid,
+    pub base_label: String,
+    pub head_label: String,
+    /// Commits on `head` not on `base`
+    pub ahead: usize,
+    /// Upstream commits HEAD doesn't have yet; their changes are left out
+    /// because the push review diffs from the merge base
+    pub behind: usize,
+    /// Upstream..HEAD review (as opposed to a marked range)
+    pub for_push: bool,
+    diff: CommitDiffInfo,
 }
 
 type UncommittedDiffResult = (Result<CommitDiffInfo, String>, Option<WorkingTreeStatus>);
@@ -152,6 +363,11 @@ struct SearchState {
     original_position: Option<usize>,
     /// Original node selection before search started
     original_node: Option<usize>,
+    /// Entries offered by the goto-branch picker (index into
+    /// `App::branches`, name), the command palette (index into
+    /// `BINDINGS`, label) or the remote picker (remote name);
+    /// empty for the graph search
+    candidates: Vec<(usize, String)>,
 }
 
 impl SearchState {
@@ -205,14 +421,86 @@ pub struct App {
     pub repo: GitRepository,
     pub repo_path: String,
     pub head_name: Option<String>,
+    /// Ahead/behind, stash count and repo state for the status bar
+    pub repo_summary: RepoSummary,
+    /// libgit2 features and external commands available at startup
+    capabilities: GitCapabilities,
+    /// Why fetch/push can't work against the default remote (None when they can)
+    remote_unsupported: Option<String>,
+    /// Whether there is a default remote for auto-fetch
+    has_default_remote: bool,
+    /// Configured remotes, for telling `<remote>/<branch>` labels apart
+    remote_names: Vec<String>,
 
     // Data
     pub commits: Vec<CommitInfo>,
+    /// Pathspec limiting the graph to commits that touch it
+    path_scope: PathScope,
     pub branches: Vec<BranchInfo>,
+    /// Branch merged branches are checked against (None when not found)
+    main_branch: Option<String>,
+    /// Local branches fully merged into the main branch
+    merged_branches: HashSet<String>,
+    /// Branches checked out in other worktrees, with the worktree path
+    worktree_branches: HashMap<String, PathBuf>,
     pub graph_layout: GraphLayout,
     show_remote_branches: bool,
+    /// Maximum number of commits loaded (grows when `--select` needs more)
+    commit_limit: usize,
+    /// Walk order of the commits not loaded yet, taken once per refresh so
+    /// each batch reads only its own commits
+    unloaded_history: Option<VecDeque<Oid>>,
+    /// Commits exist below the last loaded one
+    more_history: bool,
+    /// Loading ahead failed; retried only after the next refresh so the
+    /// error isn't shown again on every tick
+    load_ahead_failed: bool,
+    /// Commits rewritten by replace refs or grafts
+    pub replaced_oids: HashSet<Oid>,
+    /// Tag names by commit
+    pub tags: HashMap<Oid, Vec<String>>,
 
     // UI state
+    show_minimap: bool,
+    /// Annotate graph rows with layout data (developer aid)
+    show_graph_debug: bool,
+    /// Mark commits whose committer differs from the author
+    show_rewritten_marker: bool,
+    /// Parent count badge on merge commits
+    show_parent_badge: bool,
+    /// Date group separator rows in the graph
+    show_date_headers: bool,
+    date_headers: DateHeaders,
+    /// Date column colored by commit age
+    show_age_heat: bool,
+    /// Commit time span of the graph, for the age heat colors
+    age_range: Option<AgeRange>,
+    /// Full hashes in the graph and parent list instead of short ones
+    full_hashes: bool,
+    /// Countdown to the next auto-refresh/fetch in the status bar
+    show_timers: bool,
+    /// Show the raw commit object instead of the formatted commit detail
+    show_raw_commit: bool,
+    /// Started with `--read-only` (`ui.read_only` also enables the mode)
+    read_only: bool,
+    /// Show commit messages past the detail pane's length cap
+    show_full_message: bool,
+    /// Bare graph without borders, detail pane or status bar
+    focus_mode: bool,
+    /// Detail and changed files panes below the graph
+    show_detail: bool,
+    /// Where toggles are remembered between sessions (none in tests)
+    state_path: Option<PathBuf>,
+    graph_orientation: GraphOrientation,
+    /// Lane art in the commit rows (off: a plain one-line-per-commit list)
+    show_graph: bool,
+    /// Identity for commits, tags and stashes instead of `user.name` /
+    /// `user.email` (`--author` or "Commit as another author")
+    session_identity: Option<Identity>,
+    /// Word-level emphasis in the file diff view
+    word_diff: bool,
+    /// Whitespace handling for diff stats and hunks
+    whitespace: WhitespaceMode,
     pub graph_list_state: ListState,
     pub focused_pane: FocusedPane,
     /// Scroll offset of the commit detail pane (issue #27)
@@ -227,12 +515,20 @@ pub struct App {
     pub files_pane_scroll: u16,
     /// Scroll offset of the help popup
     pub help_scroll: u16,
+    /// Scroll offset of the confirmation message (clamped during render)
+    pub confirm_scroll: u16,
+    /// Text searched for in the help popup (empty: no search)
+    help_query: String,
+    /// Help line of the current search match
+    help_match: Option<usize>,
     /// Last mouse click (time, column, row) for double-click detection
     pub last_click: Option<(Instant, u16, u16)>,
     /// Clickable status bar hint regions (updated during render)
     pub status_hints: Vec<(Rect, Action)>,
     /// Performance counters (inspect via the debug server's "perf" command)
     pub perf: PerfStats,
+    /// Last triggered action, shown briefly when `ui.show_actions` is set
+    last_action: Option<(Action, Instant)>,
 
     // Branch selection state
     /// List of (node_index, branch_name) for all branches
@@ -250,10 +546,11 @@ pub struct App {
     pub stage_states: HashMap<PathBuf, StageState>,
 
     // Diff cache (async load)
-    diff_cache: Option<CommitDiffInfo>,
-    diff_cache_oid: Option<Oid>,
+    diff_cache: DiffCache,
     diff_loading_oid: Option<Oid>,
     diff_receiver: Option<Receiver<DiffResult>>,
+    /// Requests to the commit diff worker, started with the first request
+    diff_worker: Option<mpsc::Sender<DiffRequest>>,
 
     // Uncommitted diff cache
     uncommitted_diff_cache: Option<CommitDiffInfo>,
@@ -263,11 +560,21 @@ pub struct App {
     /// Cache key: working tree status at the time of caching (for invalidation)
     uncommitted_cache_key: Option<WorkingTreeStatus>,
     selected_diff_target: Option<DiffTarget>,
+    /// Upstream..HEAD diff shown by the file list instead of the selection
+    range_diff: Option<RangeDiff>,
+    /// One end of a commit range, marked with `x`
+    range_mark: Option<Oid>,
     selected_diff_target_changed_at: Instant,
+    /// Load the selection's diff even though nothing shows it (asked for by
+    /// opening the file list), then open the list; cleared when the
+    /// selection moves
+    diff_requested: bool,
 
     // Flags
     pub should_quit: bool,
     pending_refresh: bool,
+    /// Refresh on the next auto-refresh tick (focus/resize events)
+    refresh_requested: bool,
     /// Viewport height for diff scroll calculations (updated during render)
     pub diff_viewport_height: u16,
     /// Viewport width for diff horizontal scroll calculations (updated during render)
@@ -278,12 +585,16 @@ pub struct App {
     message_time: Option<std::time::Instant>,
 
     // Async fetch
-    fetch_receiver: Option<Receiver<Result<(), String>>>,
+    fetch_receiver: Option<Receiver<TransferUpdate>>,
     /// Whether to suppress error dialogs for fetch failures (for auto-fetch)
     fetch_silent: bool,
+    /// Upstream to integrate once the running fetch is done (`F`, pull)
+    pending_pull: Option<String>,
 
     // Async push
-    push_receiver: Option<Receiver<Result<(), String>>>,
+    push_receiver: Option<Receiver<TransferUpdate>>,
+    /// Latest progress line of a running fetch or push
+    transfer_progress: Option<TransferProgress>,
 
     // Auto-refresh state
     config: Config,
@@ -313,18 +624,35 @@ impl App {
         }
     }
 
-    /// Create a new application
-    pub fn new() -> Result<Self> {
+    /// Create a new application. `select` is a revision (anything
+    /// `git rev-parse` accepts) to select on startup; `capabilities` is
+    /// usually [`GitCapabilities::detect`].
+    pub fn new(select: Option<&str>, capabilities: GitCapabilities) -> Result<Self> {
         let config = Config::load();
+        let state_path = UiState::path();
         let now = Instant::now();
 
         let repo = GitRepository::discover()?;
         let repo_path = repo.path.clone();
         let head_name = repo.head_name();
+        let repo_summary = repo.summary();
+        let remote_unsupported = Self::remote_support_error(&repo, &capabilities);
+        let has_default_remote = repo.default_remote().is_some();
+        let remote_names = repo.remote_names();
 
         let show_remote_branches = config.graph.show_remote_branches;
-        let commits = repo.get_commits(500, show_remote_branches)?;
-        let branches = repo.get_branches(show_remote_branches)?;
+        let commit_limit = config.commits.initial_batch;
+        let (commits, more_history) =
+            Self::read_history(&repo, commit_limit, show_remote_branches)?;
+        let select_oid = match select {
+            Some(rev) => Some(repo.resolve_commit(rev)?),
+            None => None,
+        };
+        let (branches, main_branch, merged_branches) =
+            Self::load_branches(&repo, show_remote_branches, &config.branches)?;
+        let replaced_oids = repo.replaced_commits().unwrap_or_default();
+        let tags = repo.tags_by_commit().unwrap_or_default();
+        let worktree_branches = repo.worktree_branches().unwrap_or_default();
         let (working_tree_status, stage_states, initial_message) =
             Self::working_tree_snapshot(&repo);
         let initial_message_time = initial_message.as_ref().map(|_| now);
@@ -332,13 +660,23 @@ impl App {
             .as_ref()
             .map(|s| s.accurate_file_count());
         let head_commit_oid = repo.head_oid();
-        let graph_layout = build_graph(&commits, &branches, uncommitted_count, head_commit_oid);
+        let mut graph_layout = build_graph(
+            &commits,
+            &branches,
+            uncommitted_count,
+            head_commit_oid,
+            &config.graph.graph_options(),
+        );
+        graph_layout.attach_tags(&tags);
+        if !config.display.columns.contains(&Column::Graph) {
+            graph_layout.drop_connectors();
+        }
 
         let mut graph_list_state = ListState::default();
         graph_list_state.select(Some(0));
 
         // Build branch positions
-        let branch_positions = Self::build_branch_positions(&graph_layout);
+        let branch_positions = Self::build_branch_positions(&graph_layout, &remote_names);
 
         // Determine initial branch selection
         // If uncommitted node exists (at index 0), don't select any branch
@@ -353,15 +691,54 @@ impl App {
             Some(0)
         };
 
-        let app = Self {
+        let mut app = Self {
             mode: AppMode::Normal,
             repo,
             repo_path,
             head_name,
+            repo_summary,
+            capabilities,
+            remote_unsupported,
+            has_default_remote,
+            remote_names,
             commits,
             branches,
+            main_branch,
+            merged_branches,
             graph_layout,
             show_remote_branches,
+            commit_limit,
+            unloaded_history: None,
+            more_history,
+            load_ahead_failed: false,
+            replaced_oids,
+            tags,
+            worktree_branches,
+            show_minimap: config.graph.show_minimap,
+            show_graph_debug: config.graph.debug_overlay,
+            show_rewritten_marker: config.graph.mark_rewritten,
+            show_parent_badge: config.graph.parent_badge,
+            show_date_headers: config.graph.date_headers,
+            date_headers: DateHeaders::default(),
+            age_range: None,
+            show_age_heat: config.graph.age_heat,
+            full_hashes: config.display.full_hashes,
+            show_timers: config.ui.show_timers,
+            show_raw_commit: false,
+            read_only: false,
+            show_full_message: false,
+            focus_mode: false,
+            show_detail: state_path
+                .as_deref()
+                .map(UiState::load)
+                .and_then(|state| state.show_detail)
+                .unwrap_or(config.ui.show_detail),
+            state_path,
+            graph_orientation: GraphOrientation::default(),
+            show_graph: true,
+            session_identity: None,
+            word_diff: config.diff.word_diff,
+            whitespace: config.diff.whitespace,
             graph_list_state,
             focused_pane: FocusedPane::default(),
             detail_scroll: 0,
@@ -370,45 +747,72 @@ impl App {
             layout: LayoutMap::default(),
             files_pane_scroll: 0,
             help_scroll: 0,
+            confirm_scroll: 0,
+            help_query: String::new(),
+            help_match: None,
             last_click: None,
             status_hints: Vec::new(),
             perf: PerfStats::default(),
+            last_action: None,
             branch_positions,
             selected_branch_position,
             search_state: SearchState::default(),
             working_tree_status,
             stage_states,
-            diff_cache: None,
-            diff_cache_oid: None,
+            diff_cache: DiffCache::new(config.diff.cache_size),
             diff_loading_oid: None,
             diff_receiver: None,
+            diff_worker: None,
             uncommitted_diff_cache: None,
             uncommitted_diff_failed: false,
             uncommitted_diff_loading: false,
             uncommitted_diff_receiver: None,
             uncommitted_cache_key: None,
             selected_diff_target: None,
+            range_diff: None,
+            range_mark: None,
             selected_diff_target_changed_at: now,
+            diff_requested: false,
             should_quit: false,
             pending_refresh: false,
+            refresh_requested: false,
             diff_viewport_height: 40,
             diff_viewport_width: 80,
             message: initial_message,
             message_time: initial_message_time,
             fetch_receiver: None,
             fetch_silent: false,
+            pending_pull: None,
             push_receiver: None,
+            transfer_progress: None,
+            path_scope: PathScope::default(),
             config,
             last_refresh_time: now,
             last_fetch_time: now,
         };
+
+        if let Some(oid) = select_oid {
+            if !app.load_until(oid)? {
+                bail!(
+                    "{} is not reachable from any shown branch",
+                    select.unwrap_or("")
+                );
+            }
+            if let Some(idx) = app
+                .graph_layout
+                .nodes
+                .iter()
+                .position(|node| node.commit.as_ref().is_some_and(|c| c.oid == oid))
+            {
+                app.select_node(idx);
+            }
+        }
         Ok(app)
     }
 
     /// Clear all diff caches
     fn clear_all_diff_caches(&mut self) {
-        self.diff_cache = None;
-        self.diff_cache_oid = None;
+        self.diff_cache.clear();
         self.diff_loading_oid = None;
         self.diff_receiver = None;
         self.clear_uncommitted_diff_cache();
@@ -458,6 +862,12 @@ impl App {
     }
 
     fn current_diff_target(&self) -> Option<DiffTarget> {
+        if let Some(range) = self.range_diff() {
+            return Some(DiffTarget::Range {
+                base: range.base,
+                head: range.head,
+            });
+        }
         let node = self
             .graph_list_state
             .selected()
@@ -478,13 +888,16 @@ impl App {
             self.selected_diff_target = target;
             self.selected_diff_target_changed_at = Instant::now();
             self.detail_scroll = 0;
+            self.diff_requested = false;
         }
         target
     }
 
     fn has_cached_diff_for_target(&self, target: DiffTarget) -> bool {
         match target {
-            DiffTarget::Commit(oid) => self.diff_cache_oid == Some(oid),
+            DiffTarget::Commit(oid) => self.diff_cache.contains(oid),
+            // Computed up front when the range view is opened
+            DiffTarget::Range { .. } => true,
             DiffTarget::Uncommitted => {
                 // A present cache key means the diff was computed and has not
                 // been invalidated by refresh().  Staleness detection is handled
@@ -500,6 +913,7 @@ impl App {
         match target {
             DiffTarget::Commit(oid) => self.diff_loading_oid == Some(oid),
             DiffTarget::Uncommitted => self.uncommitted_diff_loading,
+            DiffTarget::Range { .. } => false,
         }
     }
 
@@ -544,29 +958,26 @@ impl App {
         if let Some(message) = status_message {
             self.set_message(message);
         }
-        let uncommitted_count = working_tree_status
-            .as_ref()
-            .map(|s| s.accurate_file_count());
         self.working_tree_status = working_tree_status;
         self.stage_states = stage_states;
 
         let log_started = Instant::now();
-        self.commits = self.repo.get_commits(500, self.show_remote_branches)?;
-        self.branches = self.repo.get_branches(self.show_remote_branches)?;
+        (self.commits, self.more_history) =
+            Self::read_history(&self.repo, self.commit_limit, self.show_remote_branches)?;
+        self.unloaded_history = None;
+        self.load_ahead_failed = false;
+        (self.branches, self.main_branch, self.merged_branches) =
+            Self::load_branches(&self.repo, self.show_remote_branches, &self.config.branches)?;
+        self.replaced_oids = self.repo.replaced_commits().unwrap_or_default();
+        self.tags = self.repo.tags_by_commit().unwrap_or_default();
+        self.worktree_branches = self.repo.worktree_branches().unwrap_or_default();
+        self.remote_names = self.repo.remote_names();
         self.perf.record("refresh.log", log_started.elapsed());
-        let head_commit_oid = self.repo.head_oid();
-        let graph_started = Instant::now();
-        self.graph_layout = build_graph(
-            &self.commits,
-            &self.branches,
-            uncommitted_count,
-            head_commit_oid,
-        );
-        self.perf.record("refresh.graph", graph_started.elapsed());
+        self.rebuild_graph();
         self.head_name = self.repo.head_name();
-
-        // Rebuild branch positions
-        self.branch_positions = Self::build_branch_positions(&self.graph_layout);
+        self.repo_summary = self.repo.summary();
+        self.remote_unsupported = Self::remote_support_error(&self.repo, &self.capabilities);
+        self.has_default_remote = self.repo.default_remote().is_some();
 
         // Restore selection state
         // Check if uncommitted node still exists in the new graph
@@ -610,21 +1021,8 @@ impl App {
         if force {
             self.clear_all_diff_caches();
         } else {
-            // Auto-refresh: smart cache - only clear if selection changed
-            let selected_oid = self
-                .graph_list_state
-                .selected()
-                .and_then(|idx| self.graph_layout.nodes.get(idx))
-                .and_then(|n| n.commit.as_ref())
-                .map(|c| c.oid);
-
-            // Keep commit diff cache if the same commit is still selected
-            if self.diff_cache_oid != selected_oid {
-                self.diff_cache = None;
-                self.diff_cache_oid = None;
-                self.diff_loading_oid = None;
-                self.diff_receiver = None;
-            }
+            // Auto-refresh: commit diffs are keyed by OID and stay valid, so
+            // the recently viewed ones are kept
 
             // Keep uncommitted diff cache only if:
             // 1. Uncommitted node is still selected (was_uncommitted_selected && has_uncommitted_node)
@@ -638,7 +1036,7 @@ impl App {
 
         // Clear search state on refresh to avoid stale indices
         // Skip if in search mode to prevent clearing active search results
-        if !self.is_in_search_mode() {
+        if !matches!(&self.mode, AppMode::Input { action, .. } if action.is_picker()) {
             self.search_state = SearchState::default();
         }
 
@@ -655,14 +1053,156 @@ impl App {
         Ok(())
     }
 
+    /// Lay the loaded commits out again: path scope, lanes, tags and branch
+    /// positions
+    fn rebuild_graph(&mut self) {
+        let uncommitted_count = self
+            .working_tree_status
+            .as_ref()
+            .map(|s| s.accurate_file_count());
+        let head_commit_oid = self.repo.head_oid();
+        self.path_scope.start(&self.repo_path, &self.commits);
+        let shown_commits = self.path_scope.apply(&self.commits);
+        let graph_started = Instant::now();
+        self.graph_layout = build_graph(
+            &shown_commits,
+            &self.branches,
+            uncommitted_count,
+            head_commit_oid,
+            &self.config.graph.graph_options(),
+        );
+        self.graph_layout.attach_tags(&self.tags);
+        if !self.show_graph() {
+            self.graph_layout.drop_connectors();
+        }
+        self.perf.record("refresh.graph", graph_started.elapsed());
+        self.branch_positions =
+            Self::build_branch_positions(&self.graph_layout, &self.remote_names);
+        self.update_date_headers();
+        self.age_range = AgeRange::new(&self.graph_layout.nodes);
+    }
+
+    /// Regroup the date header rows after the graph or the toggle changed
+    fn update_date_headers(&mut self) {
+        self.date_headers = if self.show_date_headers {
+            DateHeaders::new(&self.graph_layout.nodes, Local::now().date_naive())
+        } else {
+            DateHeaders::default()
+        };
+    }
+
+    /// Lighter [`refresh`](Self::refresh) after an operation that only
+    /// changes labels (creating or deleting a branch, tagging): re-reads
+    /// branches and tags and relabels the graph without walking history or
+    /// rebuilding the layout. Falls back to a full refresh when the shown
+    /// commits or HEAD would change.
+    pub fn refresh_refs(&mut self) -> Result<()> {
+        let started = Instant::now();
+        let (branches, main_branch, merged_branches) =
+            Self::load_branches(&self.repo, self.show_remote_branches, &self.config.branches)?;
+        let head_commit_oid = self.repo.head_oid();
+        if !self.refs_keep_commits(&branches, head_commit_oid) {
+            tracing::debug!("refs changed the history, refreshing fully");
+            return self.refresh(true);
+        }
+        let prev_branch_name = self.selected_branch_name().map(str::to_string);
+
+        (self.branches, self.main_branch, self.merged_branches) =
+            (branches, main_branch, merged_branches);
+        self.remote_names = self.repo.remote_names();
+        self.tags = self.repo.tags_by_commit().unwrap_or_default();
+        self.worktree_branches = self.repo.worktree_branches().unwrap_or_default();
+        self.graph_layout.relabel(&self.branches, head_commit_oid);
+        self.graph_layout.attach_tags(&self.tags);
+        self.head_name = self.repo.head_name();
+        self.repo_summary = self.repo.summary();
+
+        // Rows don't move, so only the branch selection needs restoring
+        self.branch_positions =
+            Self::build_branch_positions(&self.graph_layout, &self.remote_names);
+        self.selected_branch_position = prev_branch_name
+            .and_then(|name| self.branch_positions.iter().position(|(_, n)| n == &name));
+        if !matches!(&self.mode, AppMode::Input { action, .. } if action.is_picker()) {
+            self.search_state = SearchState::default();
+        }
+
+        self.perf.record("refresh.refs", started.elapsed());
+        Ok(())
+    }
+
+    /// Whether switching to `branches` keeps the loaded commits as they
+    /// are: HEAD stays put, every tip is already loaded, and every tip
+    /// that went away is still reachable through a loaded child
+    fn refs_keep_commits(&self, branches: &[BranchInfo], head_commit_oid: Option<Oid>) -> bool {
+        let shown_head = self
+            .graph_layout
+            .nodes
+            .iter()
+            .find(|node| node.is_head)
+            .and_then(|node| node.commit.as_ref())
+            .map(|commit| commit.oid);
+        if head_commit_oid.is_some() && shown_head != head_commit_oid {
+            return false;
+        }
+        let loaded: HashSet<Oid> = self.commits.iter().map(|commit| commit.oid).collect();
+        if !branches
+            .iter()
+            .all(|branch| loaded.contains(&branch.tip_oid))
+        {
+            return false;
+        }
+        let tips: HashSet<Oid> = branches.iter().map(|branch| branch.tip_oid).collect();
+        let parents: HashSet<Oid> = self
+            .commits
+            .iter()
+            .flat_map(|commit| commit.parent_oids.iter().copied())
+            .collect();
+        self.branches
+            .iter()
+            .map(|branch| branch.tip_oid)
+            .filter(|tip| !tips.contains(tip))
+            .all(|gone| parents.contains(&gone))
+    }
+
     /// Update fuzzy search results for the given query
     fn update_fuzzy_search(&mut self, query: &str) {
-        self.search_state.fuzzy_matches = fuzzy_search_branches(query, &self.branch_positions);
+        let candidates = self.search_candidates();
+        let mut matches = if query.is_empty() && self.is_in_list_picker() {
+            // The pickers list everything until the user starts typing
+            (0..candidates.len())
+                .map(|branch_idx| FuzzySearchResult {
+                    branch_idx,
+                    score: 0,
+                    matched_indices: Vec::new(),
+                })
+                .collect()
+        } else {
+            fuzzy_search_branches(query, candidates)
+        };
+        if self.config.branches.sort == BranchSort::Name {
+            self.search_state.fuzzy_matches = matches;
+            self.search_state.clamp_selection();
+            return;
+        }
+        // Equal scores follow the configured branch order
+        let rank = |idx: usize| {
+            let name = candidates.get(idx).map(|(_, name)| name);
+            self.branches
+                .iter()
+                .position(|branch| Some(&branch.name) == name)
+                .unwrap_or(usize::MAX)
+        };
+        matches.sort_by_cached_key(|m| (Reverse(m.score), rank(m.branch_idx)));
+        self.search_state.fuzzy_matches = matches;
         self.search_state.clamp_selection();
     }
 
     /// Jump to the currently selected search result
     fn jump_to_search_result(&mut self) {
+        // The goto-branch picker only moves once a branch is chosen
+        if !self.is_in_search_mode() {
+            return;
+        }
         let Some(result) = self.search_state.selected_result() else {
             return;
         };
@@ -694,6 +1234,15 @@ impl App {
         &self.search_state.fuzzy_matches
     }
 
+    /// Branch names the dropdown results index into
+    pub fn search_candidates(&self) -> &[(usize, String)] {
+        if self.is_in_list_picker() {
+            &self.search_state.candidates
+        } else {
+            &self.branch_positions
+        }
+    }
+
     /// Get current dropdown selection index
     pub fn search_selection(&self) -> Option<usize> {
         self.search_state.dropdown_selection
@@ -710,6 +1259,49 @@ impl App {
         )
     }
 
+    /// Check if the goto-branch picker or the command palette is open
+    fn is_in_list_picker(&self) -> bool {
+        matches!(
+            &self.mode,
+            AppMode::Input {
+                action: InputAction::GotoBranch
+                    | InputAction::CommandPalette
+                    | InputAction::PickRemote(_),
+                ..
+            }
+        )
+    }
+
+    /// Select the tip of `name` (loading more history until it's in the
+    /// graph) without checking it out
+    fn goto_branch_tip(&mut self, name: &str) -> Result<()> {
+        let Some(tip) = self
+            .branches
+            .iter()
+            .find(|branch| branch.name == name)
+            .map(|branch| branch.tip_oid)
+        else {
+            self.set_message(format!("Branch '{name}' no longer exists"));
+            return Ok(());
+        };
+        self.jump_to_commit(tip)?;
+        if self
+            .selected_commit_node()
+            .and_then(|node| node.commit.as_ref())
+            .map(|c| c.oid)
+            != Some(tip)
+        {
+            // jump_to_commit explained why (hidden by the path scope)
+            return Ok(());
+        }
+        // Put the label selection on this branch rather than a neighbour
+        if let Some(pos) = self.branch_positions.iter().position(|(_, n)| n == name) {
+            self.selected_branch_position = Some(pos);
+        }
+        self.set_message(format!("'{name}' at {}", &tip.to_string()[..7]));
+        Ok(())
+    }
+
     /// Jump to the currently checked out branch (HEAD)
     fn jump_to_head(&mut self) {
         // Find the HEAD branch name
@@ -717,6 +1309,14 @@ impl App {
             return;
         };
 
+        // Detached HEAD has no branch to select; go to the commit itself
+        if self.repo.repo.head_detached().unwrap_or(false) {
+            if let Some(idx) = self.graph_layout.nodes.iter().position(|n| n.is_head) {
+                self.select_node(idx);
+            }
+            return;
+        }
+
         // Find the branch position index that matches HEAD
         let Some((branch_pos_idx, (node_idx, _))) = self
             .branch_positions
@@ -736,26 +1336,45 @@ impl App {
         let Some(rx) = &self.fetch_receiver else {
             return;
         };
-        let Ok(fetch_result) = rx.try_recv() else {
+        let mut fetch_result = None;
+        while let Ok(update) = rx.try_recv() {
+            match update {
+                TransferUpdate::Progress(progress) => self.transfer_progress = Some(progress),
+                TransferUpdate::Done(result) => fetch_result = Some(result),
+            }
+        }
+        let Some(fetch_result) = fetch_result else {
             return;
         };
+        self.transfer_progress = None;
 
         let silent = self.fetch_silent;
         self.fetch_receiver = None;
         self.fetch_silent = false;
+        let pull = self.pending_pull.take();
 
         match fetch_result {
-            Ok(()) => {
+            Ok(remote) => {
                 self.reset_timers();
+                let message = match pull {
+                    Some(upstream) => self.finish_pull(&upstream),
+                    None => Some(format!("Fetched from {remote}")),
+                };
                 if matches!(
                     self.mode,
                     AppMode::FileSelect { .. } | AppMode::FileDiff { .. }
                 ) {
                     self.pending_refresh = true;
-                    self.set_message("Fetched from origin");
+                    if let Some(message) = message {
+                        self.set_message(message);
+                    }
                 } else {
                     match self.refresh(true) {
-                        Ok(()) => self.set_message("Fetched from origin"),
+                        Ok(()) => {
+                            if let Some(message) = message {
+                                self.set_message(message);
+                            }
+                        }
                         Err(e) => self.show_error(format!("Refresh failed: {e}")),
                     }
                 }
@@ -765,87 +1384,511 @@ impl App {
         }
     }
 
-    /// Check if fetch is currently in progress
-    pub fn is_fetching(&self) -> bool {
-        self.fetch_receiver.is_some()
+    /// Integrate `upstream` after the fetch of a pull. Returns the status
+    /// message, or None once the failure is shown
+    fn finish_pull(&mut self, upstream: &str) -> Option<String> {
+        let rebase = self.config.pull.rebase.unwrap_or_else(|| {
+            let branch = self.head_name.as_deref().unwrap_or_default();
+            self.repo.pull_rebases(branch)
+        });
+        let result = pull_current_branch(
+            &self.repo.repo,
+            rebase,
+            &self.config.messages.merge,
+            self.session_identity.as_ref(),
+        );
+        match result {
+            Ok(PullOutcome::UpToDate) => Some(format!("Already up to date with {upstream}")),
+            Ok(PullOutcome::FastForward) => Some(format!("Fast-forwarded to {upstream}")),
+            Ok(PullOutcome::Merged { unsigned_reason }) => Some(match unsigned_reason {
+                Some(reason) => format!("Merged {upstream} with a merge commit ({reason})"),
+                None => format!("Merged {upstream} with a merge commit"),
+            }),
+            Ok(PullOutcome::Rebased) => Some(format!("Rebased onto {upstream}")),
+            Err(e) => {
+                self.show_error(format!("Pull failed: {e:#}"));
+                None
+            }
+        }
     }
 
-    /// Check if push is currently in progress
-    pub fn is_pushing(&self) -> bool {
-        self.push_receiver.is_some()
-    }
+    /// Review everything HEAD would push: open the file list on the
+    /// cumulative diff from the upstream tip to HEAD
+    fn open_upstream_diff(&mut self) -> Result<()> {
+        let (upstream, upstream_tip, head) = match self.repo.head_upstream() {
+            Ok(upstream) => upstream,
+            Err(e) => {
+                self.set_message(format!("{e:#}"));
+                return Ok(());
+            }
+        };
+        let head_label = self.head_name.clone().unwrap_or_else(|| "HEAD".to_string());
+        if upstream_tip == head {
+            self.set_message(format!("'{head_label}' is up to date with '{upstream}'"));
+            return Ok(());
+        }
+        let (ahead, behind) = self.repo.repo.graph_ahead_behind(head, upstream_tip)?;
+        if ahead == 0 {
+            self.set_message(format!(
+                "Nothing to push: '{head_label}' is {behind} commit(s) behind '{upstream}'"
+            ));
+            return Ok(());
+        }
 
-    /// Check if async push has completed and process the result
-    pub fn update_push_status(&mut self) {
-        let Some(rx) = &self.push_receiver else {
-            return;
+        // Diverged branches diff from the merge base, so upstream-only
+        // changes don't show up reversed among the changes to push
+        let base = if behind > 0 {
+            self.repo.repo.merge_base(upstream_tip, head)?
+        } else {
+            upstream_tip
         };
-        let Ok(result) = rx.try_recv() else {
-            return;
+        let diff = CommitDiffInfo::from_range_with_whitespace(
+            &self.repo.repo,
+            base,
+            head,
+            self.whitespace,
+            self.config.diff.max_files,
+        )?;
+        if diff.files.is_empty() {
+            self.set_message(format!(
+                "{ahead} commit(s) ahead of '{upstream}' with no net file changes"
+            ));
+            return Ok(());
+        }
+        let file_list = diff.files.clone();
+        self.range_diff = Some(RangeDiff {
+            base,
+            head,
+            base_label: upstream,
+            head_label,
+            ahead,
+            behind,
+            for_push: true,
+            diff,
+        });
+        self.mode = AppMode::FileSelect {
+            selected_index: 0,
+            file_list,
         };
-        self.push_receiver = None;
+        Ok(())
+    }
 
-        match result {
-            Ok(()) => {
-                self.set_message("Pushed to origin");
-                self.reset_timers();
-                if let Err(e) = self.refresh(true) {
-                    self.show_error(format!("Refresh failed: {e}"));
-                }
+    /// Mark the selected commit as one end of a range; on the second mark,
+    /// show the net diffstat between the two ends.
+    fn mark_range(&mut self) -> Result<()> {
+        let Some(oid) = self
+            .selected_commit_node()
+            .and_then(|node| node.commit.as_ref())
+            .map(|commit| commit.oid)
+        else {
+            self.set_message("No commit selected");
+            return Ok(());
+        };
+        let mark = match self.range_mark {
+            None => {
+                self.range_mark = Some(oid);
+                self.set_message(format!(
+                    "Marked {}; press x on the other end of the range",
+                    &oid.to_string()[..7]
+                ));
+                return Ok(());
             }
-            Err(e) => self.show_error(e),
+            Some(mark) if mark == oid => {
+                self.range_mark = None;
+                self.set_message("Range mark cleared");
+                return Ok(());
+            }
+            Some(mark) => mark,
+        };
+        self.range_mark = None;
+
+        // Diff from the older end so additions read as additions
+        let (base, head) = if self.repo.repo.graph_descendant_of(mark, oid)? {
+            (oid, mark)
+        } else {
+            (mark, oid)
+        };
+        let (ahead, _) = self.repo.repo.graph_ahead_behind(head, base)?;
+        // Net endpoint-to-endpoint diff, so churn inside the range isn't
+        // counted twice
+        let diff = CommitDiffInfo::from_range_with_whitespace(
+            &self.repo.repo,
+            base,
+            head,
+            self.whitespace,
+            self.config.diff.max_files,
+        )?;
+        let base_label = base.to_string()[..7].to_string();
+        let head_label = head.to_string()[..7].to_string();
+        if diff.files.is_empty() {
+            self.set_message(format!(
+                "No net file changes between {base_label}..{head_label}"
+            ));
+            return Ok(());
         }
+        let message = format!(
+            "{base_label}..{head_label} ({ahead} commit{}): {} file{}, +{} −{}. Open the file list?",
+            if ahead == 1 { "" } else { "s" },
+            diff.total_files,
+            if diff.total_files == 1 { "" } else { "s" },
+            diff.total_insertions,
+            diff.total_deletions,
+        );
+        self.range_diff = Some(RangeDiff {
+            base,
+            head,
+            base_label,
+            head_label,
+            ahead,
+            behind: 0,
+            for_push: false,
+            diff,
+        });
+        self.mode = AppMode::Confirm {
+            message,
+            action: ConfirmAction::OpenRange,
+            list: None,
+        };
+        Ok(())
     }
 
-    /// Start push in background
-    fn start_push(&mut self, branch: String) {
-        let (tx, rx) = mpsc::channel();
-        let repo_path = self.repo_path.clone();
-        let message = format!("Pushing '{}' to origin...", branch);
+    /// Commit marked as one end of a range, if any
+    pub fn range_mark(&self) -> Option<Oid> {
+        self.range_mark
+    }
 
-        thread::spawn(move || {
-            let result = push_branch(&repo_path, &branch).map_err(|e| e.to_string());
-            let _ = tx.send(result);
-        });
+    /// Upstream..HEAD diff being reviewed in the file list, if any.
+    pub fn range_diff(&self) -> Option<&RangeDiff> {
+        match self.mode {
+            AppMode::FileSelect { .. } | AppMode::FileDiff { .. } => self.range_diff.as_ref(),
+            _ => None,
+        }
+    }
 
-        self.push_receiver = Some(rx);
-        self.set_message(message);
+    /// Whether the commit limit cut off the history (more commits exist
+    /// below the last loaded one)
+    pub fn history_truncated(&self) -> bool {
+        self.more_history
     }
 
-    /// Check and perform auto-refresh if interval has elapsed
-    pub fn check_auto_refresh(&mut self) {
-        if self.is_fetching() {
+    /// The newest `limit` commits, and whether older ones exist: one more
+    /// commit is read to tell, so a history of exactly `limit` commits
+    /// doesn't offer to load more
+    fn read_history(
+        repo: &GitRepository,
+        limit: usize,
+        include_remotes: bool,
+    ) -> Result<(Vec<CommitInfo>, bool)> {
+        let mut commits = repo.get_commits(limit + 1, include_remotes)?;
+        let more = commits.len() > limit;
+        commits.truncate(limit);
+        Ok((commits, more))
+    }
+
+    /// Graph rows: the nodes plus the "load more" row when truncated
+    pub fn graph_row_count(&self) -> usize {
+        self.graph_layout.nodes.len() + usize::from(self.history_truncated())
+    }
+
+    /// Whether the "load more" row below the last node is selected
+    pub fn is_load_more_selected(&self) -> bool {
+        self.history_truncated()
+            && self.graph_list_state.selected() == Some(self.graph_layout.nodes.len())
+    }
+
+    /// Load the next batch, keeping the cursor at the boundary so the first
+    /// new commit is selected
+    fn load_more(&mut self) -> Result<()> {
+        let boundary = self.graph_layout.nodes.len();
+        let loaded = self.load_next_batch()?;
+        self.select_node(boundary.min(self.graph_row_count().saturating_sub(1)));
+        self.set_message(format!("Loaded {loaded} more commit(s)"));
+        Ok(())
+    }
+
+    /// Load the next batch once the cursor comes within `LOAD_AHEAD_ROWS`
+    /// of the last loaded commit, so scrolling down rarely reaches the end
+    pub fn load_ahead(&mut self) {
+        if self.load_ahead_failed
+            || !self.history_truncated()
+            || !matches!(self.mode, AppMode::Normal)
+        {
             return;
         }
-        // A synchronous refresh would delay any queued input by its full
-        // duration (perceived as a slow click); let input win and retry on
-        // the next tick.
-        if crossterm::event::poll(Duration::ZERO).unwrap_or(false) {
+        let Some(selected) = self.graph_list_state.selected() else {
             return;
-        }
-        if matches!(
-            self.mode,
-            AppMode::FileSelect { .. } | AppMode::FileDiff { .. }
-        ) {
+        };
+        if selected + LOAD_AHEAD_ROWS < self.graph_layout.nodes.len() {
             return;
         }
-
-        let now = Instant::now();
-        let refresh_config = &self.config.refresh;
-
-        // Auto-fetch (check first as it includes refresh)
-        if refresh_config.auto_fetch
-            && now.duration_since(self.last_fetch_time).as_secs() >= refresh_config.fetch_interval
-        {
-            self.start_fetch(false, true); // silent=true for auto-fetch
-            return;
+        if let Err(e) = self.load_next_batch() {
+            self.load_ahead_failed = true;
+            self.show_error(format!("Failed to load more commits: {e}"));
         }
+    }
 
-        // Auto-refresh
-        if refresh_config.auto_refresh
-            && now.duration_since(self.last_refresh_time).as_secs()
-                >= refresh_config.refresh_interval
-        {
+    /// Append the next `commits.batch_size` commits to the loaded history.
+    /// Only the new commits are read, but the graph is laid out again since
+    /// lanes ending at the old boundary may now continue into the new rows.
+    /// Returns how many commits were added.
+    fn load_next_batch(&mut self) -> Result<usize> {
+        let batch_size = self.config.commits.batch_size;
+        let selection = self.graph_selection();
+
+        let log_started = Instant::now();
+        // Refs that moved since the refresh neither drop nor repeat commits:
+        // the order is walked afresh once, without the loaded commits
+        let unloaded = match &mut self.unloaded_history {
+            Some(unloaded) => unloaded,
+            None => {
+                let loaded: HashSet<Oid> = self.commits.iter().map(|c| c.oid).collect();
+                let order = self.repo.commit_order(self.show_remote_branches)?;
+                self.unloaded_history.insert(
+                    order
+                        .into_iter()
+                        .filter(|oid| !loaded.contains(oid))
+                        .collect(),
+                )
+            }
+        };
+        let oids: Vec<Oid> = unloaded.drain(..batch_size.min(unloaded.len())).collect();
+        self.more_history = !unloaded.is_empty();
+        let batch = self.repo.read_commits(&oids)?;
+        self.perf.record("load_more.log", log_started.elapsed());
+        let before = self.commits.len();
+        self.commits.extend(batch);
+        self.commit_limit = self.commits.len();
+        self.rebuild_graph();
+        self.restore_graph_selection(selection);
+
+        if !matches!(&self.mode, AppMode::Input { action, .. } if action.is_picker()) {
+            self.search_state = SearchState::default();
+        }
+        Ok(self.commits.len() - before)
+    }
+
+    /// Selected commit and branch, to find again once the graph is rebuilt
+    fn graph_selection(&self) -> (Option<Oid>, Option<String>) {
+        let oid = self
+            .selected_commit_node()
+            .and_then(|node| node.commit.as_ref())
+            .map(|commit| commit.oid);
+        let branch = self
+            .selected_branch_position
+            .and_then(|pos| self.branch_positions.get(pos))
+            .map(|(_, name)| name.clone());
+        (oid, branch)
+    }
+
+    /// Select what [`graph_selection`](Self::graph_selection) saved; rows
+    /// without a commit keep their index where it still exists
+    fn restore_graph_selection(&mut self, (oid, branch): (Option<Oid>, Option<String>)) {
+        self.selected_branch_position =
+            branch.and_then(|name| self.branch_positions.iter().position(|(_, n)| n == &name));
+        let node_idx = oid.and_then(|oid| {
+            self.graph_layout
+                .nodes
+                .iter()
+                .position(|node| node.commit.as_ref().is_some_and(|c| c.oid == oid))
+        });
+        match node_idx {
+            Some(idx) => self.graph_list_state.select(Some(idx)),
+            None => {
+                let last = self.graph_row_count().saturating_sub(1);
+                if let Some(idx) = self.graph_list_state.selected() {
+                    self.graph_list_state.select(Some(idx.min(last)));
+                }
+            }
+        }
+    }
+
+    /// Load batches until the commit is loaded; false when the history ends
+    /// without it
+    fn load_until(&mut self, oid: Oid) -> Result<bool> {
+        while !self.commits.iter().any(|c| c.oid == oid) {
+            if !self.history_truncated() || self.load_next_batch()? == 0 {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Select a commit, loading more history if it's beyond the loaded window
+    fn jump_to_commit(&mut self, oid: Oid) -> Result<()> {
+        if !self.load_until(oid)? {
+            bail!(
+                "{} is not reachable from any shown branch",
+                &oid.to_string()[..7]
+            );
+        }
+        let idx = self
+            .graph_layout
+            .nodes
+            .iter()
+            .position(|node| node.commit.as_ref().is_some_and(|c| c.oid == oid));
+        match idx {
+            Some(idx) => {
+                self.select_node(idx);
+                self.set_message(format!("Jumped to {}", &oid.to_string()[..7]));
+            }
+            None => self.set_message(format!(
+                "{} is hidden by the path scope",
+                &oid.to_string()[..7]
+            )),
+        }
+        Ok(())
+    }
+
+    /// Rebuild the graph once the background path check has finished
+    pub fn update_path_scope_status(&mut self) {
+        if !self.path_scope.poll() {
+            return;
+        }
+        if matches!(
+            self.mode,
+            AppMode::FileSelect { .. } | AppMode::FileDiff { .. }
+        ) {
+            self.pending_refresh = true;
+        } else if let Err(e) = self.refresh(true) {
+            self.show_error(format!("Refresh failed: {e}"));
+        }
+    }
+
+    /// Limit the graph to commits touching `paths` (relative to the
+    /// repository root); an empty list shows the full history again
+    pub fn set_path_scope(&mut self, paths: Vec<String>) -> Result<()> {
+        self.path_scope.set_paths(paths);
+        self.refresh(true)?;
+        if self.path_scope.is_active() {
+            self.set_message(format!(
+                "Showing commits touching {}",
+                self.path_scope.paths().join(" ")
+            ));
+        } else {
+            self.set_message("Path scope cleared");
+        }
+        Ok(())
+    }
+
+    /// Pathspecs the graph is limited to (empty when unscoped).
+    pub fn path_scope(&self) -> &[String] {
+        self.path_scope.paths()
+    }
+
+    /// (checked, total) commits while the path scope is being computed.
+    pub fn path_scope_progress(&self) -> Option<(usize, usize)> {
+        self.path_scope.progress()
+    }
+
+    /// Check if fetch is currently in progress
+    pub fn is_fetching(&self) -> bool {
+        self.fetch_receiver.is_some()
+    }
+
+    /// Check if push is currently in progress
+    pub fn is_pushing(&self) -> bool {
+        self.push_receiver.is_some()
+    }
+
+    /// Latest progress of a running fetch or push, once git reports any
+    pub fn transfer_progress(&self) -> Option<&TransferProgress> {
+        self.transfer_progress.as_ref()
+    }
+
+    /// Check if async push has completed and process the result
+    pub fn update_push_status(&mut self) {
+        let Some(rx) = &self.push_receiver else {
+            return;
+        };
+        let mut result = None;
+        while let Ok(update) = rx.try_recv() {
+            match update {
+                TransferUpdate::Progress(progress) => self.transfer_progress = Some(progress),
+                TransferUpdate::Done(done) => result = Some(done),
+            }
+        }
+        let Some(result) = result else {
+            return;
+        };
+        self.push_receiver = None;
+        self.transfer_progress = None;
+
+        match result {
+            Ok(remote) => {
+                self.set_message(format!("Pushed to {remote}"));
+                self.reset_timers();
+                if let Err(e) = self.refresh(true) {
+                    self.show_error(format!("Refresh failed: {e}"));
+                }
+            }
+            Err(e) => self.show_error(e),
+        }
+    }
+
+    /// Start push in background
+    fn start_push(&mut self, remote: String, branch: String, mode: PushMode) {
+        let (tx, rx) = mpsc::channel();
+        let repo_path = self.repo_path.clone();
+        let message = match mode {
+            PushMode::Normal => format!("Pushing '{branch}' to {remote}..."),
+            PushMode::ForceWithLease { .. } => {
+                format!("Force-pushing '{branch}' to {remote} (with lease)...")
+            }
+        };
+
+        thread::spawn(move || {
+            let result = push_branch(&repo_path, &remote, &branch, mode, |progress| {
+                let _ = tx.send(TransferUpdate::Progress(progress));
+            })
+            .map(|()| remote)
+            .map_err(|e| e.to_string());
+            let _ = tx.send(TransferUpdate::Done(result));
+        });
+
+        self.push_receiver = Some(rx);
+        self.set_message(message);
+    }
+
+    /// Check and perform auto-refresh if interval has elapsed
+    pub fn check_auto_refresh(&mut self) {
+        if self.is_fetching() {
+            return;
+        }
+        // A synchronous refresh would delay any queued input by its full
+        // duration (perceived as a slow click); let input win and retry on
+        // the next tick.
+        if crossterm::event::poll(Duration::ZERO).unwrap_or(false) {
+            return;
+        }
+        if matches!(
+            self.mode,
+            AppMode::FileSelect { .. } | AppMode::FileDiff { .. }
+        ) {
+            return;
+        }
+
+        let now = Instant::now();
+        let refresh_config = &self.config.refresh;
+
+        // Auto-fetch (check first as it includes refresh)
+        if refresh_config.auto_fetch
+            && !self.is_read_only()
+            && self.remote_unsupported.is_none()
+            && now.duration_since(self.last_fetch_time).as_secs() >= refresh_config.fetch_interval
+        {
+            if let Some(remote) = self.repo.default_remote() {
+                self.start_fetch(remote, false, true); // silent=true for auto-fetch
+                return;
+            }
+        }
+
+        // Auto-refresh (or a refresh requested by a focus/resize event)
+        if self.refresh_requested
+            || (refresh_config.auto_refresh
+                && now.duration_since(self.last_refresh_time).as_secs()
+                    >= refresh_config.refresh_interval)
+        {
+            self.refresh_requested = false;
             if let Err(e) = self.refresh(false) {
                 self.set_message(format!("Auto-refresh failed: {e}"));
             }
@@ -853,22 +1896,144 @@ impl App {
         }
     }
 
-    /// Start fetch in background
-    /// If `show_message` is true, displays "Fetching from origin..."
+    /// Check the default remote's URL against the available transports
+    fn remote_support_error(repo: &GitRepository, caps: &GitCapabilities) -> Option<String> {
+        let remote = repo.repo.find_remote(&repo.default_remote()?).ok()?;
+        let url = remote.pushurl().or(remote.url())?;
+        let custom_ssh = std::env::var_os("GIT_SSH_COMMAND").is_some()
+            || std::env::var_os("GIT_SSH").is_some()
+            || repo
+                .repo
+                .config()
+                .and_then(|c| c.get_string("core.sshCommand"))
+                .is_ok();
+        caps.remote_support_error(url, custom_ssh)
+    }
+
+    /// Whether the status bar shows the refresh/fetch countdown.
+    pub fn show_timers(&self) -> bool {
+        self.show_timers
+    }
+
+    /// Seconds until the next auto-refresh and auto-fetch, each None when
+    /// that timer is off (disabled, read-only, no usable remote, or a fetch
+    /// already running). A due timer waiting on a busy view shows 0.
+    pub fn timer_countdowns(&self) -> (Option<u64>, Option<u64>) {
+        let config = &self.config.refresh;
+        let remaining =
+            |since: Instant, interval: u64| interval.saturating_sub(since.elapsed().as_secs());
+        let refresh = config
+            .auto_refresh
+            .then(|| remaining(self.last_refresh_time, config.refresh_interval));
+        let fetch = (config.auto_fetch
+            && !self.is_read_only()
+            && self.has_default_remote
+            && self.remote_unsupported.is_none()
+            && !self.is_fetching())
+        .then(|| remaining(self.last_fetch_time, config.fetch_interval));
+        (refresh, fetch)
+    }
+
+    /// Why fetch/push are unavailable, if they are.
+    pub fn remote_unsupported(&self) -> Option<&str> {
+        self.remote_unsupported.as_deref()
+    }
+
+    /// libgit2 features and external commands detected at startup.
+    pub fn capabilities(&self) -> &GitCapabilities {
+        &self.capabilities
+    }
+
+    /// Terminal regained focus: refresh if `refresh.refresh_on_focus` is set
+    pub fn on_focus_gained(&mut self) {
+        if self.config.refresh.refresh_on_focus {
+            self.refresh_requested = true;
+        }
+    }
+
+    /// Terminal was resized: refresh if `refresh.refresh_on_resize` is set.
+    /// A burst of resize events results in a single refresh.
+    pub fn on_resize(&mut self) {
+        if self.config.refresh.refresh_on_resize {
+            self.refresh_requested = true;
+        }
+    }
+
+    /// Start fetch from `remote` in background
+    /// If `show_message` is true, displays "Fetching from <remote>..."
     /// If `silent` is true, errors will not show a dialog (for auto-fetch)
-    fn start_fetch(&mut self, show_message: bool, silent: bool) {
+    fn start_fetch(&mut self, remote: String, show_message: bool, silent: bool) {
         let (tx, rx) = mpsc::channel();
         let repo_path = self.repo_path.clone();
+        if show_message {
+            self.set_message(format!("Fetching from {remote}..."));
+        }
 
         thread::spawn(move || {
-            let result = fetch_origin(&repo_path).map_err(|e| e.to_string());
-            let _ = tx.send(result);
+            let result = fetch_remote(&repo_path, &remote, |progress| {
+                let _ = tx.send(TransferUpdate::Progress(progress));
+            })
+            .map(|()| remote)
+            .map_err(|e| e.to_string());
+            let _ = tx.send(TransferUpdate::Done(result));
         });
 
         self.fetch_receiver = Some(rx);
         self.fetch_silent = silent;
-        if show_message {
-            self.set_message("Fetching from origin...");
+    }
+
+    /// Run `op` against `resolved`, the remote git itself would use, or let
+    /// the user pick one when nothing settles it
+    fn choose_remote(&mut self, op: RemoteOp, resolved: Option<String>) {
+        if let Some(remote) = resolved {
+            self.run_remote_op(op, remote);
+            return;
+        }
+        let remotes = self.repo.remote_names();
+        if remotes.is_empty() {
+            self.show_error("No remote configured".to_string());
+            return;
+        }
+        let title = match &op {
+            RemoteOp::Fetch => "Fetch from remote".to_string(),
+            RemoteOp::Push {
+                branch,
+                force: false,
+            } => format!("Push '{branch}' to remote"),
+            RemoteOp::Push {
+                branch,
+                force: true,
+            } => format!("Force-push '{branch}' to remote"),
+        };
+        self.search_state = SearchState {
+            candidates: remotes.into_iter().enumerate().collect(),
+            ..SearchState::default()
+        };
+        self.mode = AppMode::Input {
+            title,
+            input: String::new(),
+            action: InputAction::PickRemote(op),
+        };
+        self.update_fuzzy_search("");
+    }
+
+    fn run_remote_op(&mut self, op: RemoteOp, remote: String) {
+        match op {
+            RemoteOp::Fetch => self.start_fetch(remote, true, false), // silent=false for manual fetch
+            RemoteOp::Push {
+                branch,
+                force: true,
+            } => self.mode = self.force_push_confirmation(remote, branch),
+            RemoteOp::Push {
+                branch,
+                force: false,
+            } => {
+                self.mode = AppMode::Confirm {
+                    message: format!("Push '{branch}' to {remote}?"),
+                    action: ConfirmAction::Push { remote, branch },
+                    list: None,
+                }
+            }
         }
     }
 
@@ -914,6 +2079,255 @@ impl App {
         self.show_remote_branches
     }
 
+    /// Whether the graph minimap column is shown.
+    pub fn show_minimap(&self) -> bool {
+        self.show_minimap
+    }
+
+    /// Whether commit rows draw the lane art: not switched off for the
+    /// session and listed in `display.columns`
+    pub fn show_graph(&self) -> bool {
+        self.show_graph && self.config.display.columns.contains(&Column::Graph)
+    }
+
+    /// Whether the graph debug overlay column is shown.
+    pub fn show_graph_debug(&self) -> bool {
+        self.show_graph_debug
+    }
+
+    /// Whether commits with a differing committer get the `~` marker.
+    pub fn show_rewritten_marker(&self) -> bool {
+        self.show_rewritten_marker
+    }
+
+    pub fn show_parent_badge(&self) -> bool {
+        self.show_parent_badge
+    }
+
+    /// Whether date group headers (`── Today ──`) separate graph rows.
+    pub fn show_date_headers(&self) -> bool {
+        self.show_date_headers
+    }
+
+    /// Date header rows of the current graph (none when they're hidden)
+    pub fn date_headers(&self) -> &DateHeaders {
+        &self.date_headers
+    }
+
+    /// Configured remote names.
+    pub fn remote_names(&self) -> &[String] {
+        &self.remote_names
+    }
+
+    /// Whether the date column is colored by commit age.
+    pub fn show_age_heat(&self) -> bool {
+        self.show_age_heat
+    }
+
+    /// Commit time span for the age heat colors (None when off or empty)
+    pub fn age_heat(&self) -> Option<AgeRange> {
+        self.age_range.filter(|_| self.show_age_heat)
+    }
+
+    /// Whether commit hashes are shown in full rather than abbreviated.
+    pub fn full_hashes(&self) -> bool {
+        self.full_hashes
+    }
+
+    /// Switch to a reloaded config. Settings with a session toggle only
+    /// change when the file changed them, so unrelated edits don't undo
+    /// toggles made since startup
+    fn apply_config(&mut self, config: Config) -> Result<()> {
+        let (old, new) = (&self.config.graph, &config.graph);
+        let update = |current: &mut bool, old: bool, new: bool| {
+            if old != new {
+                *current = new;
+            }
+        };
+        update(
+            &mut self.show_remote_branches,
+            old.show_remote_branches,
+            new.show_remote_branches,
+        );
+        update(&mut self.show_minimap, old.show_minimap, new.show_minimap);
+        update(
+            &mut self.show_graph_debug,
+            old.debug_overlay,
+            new.debug_overlay,
+        );
+        update(
+            &mut self.show_rewritten_marker,
+            old.mark_rewritten,
+            new.mark_rewritten,
+        );
+        update(
+            &mut self.show_parent_badge,
+            old.parent_badge,
+            new.parent_badge,
+        );
+        update(
+            &mut self.show_date_headers,
+            old.date_headers,
+            new.date_headers,
+        );
+        update(&mut self.show_age_heat, old.age_heat, new.age_heat);
+        update(
+            &mut self.full_hashes,
+            self.config.display.full_hashes,
+            config.display.full_hashes,
+        );
+        update(
+            &mut self.show_timers,
+            self.config.ui.show_timers,
+            config.ui.show_timers,
+        );
+        update(
+            &mut self.show_detail,
+            self.config.ui.show_detail,
+            config.ui.show_detail,
+        );
+        update(
+            &mut self.word_diff,
+            self.config.diff.word_diff,
+            config.diff.word_diff,
+        );
+        if config.diff.whitespace != self.config.diff.whitespace {
+            self.whitespace = config.diff.whitespace;
+            self.clear_all_diff_caches();
+        }
+        if config.diff.max_files != self.config.diff.max_files {
+            self.clear_all_diff_caches();
+        }
+        self.diff_cache.set_capacity(config.diff.cache_size);
+        self.config = config;
+        self.refresh(true)?;
+        self.reset_timers();
+        Ok(())
+    }
+
+    pub fn main_branch(&self) -> Option<&str> {
+        self.main_branch.as_deref()
+    }
+
+    /// Local branches fully merged into the main branch
+    pub fn merged_branches(&self) -> &HashSet<String> {
+        &self.merged_branches
+    }
+
+    /// Branches checked out in other worktrees, with the worktree path
+    pub fn worktree_branches(&self) -> &HashMap<String, PathBuf> {
+        &self.worktree_branches
+    }
+
+    /// Bare repository: history only, no working tree to check out into,
+    /// stage or commit from.
+    pub fn is_bare(&self) -> bool {
+        self.repo.repo.is_bare()
+    }
+
+    /// Number of changed files in the working tree (0 when clean).
+    pub fn dirty_file_count(&self) -> usize {
+        self.working_tree_status
+            .as_ref()
+            .map_or(0, |status| status.file_count())
+    }
+
+    /// Loaded configuration.
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Commit node glyphs (`[glyphs]` config).
+    pub fn graph_chars(&self) -> &GraphChars {
+        &self.config.glyphs
+    }
+
+    /// Recently triggered action to flash on screen (`ui.show_actions`).
+    pub fn action_indicator(&self) -> Option<&Action> {
+        self.last_action
+            .as_ref()
+            .filter(|(_, at)| at.elapsed() < ACTION_INDICATOR_DURATION)
+            .map(|(action, _)| action)
+    }
+
+    /// Read-only mode: only navigation and inspection, no repository
+    /// changes or remote operations.
+    pub fn is_read_only(&self) -> bool {
+        self.read_only || self.config.ui.read_only
+    }
+
+    /// Turn on read-only mode for the session (`--read-only`)
+    pub fn set_read_only(&mut self) {
+        self.read_only = true;
+    }
+
+    /// Identity new commits, tags and stashes use instead of the
+    /// repository's, if set for the session
+    pub fn session_identity(&self) -> Option<&Identity> {
+        self.session_identity.as_ref()
+    }
+
+    /// Commit as `identity` for the rest of the session (`--author`);
+    /// `None` goes back to `user.name` / `user.email`
+    pub fn set_session_identity(&mut self, identity: Option<Identity>) {
+        self.session_identity = identity;
+    }
+
+    /// Whether the detail pane shows the raw commit object.
+    pub fn show_raw_commit(&self) -> bool {
+        self.show_raw_commit
+    }
+
+    /// Whether the detail pane shows long commit messages in full.
+    pub fn show_full_message(&self) -> bool {
+        self.show_full_message
+    }
+
+    /// Whether the bare graph is drawn. The file list needs the detail
+    /// pane, so focus mode steps aside while it's open.
+    pub fn focus_mode(&self) -> bool {
+        self.focus_mode && !matches!(self.mode, AppMode::FileSelect { .. })
+    }
+
+    /// Whether the detail and changed files panes are shown. The file list
+    /// lives in the changed files pane, so it brings them back while open.
+    pub fn show_detail(&self) -> bool {
+        self.show_detail || matches!(self.mode, AppMode::FileSelect { .. })
+    }
+
+    pub fn graph_orientation(&self) -> GraphOrientation {
+        self.graph_orientation
+    }
+
+    /// Whether the selected diff is on screen. The detail pane isn't drawn
+    /// when hidden or in focus mode, so there's no point computing it then.
+    fn diff_visible(&self) -> bool {
+        self.show_detail() && !self.focus_mode()
+    }
+
+    /// Open the file list of the selected diff, or start loading it
+    fn enter_file_select(&mut self) {
+        if let Some(diff) = self.cached_diff() {
+            if diff.files.is_empty() {
+                self.set_message("No changed files in this diff");
+            } else {
+                let file_list = diff.files.clone();
+                self.mode = AppMode::FileSelect {
+                    selected_index: 0,
+                    file_list,
+                };
+            }
+        } else if self.is_diff_loading() {
+            self.set_message("Loading diff...");
+        } else if !self.diff_visible() && self.current_diff_target().is_some() {
+            // Not loaded while off screen; start now and open it once ready
+            self.diff_requested = true;
+            self.set_message("Loading diff...");
+        } else {
+            self.set_message("Diff not available");
+        }
+    }
+
     /// Update diff info for the selected node (commit or uncommitted changes, async)
     pub fn update_diff_cache(&mut self) {
         // Pull in completed results for commit diff
@@ -921,13 +2335,9 @@ impl App {
             match receiver.try_recv() {
                 Ok(result) => {
                     match result.diff {
-                        Ok(diff) => {
-                            self.diff_cache = Some(diff);
-                            self.diff_cache_oid = Some(result.oid);
-                        }
+                        Ok(diff) => self.diff_cache.insert(result.oid, Some(diff)),
                         Err(e) => {
-                            self.diff_cache = None;
-                            self.diff_cache_oid = Some(result.oid);
+                            self.diff_cache.insert(result.oid, None);
                             self.set_message(format!("Failed to load diff: {e}"));
                         }
                     }
@@ -1000,6 +2410,24 @@ impl App {
         let Some(target) = self.sync_selected_diff_target() else {
             return;
         };
+        if let DiffTarget::Commit(oid) = target {
+            self.diff_cache.touch(oid);
+        }
+
+        // A file list asked for while the diff was off screen opens once
+        // it's loaded (a failed load has already left a message)
+        if self.diff_requested && self.has_cached_diff_for_target(target) {
+            self.diff_requested = false;
+            if matches!(self.mode, AppMode::Normal) && self.cached_diff().is_some() {
+                self.enter_file_select();
+            }
+            return;
+        }
+
+        // Only the selected diff is computed, and only once something shows it
+        if !self.diff_visible() && !self.diff_requested {
+            return;
+        }
 
         if self.has_cached_diff_for_target(target)
             || self.is_diff_loading_for_target(target)
@@ -1020,6 +2448,8 @@ impl App {
                 // Compute uncommitted diff in the background
                 let (tx, rx) = mpsc::channel();
                 let repo_path = self.repo_path.clone();
+                let whitespace = self.whitespace;
+                let max_files = self.config.diff.max_files;
 
                 self.uncommitted_diff_failed = false;
                 self.uncommitted_diff_loading = true;
@@ -1042,39 +2472,47 @@ impl App {
                     // will no longer match the refresh-time status, correctly
                     // triggering a reload instead of caching a stale diff.
                     let status = repo.get_working_tree_status().unwrap_or_default();
-                    let diff =
-                        CommitDiffInfo::from_working_tree(&repo.repo).map_err(|e| e.to_string());
+                    let diff = CommitDiffInfo::from_working_tree_with_whitespace(
+                        &repo.repo, whitespace, max_files,
+                    )
+                    .map_err(|e| e.to_string());
                     let _ = tx.send((diff, status));
                 });
             }
             DiffTarget::Commit(oid) => {
-                tracing::debug!(%oid, "spawning commit diff computation");
+                tracing::debug!(%oid, "requesting commit diff computation");
                 // Compute diff in the background
                 let (tx, rx) = mpsc::channel();
-                let repo_path = self.repo_path.clone();
+                let request = DiffRequest {
+                    oid,
+                    whitespace: self.whitespace,
+                    max_files: self.config.diff.max_files,
+                    reply: tx,
+                };
+                let worker = self
+                    .diff_worker
+                    .get_or_insert_with(|| spawn_diff_worker(self.repo_path.clone()));
+                if let Err(mpsc::SendError(request)) = worker.send(request) {
+                    // The worker is gone (it panicked); start a new one
+                    let worker = spawn_diff_worker(self.repo_path.clone());
+                    let _ = worker.send(request);
+                    self.diff_worker = Some(worker);
+                }
 
                 self.diff_loading_oid = Some(oid);
                 self.diff_receiver = Some(rx);
-
-                thread::spawn(move || {
-                    let diff = git2::Repository::open(&repo_path)
-                        .map_err(|e| e.to_string())
-                        .and_then(|repo| {
-                            CommitDiffInfo::from_commit(&repo, oid).map_err(|e| e.to_string())
-                        });
-
-                    let _ = tx.send(DiffResult { oid, diff });
-                });
             }
+            // Computed synchronously by open_upstream_diff
+            DiffTarget::Range { .. } => {}
         }
     }
 
     /// Get cached diff info for the currently selected node
     pub fn cached_diff(&self) -> Option<&CommitDiffInfo> {
         match self.current_diff_target()? {
-            DiffTarget::Commit(oid) if self.diff_cache_oid == Some(oid) => self.diff_cache.as_ref(),
-            DiffTarget::Commit(_) => None,
+            DiffTarget::Commit(oid) => self.diff_cache.get(oid),
             DiffTarget::Uncommitted => self.uncommitted_diff_cache.as_ref(),
+            DiffTarget::Range { .. } => self.range_diff.as_ref().map(|range| &range.diff),
         }
     }
 
@@ -1092,6 +2530,25 @@ impl App {
 
     /// Handle an action
     pub fn handle_action(&mut self, action: Action) -> Result<()> {
+        if self.config.ui.show_actions {
+            self.last_action = Some((action.clone(), Instant::now()));
+        }
+        // Enter on the load-more row only loads history
+        let loads_more = action == Action::Checkout && self.is_load_more_selected();
+        if self.is_read_only() && action.mutates() && !loads_more {
+            self.set_message("Not available in read-only mode");
+            return Ok(());
+        }
+        if self.is_bare() && action.needs_work_tree() && !loads_more {
+            self.set_message("Not available in a bare repository (no working tree)");
+            return Ok(());
+        }
+        if action.writes_commit()
+            && matches!(self.mode, AppMode::Normal | AppMode::FileSelect { .. })
+            && self.ask_for_missing_identity(IdentityResume::Action(action.clone()))
+        {
+            return Ok(());
+        }
         match &self.mode {
             AppMode::Normal => self.handle_normal_action(action)?,
             AppMode::Help => self.handle_help_action(action),
@@ -1100,6 +2557,9 @@ impl App {
             AppMode::Error { .. } => self.handle_error_action(action),
             AppMode::FileSelect { .. } => self.handle_file_select_action(action)?,
             AppMode::FileDiff { .. } => self.handle_file_diff_action(action)?,
+            AppMode::TreeBrowser { .. } => self.handle_tree_action(action)?,
+            AppMode::BranchCompare { .. } => self.handle_compare_action(action)?,
+            AppMode::StashList { .. } => self.handle_stash_action(action)?,
         }
         Ok(())
     }
@@ -1122,8 +2582,8 @@ impl App {
             }
             Action::FocusNext => {
                 self.focused_pane = match self.focused_pane {
-                    FocusedPane::Graph => FocusedPane::Detail,
-                    FocusedPane::Detail => FocusedPane::Graph,
+                    FocusedPane::Graph if self.show_detail() => FocusedPane::Detail,
+                    _ => FocusedPane::Graph,
                 };
             }
             Action::MoveUp => match self.focused_pane {
@@ -1159,6 +2619,18 @@ impl App {
             Action::PrevBranch => {
                 self.move_to_prev_branch();
             }
+            Action::NextMerge => {
+                self.move_to_merge(true);
+            }
+            Action::PrevMerge => {
+                self.move_to_merge(false);
+            }
+            Action::NextTag => {
+                self.move_to_tag(true);
+            }
+            Action::PrevTag => {
+                self.move_to_tag(false);
+            }
             Action::BranchLeft => {
                 self.move_branch_left();
             }
@@ -1167,6 +2639,8 @@ impl App {
             }
             Action::ToggleHelp => {
                 self.help_scroll = 0;
+                self.help_query.clear();
+                self.help_match = None;
                 self.mode = AppMode::Help;
             }
             Action::Refresh => {
@@ -1184,17 +2658,218 @@ impl App {
                 };
                 self.set_message(format!("Remote branches {state}"));
             }
+            Action::ReloadConfig => match Config::try_load() {
+                Ok(config) => {
+                    self.apply_config(config)?;
+                    self.set_message("Config reloaded");
+                }
+                Err(e) => self.show_error(format!("{e:#}; keeping the current config")),
+            },
+            Action::ToggleMinimap => {
+                self.show_minimap = !self.show_minimap;
+                let state = if self.show_minimap { "shown" } else { "hidden" };
+                self.set_message(format!("Minimap {state}"));
+            }
+            Action::ToggleFocusMode => {
+                self.focus_mode = !self.focus_mode;
+                // The detail pane is hidden, so keys go to the graph
+                self.focused_pane = FocusedPane::Graph;
+            }
+            Action::ToggleDetail => {
+                self.show_detail = !self.show_detail;
+                self.focused_pane = FocusedPane::Graph;
+                let state = if self.show_detail { "shown" } else { "hidden" };
+                let saved = self.state_path.as_deref().map(|path| {
+                    UiState {
+                        show_detail: Some(self.show_detail),
+                    }
+                    .save(path)
+                });
+                match saved {
+                    Some(Err(e)) => {
+                        self.set_message(format!("Detail pane {state} (not remembered: {e:#})"))
+                    }
+                    _ => self.set_message(format!("Detail pane {state}")),
+                }
+            }
+            Action::ToggleGraph => {
+                self.show_graph = !self.show_graph;
+                // The horizontal layout is all lane art
+                self.graph_orientation = GraphOrientation::Vertical;
+                // Only the lane art changes: no need to reload history
+                let selection = self.graph_selection();
+                self.rebuild_graph();
+                self.restore_graph_selection(selection);
+                let state = if self.show_graph { "shown" } else { "hidden" };
+                self.set_message(format!("Graph {state}"));
+            }
+            Action::ToggleOrientation if !self.show_graph() => {
+                self.set_message("The graph is hidden");
+            }
+            Action::ToggleOrientation => {
+                self.graph_orientation = match self.graph_orientation {
+                    GraphOrientation::Vertical => GraphOrientation::Horizontal,
+                    GraphOrientation::Horizontal => GraphOrientation::Vertical,
+                };
+                let state = match self.graph_orientation {
+                    GraphOrientation::Vertical => "vertical",
+                    GraphOrientation::Horizontal => "horizontal",
+                };
+                self.set_message(format!("Graph layout: {state}"));
+            }
+            Action::ToggleGraphDebug => {
+                self.show_graph_debug = !self.show_graph_debug;
+                let state = if self.show_graph_debug {
+                    "shown"
+                } else {
+                    "hidden"
+                };
+                self.set_message(format!("Graph debug overlay {state}"));
+            }
+            Action::ToggleRewrittenMarker => {
+                self.show_rewritten_marker = !self.show_rewritten_marker;
+                let state = if self.show_rewritten_marker {
+                    "shown"
+                } else {
+                    "hidden"
+                };
+                self.set_message(format!("Committer markers {state}"));
+            }
+            Action::ToggleParentBadge => {
+                self.show_parent_badge = !self.show_parent_badge;
+                let state = if self.show_parent_badge {
+                    "shown"
+                } else {
+                    "hidden"
+                };
+                self.set_message(format!("Merge parent badges {state}"));
+            }
+            Action::ToggleDateHeaders => {
+                self.show_date_headers = !self.show_date_headers;
+                self.update_date_headers();
+                let state = if self.show_date_headers {
+                    "shown"
+                } else {
+                    "hidden"
+                };
+                self.set_message(format!("Date headers {state}"));
+            }
+            Action::ToggleTimers => {
+                self.show_timers = !self.show_timers;
+                let state = if self.show_timers { "shown" } else { "hidden" };
+                self.set_message(format!("Refresh timers {state}"));
+            }
+            Action::ToggleAgeHeat => {
+                self.show_age_heat = !self.show_age_heat;
+                let state = if self.show_age_heat { "on" } else { "off" };
+                self.set_message(format!("Commit age heat {state}"));
+            }
+            Action::ToggleHashLength => {
+                self.full_hashes = !self.full_hashes;
+                let state = if self.full_hashes { "full" } else { "short" };
+                self.set_message(format!("Commit hashes {state}"));
+            }
+            Action::ToggleRawCommit => {
+                self.show_raw_commit = !self.show_raw_commit;
+                self.detail_scroll = 0;
+            }
+            Action::ToggleFullMessage => {
+                self.show_full_message = !self.show_full_message;
+                self.detail_scroll = 0;
+                let state = if self.show_full_message {
+                    "shown in full"
+                } else {
+                    "truncated"
+                };
+                self.set_message(format!("Long commit messages {state}"));
+            }
+            Action::Pull => {
+                let upstream = self
+                    .branches
+                    .iter()
+                    .find(|branch| branch.is_head)
+                    .and_then(|branch| branch.upstream.clone());
+                if self.is_fetching() {
+                    self.set_message("Fetch already in progress");
+                } else if let Some(reason) = self.remote_unsupported.clone() {
+                    self.show_error(format!("Cannot pull: {reason}"));
+                } else if self.repo.repo.head_detached().unwrap_or(false) {
+                    self.set_message("Cannot pull: detached HEAD");
+                } else if let Some(branch) = self.head_name.clone() {
+                    match (upstream, self.repo.upstream_remote(&branch)) {
+                        (Some(upstream), Some(remote)) => {
+                            self.pending_pull = Some(upstream);
+                            self.start_fetch(remote, true, false);
+                        }
+                        _ => self.set_message(format!(
+                            "Cannot pull: '{branch}' has no upstream (push it with p to set one)"
+                        )),
+                    }
+                } else {
+                    self.set_message("Cannot pull: no current branch");
+                }
+            }
             Action::Fetch if !self.is_fetching() => {
-                self.start_fetch(true, false); // silent=false for manual fetch
+                if let Some(reason) = self.remote_unsupported.clone() {
+                    self.show_error(format!("Cannot fetch: {reason}"));
+                } else {
+                    self.choose_remote(RemoteOp::Fetch, self.repo.default_remote());
+                }
             }
             Action::Checkout => {
                 self.do_checkout()?;
             }
+            Action::CheckoutPrevious => {
+                let target = checkout_previous(&self.repo.repo)?;
+                self.refresh(true)?;
+                self.jump_to_head();
+                self.set_message(format!("Switched to {target}"));
+            }
+            Action::GotoRevision => {
+                self.mode = AppMode::Input {
+                    title: "Go to revision (HEAD~3, main@{yesterday}, v1.0^)".to_string(),
+                    input: String::new(),
+                    action: InputAction::GotoRevision,
+                };
+            }
+            Action::SessionAuthor => {
+                self.mode = AppMode::Input {
+                    title: "Commit as Name <email> this session (empty: git config)".to_string(),
+                    input: self
+                        .session_identity
+                        .as_ref()
+                        .map(Identity::to_string)
+                        .unwrap_or_default(),
+                    action: InputAction::SessionAuthor,
+                };
+            }
+            Action::PathScope => {
+                self.mode = AppMode::Input {
+                    title: "Limit to paths (empty clears)".to_string(),
+                    input: self.path_scope.paths().join(" "),
+                    action: InputAction::PathScope,
+                };
+            }
             Action::CreateBranch => {
                 self.mode = AppMode::Input {
-                    title: "New Branch Name".to_string(),
+                    title: create_branch_title(false),
+                    input: String::new(),
+                    action: InputAction::CreateBranch { switch: false },
+                };
+            }
+            Action::CreateTag => {
+                if self
+                    .selected_commit_node()
+                    .and_then(|node| node.commit.as_ref())
+                    .is_none()
+                {
+                    self.set_message("No commit selected");
+                    return Ok(());
+                }
+                self.mode = AppMode::Input {
+                    title: create_tag_title(false),
                     input: String::new(),
-                    action: InputAction::CreateBranch,
+                    action: InputAction::CreateTag { annotated: false },
                 };
             }
             Action::Search => {
@@ -1206,22 +2881,99 @@ impl App {
                     action: InputAction::Search,
                 };
             }
+            Action::GotoBranch => {
+                if self.branches.is_empty() {
+                    self.set_message("No branches; history is shown from HEAD (b: create one)");
+                    return Ok(());
+                }
+                self.search_state = SearchState {
+                    candidates: self
+                        .branches
+                        .iter()
+                        .enumerate()
+                        .map(|(idx, branch)| (idx, branch.name.clone()))
+                        .collect(),
+                    ..SearchState::default()
+                };
+                let title = match self.config.branches.enter {
+                    BranchEnter::Goto => "Go to branch",
+                    BranchEnter::Checkout => "Check out branch",
+                };
+                self.mode = AppMode::Input {
+                    title: title.to_string(),
+                    input: String::new(),
+                    action: InputAction::GotoBranch,
+                };
+                self.update_fuzzy_search("");
+            }
+            Action::CommandPalette => {
+                self.search_state = SearchState {
+                    candidates: BINDINGS
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, command)| command.in_palette)
+                        .map(|(idx, command)| match command.key {
+                            "" => (idx, command.name.to_string()),
+                            key => (idx, format!("{}  ({key})", command.name)),
+                        })
+                        .collect(),
+                    ..SearchState::default()
+                };
+                self.mode = AppMode::Input {
+                    title: "Command".to_string(),
+                    input: String::new(),
+                    action: InputAction::CommandPalette,
+                };
+                self.update_fuzzy_search("");
+            }
             Action::DeleteBranch => {
                 if let Some(branch) = self.selected_branch() {
                     if !branch.is_head && !branch.is_remote {
                         self.mode = AppMode::Confirm {
                             message: format!("Delete branch '{}'?", branch.name),
                             action: ConfirmAction::DeleteBranch(branch.name.clone()),
+                            list: None,
                         };
                     }
                 }
             }
+            Action::CleanupBranches => {
+                let Some(main) = self.main_branch.clone() else {
+                    self.set_message("No main branch found (set [branches] main)");
+                    return Ok(());
+                };
+                let mut names: Vec<String> = self.merged_branches.iter().cloned().collect();
+                if names.is_empty() {
+                    self.set_message(format!("No branches merged into {main}"));
+                    return Ok(());
+                }
+                names.sort();
+                let mut message =
+                    format!("Delete {} branch(es) merged into '{main}'?", names.len());
+                for name in names.iter().take(CLEANUP_LIST_LIMIT) {
+                    message.push_str(&format!("\n  {name}"));
+                }
+                if names.len() > CLEANUP_LIST_LIMIT {
+                    message.push_str(&format!(
+                        "\n  … and {} more",
+                        names.len() - CLEANUP_LIST_LIMIT
+                    ));
+                }
+                // Every line below the question
+                let list = Some(1..message.lines().count());
+                self.mode = AppMode::Confirm {
+                    message,
+                    action: ConfirmAction::CleanupBranches(names),
+                    list,
+                };
+            }
             Action::Merge => {
                 if let Some(branch) = self.selected_branch() {
                     if !branch.is_head {
                         self.mode = AppMode::Confirm {
                             message: format!("Merge '{}' into current branch?", branch.name),
                             action: ConfirmAction::Merge(branch.name.clone()),
+                            list: None,
                         };
                     }
                 }
@@ -1229,28 +2981,137 @@ impl App {
             Action::Rebase => {
                 if let Some(branch) = self.selected_branch() {
                     if !branch.is_head {
+                        let mut message = format!("Rebase current branch onto '{}'?", branch.name);
+                        if let Some(warning) = self.rewrite_warning(branch.tip_oid) {
+                            message.push('\n');
+                            message.push_str(&warning);
+                        }
+                        let replayed = self.repo.rebase_commits(branch.tip_oid)?;
+                        let mut list = None;
+                        if replayed.is_empty() {
+                            message.push_str(&format!(
+                                "\nNothing to replay; the branch moves to '{}'",
+                                branch.name
+                            ));
+                        } else {
+                            message.push_str(&format!("\nReplays {} commit(s):", replayed.len()));
+                            let start = message.lines().count();
+                            list = Some(start..start + replayed.len());
+                            for commit in &replayed {
+                                message.push_str(&format!(
+                                    "\n  {} {}",
+                                    commit.short_id,
+                                    truncate_to_width(&commit.message, 60)
+                                ));
+                            }
+                        }
                         self.mode = AppMode::Confirm {
-                            message: format!("Rebase current branch onto '{}'?", branch.name),
+                            message,
                             action: ConfirmAction::Rebase(branch.name.clone()),
+                            list,
                         };
                     }
                 }
             }
-            Action::EnterFileSelect => {
-                if let Some(diff) = self.cached_diff() {
-                    if diff.files.is_empty() {
-                        self.set_message("No changed files in this diff");
-                    } else {
-                        let file_list = diff.files.clone();
-                        self.mode = AppMode::FileSelect {
-                            selected_index: 0,
-                            file_list,
-                        };
-                    }
-                } else if self.is_diff_loading() {
-                    self.set_message("Loading diff...");
+            Action::CherryPick => {
+                let Some(commit) = self
+                    .selected_commit_node()
+                    .and_then(|node| node.commit.as_ref())
+                else {
+                    self.set_message("No commit selected");
+                    return Ok(());
+                };
+                if commit.parent_oids.len() > 1 {
+                    self.set_message("Cannot cherry-pick a merge commit");
+                    return Ok(());
+                }
+                let head = self.repo.head_name().unwrap_or_else(|| "HEAD".to_string());
+                self.mode = AppMode::Confirm {
+                    message: format!(
+                        "Cherry-pick {} \"{}\" onto '{head}'?",
+                        commit.short_id, commit.message
+                    ),
+                    action: ConfirmAction::CherryPick(commit.oid),
+                    list: None,
+                };
+            }
+            Action::Revert => {
+                let Some(commit) = self
+                    .selected_commit_node()
+                    .and_then(|node| node.commit.as_ref())
+                else {
+                    self.set_message("No commit selected");
+                    return Ok(());
+                };
+                self.mode = AppMode::Confirm {
+                    message: format!("Revert commit {}?", commit.short_id),
+                    action: ConfirmAction::Revert(commit.oid),
+                    list: None,
+                };
+            }
+            Action::GitIdentity => {
+                let mut message = "Git config".to_string();
+                for (key, value) in self.repo.operation_config() {
+                    let value = value.as_deref().unwrap_or("(unset)");
+                    message.push_str(&format!("\n  {key} = {value}"));
+                }
+                if let Some(identity) = &self.session_identity {
+                    message.push_str(&format!("\n  (this session commits as {identity})"));
+                }
+                message.push_str("\nChange the identity for this repository?");
+                self.mode = AppMode::Confirm {
+                    message,
+                    action: ConfirmAction::EditIdentity(None),
+                    list: None,
+                };
+            }
+            Action::StashSave => {
+                stash_save(&mut self.repo.repo, None, self.session_identity.as_ref())?;
+                self.refresh(true)?;
+                self.set_message("Saved local changes to stash@{0}");
+            }
+            Action::StashPop => {
+                if self.repo.get_stashes()?.is_empty() {
+                    self.set_message("No stash entries");
+                    return Ok(());
+                }
+                stash_pop(&mut self.repo.repo, 0)?;
+                self.refresh(true)?;
+                self.set_message("Popped stash@{0}");
+            }
+            Action::StashList => {
+                let stashes = self.repo.get_stashes()?;
+                if stashes.is_empty() {
+                    self.set_message("No stash entries");
                 } else {
-                    self.set_message("Diff not available");
+                    self.mode = AppMode::StashList {
+                        stashes,
+                        selected: 0,
+                    };
+                }
+            }
+            Action::UpstreamDiff => {
+                self.open_upstream_diff()?;
+            }
+            Action::MarkRange => {
+                self.mark_range()?;
+            }
+            Action::EnterFileSelect => self.enter_file_select(),
+            Action::CompareBranches => {
+                self.open_branch_compare()?;
+            }
+            Action::OpenTree => {
+                let Some(oid) = self
+                    .selected_commit_node()
+                    .and_then(|node| node.commit.as_ref())
+                    .map(|commit| commit.oid)
+                else {
+                    self.set_message("No commit selected");
+                    return Ok(());
+                };
+                match TreeBrowser::open(&self.repo.repo, oid) {
+                    Ok(browser) => self.mode = AppMode::TreeBrowser { browser },
+                    Err(e) => self.show_error(format!("Cannot open tree: {e}")),
                 }
             }
             Action::CopyHash => {
@@ -1279,16 +3140,21 @@ impl App {
             Action::CommitDialog => {
                 self.open_commit_dialog();
             }
-            Action::Push => {
+            Action::Push | Action::ForcePush => {
+                let force = action == Action::ForcePush;
                 if self.is_pushing() {
                     self.set_message("Push already in progress");
+                } else if let Some(reason) = self.remote_unsupported.clone() {
+                    self.show_error(format!("Cannot push: {reason}"));
                 } else if self.repo.repo.head_detached().unwrap_or(false) {
                     self.set_message("Cannot push: detached HEAD");
                 } else if let Some(branch) = self.head_name.clone() {
-                    self.mode = AppMode::Confirm {
-                        message: format!("Push '{}' to origin?", branch),
-                        action: ConfirmAction::Push(branch),
-                    };
+                    // The configured push remote wins, then the default
+                    let resolved = self
+                        .repo
+                        .push_remote(&branch)
+                        .or_else(|| self.repo.default_remote());
+                    self.choose_remote(RemoteOp::Push { branch, force }, resolved);
                 } else {
                     self.set_message("Cannot push: no current branch");
                 }
@@ -1321,8 +3187,185 @@ impl App {
             Action::ScrollToBottom => {
                 self.help_scroll = u16::MAX;
             }
+            Action::Search => {
+                self.mode = AppMode::Input {
+                    title: "Search help".to_string(),
+                    input: self.help_query.clone(),
+                    action: InputAction::HelpSearch,
+                };
+            }
+            Action::NextMatch => self.step_help_match(true),
+            Action::PrevMatch => self.step_help_match(false),
+            _ => {}
+        }
+    }
+
+    pub fn help_query(&self) -> &str {
+        &self.help_query
+    }
+
+    pub fn help_match(&self) -> Option<usize> {
+        self.help_match
+    }
+
+    /// Scroll the help to the next/previous line matching the query,
+    /// wrapping around. Without a current match, starts from the top of
+    /// the view.
+    fn step_help_match(&mut self, forward: bool) {
+        if self.help_query.is_empty() {
+            return;
+        }
+        let matches = HelpPopup::matching_lines(&self.help_query);
+        let top = self.help_scroll as usize;
+        let next = if forward {
+            matches
+                .iter()
+                .find(|&&line| {
+                    self.help_match
+                        .map_or(line >= top, |current| line > current)
+                })
+                .or(matches.first())
+        } else {
+            matches
+                .iter()
+                .rev()
+                .find(|&&line| line < self.help_match.unwrap_or(top))
+                .or(matches.last())
+        };
+        let Some(&line) = next else {
+            self.set_message(format!("No help line matches '{}'", self.help_query));
+            return;
+        };
+        self.help_match = Some(line);
+        // A little context above the match
+        self.help_scroll = line.saturating_sub(2) as u16;
+    }
+
+    fn handle_tree_action(&mut self, action: Action) -> Result<()> {
+        let AppMode::TreeBrowser { browser } = &mut self.mode else {
+            return Ok(());
+        };
+        if let Some(file) = &mut browser.file {
+            // The renderer clamps the scroll to the file length
+            match action {
+                Action::MoveDown => file.scroll = file.scroll.saturating_add(1),
+                Action::MoveUp => file.scroll = file.scroll.saturating_sub(1),
+                Action::PageDown => file.scroll = file.scroll.saturating_add(10),
+                Action::PageUp => file.scroll = file.scroll.saturating_sub(10),
+                Action::GoToTop => file.scroll = 0,
+                Action::GoToBottom => file.scroll = usize::MAX,
+                Action::TreeCollapse | Action::Cancel => browser.file = None,
+                _ => {}
+            }
+            return Ok(());
+        }
+        match action {
+            Action::MoveDown => browser.move_selection(1),
+            Action::MoveUp => browser.move_selection(-1),
+            Action::PageDown => browser.move_selection(10),
+            Action::PageUp => browser.move_selection(-10),
+            Action::GoToTop => browser.selected = 0,
+            Action::GoToBottom => browser.move_selection(isize::MAX),
+            Action::TreeExpand => {
+                if let Err(e) = browser.expand(&self.repo.repo) {
+                    self.set_message(format!("Cannot open: {e}"));
+                }
+            }
+            Action::TreeCollapse => browser.collapse(),
+            Action::Cancel => self.mode = AppMode::Normal,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn handle_stash_action(&mut self, action: Action) -> Result<()> {
+        let AppMode::StashList { stashes, selected } = &mut self.mode else {
+            return Ok(());
+        };
+        let last = stashes.len().saturating_sub(1);
+        match action {
+            Action::MoveDown => *selected = (*selected + 1).min(last),
+            Action::MoveUp => *selected = selected.saturating_sub(1),
+            Action::GoToTop => *selected = 0,
+            Action::GoToBottom => *selected = last,
+            Action::Confirm | Action::StashPop => {
+                let index = *selected;
+                self.mode = AppMode::Normal;
+                if action == Action::Confirm {
+                    stash_apply(&mut self.repo.repo, index)?;
+                    self.refresh(true)?;
+                    self.set_message(format!("Applied stash@{{{index}}}"));
+                } else {
+                    stash_pop(&mut self.repo.repo, index)?;
+                    self.refresh(true)?;
+                    self.set_message(format!("Popped stash@{{{index}}}"));
+                }
+            }
+            Action::Cancel => self.mode = AppMode::Normal,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Compare the selected branch (right) with the current one (left)
+    fn open_branch_compare(&mut self) -> Result<()> {
+        let Some(branch) = self.selected_branch() else {
+            self.set_message("No branch selected");
+            return Ok(());
+        };
+        if branch.is_head {
+            self.set_message("Select a branch other than the current one");
+            return Ok(());
+        }
+        let right = CompareSide {
+            name: branch.name.clone(),
+            tip: branch.tip_oid,
+        };
+        let Some(head) = self.repo.head_oid() else {
+            self.set_message("No commit at HEAD");
+            return Ok(());
+        };
+        let left = CompareSide {
+            name: self.head_name.clone().unwrap_or_else(|| "HEAD".to_string()),
+            tip: head,
+        };
+        match BranchCompare::open(&self.repo.repo, left, right) {
+            Ok(compare) => self.mode = AppMode::BranchCompare { compare },
+            Err(e) => self.show_error(format!("Cannot compare: {e}")),
+        }
+        Ok(())
+    }
+
+    fn handle_compare_action(&mut self, action: Action) -> Result<()> {
+        let AppMode::BranchCompare { compare } = &mut self.mode else {
+            return Ok(());
+        };
+        let repo = &self.repo.repo;
+        match action {
+            Action::MoveDown => compare.move_selection(repo, 1),
+            Action::MoveUp => compare.move_selection(repo, -1),
+            Action::PageDown => compare.move_selection(repo, 10),
+            Action::PageUp => compare.move_selection(repo, -10),
+            Action::GoToTop => compare.move_selection(repo, isize::MIN),
+            Action::GoToBottom => compare.move_selection(repo, isize::MAX),
+            Action::BranchLeft => compare.focus(repo, false),
+            Action::BranchRight => compare.focus(repo, true),
+            Action::FocusNext => {
+                let right = !compare.focus_right;
+                compare.focus(repo, right);
+            }
+            Action::Confirm => {
+                // Show the commit under the cursor in the main graph
+                let Some(oid) = compare.selected_commit().map(|commit| commit.oid) else {
+                    return Ok(());
+                };
+                self.mode = AppMode::Normal;
+                self.jump_to_commit(oid)?;
+            }
+            Action::Cancel => self.mode = AppMode::Normal,
             _ => {}
         }
+        Ok(())
     }
 
     fn handle_error_action(&mut self, action: Action) {
@@ -1381,6 +3424,9 @@ impl App {
             Action::CommitDialog => {
                 self.open_commit_dialog();
             }
+            Action::CycleWhitespace => {
+                self.cycle_whitespace()?;
+            }
             Action::Cancel | Action::Quit => {
                 self.return_to_normal();
             }
@@ -1500,6 +3546,37 @@ impl App {
                     }
                 }
             }
+            Action::ToggleWordDiff => {
+                self.word_diff = !self.word_diff;
+                if let AppMode::FileDiff {
+                    content,
+                    rendered_lines,
+                    hunk_positions,
+                    max_line_width,
+                    ..
+                } = &mut self.mode
+                {
+                    // Line count is unchanged, so the scroll position stays valid
+                    let (lines, hunks) = build_highlighted_lines(
+                        content,
+                        self.word_diff,
+                        self.config.diff.syntax_highlight,
+                    );
+                    *max_line_width = lines.iter().map(|l| l.width()).max().unwrap_or(0);
+                    *rendered_lines = lines;
+                    *hunk_positions = hunks;
+                }
+                let state = if self.word_diff { "on" } else { "off" };
+                self.set_message(format!("Word diff {state}"));
+            }
+            Action::CycleWhitespace => {
+                self.cycle_whitespace()?;
+            }
+            Action::BlameLine => {
+                if let Err(e) = self.blame_jump() {
+                    self.set_message(format!("Cannot blame: {e}"));
+                }
+            }
             Action::NextFile => {
                 let file_list_snapshot = if let AppMode::FileDiff { file_list, .. } = &self.mode {
                     file_list.clone()
@@ -1554,22 +3631,66 @@ impl App {
         Ok(())
     }
 
-    fn enter_file_diff(
-        &mut self,
-        file_index: usize,
-        file_list: Vec<FileDiffInfo>,
-        file_path: &std::path::Path,
-    ) -> Result<()> {
-        use crate::ui::file_diff_view::build_highlighted_lines;
-
-        // NOTE: Runs synchronously on the UI thread. For very large diffs (e.g. generated
-        // files, large refactors) this may briefly block input. If this becomes a problem,
-        // consider moving to a background task with a loading state, similar to commit diff summaries.
-        let started = Instant::now();
-        let content = self.load_file_diff_content(file_path)?;
-        let (rendered_lines, hunk_positions) = build_highlighted_lines(&content);
-        self.perf.record("open_file_diff", started.elapsed());
-        let total_lines = rendered_lines.len();
+    /// Leave the diff view for the commit that last changed the first context
+    /// or removed line in view, blamed on the diff's old side (the parent,
+    /// the range base or HEAD)
+    fn blame_jump(&mut self) -> Result<()> {
+        let AppMode::FileDiff {
+            content,
+            scroll_offset,
+            ..
+        } = &self.mode
+        else {
+            return Ok(());
+        };
+        let Some(line) = blame_line_from(content, *scroll_offset) else {
+            bail!("no unchanged or removed line in view");
+        };
+        // The old side may hold the file under its name before a rename
+        let path = content.old_path.clone();
+        let old_side = match self.current_diff_target() {
+            Some(DiffTarget::Commit(oid)) => self.repo.repo.find_commit(oid)?.parent_id(0).ok(),
+            Some(DiffTarget::Range { base, .. }) => Some(base),
+            Some(DiffTarget::Uncommitted) | None => self.repo.head_oid(),
+        };
+        let Some(old_side) = old_side else {
+            bail!("the commit has no parent");
+        };
+        let oid = self.repo.blame_line(old_side, &path, line)?;
+
+        self.return_to_normal();
+        self.focused_pane = FocusedPane::Graph;
+        self.jump_to_commit(oid)?;
+        if self
+            .selected_commit_node()
+            .and_then(|n| n.commit.as_ref())
+            .map(|c| c.oid)
+            == Some(oid)
+        {
+            self.set_message(format!(
+                "{}:{line} last changed in {}",
+                path.display(),
+                &oid.to_string()[..7]
+            ));
+        }
+        Ok(())
+    }
+
+    fn enter_file_diff(
+        &mut self,
+        file_index: usize,
+        file_list: Vec<FileDiffInfo>,
+        file_path: &std::path::Path,
+    ) -> Result<()> {
+        // NOTE: Runs synchronously on the UI thread. For very large diffs (e.g. generated
+        // files, large refactors) this may briefly block input. If this becomes a problem,
+        // consider moving to a background task with a loading state, similar to commit diff summaries.
+        let started = Instant::now();
+        let content = self.load_file_diff_content(file_path)?;
+        let (rendered_lines, hunk_positions) =
+            build_highlighted_lines(&content, self.word_diff, self.config.diff.syntax_highlight);
+        self.perf.record("open_file_diff", started.elapsed());
+        let total_lines = rendered_lines.len();
         let max_line_width = rendered_lines.iter().map(|l| l.width()).max().unwrap_or(0);
 
         self.mode = AppMode::FileDiff {
@@ -1589,10 +3710,13 @@ impl App {
     fn load_file_diff_content(&self, file_path: &std::path::Path) -> Result<FileDiffContent> {
         match self.current_diff_target() {
             Some(DiffTarget::Commit(oid)) => {
-                FileDiffContent::from_commit(&self.repo.repo, oid, file_path)
+                FileDiffContent::from_commit(&self.repo.repo, oid, file_path, self.whitespace)
+            }
+            Some(DiffTarget::Range { base, head }) => {
+                FileDiffContent::from_range(&self.repo.repo, base, head, file_path, self.whitespace)
             }
             Some(DiffTarget::Uncommitted) | None => {
-                FileDiffContent::from_working_tree(&self.repo.repo, file_path)
+                FileDiffContent::from_working_tree(&self.repo.repo, file_path, self.whitespace)
             }
         }
     }
@@ -1621,6 +3745,12 @@ impl App {
             return;
         }
 
+        self.replace_open_file_list(new_files);
+    }
+
+    /// Swap in a recomputed file list for FileSelect / FileDiff, keeping the
+    /// cursor on the same file where possible
+    fn replace_open_file_list(&mut self, new_files: Vec<FileDiffInfo>) {
         match &mut self.mode {
             AppMode::FileSelect {
                 selected_index,
@@ -1650,8 +3780,72 @@ impl App {
         }
     }
 
+    /// Switch to the next whitespace mode and recompute the diff being
+    /// viewed with it (stats, file list and the open file's hunks)
+    fn cycle_whitespace(&mut self) -> Result<()> {
+        self.whitespace = self.whitespace.next();
+        self.set_message(format!(
+            "Diff: {}",
+            self.whitespace
+                .label()
+                .unwrap_or("showing whitespace changes")
+        ));
+        // Every cached diff was computed with the previous mode
+        self.clear_all_diff_caches();
+        match self.current_diff_target() {
+            Some(DiffTarget::Commit(oid)) => {
+                let diff = CommitDiffInfo::from_commit_with_whitespace(
+                    &self.repo.repo,
+                    oid,
+                    self.whitespace,
+                    self.config.diff.max_files,
+                )?;
+                self.diff_cache.insert(oid, Some(diff));
+            }
+            Some(DiffTarget::Range { base, head }) => {
+                let diff = CommitDiffInfo::from_range_with_whitespace(
+                    &self.repo.repo,
+                    base,
+                    head,
+                    self.whitespace,
+                    self.config.diff.max_files,
+                )?;
+                if let Some(range) = &mut self.range_diff {
+                    range.diff = diff;
+                }
+            }
+            // Reloaded in the background; the open file list follows via
+            // sync_file_list_with_uncommitted_diff
+            Some(DiffTarget::Uncommitted) | None => {}
+        }
+        if let Some(files) = self.cached_diff().map(|diff| diff.files.clone()) {
+            if !files.is_empty() {
+                self.replace_open_file_list(files);
+            }
+        }
+
+        if let AppMode::FileDiff {
+            file_index,
+            file_list,
+            ..
+        } = &self.mode
+        {
+            let (file_index, file_list) = (*file_index, file_list.clone());
+            if let Some(path) = file_list.get(file_index).map(|file| file.path.clone()) {
+                self.enter_file_diff(file_index, file_list, &path)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Active whitespace handling for diffs
+    pub fn whitespace(&self) -> WhitespaceMode {
+        self.whitespace
+    }
+
     fn return_to_normal(&mut self) {
         self.mode = AppMode::Normal;
+        self.range_diff = None;
         if self.pending_refresh {
             self.pending_refresh = false;
             if let Err(e) = self.refresh(true) {
@@ -1674,36 +3868,220 @@ impl App {
         match action {
             Action::Confirm => {
                 match input_action {
-                    InputAction::CreateBranch => {
-                        if !input.is_empty() {
-                            if let Some(node) = self.selected_commit_node() {
-                                if let Some(commit) = &node.commit {
-                                    create_branch(&self.repo.repo, &input, commit.oid)?;
-                                    self.refresh(true)?;
-                                }
+                    InputAction::CreateBranch { switch } => {
+                        let from = self
+                            .selected_commit_node()
+                            .and_then(|node| node.commit.as_ref())
+                            .map(|commit| commit.oid);
+                        if let (false, Some(from)) = (input.is_empty(), from) {
+                            create_branch(&self.repo.repo, &input, from)?;
+                            let switched = switch.then(|| checkout_branch(&self.repo.repo, &input));
+                            if switch {
+                                self.refresh(true)?;
+                            } else {
+                                self.refresh_refs()?;
+                            }
+                            if let Some(Err(e)) = switched {
+                                self.show_error(format!(
+                                    "Created '{input}' but cannot switch: {e}"
+                                ));
+                                return Ok(());
+                            }
+                        }
+                    }
+                    InputAction::CreateTag { annotated } => {
+                        let name = input.trim().to_string();
+                        if name.is_empty() {
+                            self.set_message("Tag name is empty");
+                            return Ok(());
+                        }
+                        // The tagger needs an identity; lightweight tags don't
+                        if annotated {
+                            if !self.ask_for_missing_identity(IdentityResume::TagMessage {
+                                name: name.clone(),
+                            }) {
+                                self.ask_tag_message(name);
                             }
+                            return Ok(());
+                        }
+                        self.tag_selected_commit(&name, None)?;
+                    }
+                    InputAction::TagMessage { name } => {
+                        let message = input.trim().to_string();
+                        if message.is_empty() {
+                            self.set_message("Tag message is empty");
+                            return Ok(());
                         }
+                        self.tag_selected_commit(&name, Some(&message))?;
                     }
                     InputAction::Search => {
                         // Jump to selected result and exit search mode
                         self.jump_to_search_result();
                     }
+                    InputAction::GotoBranch => {
+                        let name = self
+                            .search_state
+                            .selected_result()
+                            .and_then(|result| self.search_state.candidates.get(result.branch_idx))
+                            .map(|(_, name)| name.clone());
+                        self.search_state = SearchState::default();
+                        self.mode = AppMode::Normal;
+                        if let Some(name) = name {
+                            self.goto_branch_tip(&name)?;
+                            // Only check out once the tip is actually selected
+                            if self.config.branches.enter == BranchEnter::Checkout
+                                && self.selected_branch().map(|b| b.name.as_str())
+                                    == Some(name.as_str())
+                            {
+                                self.handle_action(Action::Checkout)?;
+                            }
+                        }
+                        return Ok(());
+                    }
+                    InputAction::CommandPalette => {
+                        let command = self
+                            .search_state
+                            .selected_result()
+                            .and_then(|result| self.search_state.candidates.get(result.branch_idx))
+                            .and_then(|(idx, _)| BINDINGS.get(*idx));
+                        self.search_state = SearchState::default();
+                        self.mode = AppMode::Normal;
+                        if let Some(command) = command {
+                            self.handle_action(command.action.clone())?;
+                        }
+                        return Ok(());
+                    }
+                    InputAction::PickRemote(op) => {
+                        let remote = self
+                            .search_state
+                            .selected_result()
+                            .and_then(|result| self.search_state.candidates.get(result.branch_idx))
+                            .map(|(_, name)| name.clone());
+                        self.search_state = SearchState::default();
+                        self.mode = AppMode::Normal;
+                        if let Some(remote) = remote {
+                            self.run_remote_op(op, remote);
+                        }
+                        return Ok(());
+                    }
                     InputAction::CommitMessage => {
                         let message = input.trim().to_string();
                         if message.is_empty() {
                             self.set_message("Commit message is empty");
                             return Ok(());
                         }
-                        let oid = create_commit(&self.repo.repo, &message)?;
-                        self.set_message(format!("Committed {}", &oid.to_string()[..7]));
+                        let written = create_commit(
+                            &self.repo.repo,
+                            &message,
+                            self.session_identity.as_ref(),
+                        )?;
+                        let short = &written.oid.to_string()[..7];
+                        match written.unsigned_reason {
+                            Some(reason) => {
+                                self.set_message(format!("Committed {short} ({reason})"))
+                            }
+                            None => self.set_message(format!("Committed {short}")),
+                        }
                         self.refresh(true)?;
                     }
+                    InputAction::PathScope => {
+                        let paths = input.split_whitespace().map(String::from).collect();
+                        self.set_path_scope(paths)?;
+                    }
+                    InputAction::SessionAuthor => {
+                        if input.trim().is_empty() {
+                            self.session_identity = None;
+                            self.set_message("Committing as user.name / user.email again");
+                            return Ok(());
+                        }
+                        match Identity::parse(&input) {
+                            Ok(identity) => {
+                                self.set_message(format!("Committing as {identity} this session"));
+                                self.session_identity = Some(identity);
+                            }
+                            Err(e) => {
+                                self.show_error(format!("{e:#}"));
+                                return Ok(());
+                            }
+                        }
+                    }
+                    InputAction::IdentityName { resume } => {
+                        let name = input.trim().to_string();
+                        if name.is_empty() {
+                            self.set_message("Name is empty");
+                            return Ok(());
+                        }
+                        let email = self.config_value("user.email");
+                        self.mode = AppMode::Input {
+                            title: "user.email (this repository)".to_string(),
+                            input: email,
+                            action: InputAction::IdentityEmail { name, resume },
+                        };
+                        return Ok(());
+                    }
+                    InputAction::IdentityEmail { name, resume } => {
+                        let email = input.trim().to_string();
+                        if email.is_empty() {
+                            self.set_message("Email is empty");
+                            return Ok(());
+                        }
+                        set_identity(&self.repo.repo, &name, &email)?;
+                        self.set_message(format!("Identity set to {name} <{email}>"));
+                        self.mode = AppMode::Normal;
+                        match resume {
+                            Some(IdentityResume::Action(action)) => self.handle_action(action)?,
+                            Some(IdentityResume::TagMessage { name }) => self.ask_tag_message(name),
+                            None => {}
+                        }
+                        return Ok(());
+                    }
+                    InputAction::HelpSearch => {
+                        self.help_query = input.trim().to_string();
+                        self.help_match = None;
+                        self.mode = AppMode::Help;
+                        self.step_help_match(true);
+                        return Ok(());
+                    }
+                    InputAction::GotoRevision => {
+                        let rev = input.trim();
+                        if rev.is_empty() {
+                            self.mode = AppMode::Normal;
+                            return Ok(());
+                        }
+                        // Show the resolved commit so the user can check it
+                        // before the cursor moves
+                        match self.repo.resolve_commit(rev) {
+                            Ok(oid) => {
+                                let summary: String = self
+                                    .repo
+                                    .repo
+                                    .find_commit(oid)
+                                    .ok()
+                                    .and_then(|c| c.summary().map(|s| truncate_to_width(s, 40)))
+                                    .unwrap_or_default();
+                                self.mode = AppMode::Confirm {
+                                    message: format!(
+                                        "{rev} = {} \"{summary}\". Jump?",
+                                        &oid.to_string()[..7]
+                                    ),
+                                    action: ConfirmAction::GotoCommit(oid),
+                                    list: None,
+                                };
+                            }
+                            Err(e) => self.show_error(format!("{e:#}")),
+                        }
+                        return Ok(());
+                    }
                 }
                 // Clear search state after confirming
                 self.search_state = SearchState::default();
                 self.mode = AppMode::Normal;
             }
             Action::Cancel => {
+                if matches!(input_action, InputAction::HelpSearch) {
+                    self.mode = AppMode::Help;
+                    return Ok(());
+                }
                 // Restore position when canceling search
                 if matches!(input_action, InputAction::Search) {
                     self.restore_search_position();
@@ -1711,11 +4089,30 @@ impl App {
                 self.search_state = SearchState::default();
                 self.mode = AppMode::Normal;
             }
+            Action::InputToggle => match input_action {
+                InputAction::CreateBranch { switch } => {
+                    self.mode = AppMode::Input {
+                        title: create_branch_title(!switch),
+                        input,
+                        action: InputAction::CreateBranch { switch: !switch },
+                    };
+                }
+                InputAction::CreateTag { annotated } => {
+                    self.mode = AppMode::Input {
+                        title: create_tag_title(!annotated),
+                        input,
+                        action: InputAction::CreateTag {
+                            annotated: !annotated,
+                        },
+                    };
+                }
+                _ => {}
+            },
             Action::InputChar(c) => {
                 input.push(c);
 
                 // Incremental fuzzy search with live preview
-                if matches!(input_action, InputAction::Search) {
+                if input_action.is_picker() {
                     self.update_fuzzy_search(&input);
                     self.jump_to_search_result();
                 }
@@ -1729,6 +4126,10 @@ impl App {
             Action::InputBackspace => {
                 // Empty input + backspace = cancel (like Esc)
                 if input.is_empty() {
+                    if matches!(input_action, InputAction::HelpSearch) {
+                        self.mode = AppMode::Help;
+                        return Ok(());
+                    }
                     if matches!(input_action, InputAction::Search) {
                         self.restore_search_position();
                     }
@@ -1740,7 +4141,7 @@ impl App {
                 input.pop();
 
                 // Update fuzzy search on backspace with live preview
-                if matches!(input_action, InputAction::Search) {
+                if input_action.is_picker() {
                     self.update_fuzzy_search(&input);
                     self.jump_to_search_result();
                 }
@@ -1784,20 +4185,100 @@ impl App {
 
         match action {
             Action::Confirm => {
+                self.confirm_scroll = 0;
                 match confirm_action {
                     ConfirmAction::DeleteBranch(name) => {
                         delete_branch(&self.repo.repo, &name)?;
+                        self.refresh_refs()?;
+                        self.mode = AppMode::Normal;
+                        return Ok(());
+                    }
+                    ConfirmAction::CleanupBranches(names) => {
+                        self.mode = AppMode::Normal;
+                        self.cleanup_branches(&names)?;
+                        return Ok(());
                     }
                     ConfirmAction::Merge(name) => {
-                        merge_branch(&self.repo.repo, &name)?;
+                        if let Some(reason) = merge_branch(
+                            &self.repo.repo,
+                            &name,
+                            &self.config.messages.merge,
+                            self.session_identity.as_ref(),
+                        )? {
+                            self.set_message(format!("Merged {name} ({reason})"));
+                        }
                     }
                     ConfirmAction::Rebase(name) => {
-                        rebase_branch(&self.repo.repo, &name)?;
+                        rebase_branch(&self.repo.repo, &name, self.session_identity.as_ref())?;
+                    }
+                    ConfirmAction::CherryPick(oid) => {
+                        let written = cherry_pick_commit(
+                            &self.repo.repo,
+                            oid,
+                            self.session_identity.as_ref(),
+                        )?;
+                        let short = &written.oid.to_string()[..7];
+                        match written.unsigned_reason {
+                            Some(reason) => {
+                                self.set_message(format!("Cherry-picked as {short} ({reason})"))
+                            }
+                            None => self.set_message(format!("Cherry-picked as {short}")),
+                        }
+                    }
+                    ConfirmAction::Revert(oid) => {
+                        let written = revert_commit(
+                            &self.repo.repo,
+                            oid,
+                            &self.config.messages.revert,
+                            self.session_identity.as_ref(),
+                        )?;
+                        let short = &written.oid.to_string()[..7];
+                        match written.unsigned_reason {
+                            Some(reason) => {
+                                self.set_message(format!("Reverted as {short} ({reason})"))
+                            }
+                            None => self.set_message(format!("Reverted as {short}")),
+                        }
                     }
-                    ConfirmAction::Push(branch) => {
+                    ConfirmAction::Push { remote, branch } => {
                         // Runs in the background; no refresh needed yet
-                        self.start_push(branch);
+                        self.start_push(remote, branch, PushMode::Normal);
+                        self.mode = AppMode::Normal;
+                        return Ok(());
+                    }
+                    ConfirmAction::ForcePush {
+                        remote,
+                        branch,
+                        expected,
+                    } => {
+                        self.start_push(remote, branch, PushMode::ForceWithLease { expected });
+                        self.mode = AppMode::Normal;
+                        return Ok(());
+                    }
+                    ConfirmAction::GotoCommit(oid) => {
                         self.mode = AppMode::Normal;
+                        self.jump_to_commit(oid)?;
+                        return Ok(());
+                    }
+                    ConfirmAction::EditIdentity(resume) => {
+                        let name = self.config_value("user.name");
+                        self.mode = AppMode::Input {
+                            title: "user.name (this repository)".to_string(),
+                            input: name,
+                            action: InputAction::IdentityName { resume },
+                        };
+                        return Ok(());
+                    }
+                    ConfirmAction::OpenRange => {
+                        let file_list = self
+                            .range_diff
+                            .as_ref()
+                            .map(|range| range.diff.files.clone())
+                            .unwrap_or_default();
+                        self.mode = AppMode::FileSelect {
+                            selected_index: 0,
+                            file_list,
+                        };
                         return Ok(());
                     }
                 }
@@ -1805,7 +4286,17 @@ impl App {
                 self.mode = AppMode::Normal;
             }
             Action::Cancel => {
+                self.confirm_scroll = 0;
                 self.mode = AppMode::Normal;
+                self.range_diff = None;
+            }
+            Action::ScrollDown => self.confirm_scroll = self.confirm_scroll.saturating_add(1),
+            Action::ScrollUp => self.confirm_scroll = self.confirm_scroll.saturating_sub(1),
+            Action::ScrollPageDown => {
+                self.confirm_scroll = self.confirm_scroll.saturating_add(10);
+            }
+            Action::ScrollPageUp => {
+                self.confirm_scroll = self.confirm_scroll.saturating_sub(10);
             }
             _ => {}
         }
@@ -1828,7 +4319,7 @@ impl App {
     }
 
     pub fn move_selection(&mut self, delta: i32) {
-        let max = self.graph_layout.nodes.len().saturating_sub(1);
+        let max = self.graph_row_count().saturating_sub(1);
         let current = self.graph_list_state.selected().unwrap_or(0);
         let new = (current as i32 + delta).clamp(0, max as i32) as usize;
         self.graph_list_state.select(Some(new));
@@ -1837,7 +4328,7 @@ impl App {
 
     /// Select a graph node by absolute index (mouse click)
     pub fn select_node(&mut self, idx: usize) {
-        if idx >= self.graph_layout.nodes.len() {
+        if idx >= self.graph_row_count() {
             return;
         }
         self.graph_list_state.select(Some(idx));
@@ -1957,6 +4448,62 @@ impl App {
         };
     }
 
+    /// Tag the selected commit and show the new tag in the graph
+    fn tag_selected_commit(&mut self, name: &str, message: Option<&str>) -> Result<()> {
+        let Some(oid) = self
+            .selected_commit_node()
+            .and_then(|node| node.commit.as_ref())
+            .map(|commit| commit.oid)
+        else {
+            return Ok(());
+        };
+        create_tag(
+            &self.repo.repo,
+            name,
+            oid,
+            message,
+            self.session_identity.as_ref(),
+        )?;
+        self.refresh_refs()?;
+        self.set_message(format!("Tagged {} as {name}", &oid.to_string()[..7]));
+        Ok(())
+    }
+
+    /// Second step of an annotated tag: its message
+    fn ask_tag_message(&mut self, name: String) {
+        self.mode = AppMode::Input {
+            title: format!("Message for tag '{name}'"),
+            input: String::new(),
+            action: InputAction::TagMessage { name },
+        };
+    }
+
+    /// When `repo.signature()` fails, ask for `user.name` / `user.email`
+    /// instead of letting git2's error surface mid-operation, and continue
+    /// with `resume` once they are saved. Returns whether the prompt was shown
+    fn ask_for_missing_identity(&mut self, resume: IdentityResume) -> bool {
+        if self.session_identity.is_some() || self.repo.repo.signature().is_ok() {
+            return false;
+        }
+        self.mode = AppMode::Confirm {
+            message: "No user.name / user.email configured.\n\
+                      Set them for this repository?"
+                .to_string(),
+            action: ConfirmAction::EditIdentity(Some(resume)),
+            list: None,
+        };
+        true
+    }
+
+    /// A repository config value, empty when unset
+    fn config_value(&self, key: &str) -> String {
+        self.repo
+            .repo
+            .config()
+            .and_then(|config| config.get_string(key))
+            .unwrap_or_default()
+    }
+
     /// Sync branch selection to the first branch of the given node
     fn sync_branch_selection_to_node(&mut self, node_idx: usize) {
         self.selected_branch_position = self
@@ -1975,6 +4522,8 @@ impl App {
             Some(pos) => {
                 if pos + 1 < self.branch_positions.len() {
                     pos + 1
+                } else if self.config.ui.wrap_jumps {
+                    0
                 } else {
                     return; // Already at the last branch
                 }
@@ -1998,6 +4547,8 @@ impl App {
             Some(pos) => {
                 if pos > 0 {
                     pos - 1
+                } else if self.config.ui.wrap_jumps {
+                    self.branch_positions.len() - 1
                 } else {
                     return; // Already at the first branch
                 }
@@ -2011,6 +4562,69 @@ impl App {
         }
     }
 
+    /// Move to the nearest merge commit below (`forward`) or above the
+    /// selected row
+    fn move_to_merge(&mut self, forward: bool) {
+        self.move_to_matching(forward, "merge commit", |node| {
+            node.commit
+                .as_ref()
+                .is_some_and(|c| c.parent_oids.len() > 1)
+        });
+    }
+
+    /// Move to the nearest tagged commit below (`forward`) or above the
+    /// selected row and name its tags
+    fn move_to_tag(&mut self, forward: bool) {
+        let tagged: HashSet<Oid> = self.tags.keys().copied().collect();
+        let found = self.move_to_matching(forward, "tag", |node| {
+            node.commit
+                .as_ref()
+                .is_some_and(|c| tagged.contains(&c.oid))
+        });
+        if let Some(names) = found
+            .and_then(|idx| self.graph_layout.nodes[idx].commit.as_ref())
+            .and_then(|c| self.tags.get(&c.oid))
+        {
+            self.set_message(format!("Tag {}", names.join(", ")));
+        }
+    }
+
+    /// Select the nearest node matching `predicate` in the given direction,
+    /// wrapping around when `ui.wrap_jumps` is set
+    fn move_to_matching(
+        &mut self,
+        forward: bool,
+        what: &str,
+        predicate: impl Fn(&GraphNode) -> bool,
+    ) -> Option<usize> {
+        let len = self.graph_layout.nodes.len();
+        let current = self.graph_list_state.selected().unwrap_or(0).min(len);
+        let is_match = |idx: &usize| predicate(&self.graph_layout.nodes[*idx]);
+        let target = if forward {
+            (current + 1..len).find(is_match).or_else(|| {
+                self.config
+                    .ui
+                    .wrap_jumps
+                    .then(|| (0..current).find(is_match))?
+            })
+        } else {
+            (0..current).rev().find(is_match).or_else(|| {
+                self.config
+                    .ui
+                    .wrap_jumps
+                    .then(|| (current + 1..len).rev().find(is_match))?
+            })
+        };
+        match target {
+            Some(idx) => self.select_node(idx),
+            None => {
+                let direction = if forward { "below" } else { "above" };
+                self.set_message(format!("No {what} {direction}"));
+            }
+        }
+        target
+    }
+
     /// Move to an adjacent branch within the same commit
     fn move_branch_within_node(&mut self, delta: isize) {
         let Some(pos) = self.selected_branch_position else {
@@ -2079,9 +4693,24 @@ impl App {
     }
 
     fn do_checkout(&mut self) -> Result<()> {
+        if self.is_load_more_selected() {
+            return self.load_more();
+        }
         if let Some(branch) = self.selected_branch() {
             let branch_name = branch.name.clone();
-            if branch_name.starts_with("origin/") {
+            let is_remote = branch.is_remote;
+            // Checking out <remote>/x updates and checks out the local x
+            let local_name = split_remote_branch(&self.repo.repo, &branch_name)
+                .filter(|_| is_remote)
+                .map_or(branch_name.as_str(), |(_, local)| local);
+            if let Some(path) = self.worktree_branches.get(local_name) {
+                self.show_error(format!(
+                    "'{local_name}' is checked out in the worktree at {}; switch there instead",
+                    path.display()
+                ));
+                return Ok(());
+            }
+            if is_remote {
                 // For remote branches, create a local branch and check it out
                 checkout_remote_branch(&self.repo.repo, &branch_name)?;
             } else {
@@ -2097,16 +4726,118 @@ impl App {
         Ok(())
     }
 
+    /// Confirm a force-push leased on our remote-tracking ref: git refuses
+    /// if the remote moved since we last fetched
+    fn force_push_confirmation(&self, remote: String, branch: String) -> AppMode {
+        let tracking = format!("{remote}/{branch}");
+        let expected = self
+            .repo
+            .repo
+            .find_reference(&format!("refs/remotes/{tracking}"))
+            .ok()
+            .and_then(|reference| reference.target());
+        let message = match expected {
+            Some(oid) => format!(
+                "Force-push '{branch}' to {remote} (with lease)?\n\
+                 ⚠ Replaces {tracking} ({}) — refused if it has moved since the last fetch",
+                &oid.to_string()[..7]
+            ),
+            None => format!(
+                "Force-push '{branch}' to {remote} (with lease)?\n\
+                 {tracking} is not known yet; refused if it exists on {remote}"
+            ),
+        };
+        AppMode::Confirm {
+            message,
+            action: ConfirmAction::ForcePush {
+                remote,
+                branch,
+                expected,
+            },
+            list: None,
+        }
+    }
+
+    /// Warning for confirmations of operations that rewrite HEAD's history
+    /// down to `base`, when some of those commits are already pushed
+    fn rewrite_warning(&self, base: Oid) -> Option<String> {
+        let (upstream, count) = self.repo.pushed_commits_since(base)?;
+        Some(format!(
+            "⚠ {count} of these commit(s) are already on {upstream} — rewriting will require a force-push"
+        ))
+    }
+
+    /// Delete `names`, re-checking each is still merged into the main
+    /// branch (and not checked out) right before deleting it
+    fn cleanup_branches(&mut self, names: &[String]) -> Result<()> {
+        let branches = self.repo.get_branches(false)?;
+        let merged = self
+            .main_branch
+            .as_deref()
+            .map(|main| BranchInfo::merged_into(&self.repo.repo, &branches, main))
+            .unwrap_or_default();
+
+        let mut deleted = 0;
+        let mut failed = Vec::new();
+        for name in names {
+            let result = if merged.contains(name) {
+                delete_branch(&self.repo.repo, name)
+            } else {
+                Err(anyhow!("no longer merged"))
+            };
+            match result {
+                Ok(()) => deleted += 1,
+                Err(e) => failed.push(format!("{name} ({e})")),
+            }
+        }
+        tracing::info!(deleted, failed = failed.len(), "cleaned up merged branches");
+
+        self.refresh_refs()?;
+        if failed.is_empty() {
+            self.set_message(format!("Deleted {deleted} merged branch(es)"));
+        } else {
+            self.show_error(format!(
+                "Deleted {deleted} of {} branches; failed: {}",
+                names.len(),
+                failed.join(", ")
+            ));
+        }
+        Ok(())
+    }
+
+    /// Branches in display order, the main branch and the local branches
+    /// merged into it (left out of the list when `merged = "hide"`)
+    fn load_branches(
+        repo: &GitRepository,
+        show_remote_branches: bool,
+        config: &BranchesConfig,
+    ) -> Result<(Vec<BranchInfo>, Option<String>, HashSet<String>)> {
+        let mut branches = repo.get_branches(show_remote_branches)?;
+        BranchInfo::sort(&repo.repo, &mut branches, config.sort);
+        let main = BranchInfo::main_branch(&branches, &repo.repo, config.main.as_deref());
+        let merged = main
+            .as_deref()
+            .map(|main| BranchInfo::merged_into(&repo.repo, &branches, main))
+            .unwrap_or_default();
+        if config.merged == MergedBranches::Hide {
+            branches.retain(|b| b.is_remote || !merged.contains(&b.name));
+        }
+        Ok((branches, main, merged))
+    }
+
     /// Build a flat list of (node_index, branch_name) for all branches
     /// Excludes remote branches that have a matching local branch (e.g., origin/main when main exists)
     /// Order matches optimize_branch_display: local branches first, then remote-only branches
-    fn build_branch_positions(graph_layout: &GraphLayout) -> Vec<(usize, String)> {
+    fn build_branch_positions(
+        graph_layout: &GraphLayout,
+        remotes: &[String],
+    ) -> Vec<(usize, String)> {
         graph_layout
             .nodes
             .iter()
             .enumerate()
             .flat_map(|(node_idx, node)| {
-                filter_remote_duplicates(&node.branch_names)
+                filter_remote_duplicates(&node.labels, remotes)
                     .into_iter()
                     .map(move |name| (node_idx, name.to_string()))
             })
@@ -2119,12 +4850,24 @@ mod tests {
     use std::fs;
     use std::path::{Path, PathBuf};
 
-    use chrono::Local;
     use git2::{Oid, Repository, Signature};
     use tempfile::TempDir;
 
     use super::*;
+    use crate::config::{CommitsConfig, DiffConfig};
     use crate::git::graph::{CellType, GraphNode};
+    use crate::git::{build_graph_default, FileChangeKind};
+
+    /// Everything available, so fixtures don't run `git` and `ssh` to find out
+    const CAPABILITIES: GitCapabilities = GitCapabilities {
+        libgit2_version: (1, 8, 1),
+        vendored: true,
+        https: true,
+        ssh: true,
+        threads: true,
+        git_cli: true,
+        ssh_cli: true,
+    };
 
     fn init_repo() -> (TempDir, GitRepository) {
         let tempdir = tempfile::tempdir().unwrap();
@@ -2170,7 +4913,9 @@ mod tests {
     fn make_app_from_repo(repo: GitRepository) -> App {
         let now = Instant::now();
         let show_remote_branches = true;
-        let commits = repo.get_commits(500, show_remote_branches).unwrap();
+        let commits = repo
+            .get_commits(CommitsConfig::default().initial_batch, show_remote_branches)
+            .unwrap();
         let branches = repo.get_branches(show_remote_branches).unwrap();
         let (working_tree_status, stage_states, initial_message) =
             App::working_tree_snapshot(&repo);
@@ -2179,12 +4924,14 @@ mod tests {
             .as_ref()
             .map(|s| s.accurate_file_count());
         let head_commit_oid = repo.head_oid();
-        let graph_layout = build_graph(&commits, &branches, uncommitted_count, head_commit_oid);
+        let graph_layout =
+            build_graph_default(&commits, &branches, uncommitted_count, head_commit_oid);
 
         let mut graph_list_state = ListState::default();
         graph_list_state.select(Some(0));
 
-        let branch_positions = App::build_branch_positions(&graph_layout);
+        let remote_names = repo.remote_names();
+        let branch_positions = App::build_branch_positions(&graph_layout, &remote_names);
         let has_uncommitted_node = graph_layout
             .nodes
             .first()
@@ -2200,10 +4947,45 @@ mod tests {
             repo,
             repo_path: String::new(),
             head_name: None,
+            repo_summary: RepoSummary::default(),
+            capabilities: CAPABILITIES,
+            remote_unsupported: None,
+            has_default_remote: false,
+            remote_names,
             commits,
             branches,
+            main_branch: None,
+            merged_branches: HashSet::new(),
             graph_layout,
             show_remote_branches,
+            commit_limit: CommitsConfig::default().initial_batch,
+            unloaded_history: None,
+            more_history: false,
+            load_ahead_failed: false,
+            replaced_oids: HashSet::new(),
+            tags: HashMap::new(),
+            worktree_branches: HashMap::new(),
+            show_minimap: false,
+            show_graph_debug: false,
+            show_rewritten_marker: false,
+            show_parent_badge: false,
+            show_date_headers: false,
+            date_headers: DateHeaders::default(),
+            age_range: None,
+            show_age_heat: false,
+            full_hashes: false,
+            show_timers: false,
+            show_raw_commit: false,
+            read_only: false,
+            show_full_message: false,
+            focus_mode: false,
+            show_detail: true,
+            state_path: None,
+            graph_orientation: GraphOrientation::default(),
+            show_graph: true,
+            session_identity: None,
+            word_diff: true,
+            whitespace: WhitespaceMode::default(),
             graph_list_state,
             focused_pane: FocusedPane::default(),
             detail_scroll: 0,
@@ -2212,34 +4994,45 @@ mod tests {
             layout: LayoutMap::default(),
             files_pane_scroll: 0,
             help_scroll: 0,
+            confirm_scroll: 0,
+            help_query: String::new(),
+            help_match: None,
             last_click: None,
             status_hints: Vec::new(),
             perf: PerfStats::default(),
+            last_action: None,
             branch_positions,
             selected_branch_position,
             search_state: SearchState::default(),
             working_tree_status,
             stage_states,
-            diff_cache: None,
-            diff_cache_oid: None,
+            diff_cache: DiffCache::new(DiffConfig::default().cache_size),
             diff_loading_oid: None,
             diff_receiver: None,
+            diff_worker: None,
             uncommitted_diff_cache: None,
             uncommitted_diff_failed: false,
             uncommitted_diff_loading: false,
             uncommitted_diff_receiver: None,
             uncommitted_cache_key: None,
             selected_diff_target: None,
+            range_diff: None,
+            range_mark: None,
             selected_diff_target_changed_at: now,
+            diff_requested: false,
             should_quit: false,
             pending_refresh: false,
+            refresh_requested: false,
             diff_viewport_height: 40,
             diff_viewport_width: 80,
             message: initial_message,
             message_time: initial_message_time,
             fetch_receiver: None,
             fetch_silent: false,
+            pending_pull: None,
             push_receiver: None,
+            transfer_progress: None,
+            path_scope: PathScope::default(),
             config: Config::default(),
             last_refresh_time: now,
             last_fetch_time: now,
@@ -2252,6 +5045,8 @@ mod tests {
             short_id: oid.to_string()[..7].to_string(),
             author_name: "Test User".to_string(),
             author_email: "test@example.com".to_string(),
+            committer_name: "Test User".to_string(),
+            committer_email: "test@example.com".to_string(),
             timestamp: Local::now(),
             message: "test".to_string(),
             full_message: "test".to_string(),
@@ -2275,13 +5070,48 @@ mod tests {
             repo_path: repo.path.clone(),
             repo,
             head_name: None,
+            repo_summary: RepoSummary::default(),
+            capabilities: CAPABILITIES,
+            remote_unsupported: None,
+            has_default_remote: false,
+            remote_names: Vec::new(),
             commits,
             branches: Vec::new(),
+            main_branch: None,
+            merged_branches: HashSet::new(),
             graph_layout: GraphLayout {
                 nodes: vec![node],
                 max_lane: 0,
             },
             show_remote_branches: true,
+            commit_limit: CommitsConfig::default().initial_batch,
+            unloaded_history: None,
+            more_history: false,
+            load_ahead_failed: false,
+            replaced_oids: HashSet::new(),
+            tags: HashMap::new(),
+            worktree_branches: HashMap::new(),
+            show_minimap: false,
+            show_graph_debug: false,
+            show_rewritten_marker: false,
+            show_parent_badge: false,
+            show_date_headers: false,
+            date_headers: DateHeaders::default(),
+            age_range: None,
+            show_age_heat: false,
+            full_hashes: false,
+            show_timers: false,
+            show_raw_commit: false,
+            read_only: false,
+            show_full_message: false,
+            focus_mode: false,
+            show_detail: true,
+            state_path: None,
+            graph_orientation: GraphOrientation::default(),
+            show_graph: true,
+            session_identity: None,
+            word_diff: true,
+            whitespace: WhitespaceMode::default(),
             graph_list_state,
             focused_pane: FocusedPane::default(),
             detail_scroll: 0,
@@ -2290,34 +5120,45 @@ mod tests {
             layout: LayoutMap::default(),
             files_pane_scroll: 0,
             help_scroll: 0,
+            confirm_scroll: 0,
+            help_query: String::new(),
+            help_match: None,
             last_click: None,
             status_hints: Vec::new(),
             perf: PerfStats::default(),
+            last_action: None,
             branch_positions: Vec::new(),
             selected_branch_position: None,
             search_state: SearchState::default(),
             working_tree_status,
             stage_states: HashMap::new(),
-            diff_cache: None,
-            diff_cache_oid: None,
+            diff_cache: DiffCache::new(DiffConfig::default().cache_size),
             diff_loading_oid: None,
             diff_receiver: None,
+            diff_worker: None,
             uncommitted_diff_cache: None,
             uncommitted_diff_failed: false,
             uncommitted_diff_loading: false,
             uncommitted_diff_receiver: None,
             uncommitted_cache_key: None,
             selected_diff_target: Some(diff_target),
+            range_diff: None,
+            range_mark: None,
             selected_diff_target_changed_at: Instant::now() - DIFF_LOAD_DEBOUNCE,
+            diff_requested: false,
             should_quit: false,
             pending_refresh: false,
+            refresh_requested: false,
             diff_viewport_height: 40,
             diff_viewport_width: 80,
             message: None,
             message_time: None,
             fetch_receiver: None,
             fetch_silent: false,
+            pending_pull: None,
             push_receiver: None,
+            transfer_progress: None,
+            path_scope: PathScope::default(),
             config: Config::default(),
             last_refresh_time: Instant::now(),
             last_fetch_time: Instant::now(),
@@ -2329,8 +5170,10 @@ mod tests {
             commit: Some(make_commit(selected_oid)),
             lane: 0,
             color_index: 0,
-            branch_names: Vec::new(),
+            labels: Vec::new(),
+            tag_names: Vec::new(),
             is_head: false,
+            is_current_tip: false,
             is_uncommitted: false,
             uncommitted_count: None,
             cells: vec![CellType::Commit(0)],
@@ -2345,8 +5188,10 @@ mod tests {
             commit: None,
             lane: 0,
             color_index: 0,
-            branch_names: Vec::new(),
+            labels: Vec::new(),
+            tag_names: Vec::new(),
             is_head: false,
+            is_current_tip: false,
             is_uncommitted: true,
             uncommitted_count: Some(1),
             cells: vec![CellType::Commit(0)],
@@ -2373,53 +5218,156 @@ mod tests {
         let selected_oid = Oid::from_str("1111111111111111111111111111111111111111").unwrap();
         let in_flight_oid = Oid::from_str("2222222222222222222222222222222222222222").unwrap();
         let mut app = make_app(selected_oid, Some(in_flight_oid));
-        app.diff_cache = Some(CommitDiffInfo::default());
-        app.diff_cache_oid = Some(selected_oid);
+        app.diff_cache
+            .insert(selected_oid, Some(CommitDiffInfo::default()));
 
         assert!(!app.is_diff_loading());
     }
 
     #[test]
-    fn failed_commit_diff_load_is_cached_to_avoid_immediate_retry() {
+    fn hidden_detail_pane_skips_diff_loading_until_files_are_opened() {
         let selected_oid = Oid::from_str("1111111111111111111111111111111111111111").unwrap();
-        let mut app = make_app(selected_oid, Some(selected_oid));
-        let (tx, rx) = mpsc::channel();
-        tx.send(DiffResult {
-            oid: selected_oid,
-            diff: Err("boom".to_string()),
-        })
-        .unwrap();
-        app.diff_receiver = Some(rx);
+        let mut app = make_app(selected_oid, None);
+        app.handle_action(Action::ToggleDetail).unwrap();
+        assert!(!app.show_detail());
 
         app.update_diff_cache();
+        assert!(app.diff_loading_oid.is_none());
+
+        app.handle_action(Action::EnterFileSelect).unwrap();
+        assert_eq!(app.message.as_deref(), Some("Loading diff..."));
         app.update_diff_cache();
+        assert_eq!(app.diff_loading_oid, Some(selected_oid));
+    }
 
-        assert!(app.diff_cache.is_none());
-        assert_eq!(app.diff_cache_oid, Some(selected_oid));
-        assert!(app.cached_diff().is_none());
-        assert!(!app.is_diff_loading());
-        assert!(app.diff_loading_oid.is_none());
-        assert!(app.diff_receiver.is_none());
-        assert_eq!(app.message.as_deref(), Some("Failed to load diff: boom"));
+    #[test]
+    fn detail_toggle_is_remembered() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let path = tempdir.path().join("state.toml");
+        let mut app = make_app(Oid::zero(), None);
+        app.state_path = Some(path.clone());
+
+        app.handle_action(Action::ToggleDetail).unwrap();
+        assert_eq!(UiState::load(&path).show_detail, Some(false));
+        app.handle_action(Action::ToggleDetail).unwrap();
+        assert_eq!(UiState::load(&path).show_detail, Some(true));
+        assert_eq!(app.message.as_deref(), Some("Detail pane shown"));
     }
 
     #[test]
-    fn failed_uncommitted_diff_load_is_cached_to_avoid_immediate_retry() {
-        let mut app = make_uncommitted_app();
-        let (tx, rx) = mpsc::channel();
-        let cache_key = app.working_tree_status.clone();
-        tx.send((Err("boom".to_string()), cache_key)).unwrap();
-        app.uncommitted_diff_loading = true;
-        app.uncommitted_diff_receiver = Some(rx);
+    fn file_list_asked_for_in_focus_mode_opens_once_loaded() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(tempdir.path()).unwrap();
+        commit_file(&repo, "a.txt", "a\n", "add a");
 
+        let git_repo = GitRepository::open(tempdir.path()).unwrap();
+        let mut app = make_app_from_repo(git_repo);
+        app.repo_path = tempdir.path().to_string_lossy().into_owned();
+        app.handle_action(Action::ToggleFocusMode).unwrap();
         app.update_diff_cache();
+        app.selected_diff_target_changed_at = Instant::now() - DIFF_LOAD_DEBOUNCE;
         app.update_diff_cache();
+        assert!(!app.has_in_flight_diff());
 
-        assert!(app.uncommitted_diff_cache.is_none());
-        assert!(app.uncommitted_diff_failed);
-        assert!(app.cached_diff().is_none());
-        assert!(!app.is_diff_loading());
-        assert!(!app.uncommitted_diff_loading);
+        app.handle_action(Action::EnterFileSelect).unwrap();
+        for _ in 0..1000 {
+            if matches!(app.mode, AppMode::FileSelect { .. }) {
+                break;
+            }
+            app.update_diff_cache();
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        let AppMode::FileSelect { file_list, .. } = &app.mode else {
+            panic!("expected the file list, got {:?}", app.mode);
+        };
+        assert_eq!(file_list.len(), 1);
+        assert!(!app.diff_requested);
+    }
+
+    #[test]
+    fn one_diff_worker_serves_successive_selections() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(tempdir.path()).unwrap();
+        let first = commit_file(&repo, "a.txt", "a\n", "add a");
+        let second = commit_file(&repo, "b.txt", "b\n", "add b");
+
+        let mut app = make_app_from_repo(GitRepository::open(tempdir.path()).unwrap());
+        app.repo_path = tempdir.path().to_string_lossy().into_owned();
+        let wait_for = |app: &mut App, oid: Oid| {
+            for _ in 0..1000 {
+                app.update_diff_cache();
+                if app.diff_cache.contains(oid) {
+                    return;
+                }
+                std::thread::sleep(Duration::from_millis(5));
+            }
+            panic!("diff of {oid} never arrived");
+        };
+        app.update_diff_cache();
+        app.selected_diff_target_changed_at = Instant::now() - DIFF_LOAD_DEBOUNCE;
+        wait_for(&mut app, second);
+
+        // The next selection goes to the running worker instead of a new one
+        let worker = app.diff_worker.take().unwrap();
+        let (tx, rx) = mpsc::channel();
+        app.diff_worker = Some(tx);
+        app.handle_action(Action::MoveDown).unwrap();
+        app.update_diff_cache();
+        app.selected_diff_target_changed_at = Instant::now() - DIFF_LOAD_DEBOUNCE;
+        app.update_diff_cache();
+        let request = rx.try_recv().unwrap();
+        assert_eq!(request.oid, first);
+        worker.send(request).unwrap();
+        wait_for(&mut app, first);
+
+        assert_eq!(
+            app.cached_diff().unwrap().files[0].path,
+            PathBuf::from("a.txt")
+        );
+        assert!(app.diff_cache.get(second).is_some());
+    }
+
+    #[test]
+    fn failed_commit_diff_load_is_cached_to_avoid_immediate_retry() {
+        let selected_oid = Oid::from_str("1111111111111111111111111111111111111111").unwrap();
+        let mut app = make_app(selected_oid, Some(selected_oid));
+        let (tx, rx) = mpsc::channel();
+        tx.send(DiffResult {
+            oid: selected_oid,
+            diff: Err("boom".to_string()),
+        })
+        .unwrap();
+        app.diff_receiver = Some(rx);
+
+        app.update_diff_cache();
+        app.update_diff_cache();
+
+        assert!(app.diff_cache.contains(selected_oid));
+        assert!(app.diff_cache.get(selected_oid).is_none());
+        assert!(app.cached_diff().is_none());
+        assert!(!app.is_diff_loading());
+        assert!(app.diff_loading_oid.is_none());
+        assert!(app.diff_receiver.is_none());
+        assert_eq!(app.message.as_deref(), Some("Failed to load diff: boom"));
+    }
+
+    #[test]
+    fn failed_uncommitted_diff_load_is_cached_to_avoid_immediate_retry() {
+        let mut app = make_uncommitted_app();
+        let (tx, rx) = mpsc::channel();
+        let cache_key = app.working_tree_status.clone();
+        tx.send((Err("boom".to_string()), cache_key)).unwrap();
+        app.uncommitted_diff_loading = true;
+        app.uncommitted_diff_receiver = Some(rx);
+
+        app.update_diff_cache();
+        app.update_diff_cache();
+
+        assert!(app.uncommitted_diff_cache.is_none());
+        assert!(app.uncommitted_diff_failed);
+        assert!(app.cached_diff().is_none());
+        assert!(!app.is_diff_loading());
+        assert!(!app.uncommitted_diff_loading);
         assert!(app.uncommitted_diff_receiver.is_none());
         assert_eq!(app.message.as_deref(), Some("Failed to load diff: boom"));
     }
@@ -2465,6 +5413,1760 @@ mod tests {
         assert_eq!(app.message.as_deref(), Some("Remote branches shown"));
     }
 
+    #[test]
+    fn raw_commit_object_matches_stored_commit() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(tempdir.path()).unwrap();
+        let first = commit_file(&repo, "a.txt", "a\n", "first");
+        let second = commit_file(&repo, "a.txt", "b\n", "second\n\nbody line");
+        let tree = repo.find_commit(second).unwrap().tree_id();
+
+        let git_repo = GitRepository::open(tempdir.path()).unwrap();
+        let raw = git_repo.raw_commit_object(second).unwrap();
+
+        assert!(raw.starts_with(&format!("tree {tree}\nparent {first}\nauthor Test User")));
+        assert!(raw.contains("\ncommitter Test User <test@example.com> "));
+        assert!(raw.ends_with("\n\nsecond\n\nbody line"));
+    }
+
+    #[test]
+    fn goto_revision_confirms_resolved_commit_before_jumping() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(tempdir.path()).unwrap();
+        let first = commit_file(&repo, "a.txt", "a\n", "first");
+        commit_file(&repo, "a.txt", "b\n", "second");
+        commit_file(&repo, "a.txt", "c\n", "third");
+
+        let git_repo = GitRepository::open(tempdir.path()).unwrap();
+        let mut app = make_app_from_repo(git_repo);
+        let type_rev = |app: &mut App, rev: &str| {
+            app.handle_action(Action::GotoRevision).unwrap();
+            for c in rev.chars() {
+                app.handle_action(Action::InputChar(c)).unwrap();
+            }
+            app.handle_action(Action::Confirm).unwrap();
+        };
+
+        type_rev(&mut app, "HEAD~2");
+        let AppMode::Confirm { message, .. } = &app.mode else {
+            panic!("expected confirmation, got {:?}", app.mode);
+        };
+        assert!(message.contains(&first.to_string()[..7]));
+        assert!(message.contains("first"));
+        app.handle_action(Action::Confirm).unwrap();
+        assert_eq!(
+            app.selected_commit_node()
+                .and_then(|node| node.commit.as_ref())
+                .map(|c| c.oid),
+            Some(first)
+        );
+
+        type_rev(&mut app, "HEAD~5");
+        let AppMode::Error { message } = &app.mode else {
+            panic!("expected error, got {:?}", app.mode);
+        };
+        assert!(message.starts_with("Unknown revision: HEAD~5"));
+    }
+
+    #[test]
+    fn hiding_the_graph_drops_connector_rows() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(tempdir.path()).unwrap();
+        let base = commit_file(&repo, "a.txt", "a\n", "base");
+        repo.branch("feat", &repo.find_commit(base).unwrap(), false)
+            .unwrap();
+        commit_file(&repo, "a.txt", "main\n", "main work");
+        repo.set_head("refs/heads/feat").unwrap();
+        commit_file(&repo, "b.txt", "feat\n", "feat work");
+
+        let git_repo = GitRepository::open(tempdir.path()).unwrap();
+        let mut app = make_app_from_repo(git_repo);
+        app.repo_path = tempdir.path().to_string_lossy().into_owned();
+        let connectors = |app: &App| {
+            app.graph_layout
+                .nodes
+                .iter()
+                .filter(|node| node.is_connector())
+                .count()
+        };
+        assert!(connectors(&app) > 0);
+        let last = app.graph_layout.nodes.len() - 1;
+        app.select_node(last);
+        let selected = app.selected_commit_node().unwrap().commit.clone().unwrap();
+        app.diff_cache.insert(selected.oid, None);
+
+        app.handle_action(Action::ToggleGraph).unwrap();
+        assert!(!app.show_graph());
+        assert_eq!(connectors(&app), 0);
+        // A display toggle keeps the selection and the loaded diffs
+        let still = app.selected_commit_node().unwrap().commit.as_ref().unwrap();
+        assert_eq!(still.oid, selected.oid);
+        assert!(app.diff_cache.contains(selected.oid));
+        app.handle_action(Action::ToggleOrientation).unwrap();
+        assert_eq!(app.graph_orientation(), GraphOrientation::Vertical);
+
+        app.handle_action(Action::ToggleGraph).unwrap();
+        assert!(connectors(&app) > 0);
+    }
+
+    #[test]
+    fn path_scope_limits_graph_to_touching_commits() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(tempdir.path()).unwrap();
+        let first = commit_file(&repo, "a.txt", "a\n", "add a");
+        let other = commit_file(&repo, "b.txt", "b\n", "add b");
+        let second = commit_file(&repo, "a.txt", "aa\n", "change a");
+
+        let git_repo = GitRepository::open(tempdir.path()).unwrap();
+        let mut app = make_app_from_repo(git_repo);
+        app.repo_path = tempdir.path().to_string_lossy().into_owned();
+        app.set_path_scope(vec!["a.txt".to_string()]).unwrap();
+        while app.path_scope_progress().is_some() {
+            app.update_path_scope_status();
+            std::thread::sleep(Duration::from_millis(5));
+        }
+
+        let shown: Vec<Oid> = app
+            .graph_layout
+            .nodes
+            .iter()
+            .filter_map(|node| node.commit.as_ref().map(|c| c.oid))
+            .collect();
+        assert_eq!(shown, [second, first]);
+        assert!(app.commits.iter().any(|c| c.oid == other));
+
+        app.set_path_scope(Vec::new()).unwrap();
+        assert_eq!(app.graph_layout.nodes.len(), 3);
+    }
+
+    #[test]
+    fn committer_differs_when_someone_else_commits() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(tempdir.path()).unwrap();
+        let own = commit_file(&repo, "a.txt", "a\n", "own");
+        let tree = repo.find_commit(own).unwrap().tree().unwrap();
+        let author = Signature::now("Test User", "test@example.com").unwrap();
+        let committer = Signature::now("Maintainer", "maint@example.com").unwrap();
+        let parent = repo.find_commit(own).unwrap();
+        let applied = repo
+            .commit(
+                Some("HEAD"),
+                &author,
+                &committer,
+                "applied",
+                &tree,
+                &[&parent],
+            )
+            .unwrap();
+
+        let commits = GitRepository::open(tempdir.path())
+            .unwrap()
+            .get_commits(10, true)
+            .unwrap();
+        let differs = |oid| {
+            commits
+                .iter()
+                .find(|c| c.oid == oid)
+                .unwrap()
+                .committer_differs()
+        };
+
+        assert!(differs(applied));
+        assert!(!differs(own));
+    }
+
+    #[test]
+    fn replace_refs_and_grafts_mark_commits_as_replaced() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(tempdir.path()).unwrap();
+        let first = commit_file(&repo, "a.txt", "a\n", "first");
+        let second = commit_file(&repo, "a.txt", "b\n", "second");
+        let third = commit_file(&repo, "a.txt", "c\n", "third");
+        repo.reference(&format!("refs/replace/{second}"), first, true, "replace")
+            .unwrap();
+        fs::write(repo.path().join("info/grafts"), format!("{third}\n")).unwrap();
+
+        let git_repo = GitRepository::open(tempdir.path()).unwrap();
+        let replaced = git_repo.replaced_commits().unwrap();
+
+        assert_eq!(replaced, HashSet::from([second, third]));
+    }
+
+    #[test]
+    fn repo_summary_reports_stashes_and_upstream_divergence() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let mut repo = Repository::init(tempdir.path()).unwrap();
+        let base = commit_file(&repo, "a.txt", "a\n", "base");
+        repo.remote("origin", "https://example.com/repo.git")
+            .unwrap();
+        repo.reference("refs/remotes/origin/main", base, true, "remote")
+            .unwrap();
+        let head = repo.head().unwrap();
+        let branch_name = head.shorthand().unwrap().to_string();
+        drop(head);
+        repo.find_branch(&branch_name, git2::BranchType::Local)
+            .unwrap()
+            .set_upstream(Some("origin/main"))
+            .unwrap();
+        commit_file(&repo, "a.txt", "b\n", "ahead");
+
+        fs::write(tempdir.path().join("a.txt"), "dirty\n").unwrap();
+        let signature = Signature::now("Test User", "test@example.com").unwrap();
+        repo.stash_save(&signature, "wip", None).unwrap();
+
+        let summary = GitRepository::open(tempdir.path()).unwrap().summary();
+
+        assert_eq!(summary.ahead_behind, Some((1, 0)));
+        assert_eq!(summary.stash_count, 1);
+        assert_eq!(summary.state, None);
+    }
+
+    #[test]
+    fn upstream_diff_reviews_cumulative_changes_to_push() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(tempdir.path()).unwrap();
+        let base = commit_file(&repo, "a.txt", "a\n", "base");
+        let git_repo = GitRepository::open(tempdir.path()).unwrap();
+        let mut app = make_app_from_repo(git_repo);
+
+        app.handle_action(Action::UpstreamDiff).unwrap();
+        assert!(matches!(app.mode, AppMode::Normal));
+        assert!(app
+            .message
+            .as_deref()
+            .unwrap()
+            .contains("has no upstream branch"));
+
+        repo.remote("origin", "https://example.com/repo.git")
+            .unwrap();
+        repo.reference("refs/remotes/origin/main", base, true, "remote")
+            .unwrap();
+        let branch_name = repo.head().unwrap().shorthand().unwrap().to_string();
+        repo.find_branch(&branch_name, git2::BranchType::Local)
+            .unwrap()
+            .set_upstream(Some("origin/main"))
+            .unwrap();
+
+        app.handle_action(Action::UpstreamDiff).unwrap();
+        assert!(app.message.as_deref().unwrap().contains("up to date"));
+
+        commit_file(&repo, "a.txt", "b\n", "change a");
+        commit_file(&repo, "b.txt", "b\n", "add b");
+        app.refresh(true).unwrap();
+        app.handle_action(Action::UpstreamDiff).unwrap();
+
+        let AppMode::FileSelect { file_list, .. } = &app.mode else {
+            panic!("expected file list, got {:?}", app.mode);
+        };
+        assert_eq!(file_list.len(), 2);
+        let range = app.range_diff().unwrap();
+        assert_eq!(range.ahead, 2);
+        assert_eq!(range.base_label, "origin/main");
+        assert_eq!(app.cached_diff().unwrap().total_files, 2);
+
+        app.handle_action(Action::Cancel).unwrap();
+        assert!(app.range_diff().is_none());
+    }
+
+    #[test]
+    fn upstream_diff_of_a_diverged_branch_starts_at_the_merge_base() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(tempdir.path()).unwrap();
+        let base = commit_file(&repo, "a.txt", "a\n", "base");
+        let upstream = commit_file(&repo, "upstream.txt", "u\n", "upstream only");
+        repo.remote("origin", "https://example.com/repo.git")
+            .unwrap();
+        repo.reference("refs/remotes/origin/main", upstream, true, "remote")
+            .unwrap();
+        let branch_name = repo.head().unwrap().shorthand().unwrap().to_string();
+        let mut branch = repo
+            .find_branch(&branch_name, git2::BranchType::Local)
+            .unwrap();
+        branch.set_upstream(Some("origin/main")).unwrap();
+        repo.reset(
+            repo.find_commit(base).unwrap().as_object(),
+            git2::ResetType::Hard,
+            None,
+        )
+        .unwrap();
+        commit_file(&repo, "local.txt", "l\n", "local only");
+        let mut app = make_app_from_repo(GitRepository::open(tempdir.path()).unwrap());
+
+        app.handle_action(Action::UpstreamDiff).unwrap();
+
+        let AppMode::FileSelect { file_list, .. } = &app.mode else {
+            panic!("expected file list, got {:?}", app.mode);
+        };
+        let paths: Vec<_> = file_list.iter().map(|file| file.path.clone()).collect();
+        assert_eq!(paths, [PathBuf::from("local.txt")]);
+        let range = app.range_diff().unwrap();
+        assert_eq!(range.base, base);
+        assert_eq!((range.ahead, range.behind), (1, 1));
+    }
+
+    #[test]
+    fn branch_sort_orders_by_recency_and_ahead_with_head_first() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(tempdir.path()).unwrap();
+        let base = commit_file(&repo, "a.txt", "a\n", "base");
+        let tree = repo.find_commit(base).unwrap().tree().unwrap();
+        let commit_at = |parent: Oid, seconds: i64| {
+            let sig = Signature::new(
+                "Test User",
+                "test@example.com",
+                &git2::Time::new(seconds, 0),
+            )
+            .unwrap();
+            let parent = repo.find_commit(parent).unwrap();
+            repo.commit(None, &sig, &sig, "work", &tree, &[&parent])
+                .unwrap()
+        };
+        // "aaa" is older but two commits ahead; "zzz" is newest, one ahead
+        let aaa1 = commit_at(base, 1_000);
+        let aaa2 = commit_at(aaa1, 2_000);
+        let zzz = commit_at(base, 3_000);
+        for (name, oid) in [("aaa", aaa2), ("zzz", zzz)] {
+            repo.branch(name, &repo.find_commit(oid).unwrap(), false)
+                .unwrap();
+        }
+
+        let git_repo = GitRepository::open(tempdir.path()).unwrap();
+        let head = git_repo.head_name().unwrap();
+        let names = |order| {
+            let mut branches = git_repo.get_branches(false).unwrap();
+            BranchInfo::sort(&git_repo.repo, &mut branches, order);
+            branches.into_iter().map(|b| b.name).collect::<Vec<_>>()
+        };
+
+        assert_eq!(names(BranchSort::Name), [head.as_str(), "aaa", "zzz"]);
+        assert_eq!(names(BranchSort::Recent)[1..], ["zzz", "aaa"]);
+        assert_eq!(names(BranchSort::Ahead)[1..], ["aaa", "zzz"]);
+        assert_eq!(names(BranchSort::Recent)[0], head);
+    }
+
+    #[test]
+    fn merged_branches_are_detected_and_can_be_hidden() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(tempdir.path()).unwrap();
+        let base = commit_file(&repo, "a.txt", "a\n", "base");
+        repo.branch("done", &repo.find_commit(base).unwrap(), false)
+            .unwrap();
+        commit_file(&repo, "a.txt", "b\n", "main work");
+        let main = repo.head().unwrap().shorthand().unwrap().to_string();
+        let wip = {
+            let tree = repo.find_commit(base).unwrap().tree().unwrap();
+            let sig = Signature::now("Test User", "test@example.com").unwrap();
+            let parent = repo.find_commit(base).unwrap();
+            repo.commit(None, &sig, &sig, "wip", &tree, &[&parent])
+                .unwrap()
+        };
+        repo.branch("wip", &repo.find_commit(wip).unwrap(), false)
+            .unwrap();
+
+        let mut app = make_app_from_repo(GitRepository::open(tempdir.path()).unwrap());
+        app.refresh(true).unwrap();
+        assert_eq!(app.main_branch(), Some(main.as_str()));
+        assert_eq!(app.merged_branches(), &HashSet::from(["done".to_string()]));
+        assert!(app.branches.iter().any(|b| b.name == "done"));
+
+        app.config.branches.merged = MergedBranches::Hide;
+        app.refresh(true).unwrap();
+        assert!(app.branches.iter().all(|b| b.name != "done"));
+        assert!(app.branches.iter().any(|b| b.name == "wip"));
+    }
+
+    #[test]
+    fn cleanup_deletes_only_merged_branches() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(tempdir.path()).unwrap();
+        let base = commit_file(&repo, "a.txt", "a\n", "base");
+        for name in ["done", "old"] {
+            repo.branch(name, &repo.find_commit(base).unwrap(), false)
+                .unwrap();
+        }
+        commit_file(&repo, "a.txt", "b\n", "main work");
+
+        let mut app = make_app_from_repo(GitRepository::open(tempdir.path()).unwrap());
+        app.refresh(true).unwrap();
+        app.handle_action(Action::CleanupBranches).unwrap();
+        let AppMode::Confirm { message, .. } = &app.mode else {
+            panic!("expected confirmation, got {:?}", app.mode);
+        };
+        assert!(message.contains("\n  done\n  old"), "{message}");
+
+        // "old" moves past main before the user confirms
+        let tree = repo.find_commit(base).unwrap().tree().unwrap();
+        let sig = Signature::now("Test User", "test@example.com").unwrap();
+        let ahead = repo
+            .commit(
+                None,
+                &sig,
+                &sig,
+                "more",
+                &tree,
+                &[&repo.find_commit(base).unwrap()],
+            )
+            .unwrap();
+        repo.branch("old", &repo.find_commit(ahead).unwrap(), true)
+            .unwrap();
+
+        app.handle_action(Action::Confirm).unwrap();
+        assert!(repo.find_branch("done", git2::BranchType::Local).is_err());
+        assert!(repo.find_branch("old", git2::BranchType::Local).is_ok());
+        let AppMode::Error { message } = &app.mode else {
+            panic!("expected error, got {:?}", app.mode);
+        };
+        assert!(message.contains("old (no longer merged)"), "{message}");
+    }
+
+    #[test]
+    fn reloaded_config_keeps_session_toggles_it_did_not_change() {
+        let (_tempdir, repo) = init_repo();
+        let mut app = make_app_from_repo(repo);
+        app.handle_action(Action::ToggleMinimap).unwrap();
+        assert!(app.show_minimap());
+
+        let mut config = Config::default();
+        config.graph.parent_badge = false;
+        config.branches.sort = BranchSort::Recent;
+        app.apply_config(config).unwrap();
+
+        assert!(app.show_minimap());
+        assert!(!app.show_parent_badge());
+        assert_eq!(app.config().branches.sort, BranchSort::Recent);
+    }
+
+    #[test]
+    fn hash_length_toggles_and_follows_the_config_default() {
+        let (_tempdir, repo) = init_repo();
+        let mut app = make_app_from_repo(repo);
+        assert!(!app.full_hashes());
+
+        app.handle_action(Action::ToggleHashLength).unwrap();
+        assert!(app.full_hashes());
+        assert_eq!(app.message.as_deref(), Some("Commit hashes full"));
+
+        let mut config = Config::default();
+        config.display.full_hashes = true;
+        app.apply_config(config).unwrap();
+        app.handle_action(Action::ToggleHashLength).unwrap();
+        let mut config = Config::default();
+        config.display.full_hashes = true;
+        app.apply_config(config).unwrap();
+        assert!(!app.full_hashes());
+
+        app.handle_action(Action::ToggleHashLength).unwrap();
+        app.apply_config(Config::default()).unwrap();
+        assert!(!app.full_hashes());
+    }
+
+    #[test]
+    fn load_more_row_extends_truncated_history() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(tempdir.path()).unwrap();
+        for i in 0..3 {
+            commit_file(&repo, "a.txt", &format!("{i}\n"), &format!("commit {i}"));
+        }
+        let mut app = make_app_from_repo(GitRepository::open(tempdir.path()).unwrap());
+        app.commit_limit = 2;
+        app.refresh(true).unwrap();
+        assert!(app.history_truncated());
+        assert_eq!(app.graph_row_count(), 3);
+
+        app.handle_action(Action::GoToBottom).unwrap();
+        app.handle_action(Action::MoveDown).unwrap();
+        assert!(app.is_load_more_selected());
+
+        app.handle_action(Action::Checkout).unwrap();
+        assert!(!app.history_truncated());
+        assert_eq!(app.commits.len(), 3);
+        assert_eq!(app.graph_list_state.selected(), Some(2));
+        assert_eq!(app.message.as_deref(), Some("Loaded 1 more commit(s)"));
+    }
+
+    #[test]
+    fn history_of_exactly_the_limit_has_no_load_more_row() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(tempdir.path()).unwrap();
+        for i in 0..2 {
+            commit_file(&repo, "a.txt", &format!("{i}\n"), &format!("commit {i}"));
+        }
+        let mut app = make_app_from_repo(GitRepository::open(tempdir.path()).unwrap());
+        app.commit_limit = 2;
+        app.refresh(true).unwrap();
+        assert_eq!(app.commits.len(), 2);
+        assert!(!app.history_truncated());
+        assert_eq!(app.graph_row_count(), 2);
+
+        // Likewise when a batch takes the last commits
+        commit_file(&repo, "a.txt", "2\n", "commit 2");
+        commit_file(&repo, "a.txt", "3\n", "commit 3");
+        app.refresh(true).unwrap();
+        assert!(app.history_truncated());
+        app.config.commits.batch_size = 2;
+        app.select_node(1);
+        app.load_ahead();
+        assert_eq!(app.commits.len(), 4);
+        assert!(!app.history_truncated());
+    }
+
+    #[test]
+    fn cursor_near_the_end_loads_the_next_batch() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(tempdir.path()).unwrap();
+        for i in 0..5 {
+            commit_file(&repo, "a.txt", &format!("{i}\n"), &format!("commit {i}"));
+        }
+        let mut app = make_app_from_repo(GitRepository::open(tempdir.path()).unwrap());
+        app.config.commits.batch_size = 2;
+        app.commit_limit = 2;
+        app.refresh(true).unwrap();
+        app.select_node(1);
+        let selected = app.selected_commit_node().unwrap().commit.clone().unwrap();
+
+        app.load_ahead();
+        assert_eq!(app.commits.len(), 4);
+        assert!(app.history_truncated());
+        assert_eq!(
+            app.selected_commit_node()
+                .unwrap()
+                .commit
+                .as_ref()
+                .unwrap()
+                .oid,
+            selected.oid
+        );
+
+        app.load_ahead();
+        assert!(!app.history_truncated());
+        let messages: Vec<&str> = app.commits.iter().map(|c| c.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            ["commit 4", "commit 3", "commit 2", "commit 1", "commit 0"]
+        );
+        assert_eq!(app.graph_layout.nodes.len(), 5);
+    }
+
+    #[test]
+    fn next_batch_continues_after_a_rewound_branch() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(tempdir.path()).unwrap();
+        let mut oids = Vec::new();
+        for i in 0..6 {
+            oids.push(commit_file(
+                &repo,
+                "a.txt",
+                &format!("{i}\n"),
+                &format!("commit {i}"),
+            ));
+        }
+        let mut app = make_app_from_repo(GitRepository::open(tempdir.path()).unwrap());
+        app.config.commits.batch_size = 2;
+        app.commit_limit = 2;
+        app.refresh(true).unwrap();
+
+        // Rewinding drops "commit 5" from the walk, which used to shift the
+        // offset past "commit 3"
+        let head = repo.head().unwrap().name().unwrap().to_string();
+        repo.reference(&head, oids[4], true, "rewind").unwrap();
+        app.load_ahead();
+        let messages: Vec<&str> = app.commits.iter().map(|c| c.message.as_str()).collect();
+        assert_eq!(messages, ["commit 5", "commit 4", "commit 3", "commit 2"]);
+        assert!(app.history_truncated());
+    }
+
+    #[test]
+    fn failed_load_ahead_waits_for_the_next_refresh() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(tempdir.path()).unwrap();
+        for i in 0..4 {
+            commit_file(&repo, "a.txt", &format!("{i}\n"), &format!("commit {i}"));
+        }
+        let mut app = make_app_from_repo(GitRepository::open(tempdir.path()).unwrap());
+        app.config.commits.batch_size = 2;
+        app.commit_limit = 2;
+        app.refresh(true).unwrap();
+        app.select_node(1);
+
+        app.unloaded_history = Some(VecDeque::from([Oid::from_bytes(&[1; 20]).unwrap()]));
+        app.load_ahead();
+        assert!(matches!(app.mode, AppMode::Error { .. }));
+        app.handle_action(Action::Quit).unwrap();
+        app.load_ahead();
+        assert!(matches!(app.mode, AppMode::Normal));
+        assert_eq!(app.commits.len(), 2);
+
+        app.refresh(true).unwrap();
+        app.load_ahead();
+        assert_eq!(app.commits.len(), 4);
+    }
+
+    #[test]
+    fn goto_branch_loads_history_until_the_tip_is_shown() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(tempdir.path()).unwrap();
+        let first = commit_file(&repo, "a.txt", "0\n", "commit 0");
+        repo.branch("old", &repo.find_commit(first).unwrap(), false)
+            .unwrap();
+        for i in 1..4 {
+            commit_file(&repo, "a.txt", &format!("{i}\n"), &format!("commit {i}"));
+        }
+        let mut app = make_app_from_repo(GitRepository::open(tempdir.path()).unwrap());
+        app.commit_limit = 2;
+        app.refresh(true).unwrap();
+        assert!(!app.commits.iter().any(|c| c.oid == first));
+
+        app.handle_action(Action::GotoBranch).unwrap();
+        assert_eq!(app.search_match_count(), app.branches.len());
+        for c in "old".chars() {
+            app.handle_action(Action::InputChar(c)).unwrap();
+        }
+        // Typing doesn't move the graph; only confirming does
+        assert_eq!(app.graph_list_state.selected(), Some(0));
+        app.handle_action(Action::Confirm).unwrap();
+
+        assert!(matches!(app.mode, AppMode::Normal));
+        let selected = app
+            .selected_commit_node()
+            .and_then(|node| node.commit.as_ref());
+        assert_eq!(selected.map(|c| c.oid), Some(first));
+        assert_eq!(app.selected_branch().map(|b| b.name.as_str()), Some("old"));
+    }
+
+    #[test]
+    fn branch_list_can_check_out_on_enter() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(tempdir.path()).unwrap();
+        let first = commit_file(&repo, "a.txt", "0\n", "commit 0");
+        repo.branch("old", &repo.find_commit(first).unwrap(), false)
+            .unwrap();
+        commit_file(&repo, "a.txt", "1\n", "commit 1");
+        let mut app = make_app_from_repo(GitRepository::open(tempdir.path()).unwrap());
+        app.config.branches.enter = BranchEnter::Checkout;
+
+        app.handle_action(Action::GotoBranch).unwrap();
+        assert!(matches!(&app.mode, AppMode::Input { title, .. } if title == "Check out branch"));
+        for c in "old".chars() {
+            app.handle_action(Action::InputChar(c)).unwrap();
+        }
+        app.handle_action(Action::Confirm).unwrap();
+
+        assert_eq!(app.head_name.as_deref(), Some("old"));
+        assert_eq!(app.selected_branch().map(|b| b.name.as_str()), Some("old"));
+    }
+
+    #[test]
+    fn whitespace_modes_recompute_the_open_file_list() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(tempdir.path()).unwrap();
+        commit_file(&repo, "a.rs", "fn a() {\n  x\n}\n", "add");
+        let reindent = commit_file(&repo, "a.rs", "fn a() {\n    x\n}\n", "reindent");
+        let mut app = make_app_from_repo(GitRepository::open(tempdir.path()).unwrap());
+        let diff = CommitDiffInfo::from_commit(
+            &app.repo.repo,
+            reindent,
+            crate::git::diff::DEFAULT_MAX_FILES,
+        )
+        .unwrap();
+        app.diff_cache.insert(reindent, Some(diff));
+        app.open_file_select(0);
+
+        let insertions = |app: &App| match &app.mode {
+            AppMode::FileSelect { file_list, .. } => file_list[0].insertions,
+            _ => panic!("file list closed"),
+        };
+        assert_eq!(insertions(&app), 1);
+
+        app.handle_action(Action::CycleWhitespace).unwrap();
+        assert_eq!(app.whitespace(), WhitespaceMode::IgnoreEol);
+        assert_eq!(insertions(&app), 1);
+
+        app.handle_action(Action::CycleWhitespace).unwrap();
+        assert_eq!(app.whitespace(), WhitespaceMode::IgnoreChange);
+        assert_eq!(insertions(&app), 0);
+        assert_eq!(app.cached_diff().unwrap().total_insertions, 0);
+        assert_eq!(
+            app.message.as_deref(),
+            Some("Diff: ignoring whitespace changes")
+        );
+
+        app.handle_action(Action::OpenFileDiff).unwrap();
+        let AppMode::FileDiff { content, .. } = &app.mode else {
+            panic!("file diff not opened");
+        };
+        assert_eq!((content.total_additions, content.total_deletions), (0, 0));
+
+        app.handle_action(Action::CycleWhitespace).unwrap();
+        app.handle_action(Action::CycleWhitespace).unwrap();
+        assert_eq!(app.whitespace(), WhitespaceMode::Show);
+        let AppMode::FileDiff { content, .. } = &app.mode else {
+            panic!("file diff closed");
+        };
+        assert_eq!((content.total_additions, content.total_deletions), (1, 1));
+    }
+
+    #[test]
+    fn tree_browser_shows_files_at_the_selected_commit() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(tempdir.path()).unwrap();
+        commit_file(&repo, "a.txt", "old\n", "first");
+        commit_file(&repo, "a.txt", "new\n", "second");
+        let mut app = make_app_from_repo(GitRepository::open(tempdir.path()).unwrap());
+        app.handle_action(Action::MoveDown).unwrap();
+
+        app.handle_action(Action::OpenTree).unwrap();
+        app.handle_action(Action::TreeExpand).unwrap();
+        let AppMode::TreeBrowser { browser } = &app.mode else {
+            panic!("expected the tree browser");
+        };
+        assert_eq!(browser.file.as_ref().unwrap().lines, ["old"]);
+
+        // Esc closes the file first, then the browser
+        app.handle_action(Action::Cancel).unwrap();
+        assert!(matches!(&app.mode, AppMode::TreeBrowser { browser } if browser.file.is_none()));
+        app.handle_action(Action::Cancel).unwrap();
+        assert!(matches!(app.mode, AppMode::Normal));
+    }
+
+    #[test]
+    fn create_branch_can_switch_to_the_new_branch() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(tempdir.path()).unwrap();
+        commit_file(&repo, "a.txt", "a\n", "first");
+        let mut app = make_app_from_repo(GitRepository::open(tempdir.path()).unwrap());
+        let original = app.repo.head_name();
+
+        app.handle_action(Action::CreateBranch).unwrap();
+        app.handle_action(Action::InputChar('x')).unwrap();
+        app.handle_action(Action::Confirm).unwrap();
+        assert_eq!(app.head_name, original);
+
+        app.handle_action(Action::CreateBranch).unwrap();
+        app.handle_action(Action::InputToggle).unwrap();
+        app.handle_action(Action::InputChar('y')).unwrap();
+        app.handle_action(Action::Confirm).unwrap();
+        assert!(matches!(app.mode, AppMode::Normal));
+        assert_eq!(app.head_name.as_deref(), Some("y"));
+        assert!(app.branches.iter().any(|b| b.name == "x"));
+    }
+
+    #[test]
+    fn checkout_refuses_branches_checked_out_in_another_worktree() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(tempdir.path().join("main")).unwrap();
+        let first = commit_file(&repo, "a.txt", "a\n", "first");
+        let branch = repo
+            .branch("feature", &repo.find_commit(first).unwrap(), false)
+            .unwrap();
+        let mut opts = git2::WorktreeAddOptions::new();
+        opts.reference(Some(branch.get()));
+        repo.worktree("feature", &tempdir.path().join("feature"), Some(&opts))
+            .unwrap();
+
+        let mut app = make_app_from_repo(GitRepository::open(repo.workdir().unwrap()).unwrap());
+        app.refresh(true).unwrap();
+        let path = app.worktree_branches().get("feature").cloned();
+        assert_eq!(
+            path,
+            Some(tempdir.path().join("feature").canonicalize().unwrap())
+        );
+        let before = app.repo.head_name();
+
+        app.selected_branch_position = app
+            .branch_positions
+            .iter()
+            .position(|(_, name)| name == "feature");
+        app.handle_action(Action::Checkout).unwrap();
+        assert!(matches!(&app.mode, AppMode::Error { message } if message.contains("worktree")));
+        assert_eq!(app.repo.head_name(), before);
+    }
+
+    #[test]
+    fn command_palette_runs_the_chosen_action() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(tempdir.path()).unwrap();
+        commit_file(&repo, "a.txt", "a\n", "first");
+        let mut app = make_app_from_repo(GitRepository::open(tempdir.path()).unwrap());
+        assert!(!app.show_minimap());
+
+        app.handle_action(Action::CommandPalette).unwrap();
+        assert_eq!(
+            app.search_match_count(),
+            BINDINGS.iter().filter(|command| command.in_palette).count()
+        );
+        for c in "minimap".chars() {
+            app.handle_action(Action::InputChar(c)).unwrap();
+        }
+        app.handle_action(Action::Confirm).unwrap();
+
+        assert!(matches!(app.mode, AppMode::Normal));
+        assert!(app.show_minimap());
+    }
+
+    #[test]
+    fn marked_range_shows_net_diffstat() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(tempdir.path()).unwrap();
+        commit_file(&repo, "a.txt", "old\n", "first");
+        commit_file(&repo, "b.txt", "1\n2\n", "second");
+        commit_file(&repo, "a.txt", "new\n", "third");
+        let mut app = make_app_from_repo(GitRepository::open(tempdir.path()).unwrap());
+
+        // Marking the newer end first still diffs from the older one
+        app.handle_action(Action::MarkRange).unwrap();
+        assert!(app.range_mark().is_some());
+        app.handle_action(Action::MoveDown).unwrap();
+        app.handle_action(Action::MoveDown).unwrap();
+        app.handle_action(Action::MarkRange).unwrap();
+        assert!(app.range_mark().is_none());
+        let AppMode::Confirm { message, .. } = &app.mode else {
+            panic!("expected the diffstat popup, got {:?}", app.mode);
+        };
+        assert!(message.contains("(2 commits): 2 files, +3 −1"), "{message}");
+
+        app.handle_action(Action::Confirm).unwrap();
+        let AppMode::FileSelect { file_list, .. } = &app.mode else {
+            panic!("expected file list, got {:?}", app.mode);
+        };
+        assert_eq!(file_list.len(), 2);
+        assert!(!app.range_diff().unwrap().for_push);
+    }
+
+    #[test]
+    fn focus_mode_steps_aside_for_the_file_list() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(tempdir.path()).unwrap();
+        commit_file(&repo, "a.txt", "a\n", "first");
+        let mut app = make_app_from_repo(GitRepository::open(tempdir.path()).unwrap());
+        app.focused_pane = FocusedPane::Detail;
+
+        app.handle_action(Action::ToggleFocusMode).unwrap();
+        assert!(app.focus_mode());
+        assert_eq!(app.focused_pane, FocusedPane::Graph);
+
+        app.mode = AppMode::FileSelect {
+            selected_index: 0,
+            file_list: Vec::new(),
+        };
+        assert!(!app.focus_mode());
+        app.handle_action(Action::Cancel).unwrap();
+        assert!(app.focus_mode());
+
+        app.handle_action(Action::ToggleFocusMode).unwrap();
+        assert!(!app.focus_mode());
+    }
+
+    #[test]
+    fn bare_repository_shows_history_but_refuses_work_tree_actions() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let source = tempdir.path().join("source");
+        let repo = Repository::init(&source).unwrap();
+        commit_file(&repo, "a.txt", "a\n", "first");
+        commit_file(&repo, "a.txt", "b\n", "second");
+        let mirror = tempdir.path().join("mirror.git");
+        git2::build::RepoBuilder::new()
+            .bare(true)
+            .clone(source.to_str().unwrap(), &mirror)
+            .unwrap();
+
+        let mut app = make_app_from_repo(GitRepository::open(&mirror).unwrap());
+        assert!(app.is_bare());
+        assert_eq!(app.graph_layout.nodes.len(), 2);
+        assert!(app
+            .graph_layout
+            .nodes
+            .iter()
+            .all(|node| !node.is_uncommitted));
+
+        app.handle_action(Action::MoveDown).unwrap();
+        app.handle_action(Action::Checkout).unwrap();
+        assert!(app.message.as_deref().unwrap().contains("bare repository"));
+        app.handle_action(Action::CommitDialog).unwrap();
+        assert!(matches!(app.mode, AppMode::Normal));
+    }
+
+    #[test]
+    fn pull_fetches_then_fast_forwards_to_the_upstream() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(tempdir.path()).unwrap();
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "Test User").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+        let first = commit_file(&repo, "a.txt", "1\n", "first");
+        let remote_dir = tempfile::tempdir().unwrap();
+        Repository::init_bare(remote_dir.path()).unwrap();
+        repo.remote("origin", remote_dir.path().to_str().unwrap())
+            .unwrap();
+        let repo_path = tempdir.path().to_str().unwrap();
+        let mut app = make_app_from_repo(GitRepository::open(tempdir.path()).unwrap());
+        app.repo_path = repo_path.to_string();
+        app.refresh(true).unwrap();
+        let branch = app.head_name.clone().unwrap();
+
+        app.handle_action(Action::Pull).unwrap();
+        assert!(!app.is_fetching());
+        assert_eq!(
+            app.message.as_deref(),
+            Some(
+                format!("Cannot pull: '{branch}' has no upstream (push it with p to set one)")
+                    .as_str()
+            )
+        );
+
+        // Publish a second commit, then drop it locally
+        let second = commit_file(&repo, "a.txt", "2\n", "second");
+        push_branch(repo_path, "origin", &branch, PushMode::Normal, |_| {}).unwrap();
+        repo.reset(
+            repo.find_commit(first).unwrap().as_object(),
+            git2::ResetType::Hard,
+            None,
+        )
+        .unwrap();
+        app.refresh(true).unwrap();
+
+        app.handle_action(Action::Pull).unwrap();
+        assert!(app.is_fetching());
+        let start = Instant::now();
+        while app.is_fetching() && start.elapsed() < Duration::from_secs(10) {
+            thread::sleep(Duration::from_millis(10));
+            app.update_fetch_status();
+        }
+        assert_eq!(repo.head().unwrap().target(), Some(second));
+        assert_eq!(
+            app.message.as_deref(),
+            Some(format!("Fast-forwarded to origin/{branch}").as_str())
+        );
+        assert_eq!(app.commits[0].oid, second);
+    }
+
+    #[test]
+    fn push_and_fetch_ask_only_when_no_remote_resolves() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(tempdir.path()).unwrap();
+        commit_file(&repo, "a.txt", "a\n", "first");
+        repo.remote("fork", "https://example.com/fork.git").unwrap();
+        repo.remote("upstream", "https://example.com/repo.git")
+            .unwrap();
+        let mut app = make_app_from_repo(GitRepository::open(tempdir.path()).unwrap());
+        app.refresh(true).unwrap();
+        let branch = app.head_name.clone().unwrap();
+
+        // Neither origin nor any branch config settles it: ask
+        app.handle_action(Action::Fetch).unwrap();
+        assert!(matches!(
+            &app.mode,
+            AppMode::Input {
+                action: InputAction::PickRemote(RemoteOp::Fetch),
+                ..
+            }
+        ));
+        assert_eq!(
+            app.search_candidates()
+                .iter()
+                .map(|(_, name)| name.as_str())
+                .collect::<Vec<_>>(),
+            ["fork", "upstream"]
+        );
+        app.handle_action(Action::Cancel).unwrap();
+
+        app.handle_action(Action::Push).unwrap();
+        assert!(matches!(
+            &app.mode,
+            AppMode::Input {
+                action: InputAction::PickRemote(RemoteOp::Push { force: false, .. }),
+                ..
+            }
+        ));
+        app.handle_action(Action::Confirm).unwrap();
+        assert!(matches!(
+            &app.mode,
+            AppMode::Confirm { action: ConfirmAction::Push { remote, .. }, .. } if remote == "fork"
+        ));
+        app.handle_action(Action::Cancel).unwrap();
+
+        // The branch's upstream remote is used directly
+        repo.config()
+            .unwrap()
+            .set_str(&format!("branch.{branch}.remote"), "upstream")
+            .unwrap();
+        app.handle_action(Action::Push).unwrap();
+        assert!(matches!(
+            &app.mode,
+            AppMode::Confirm { action: ConfirmAction::Push { remote, .. }, .. } if remote == "upstream"
+        ));
+        app.handle_action(Action::Cancel).unwrap();
+
+        // A configured push remote wins over it
+        repo.config()
+            .unwrap()
+            .set_str(&format!("branch.{branch}.pushRemote"), "fork")
+            .unwrap();
+        app.handle_action(Action::Push).unwrap();
+        assert!(matches!(
+            &app.mode,
+            AppMode::Confirm { action: ConfirmAction::Push { remote, .. }, .. } if remote == "fork"
+        ));
+    }
+
+    #[test]
+    fn blame_line_jumps_to_commit_that_last_changed_it() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(tempdir.path()).unwrap();
+        let first = commit_file(&repo, "a.txt", "a\nb\nc\n", "first");
+        let second = commit_file(&repo, "a.txt", "a\nB\nc\n", "second");
+        commit_file(&repo, "a.txt", "a\nB\nc\nd\n", "third");
+
+        let mut app = make_app_from_repo(GitRepository::open(tempdir.path()).unwrap());
+        let open_diff = |app: &mut App, scroll: usize| {
+            app.select_node(0);
+            app.enter_file_diff(0, Vec::new(), Path::new("a.txt"))
+                .unwrap();
+            if let AppMode::FileDiff { scroll_offset, .. } = &mut app.mode {
+                *scroll_offset = scroll;
+            }
+            app.handle_action(Action::BlameLine).unwrap();
+        };
+        let selected = |app: &App| {
+            app.selected_commit_node()
+                .unwrap()
+                .commit
+                .as_ref()
+                .unwrap()
+                .oid
+        };
+
+        // Rows: spacer, hunk header, then " a", " B", " c", "+d"
+        open_diff(&mut app, 0);
+        assert!(matches!(app.mode, AppMode::Normal));
+        assert_eq!(selected(&app), first);
+
+        open_diff(&mut app, 3);
+        assert_eq!(selected(&app), second);
+
+        // Added lines come from the commit itself, so there's nothing to blame
+        open_diff(&mut app, 5);
+        assert!(matches!(app.mode, AppMode::FileDiff { .. }));
+    }
+
+    #[test]
+    fn blame_line_follows_a_rename_to_the_old_path() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(tempdir.path()).unwrap();
+        let first = commit_file(&repo, "a.txt", "1\n2\n3\n4\n5\n6\n", "first");
+        let second = commit_file(&repo, "a.txt", "1\nTWO\n3\n4\n5\n6\n", "second");
+        let mut index = repo.index().unwrap();
+        index.remove_path(Path::new("a.txt")).unwrap();
+        index.write().unwrap();
+        fs::remove_file(tempdir.path().join("a.txt")).unwrap();
+        commit_file(&repo, "b.txt", "1\nTWO\n3\n4\nFIVE\n6\n", "rename");
+
+        let mut app = make_app_from_repo(GitRepository::open(tempdir.path()).unwrap());
+        app.select_node(0);
+        app.enter_file_diff(0, Vec::new(), Path::new("b.txt"))
+            .unwrap();
+        let AppMode::FileDiff { content, .. } = &mut app.mode else {
+            panic!("expected the file diff, got {:?}", app.mode);
+        };
+        assert_eq!(content.kind, FileChangeKind::Renamed);
+        assert_eq!(content.old_path, Path::new("a.txt"));
+
+        // Rows: spacer, hunk header, then " TWO", " 3", " 4", "-5", "+FIVE"
+        if let AppMode::FileDiff { scroll_offset, .. } = &mut app.mode {
+            *scroll_offset = 2;
+        }
+        app.handle_action(Action::BlameLine).unwrap();
+        let selected = app
+            .selected_commit_node()
+            .unwrap()
+            .commit
+            .as_ref()
+            .unwrap()
+            .oid;
+        assert_eq!(selected, second);
+        assert_eq!(
+            app.message,
+            Some(format!(
+                "a.txt:2 last changed in {}",
+                &second.to_string()[..7]
+            ))
+        );
+
+        // Lines the rename kept as they were go back to the first commit
+        app.select_node(0);
+        app.enter_file_diff(0, Vec::new(), Path::new("b.txt"))
+            .unwrap();
+        if let AppMode::FileDiff { scroll_offset, .. } = &mut app.mode {
+            *scroll_offset = 3;
+        }
+        app.handle_action(Action::BlameLine).unwrap();
+        assert_eq!(
+            app.selected_commit_node()
+                .unwrap()
+                .commit
+                .as_ref()
+                .unwrap()
+                .oid,
+            first
+        );
+    }
+
+    #[test]
+    fn detached_head_is_marked_apart_from_the_branch_tip() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(tempdir.path()).unwrap();
+        let first = commit_file(&repo, "a.txt", "1\n", "first");
+        let second = commit_file(&repo, "a.txt", "2\n", "second");
+
+        let mut app = make_app_from_repo(GitRepository::open(tempdir.path()).unwrap());
+        app.refresh(true).unwrap();
+        let node = |app: &App, oid: Oid| {
+            app.graph_layout
+                .nodes
+                .iter()
+                .find(|n| n.commit.as_ref().is_some_and(|c| c.oid == oid))
+                .cloned()
+                .unwrap()
+        };
+        let tip = node(&app, second);
+        assert!(tip.is_head && tip.is_current_tip);
+
+        crate::git::operations::checkout_commit(&repo, first).unwrap();
+        app.refresh(true).unwrap();
+        let tip = node(&app, second);
+        assert!(!tip.is_head && tip.is_current_tip);
+        let head = node(&app, first);
+        assert!(head.is_head && !head.is_current_tip);
+
+        app.select_node(0);
+        app.handle_action(Action::JumpToHead).unwrap();
+        assert_eq!(
+            app.selected_commit_node()
+                .unwrap()
+                .commit
+                .as_ref()
+                .unwrap()
+                .oid,
+            first
+        );
+    }
+
+    #[test]
+    fn help_search_steps_through_matching_lines() {
+        let (_tempdir, repo) = init_repo();
+        let mut app = make_app_from_repo(repo);
+        let search = |app: &mut App, text: &str| {
+            app.handle_action(Action::Search).unwrap();
+            // The box starts with the previous query
+            if let AppMode::Input { input, .. } = &mut app.mode {
+                input.clear();
+            }
+            for c in text.chars() {
+                app.handle_action(Action::InputChar(c)).unwrap();
+            }
+            app.handle_action(Action::Confirm).unwrap();
+        };
+        app.handle_action(Action::ToggleHelp).unwrap();
+
+        let matches = HelpPopup::matching_lines("BRANCH");
+        assert!(matches.len() > 2);
+        search(&mut app, "branch");
+        assert!(matches!(app.mode, AppMode::Help));
+        assert_eq!(app.help_match(), Some(matches[0]));
+        app.handle_action(Action::NextMatch).unwrap();
+        assert_eq!(app.help_match(), Some(matches[1]));
+        app.handle_action(Action::PrevMatch).unwrap();
+        app.handle_action(Action::PrevMatch).unwrap();
+        assert_eq!(app.help_match(), matches.last().copied());
+
+        search(&mut app, "no such key");
+        assert_eq!(app.help_match(), None);
+        assert!(app.get_message().unwrap().contains("no such key"));
+
+        // Esc in the search box goes back to the help, Esc there closes it
+        app.handle_action(Action::Search).unwrap();
+        app.handle_action(Action::Cancel).unwrap();
+        assert!(matches!(app.mode, AppMode::Help));
+        app.handle_action(Action::Cancel).unwrap();
+        assert!(matches!(app.mode, AppMode::Normal));
+    }
+
+    #[test]
+    fn cherry_pick_confirms_then_replays_the_selected_commit() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(tempdir.path()).unwrap();
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "Test").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+        config.set_bool("commit.gpgsign", false).unwrap();
+        let first = commit_file(&repo, "a.txt", "a\n", "first");
+        let picked = commit_file(&repo, "b.txt", "b\n", "add b");
+        repo.branch("feature", &repo.find_commit(picked).unwrap(), false)
+            .unwrap();
+        repo.reset(
+            repo.find_commit(first).unwrap().as_object(),
+            git2::ResetType::Hard,
+            None,
+        )
+        .unwrap();
+
+        let mut app = make_app_from_repo(GitRepository::open(tempdir.path()).unwrap());
+        let idx = app
+            .graph_layout
+            .nodes
+            .iter()
+            .position(|node| node.commit.as_ref().is_some_and(|c| c.oid == picked))
+            .unwrap();
+        app.select_node(idx);
+        app.handle_action(Action::CherryPick).unwrap();
+        assert!(matches!(
+            app.mode,
+            AppMode::Confirm { action: ConfirmAction::CherryPick(oid), .. } if oid == picked
+        ));
+
+        app.handle_action(Action::Confirm).unwrap();
+        assert!(matches!(app.mode, AppMode::Normal));
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.summary(), Some("add b"));
+        assert_eq!(head.parent_id(0).unwrap(), first);
+        assert!(app
+            .message
+            .as_deref()
+            .unwrap()
+            .starts_with("Cherry-picked as"));
+    }
+
+    #[test]
+    fn missing_identity_is_asked_for_before_committing() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(tempdir.path()).unwrap();
+        commit_file(&repo, "a.txt", "a\n", "first");
+        // An empty name makes repo.signature() fail whatever the global
+        // config says
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "").unwrap();
+        config.set_str("user.email", "").unwrap();
+        fs::write(tempdir.path().join("b.txt"), "b\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("b.txt")).unwrap();
+        index.write().unwrap();
+        let mut app = make_app_from_repo(GitRepository::open(tempdir.path()).unwrap());
+
+        app.handle_action(Action::CommitDialog).unwrap();
+        assert!(matches!(
+            app.mode,
+            AppMode::Confirm {
+                action: ConfirmAction::EditIdentity(Some(IdentityResume::Action(
+                    Action::CommitDialog
+                ))),
+                ..
+            }
+        ));
+        app.handle_action(Action::Confirm).unwrap();
+        for c in "Ann".chars() {
+            app.handle_action(Action::InputChar(c)).unwrap();
+        }
+        app.handle_action(Action::Confirm).unwrap();
+        assert!(matches!(
+            &app.mode,
+            AppMode::Input { action: InputAction::IdentityEmail { name, .. }, .. } if name == "Ann"
+        ));
+        for c in "ann@example.com".chars() {
+            app.handle_action(Action::InputChar(c)).unwrap();
+        }
+        app.handle_action(Action::Confirm).unwrap();
+
+        let config = repo.config().unwrap().snapshot().unwrap();
+        assert_eq!(config.get_str("user.name").unwrap(), "Ann");
+        assert_eq!(config.get_str("user.email").unwrap(), "ann@example.com");
+        // The commit dialog opens once the identity is saved
+        assert!(matches!(
+            app.mode,
+            AppMode::Input {
+                action: InputAction::CommitMessage,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn session_author_commits_without_touching_the_config() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(tempdir.path()).unwrap();
+        commit_file(&repo, "a.txt", "a\n", "first");
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "").unwrap();
+        config.set_str("user.email", "").unwrap();
+        fs::write(tempdir.path().join("b.txt"), "b\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("b.txt")).unwrap();
+        index.write().unwrap();
+        let mut app = make_app_from_repo(GitRepository::open(tempdir.path()).unwrap());
+        let type_text = |app: &mut App, text: &str| {
+            for c in text.chars() {
+                app.handle_action(Action::InputChar(c)).unwrap();
+            }
+            app.handle_action(Action::Confirm).unwrap();
+        };
+
+        app.handle_action(Action::SessionAuthor).unwrap();
+        type_text(&mut app, "Ann ann@example.com");
+        assert!(matches!(app.mode, AppMode::Error { .. }));
+        assert!(app.session_identity().is_none());
+        app.mode = AppMode::Normal;
+
+        app.handle_action(Action::SessionAuthor).unwrap();
+        type_text(&mut app, "Ann <ann@example.com>");
+        assert_eq!(
+            app.session_identity().map(ToString::to_string).as_deref(),
+            Some("Ann <ann@example.com>")
+        );
+
+        // No identity prompt: the commit dialog opens right away
+        app.handle_action(Action::CommitDialog).unwrap();
+        type_text(&mut app, "add b");
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.message(), Some("add b"));
+        assert_eq!(head.author().name(), Some("Ann"));
+        assert_eq!(head.committer().email(), Some("ann@example.com"));
+        let config = repo.config().unwrap().snapshot().unwrap();
+        assert_eq!(config.get_str("user.name").unwrap(), "");
+    }
+
+    #[test]
+    fn create_tag_tags_the_selected_commit() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(tempdir.path()).unwrap();
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "Test").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+        let first = commit_file(&repo, "a.txt", "a\n", "first");
+        commit_file(&repo, "a.txt", "b\n", "second");
+        let mut app = make_app_from_repo(GitRepository::open(tempdir.path()).unwrap());
+        app.select_node(1);
+
+        app.handle_action(Action::CreateTag).unwrap();
+        for c in "v1".chars() {
+            app.handle_action(Action::InputChar(c)).unwrap();
+        }
+        app.handle_action(Action::InputToggle).unwrap();
+        app.handle_action(Action::Confirm).unwrap();
+        assert!(matches!(
+            &app.mode,
+            AppMode::Input { action: InputAction::TagMessage { name }, .. } if name == "v1"
+        ));
+        for c in "First release".chars() {
+            app.handle_action(Action::InputChar(c)).unwrap();
+        }
+        app.handle_action(Action::Confirm).unwrap();
+
+        let tag = repo
+            .find_reference("refs/tags/v1")
+            .unwrap()
+            .peel_to_tag()
+            .unwrap();
+        assert_eq!(tag.target_id(), first);
+        assert_eq!(tag.message(), Some("First release"));
+        assert_eq!(app.tags.get(&first), Some(&vec!["v1".to_string()]));
+        let node = app
+            .graph_layout
+            .nodes
+            .iter()
+            .find(|node| node.commit.as_ref().is_some_and(|c| c.oid == first))
+            .unwrap();
+        assert_eq!(node.tag_names, vec!["v1".to_string()]);
+    }
+
+    #[test]
+    fn annotated_tag_asks_for_missing_identity() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(tempdir.path()).unwrap();
+        commit_file(&repo, "a.txt", "a\n", "first");
+        repo.config().unwrap().set_str("user.name", "").unwrap();
+        let mut app = make_app_from_repo(GitRepository::open(tempdir.path()).unwrap());
+        app.select_node(0);
+
+        // Lightweight tags need no tagger
+        app.handle_action(Action::CreateTag).unwrap();
+        app.handle_action(Action::InputChar('a')).unwrap();
+        app.handle_action(Action::Confirm).unwrap();
+        assert!(repo.find_reference("refs/tags/a").is_ok());
+
+        app.handle_action(Action::CreateTag).unwrap();
+        app.handle_action(Action::InputChar('b')).unwrap();
+        app.handle_action(Action::InputToggle).unwrap();
+        app.handle_action(Action::Confirm).unwrap();
+        assert!(matches!(
+            &app.mode,
+            AppMode::Confirm {
+                action: ConfirmAction::EditIdentity(Some(IdentityResume::TagMessage { name })),
+                ..
+            } if name == "b"
+        ));
+
+        // Saving the identity continues with the message of the same tag
+        app.handle_action(Action::Confirm).unwrap();
+        for c in "Ann".chars() {
+            app.handle_action(Action::InputChar(c)).unwrap();
+        }
+        app.handle_action(Action::Confirm).unwrap();
+        for c in "ann@example.com".chars() {
+            app.handle_action(Action::InputChar(c)).unwrap();
+        }
+        app.handle_action(Action::Confirm).unwrap();
+        assert!(matches!(
+            &app.mode,
+            AppMode::Input { action: InputAction::TagMessage { name }, .. } if name == "b"
+        ));
+        for c in "release".chars() {
+            app.handle_action(Action::InputChar(c)).unwrap();
+        }
+        app.handle_action(Action::Confirm).unwrap();
+        let tag = repo
+            .find_reference("refs/tags/b")
+            .unwrap()
+            .peel_to_tag()
+            .unwrap();
+        assert_eq!(tag.message(), Some("release"));
+    }
+
+    #[test]
+    fn stash_keys_save_and_pop_local_changes() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(tempdir.path()).unwrap();
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "Test").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+        commit_file(&repo, "a.txt", "a\n", "first");
+        let path = tempdir.path().join("a.txt");
+        std::fs::write(&path, "changed\n").unwrap();
+
+        let mut app = make_app_from_repo(GitRepository::open(tempdir.path()).unwrap());
+        assert!(app.working_tree_status.is_some());
+        app.handle_action(Action::StashSave).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "a\n");
+        assert!(app.working_tree_status.is_none());
+        assert_eq!(app.repo_summary.stash_count, 1);
+
+        app.handle_action(Action::StashList).unwrap();
+        assert!(matches!(
+            &app.mode,
+            AppMode::StashList { stashes, selected: 0 } if stashes.len() == 1
+        ));
+        app.handle_action(Action::Cancel).unwrap();
+
+        app.handle_action(Action::StashPop).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "changed\n");
+        assert_eq!(app.repo_summary.stash_count, 0);
+        assert_eq!(app.message.as_deref(), Some("Popped stash@{0}"));
+        app.handle_action(Action::StashPop).unwrap();
+        assert_eq!(app.message.as_deref(), Some("No stash entries"));
+    }
+
+    #[test]
+    fn read_only_mode_refuses_mutating_actions() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(tempdir.path()).unwrap();
+        commit_file(&repo, "a.txt", "a\n", "first");
+        commit_file(&repo, "a.txt", "b\n", "second");
+        let path = tempdir.path().join("a.txt");
+        std::fs::write(&path, "changed\n").unwrap();
+        let head = repo.head().unwrap().target();
+
+        let mut app = make_app_from_repo(GitRepository::open(tempdir.path()).unwrap());
+        app.config.ui.read_only = true;
+        assert!(app.is_read_only());
+        for action in [Action::StashSave, Action::CreateBranch, Action::Fetch] {
+            app.handle_action(action).unwrap();
+            assert!(matches!(app.mode, AppMode::Normal));
+            assert_eq!(
+                app.message.as_deref(),
+                Some("Not available in read-only mode")
+            );
+        }
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "changed\n");
+
+        // Navigation still works; checking out the older commit doesn't
+        let selected = app.graph_list_state.selected();
+        app.handle_action(Action::GoToBottom).unwrap();
+        assert_ne!(app.graph_list_state.selected(), selected);
+        app.handle_action(Action::Checkout).unwrap();
+        assert!(matches!(app.mode, AppMode::Normal));
+        assert_eq!(repo.head().unwrap().target(), head);
+    }
+
+    #[test]
+    fn timer_countdowns_follow_the_refresh_config() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(tempdir.path()).unwrap();
+        commit_file(&repo, "a.txt", "a\n", "first");
+        let mut app = make_app_from_repo(GitRepository::open(tempdir.path()).unwrap());
+        app.reset_timers();
+
+        // No remote, so only the refresh timer runs
+        let (refresh, fetch) = app.timer_countdowns();
+        assert!(refresh.is_some_and(|secs| secs <= app.config.refresh.refresh_interval));
+        assert_eq!(fetch, None);
+
+        app.has_default_remote = true;
+        let (_, fetch) = app.timer_countdowns();
+        assert!(fetch.is_some_and(|secs| secs <= app.config.refresh.fetch_interval));
+
+        app.config.refresh.auto_refresh = false;
+        app.config.ui.read_only = true;
+        assert_eq!(app.timer_countdowns(), (None, None));
+    }
+
+    #[test]
+    fn label_only_changes_relabel_without_rebuilding() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(tempdir.path()).unwrap();
+        let first = commit_file(&repo, "a.txt", "a\n", "first");
+        let second = commit_file(&repo, "a.txt", "b\n", "second");
+        let mut app = make_app_from_repo(GitRepository::open(tempdir.path()).unwrap());
+        let full_refreshes = |app: &App| {
+            app.perf
+                .ops()
+                .find(|(name, _)| *name == "refresh")
+                .map_or(0, |(_, agg)| agg.count)
+        };
+        let labels_of = |app: &App, oid: Oid| {
+            let node = app
+                .graph_layout
+                .nodes
+                .iter()
+                .find(|node| node.commit.as_ref().is_some_and(|c| c.oid == oid))
+                .unwrap();
+            node.labels
+                .iter()
+                .map(|label| label.name.clone())
+                .collect::<Vec<_>>()
+        };
+
+        create_branch(&app.repo.repo, "old", first).unwrap();
+        app.refresh_refs().unwrap();
+        assert_eq!(labels_of(&app, first), vec!["old".to_string()]);
+        delete_branch(&app.repo.repo, "old").unwrap();
+        app.refresh_refs().unwrap();
+        assert!(labels_of(&app, first).is_empty());
+        assert_eq!(full_refreshes(&app), 0);
+        assert!(app.graph_layout.nodes.iter().any(|node| node.is_head));
+
+        // A branch with a commit of its own changes the history
+        repo.set_head_detached(second).unwrap();
+        let side = commit_file(&repo, "side.txt", "s\n", "side");
+        repo.branch("side", &repo.find_commit(side).unwrap(), false)
+            .unwrap();
+        repo.set_head(&format!("refs/heads/{}", app.head_name.clone().unwrap()))
+            .unwrap();
+        app.refresh(true).unwrap();
+        let before = full_refreshes(&app);
+        delete_branch(&app.repo.repo, "side").unwrap();
+        app.refresh_refs().unwrap();
+        assert_eq!(full_refreshes(&app), before + 1);
+        assert!(!app.commits.iter().any(|c| c.oid == side));
+    }
+
+    #[test]
+    fn history_starts_at_head_when_no_branches_exist() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(tempdir.path()).unwrap();
+        commit_file(&repo, "a.txt", "1\n", "first");
+        let second = commit_file(&repo, "a.txt", "2\n", "second");
+        let branch = repo.head().unwrap().shorthand().unwrap().to_string();
+        repo.set_head_detached(second).unwrap();
+        repo.find_branch(&branch, git2::BranchType::Local)
+            .unwrap()
+            .delete()
+            .unwrap();
+
+        let mut app = make_app_from_repo(GitRepository::open(tempdir.path()).unwrap());
+        assert!(app.branches.is_empty());
+        assert_eq!(app.commits.len(), 2);
+        assert_eq!(app.commits[0].oid, second);
+
+        app.handle_action(Action::GotoBranch).unwrap();
+        assert!(matches!(app.mode, AppMode::Normal));
+        assert_eq!(
+            app.message.as_deref(),
+            Some("No branches; history is shown from HEAD (b: create one)")
+        );
+    }
+
+    #[test]
+    fn remote_labels_matching_a_local_branch_are_skipped_for_any_remote() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(tempdir.path()).unwrap();
+        let head = commit_file(&repo, "a.txt", "a\n", "first");
+        let branch = repo.head().unwrap().shorthand().unwrap().to_string();
+        repo.remote("upstream", "https://example.com/repo.git")
+            .unwrap();
+        for name in [branch.as_str(), "other"] {
+            repo.reference(
+                &format!("refs/remotes/upstream/{name}"),
+                head,
+                true,
+                "remote",
+            )
+            .unwrap();
+        }
+
+        let app = make_app_from_repo(GitRepository::open(tempdir.path()).unwrap());
+
+        let names: Vec<&str> = app
+            .branch_positions
+            .iter()
+            .map(|(_, name)| name.as_str())
+            .collect();
+        assert_eq!(names, [branch.as_str(), "upstream/other"]);
+    }
+
+    #[test]
+    fn merge_jumps_skip_regular_commits() {
+        let commits: Vec<CommitInfo> = [
+            (5, vec![4, 3]),
+            (4, vec![2]),
+            (3, vec![2]),
+            (2, vec![1]),
+            (1, vec![]),
+        ]
+        .into_iter()
+        .map(|(n, parents): (u8, Vec<u8>)| CommitInfo {
+            parent_oids: parents
+                .into_iter()
+                .map(|p| Oid::from_bytes(&[p; 20]).unwrap())
+                .collect(),
+            ..make_commit(Oid::from_bytes(&[n; 20]).unwrap())
+        })
+        .collect();
+        let (_tempdir, repo) = init_repo();
+        let mut app = make_app_from_repo(repo);
+        app.graph_layout = build_graph_default(&commits, &[], None, None);
+        app.select_node(4);
+
+        app.handle_action(Action::PrevMerge).unwrap();
+        assert_eq!(app.graph_list_state.selected(), Some(0));
+        app.handle_action(Action::NextMerge).unwrap();
+        assert_eq!(app.graph_list_state.selected(), Some(0));
+        assert_eq!(app.message.as_deref(), Some("No merge commit below"));
+    }
+
+    #[test]
+    fn tag_jumps_name_the_tag_and_wrap_when_enabled() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(tempdir.path()).unwrap();
+        let v1 = commit_file(&repo, "a.txt", "1\n", "release 1");
+        let v2 = commit_file(&repo, "a.txt", "2\n", "release 2");
+        commit_file(&repo, "a.txt", "3\n", "work");
+        let sig = Signature::now("Test User", "test@example.com").unwrap();
+        repo.tag_lightweight("v1.0", &repo.find_object(v1, None).unwrap(), false)
+            .unwrap();
+        repo.tag(
+            "v2.0",
+            &repo.find_object(v2, None).unwrap(),
+            &sig,
+            "v2",
+            false,
+        )
+        .unwrap();
+
+        let mut app = make_app_from_repo(GitRepository::open(tempdir.path()).unwrap());
+        app.refresh(true).unwrap();
+        app.select_node(0);
+
+        app.handle_action(Action::NextTag).unwrap();
+        assert_eq!(app.graph_list_state.selected(), Some(1));
+        assert_eq!(app.message.as_deref(), Some("Tag v2.0"));
+        app.handle_action(Action::NextTag).unwrap();
+        assert_eq!(app.graph_list_state.selected(), Some(2));
+        app.handle_action(Action::NextTag).unwrap();
+        assert_eq!(app.message.as_deref(), Some("No tag below"));
+
+        app.config.ui.wrap_jumps = true;
+        app.handle_action(Action::NextTag).unwrap();
+        assert_eq!(app.graph_list_state.selected(), Some(1));
+    }
+
+    #[test]
+    fn rebase_confirmation_warns_about_pushed_commits() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(tempdir.path()).unwrap();
+        let base = commit_file(&repo, "a.txt", "a\n", "base");
+        let pushed = commit_file(&repo, "a.txt", "b\n", "pushed");
+        commit_file(&repo, "a.txt", "c\n", "local");
+        let sig = Signature::now("Test User", "test@example.com").unwrap();
+        let base_commit = repo.find_commit(base).unwrap();
+        repo.commit(
+            Some("refs/heads/onto"),
+            &sig,
+            &sig,
+            "onto",
+            &base_commit.tree().unwrap(),
+            &[&base_commit],
+        )
+        .unwrap();
+        repo.remote("origin", "https://example.com/repo.git")
+            .unwrap();
+        repo.reference("refs/remotes/origin/main", pushed, true, "remote")
+            .unwrap();
+        // Rebasing writes commits, so it asks for an identity without one
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "Test User").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+        let branch_name = repo.head().unwrap().shorthand().unwrap().to_string();
+        repo.find_branch(&branch_name, git2::BranchType::Local)
+            .unwrap()
+            .set_upstream(Some("origin/main"))
+            .unwrap();
+
+        let mut app = make_app_from_repo(GitRepository::open(tempdir.path()).unwrap());
+        app.refresh(true).unwrap();
+        app.selected_branch_position = app.branch_positions.iter().position(|(_, n)| n == "onto");
+        app.handle_action(Action::Rebase).unwrap();
+
+        let AppMode::Confirm { message, .. } = &app.mode else {
+            panic!("expected confirmation, got {:?}", app.mode);
+        };
+        assert!(
+            message.contains("1 of these commit(s) are already on origin/main"),
+            "{message}"
+        );
+    }
+
+    #[test]
+    fn rebase_confirmation_lists_the_replayed_commits() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(tempdir.path()).unwrap();
+        let base = commit_file(&repo, "a.txt", "a\n", "base");
+        repo.branch("onto", &repo.find_commit(base).unwrap(), false)
+            .unwrap();
+        commit_file(&repo, "a.txt", "b\n", "first");
+        commit_file(&repo, "a.txt", "c\n", "second");
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "Test User").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+
+        let mut app = make_app_from_repo(GitRepository::open(tempdir.path()).unwrap());
+        app.refresh(true).unwrap();
+        app.selected_branch_position = app.branch_positions.iter().position(|(_, n)| n == "onto");
+        app.handle_action(Action::Rebase).unwrap();
+
+        let AppMode::Confirm { message, list, .. } = &app.mode else {
+            panic!("expected confirmation, got {:?}", app.mode);
+        };
+        assert_eq!(list, &Some(2..4));
+        let lines: Vec<&str> = message.lines().skip(1).collect();
+        assert_eq!(lines[0], "Replays 2 commit(s):");
+        assert!(lines[1].ends_with(" first"), "{message}");
+        assert!(lines[2].ends_with(" second"), "{message}");
+
+        app.handle_action(Action::ScrollDown).unwrap();
+        assert_eq!(app.confirm_scroll, 1);
+
+        // The next confirmation opens at the top again
+        app.handle_action(Action::Cancel).unwrap();
+        assert_eq!(app.confirm_scroll, 0);
+    }
+
     #[test]
     fn stale_working_tree_status_recovers_after_diff_load_completes() {
         let tempdir = tempfile::tempdir().unwrap();
@@ -1,8 +1,18 @@
 //! Configuration management
 
+use std::env;
 use std::fs;
+use std::path::{Path, PathBuf};
 
-use serde::Deserialize;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::git::branch::{BranchEnter, BranchSort, MergedBranches};
+use crate::git::{diff::DEFAULT_MAX_FILES, GraphOptions, WhitespaceMode};
+use crate::graph::{chars::GraphChars, colors::ColorMode};
+
+/// Environment variable naming an alternate config file
+const CONFIG_ENV: &str = "KEIFU_CONFIG";
 
 /// Application configuration
 #[derive(Debug, Clone, Default, Deserialize)]
@@ -10,6 +20,195 @@ use serde::Deserialize;
 pub struct Config {
     pub refresh: RefreshConfig,
     pub graph: GraphConfig,
+    pub ui: UiConfig,
+    pub glyphs: GraphChars,
+    pub diff: DiffConfig,
+    pub branches: BranchesConfig,
+    pub messages: MessagesConfig,
+    pub display: DisplayConfig,
+    pub commits: CommitsConfig,
+    pub pull: PullConfig,
+}
+
+/// How `F` (pull) integrates the fetched upstream
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct PullConfig {
+    /// Replay local commits onto the upstream instead of merging it, when
+    /// the branch can't simply fast-forward. Unset follows git's
+    /// `branch.<name>.rebase` / `pull.rebase`
+    pub rebase: Option<bool>,
+}
+
+/// How much history is read into the graph
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct CommitsConfig {
+    /// Commits loaded at startup (minimum: 1, default: 500)
+    #[serde(deserialize_with = "deserialize_at_least_one")]
+    pub initial_batch: usize,
+    /// Commits added each time the cursor nears the end of the loaded
+    /// history (minimum: 1, default: 500)
+    #[serde(deserialize_with = "deserialize_at_least_one")]
+    pub batch_size: usize,
+}
+
+impl Default for CommitsConfig {
+    fn default() -> Self {
+        Self {
+            initial_batch: 500,
+            batch_size: 500,
+        }
+    }
+}
+
+/// Fields a commit row can be built from, in the order `[display] columns`
+/// lists them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Column {
+    /// Lane art
+    Graph,
+    /// Commit hash, short or full (`display.full_hashes`)
+    Hash,
+    /// Author name, clipped to `display.author_width` columns (default 8)
+    Author,
+    /// Commit date (`YYYY-MM-DD`)
+    Date,
+    /// Branch labels, markers and the commit message; takes the space left
+    Subject,
+}
+
+impl Column {
+    /// Lane art, then the message, then date, author and hash on the right
+    pub const DEFAULT: [Column; 5] = [
+        Column::Graph,
+        Column::Subject,
+        Column::Date,
+        Column::Author,
+        Column::Hash,
+    ];
+}
+
+/// Commit row layout
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct DisplayConfig {
+    /// Fields of a commit row, left to right; fixed-width fields after the
+    /// subject are right-aligned
+    #[serde(deserialize_with = "deserialize_columns")]
+    pub columns: Vec<Column>,
+    /// Display columns of the author field (minimum: 1, default: 8)
+    #[serde(deserialize_with = "deserialize_author_width")]
+    pub author_width: usize,
+    /// Show full 40-character hashes in the hash column and parent list
+    /// instead of the 7-character short form
+    pub full_hashes: bool,
+}
+
+impl Default for DisplayConfig {
+    fn default() -> Self {
+        Self {
+            columns: Column::DEFAULT.to_vec(),
+            author_width: 8,
+            full_hashes: false,
+        }
+    }
+}
+
+/// Templates for commit messages keifu writes itself
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct MessagesConfig {
+    /// Merge commits: `{branch}`, `{head}`, `{oid}`, `{short}`, `{subject}`
+    pub merge: String,
+    /// Revert commits: `{oid}`, `{short}`, `{subject}` of the reverted commit
+    pub revert: String,
+}
+
+impl Default for MessagesConfig {
+    fn default() -> Self {
+        Self {
+            merge: "Merge branch '{branch}'".to_string(),
+            revert: "Revert \"{subject}\"\n\nThis reverts commit {oid}.\n".to_string(),
+        }
+    }
+}
+
+/// Branch list configuration
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct BranchesConfig {
+    /// Order of branch labels on a commit and of tied search results
+    pub sort: BranchSort,
+    /// Main branch merged branches are checked against (unset: detect from
+    /// origin/HEAD, then `main`, then `master`)
+    pub main: Option<String>,
+    /// Display of local branches fully merged into the main branch
+    pub merged: MergedBranches,
+    /// Enter in the "Go to branch" list: jump to the tip or also check the
+    /// branch out
+    pub enter: BranchEnter,
+}
+
+/// File diff view configuration
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct DiffConfig {
+    /// Emphasize the changed words within modified lines
+    pub word_diff: bool,
+    /// Color diff lines by the file's language (plain +/- coloring when off)
+    pub syntax_highlight: bool,
+    /// Whitespace handling for diff stats and hunks at startup
+    pub whitespace: WhitespaceMode,
+    /// Files listed per commit before "...and N more files"
+    pub max_files: usize,
+    /// Commit diffs kept for revisiting (minimum: 1, default: 64)
+    #[serde(deserialize_with = "deserialize_at_least_one")]
+    pub cache_size: usize,
+}
+
+impl Default for DiffConfig {
+    fn default() -> Self {
+        Self {
+            word_diff: true,
+            syntax_highlight: true,
+            whitespace: WhitespaceMode::default(),
+            max_files: DEFAULT_MAX_FILES,
+            cache_size: 64,
+        }
+    }
+}
+
+/// UI behavior configuration
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct UiConfig {
+    /// Briefly show the name of each triggered action (for learning
+    /// keybindings and debugging input handling)
+    pub show_actions: bool,
+    /// Branch, merge and tag jumps wrap around at the ends of the graph
+    pub wrap_jumps: bool,
+    /// Only navigate and inspect: refuse checkout, branch/tag, merge,
+    /// stash, staging, commit and remote operations
+    pub read_only: bool,
+    /// Show the seconds until the next auto-refresh and auto-fetch in the
+    /// status bar
+    pub show_timers: bool,
+    /// Show the commit detail and changed files panes below the graph
+    pub show_detail: bool,
+}
+
+impl Default for UiConfig {
+    fn default() -> Self {
+        Self {
+            show_actions: false,
+            wrap_jumps: false,
+            read_only: false,
+            show_timers: false,
+            show_detail: true,
+        }
+    }
 }
 
 /// Commit graph display configuration
@@ -18,12 +217,58 @@ pub struct Config {
 pub struct GraphConfig {
     /// Show remote branches and commits only reachable from remote branches
     pub show_remote_branches: bool,
+    /// Show a minimap column summarizing the whole loaded graph
+    pub show_minimap: bool,
+    /// Maximum width of the lane art in columns (0 = at most half the pane)
+    pub max_width: usize,
+    /// Annotate rows with lane/connector data (for debugging the layout)
+    pub debug_overlay: bool,
+    /// How branch lanes are colored
+    pub color_mode: ColorMode,
+    /// Seed for `color_mode = "deterministic"`
+    pub color_seed: u64,
+    /// Mark commits whose committer differs from the author with `~`
+    pub mark_rewritten: bool,
+    /// Show the number of parents on merge commits (`×2`, `×3`, ...)
+    pub parent_badge: bool,
+    /// Draw merge/branch-off connections on their own row below the commit
+    pub connector_rows: bool,
+    /// Fold lanes past this many into one shared overflow lane (0 = unlimited)
+    pub max_lanes: usize,
+    /// Separator rows between commits of different days/weeks (`── Today ──`)
+    pub date_headers: bool,
+    /// Color the date column from bright (newest loaded commit) to dim
+    /// (oldest)
+    pub age_heat: bool,
 }
 
 impl Default for GraphConfig {
     fn default() -> Self {
         Self {
             show_remote_branches: true,
+            show_minimap: false,
+            max_width: 0,
+            debug_overlay: false,
+            color_mode: ColorMode::default(),
+            color_seed: 0,
+            mark_rewritten: false,
+            parent_badge: false,
+            connector_rows: false,
+            max_lanes: 0,
+            date_headers: false,
+            age_heat: false,
+        }
+    }
+}
+
+impl GraphConfig {
+    /// Layout options for [`crate::git::build_graph`]
+    pub fn graph_options(&self) -> GraphOptions {
+        GraphOptions {
+            color_mode: self.color_mode,
+            color_seed: self.color_seed,
+            connector_rows: self.connector_rows,
+            max_lanes: self.max_lanes,
         }
     }
 }
@@ -42,6 +287,10 @@ pub struct RefreshConfig {
     /// Interval in seconds for remote fetch (minimum: 10, default: 60)
     #[serde(deserialize_with = "deserialize_fetch_interval")]
     pub fetch_interval: u64,
+    /// Refresh when the terminal regains focus
+    pub refresh_on_focus: bool,
+    /// Refresh when the terminal is resized
+    pub refresh_on_resize: bool,
 }
 
 impl Default for RefreshConfig {
@@ -51,6 +300,8 @@ impl Default for RefreshConfig {
             refresh_interval: 10,
             auto_fetch: true,
             fetch_interval: 60,
+            refresh_on_focus: false,
+            refresh_on_resize: false,
         }
     }
 }
@@ -71,21 +322,219 @@ where
     Ok(value.max(10))
 }
 
+fn deserialize_author_width<'de, D>(deserializer: D) -> Result<usize, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value = usize::deserialize(deserializer)?;
+    Ok(value.max(1))
+}
+
+fn deserialize_at_least_one<'de, D>(deserializer: D) -> Result<usize, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value = usize::deserialize(deserializer)?;
+    Ok(value.max(1))
+}
+
+/// Repeated columns keep their first position; an empty list means the default
+fn deserialize_columns<'de, D>(deserializer: D) -> Result<Vec<Column>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let mut columns = Vec::<Column>::deserialize(deserializer)?;
+    let mut seen = Vec::new();
+    columns.retain(|column| {
+        let first = !seen.contains(column);
+        seen.push(*column);
+        first
+    });
+    if columns.is_empty() {
+        columns = Column::DEFAULT.to_vec();
+    }
+    Ok(columns)
+}
+
+/// Expand a leading `~` and `$VAR` / `${VAR}` in a path taken from the
+/// environment or the config; unset variables are left as written
+pub fn expand_path(path: &str) -> PathBuf {
+    let mut expanded = String::new();
+    let mut rest = path;
+    if let Some(after) = path.strip_prefix('~') {
+        if after.is_empty() || after.starts_with('/') {
+            if let Some(home) = dirs::home_dir() {
+                expanded.push_str(&home.to_string_lossy());
+                rest = after;
+            }
+        }
+    }
+    while let Some(pos) = rest.find('$') {
+        expanded.push_str(&rest[..pos]);
+        let after = &rest[pos + 1..];
+        // Length of the reference after the `$`
+        let (name, len) = match after.strip_prefix('{') {
+            Some(braced) => match braced.find('}') {
+                Some(end) => (&braced[..end], end + 2),
+                None => ("", 0),
+            },
+            None => {
+                let end = after
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                    .unwrap_or(after.len());
+                (&after[..end], end)
+            }
+        };
+        match env::var(name) {
+            Ok(value) if !name.is_empty() => expanded.push_str(&value),
+            _ => expanded.push_str(&rest[pos..pos + 1 + len]),
+        }
+        rest = &rest[pos + 1 + len..];
+    }
+    expanded.push_str(rest);
+    PathBuf::from(expanded)
+}
+
 impl Config {
-    /// Load config from ~/.config/keifu/config.toml
+    /// Config file: `$KEIFU_CONFIG` (see [`expand_path`]) when set, else
+    /// ~/.config/keifu/config.toml
+    pub fn path() -> Option<PathBuf> {
+        match env::var(CONFIG_ENV) {
+            Ok(path) if !path.is_empty() => Some(expand_path(&path)),
+            _ => dirs::config_dir().map(|p| p.join("keifu/config.toml")),
+        }
+    }
+
+    /// Load config from [`Config::path`]
     /// Returns default config if file doesn't exist or is invalid
     pub fn load() -> Self {
-        let path = dirs::config_dir()
-            .map(|p| p.join("keifu/config.toml"))
-            .filter(|p| p.exists());
+        Self::try_load().unwrap_or_default()
+    }
+
+    /// Load config, reporting why the file couldn't be read or parsed
+    /// Returns default config if the default file doesn't exist; a missing
+    /// `$KEIFU_CONFIG` file is an error
+    pub fn try_load() -> Result<Self> {
+        let explicit = env::var_os(CONFIG_ENV).is_some_and(|path| !path.is_empty());
+        let path = Self::path().filter(|p| explicit || p.exists());
 
         let Some(path) = path else {
-            return Self::default();
+            return Ok(Self::default());
         };
 
-        fs::read_to_string(&path)
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        toml::from_str(&content).with_context(|| format!("Invalid config {}", path.display()))
+    }
+}
+
+/// Toggles remembered between sessions. They live in `state.toml` next to
+/// the config file, so the user's config is never rewritten.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct UiState {
+    /// Last `i` toggle; takes precedence over `ui.show_detail`
+    pub show_detail: Option<bool>,
+}
+
+impl UiState {
+    /// `state.toml` in the directory of [`Config::path`]
+    pub fn path() -> Option<PathBuf> {
+        Config::path().map(|path| path.with_file_name("state.toml"))
+    }
+
+    /// Load the saved state; a missing or unreadable file is the empty state
+    pub fn load(path: &Path) -> Self {
+        fs::read_to_string(path)
             .ok()
             .and_then(|content| toml::from_str(&content).ok())
             .unwrap_or_default()
     }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        fs::write(path, toml::to_string(self)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_columns_drop_repeats_and_fall_back_when_empty() {
+        let config: Config =
+            toml::from_str("[display]\ncolumns = ['graph', 'hash', 'subject', 'hash']").unwrap();
+        assert_eq!(
+            config.display.columns,
+            vec![Column::Graph, Column::Hash, Column::Subject]
+        );
+
+        let config: Config = toml::from_str("[display]\ncolumns = []").unwrap();
+        assert_eq!(config.display.columns, Column::DEFAULT.to_vec());
+
+        assert!(toml::from_str::<Config>("[display]\ncolumns = ['sha']").is_err());
+    }
+
+    #[test]
+    fn author_width_defaults_to_eight_and_is_at_least_one() {
+        assert_eq!(Config::default().display.author_width, 8);
+        let config: Config = toml::from_str("[display]\nauthor_width = 14").unwrap();
+        assert_eq!(config.display.author_width, 14);
+        let config: Config = toml::from_str("[display]\nauthor_width = 0").unwrap();
+        assert_eq!(config.display.author_width, 1);
+    }
+
+    #[test]
+    fn ui_state_round_trips_and_tolerates_bad_files() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let path = tempdir.path().join("keifu/state.toml");
+        assert_eq!(UiState::load(&path), UiState::default());
+
+        let state = UiState {
+            show_detail: Some(false),
+        };
+        state.save(&path).unwrap();
+        assert_eq!(UiState::load(&path), state);
+
+        fs::write(&path, "show_detail = 'maybe'").unwrap();
+        assert_eq!(UiState::load(&path), UiState::default());
+    }
+
+    #[test]
+    fn commit_batches_are_at_least_one() {
+        let config: Config = toml::from_str("[commits]\nbatch_size = 0").unwrap();
+        assert_eq!(config.commits.batch_size, 1);
+        assert_eq!(config.commits.initial_batch, 500);
+    }
+
+    #[test]
+    fn paths_expand_home_and_environment_variables() {
+        env::set_var("KEIFU_TEST_CONFIG_DIR", "/srv/keifu");
+        assert_eq!(
+            expand_path("$KEIFU_TEST_CONFIG_DIR/a.toml"),
+            PathBuf::from("/srv/keifu/a.toml")
+        );
+        assert_eq!(
+            expand_path("${KEIFU_TEST_CONFIG_DIR}x/$KEIFU_TEST_UNSET_VAR"),
+            PathBuf::from("/srv/keifux/$KEIFU_TEST_UNSET_VAR")
+        );
+        if let Some(home) = dirs::home_dir() {
+            assert_eq!(expand_path("~/k.toml"), home.join("k.toml"));
+        }
+        assert_eq!(expand_path("~user/$"), PathBuf::from("~user/$"));
+    }
+
+    #[test]
+    fn syntax_highlight_defaults_on() {
+        let config: Config = toml::from_str("[diff]\nword_diff = false").unwrap();
+        assert!(config.diff.syntax_highlight);
+        let config: Config = toml::from_str("[diff]\nsyntax_highlight = false").unwrap();
+        assert!(!config.diff.syntax_highlight);
+        assert!(config.diff.word_diff);
+    }
 }
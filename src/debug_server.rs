@@ -23,7 +23,7 @@ use serde::Deserialize;
 use serde_json::{json, Value};
 
 use crate::{
-    app::{App, AppMode, FocusedPane},
+    app::{App, AppMode, FocusedPane, GraphOrientation},
     keybindings::map_key_to_action,
     mouse, ui,
 };
@@ -184,11 +184,18 @@ fn state_json(app: &App) -> Value {
         AppMode::Error { .. } => "error",
         AppMode::FileSelect { .. } => "file_select",
         AppMode::FileDiff { .. } => "file_diff",
+        AppMode::TreeBrowser { .. } => "tree",
+        AppMode::BranchCompare { .. } => "compare",
+        AppMode::StashList { .. } => "stash_list",
     };
     let focused = match app.focused_pane {
         FocusedPane::Graph => "graph",
         FocusedPane::Detail => "detail",
     };
+    let orientation = match app.graph_orientation() {
+        GraphOrientation::Vertical => "vertical",
+        GraphOrientation::Horizontal => "horizontal",
+    };
     let selected = app.graph_list_state.selected();
     let selected_commit = selected
         .and_then(|idx| app.graph_layout.nodes.get(idx))
@@ -205,11 +212,33 @@ fn state_json(app: &App) -> Value {
         "head": app.head_name,
         "node_count": app.graph_layout.nodes.len(),
         "show_remote_branches": app.show_remote_branches(),
+        "show_minimap": app.show_minimap(),
+        "focus_mode": app.focus_mode(),
+        "show_detail": app.show_detail(),
+        "graph_orientation": orientation,
+        "show_graph": app.show_graph(),
+        "session_identity": app.session_identity().map(|identity| identity.to_string()),
+        "show_graph_debug": app.show_graph_debug(),
+        "show_rewritten_marker": app.show_rewritten_marker(),
+        "show_parent_badge": app.show_parent_badge(),
+        "show_date_headers": app.show_date_headers(),
+        "show_age_heat": app.show_age_heat(),
+        "full_hashes": app.full_hashes(),
+        "show_timers": app.show_timers(),
+        "timer_countdowns": app.timer_countdowns(),
+        "path_scope": app.path_scope(),
+        "path_scope_progress": app.path_scope_progress(),
+        "show_raw_commit": app.show_raw_commit(),
         "detail_scroll": app.detail_scroll,
         "help_scroll": app.help_scroll,
+        "confirm_scroll": app.confirm_scroll,
+        "help_query": app.help_query(),
+        "help_match": app.help_match(),
         "message": app.get_message(),
         "is_fetching": app.is_fetching(),
         "is_pushing": app.is_pushing(),
+        "remote_unsupported": app.remote_unsupported(),
+        "libgit2_ssh": app.capabilities().ssh,
     })
 }
 
@@ -1,23 +1,77 @@
 //! Branch info structure and operations
 
+use std::cmp::Reverse;
+use std::collections::HashSet;
+
 use anyhow::Result;
 use git2::{BranchType, Oid, Repository};
+use serde::Deserialize;
+
+/// Branch ordering for labels and search results (HEAD always comes first)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BranchSort {
+    /// Alphabetical
+    #[default]
+    Name,
+    /// Most recently committed tip first
+    Recent,
+    /// Most commits not yet in HEAD first
+    Ahead,
+}
+
+/// How local branches fully merged into the main branch are displayed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MergedBranches {
+    /// Like any other branch
+    #[default]
+    Show,
+    /// Grayed out
+    Dim,
+    /// Left out of the labels and branch navigation
+    Hide,
+}
+
+/// What confirming a branch in the "Go to branch" list does
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BranchEnter {
+    /// Select the branch tip in the graph without switching to it
+    #[default]
+    Goto,
+    /// Select the tip and check the branch out
+    Checkout,
+}
 
 #[derive(Debug, Clone)]
 pub struct BranchInfo {
     pub name: String,
     pub is_head: bool,
+    /// HEAD's branch, or with a detached HEAD the branch it was detached
+    /// from (`@{-1}`, where `-` returns to)
+    pub is_current: bool,
     pub is_remote: bool,
     pub upstream: Option<String>,
     pub tip_oid: Oid,
 }
 
+/// Branch HEAD was detached from, when HEAD is detached and the previous
+/// checkout (`@{-1}`) was a branch
+fn detached_from(repo: &Repository) -> Option<String> {
+    if !repo.head_detached().unwrap_or(false) {
+        return None;
+    }
+    let (_, reference) = repo.revparse_ext("@{-1}").ok()?;
+    reference
+        .filter(|r| r.is_branch())
+        .and_then(|r| r.shorthand().map(str::to_string))
+}
+
 impl BranchInfo {
     pub fn list_all(repo: &Repository, include_remotes: bool) -> Result<Vec<Self>> {
         let mut branches = Vec::new();
-
-        // Get HEAD
-        let head_oid = repo.head().ok().and_then(|r| r.target());
+        let detached_from = detached_from(repo);
 
         // Local branches
         for branch_result in repo.branches(Some(BranchType::Local))? {
@@ -25,12 +79,9 @@ impl BranchInfo {
             if let Some(name) = branch.name()? {
                 let reference = branch.get();
                 if let Some(oid) = reference.target() {
-                    let is_head = head_oid.map(|h| h == oid).unwrap_or(false)
-                        && repo
-                            .head()
-                            .ok()
-                            .and_then(|h| h.shorthand().map(|s| s == name))
-                            .unwrap_or(false);
+                    // Compares HEAD's symbolic target with the full ref name,
+                    // so it works the same for loose and packed refs
+                    let is_head = branch.is_head();
 
                     let upstream = branch
                         .upstream()
@@ -40,6 +91,7 @@ impl BranchInfo {
                     branches.push(BranchInfo {
                         name: name.to_string(),
                         is_head,
+                        is_current: is_head || detached_from.as_deref() == Some(name),
                         is_remote: false,
                         upstream,
                         tip_oid: oid,
@@ -58,6 +110,7 @@ impl BranchInfo {
                         branches.push(BranchInfo {
                             name: name.to_string(),
                             is_head: false,
+                            is_current: false,
                             is_remote: true,
                             upstream: None,
                             tip_oid: oid,
@@ -72,4 +125,73 @@ impl BranchInfo {
 
         Ok(branches)
     }
+
+    /// Reorder branches by `order`, keeping HEAD first and falling back to
+    /// the name for ties
+    pub fn sort(repo: &Repository, branches: &mut [BranchInfo], order: BranchSort) {
+        if order == BranchSort::Name {
+            return;
+        }
+        let head = repo.head().ok().and_then(|head| head.target());
+        branches.sort_by_cached_key(|branch| {
+            let key = match order {
+                BranchSort::Name => 0,
+                BranchSort::Recent => repo
+                    .find_commit(branch.tip_oid)
+                    .map_or(0, |commit| commit.time().seconds()),
+                BranchSort::Ahead => head
+                    .and_then(|head| repo.graph_ahead_behind(branch.tip_oid, head).ok())
+                    .map_or(0, |(ahead, _)| ahead as i64),
+            };
+            (Reverse(branch.is_head), Reverse(key), branch.name.clone())
+        });
+    }
+
+    /// Name of the main branch: `configured` if it exists, otherwise the
+    /// branch `origin/HEAD` points to, then `main`, then `master`
+    pub fn main_branch(
+        branches: &[BranchInfo],
+        repo: &Repository,
+        configured: Option<&str>,
+    ) -> Option<String> {
+        let exists = |name: &str| branches.iter().any(|b| !b.is_remote && b.name == name);
+        if let Some(name) = configured {
+            return exists(name).then(|| name.to_string());
+        }
+        let origin_head = repo
+            .find_reference("refs/remotes/origin/HEAD")
+            .ok()
+            .and_then(|r| r.symbolic_target().map(|t| t.to_string()))
+            .and_then(|t| {
+                t.strip_prefix("refs/remotes/origin/")
+                    .map(|s| s.to_string())
+            });
+        origin_head
+            .into_iter()
+            .chain(["main".to_string(), "master".to_string()])
+            .find(|name| exists(name))
+    }
+
+    /// Local branches (other than `main` and the current branch) whose tip
+    /// is reachable from `main`, i.e. what `git branch --merged main` lists
+    pub fn merged_into(repo: &Repository, branches: &[BranchInfo], main: &str) -> HashSet<String> {
+        let Some(main_tip) = branches
+            .iter()
+            .find(|b| !b.is_remote && b.name == main)
+            .map(|b| b.tip_oid)
+        else {
+            return HashSet::new();
+        };
+        branches
+            .iter()
+            .filter(|b| !b.is_remote && !b.is_head && b.name != main)
+            .filter(|b| {
+                b.tip_oid == main_tip
+                    || repo
+                        .graph_descendant_of(main_tip, b.tip_oid)
+                        .unwrap_or(false)
+            })
+            .map(|b| b.name.clone())
+            .collect()
+    }
 }
@@ -0,0 +1,144 @@
+//! libgit2 build information and transport support
+
+use std::process::{Command, Stdio};
+
+/// What the linked libgit2 was built with, plus the external commands
+/// remote operations depend on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GitCapabilities {
+    pub libgit2_version: (u32, u32, u32),
+    pub vendored: bool,
+    pub https: bool,
+    pub ssh: bool,
+    pub threads: bool,
+    /// `git` executable on PATH (fetch and push shell out to it)
+    pub git_cli: bool,
+    /// `ssh` executable on PATH (used by `git` for SSH remotes)
+    pub ssh_cli: bool,
+}
+
+impl GitCapabilities {
+    /// Query libgit2's compile-time features and look for external commands
+    pub fn detect() -> Self {
+        let version = git2::Version::get();
+        Self {
+            libgit2_version: version.libgit2_version(),
+            vendored: version.vendored(),
+            https: version.https(),
+            ssh: version.ssh(),
+            threads: version.threads(),
+            git_cli: command_available("git", "--version"),
+            ssh_cli: command_available("ssh", "-V"),
+        }
+    }
+
+    /// Why fetch/push cannot work against a remote with this URL, if they can't.
+    /// `custom_ssh` is true when git is configured with its own SSH command.
+    pub fn remote_support_error(&self, url: &str, custom_ssh: bool) -> Option<String> {
+        if !self.git_cli {
+            return Some(
+                "Remote operations need the `git` command, which was not found on PATH".to_string(),
+            );
+        }
+        if is_ssh_url(url) && !self.ssh_cli && !custom_ssh {
+            return Some(format!(
+                "'{url}' is an SSH remote but no `ssh` command was found; \
+                 install OpenSSH or set core.sshCommand"
+            ));
+        }
+        None
+    }
+
+    /// Names of the enabled features
+    pub fn features(&self) -> Vec<&'static str> {
+        [
+            ("https", self.https),
+            ("ssh", self.ssh),
+            ("threads", self.threads),
+        ]
+        .into_iter()
+        .filter_map(|(name, enabled)| enabled.then_some(name))
+        .collect()
+    }
+}
+
+fn command_available(program: &str, arg: &str) -> bool {
+    Command::new(program)
+        .arg(arg)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok()
+}
+
+/// `ssh://...`, `git+ssh://...` or scp-like `user@host:path`
+fn is_ssh_url(url: &str) -> bool {
+    if let Some((scheme, _)) = url.split_once("://") {
+        return scheme.contains("ssh");
+    }
+    // scp-like syntax has a colon before any slash (and isn't a Windows drive)
+    match url.find(':') {
+        Some(colon) => colon > 1 && !url[..colon].contains('/'),
+        None => false,
+    }
+}
+
+/// Multi-line version report for `--version` and bug reports
+pub fn build_info() -> String {
+    let caps = GitCapabilities::detect();
+    let (major, minor, patch) = caps.libgit2_version;
+    let features = caps.features();
+    format!(
+        "keifu {}\ngit2 {}, libgit2 {}.{}.{}{}\nfeatures: {}\ngit command: {}",
+        env!("CARGO_PKG_VERSION"),
+        git2::Version::get().crate_version(),
+        major,
+        minor,
+        patch,
+        if caps.vendored { " (vendored)" } else { "" },
+        if features.is_empty() {
+            "none".to_string()
+        } else {
+            features.join(", ")
+        },
+        if caps.git_cli { "found" } else { "not found" },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_ssh_urls() {
+        assert!(is_ssh_url("git@github.com:trasta298/keifu.git"));
+        assert!(is_ssh_url("ssh://git@example.com/repo.git"));
+        assert!(is_ssh_url("git+ssh://example.com/repo.git"));
+        assert!(!is_ssh_url("https://github.com/trasta298/keifu.git"));
+        assert!(!is_ssh_url("/srv/git/repo.git"));
+        assert!(!is_ssh_url("C:\\repos\\keifu"));
+        assert!(!is_ssh_url("./relative/path:with-colon"));
+    }
+
+    #[test]
+    fn ssh_remote_without_ssh_command_is_reported() {
+        let caps = GitCapabilities {
+            libgit2_version: (1, 8, 1),
+            vendored: true,
+            https: true,
+            ssh: false,
+            threads: true,
+            git_cli: true,
+            ssh_cli: false,
+        };
+        assert!(caps
+            .remote_support_error("git@github.com:a/b.git", false)
+            .is_some());
+        assert!(caps
+            .remote_support_error("git@github.com:a/b.git", true)
+            .is_none());
+        assert!(caps
+            .remote_support_error("https://github.com/a/b.git", false)
+            .is_none());
+    }
+}
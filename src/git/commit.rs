@@ -9,12 +9,22 @@ pub struct CommitInfo {
     pub short_id: String,
     pub author_name: String,
     pub author_email: String,
+    pub committer_name: String,
+    pub committer_email: String,
     pub timestamp: DateTime<Local>,
     pub message: String,
     pub full_message: String,
     pub parent_oids: Vec<Oid>,
 }
 
+/// A person credited with a `Co-authored-by:` trailer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoAuthor {
+    pub name: String,
+    /// Empty when the trailer has no address
+    pub email: String,
+}
+
 impl CommitInfo {
     pub fn from_git2_commit(commit: &git2::Commit) -> Self {
         let oid = commit.id();
@@ -24,6 +34,10 @@ impl CommitInfo {
         let author_name = author.name().unwrap_or("Unknown").to_string();
         let author_email = author.email().unwrap_or("").to_string();
 
+        let committer = commit.committer();
+        let committer_name = committer.name().unwrap_or("Unknown").to_string();
+        let committer_email = committer.email().unwrap_or("").to_string();
+
         let time = commit.time();
         let timestamp = Local.timestamp_opt(time.seconds(), 0).unwrap();
 
@@ -37,10 +51,120 @@ impl CommitInfo {
             short_id,
             author_name,
             author_email,
+            committer_name,
+            committer_email,
             timestamp,
             message,
             full_message,
             parent_oids,
         }
     }
+
+    /// Committed by someone other than the author (rebased, amended by
+    /// another person, cherry-picked, applied from a patch)
+    pub fn committer_differs(&self) -> bool {
+        self.committer_name != self.author_name || self.committer_email != self.author_email
+    }
+
+    /// People credited with `Co-authored-by:` trailers, in message order.
+    /// Matching is lenient: any case, spaces before the colon, and a missing
+    /// name or address are accepted. Repeats and the author are dropped.
+    pub fn co_authors(&self) -> Vec<CoAuthor> {
+        let mut co_authors: Vec<CoAuthor> = Vec::new();
+        for line in self.full_message.lines() {
+            let Some((key, value)) = line.trim().split_once(':') else {
+                continue;
+            };
+            if !key.trim().eq_ignore_ascii_case("co-authored-by") {
+                continue;
+            }
+            let Some(co_author) = parse_co_author(value) else {
+                continue;
+            };
+            let same_as = |email: &str, name: &str| {
+                if co_author.email.is_empty() || email.is_empty() {
+                    co_author.name.eq_ignore_ascii_case(name)
+                } else {
+                    co_author.email.eq_ignore_ascii_case(email)
+                }
+            };
+            if same_as(&self.author_email, &self.author_name)
+                || co_authors
+                    .iter()
+                    .any(|seen| same_as(&seen.email, &seen.name))
+            {
+                continue;
+            }
+            co_authors.push(co_author);
+        }
+        co_authors
+    }
+}
+
+/// `Name <email>`, `Name`, `<email>` or a bare `email@host`
+fn parse_co_author(value: &str) -> Option<CoAuthor> {
+    let value = value.trim();
+    let (name, email) = match value.split_once('<') {
+        Some((name, rest)) => (name.trim(), rest.trim_end_matches('>').trim()),
+        None if value.contains('@') && !value.contains(' ') => ("", value),
+        None => (value, ""),
+    };
+    if name.is_empty() && email.is_empty() {
+        return None;
+    }
+    let name = if name.is_empty() { email } else { name };
+    Some(CoAuthor {
+        name: name.to_string(),
+        email: email.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commit_with_message(full_message: &str) -> CommitInfo {
+        CommitInfo {
+            oid: Oid::zero(),
+            short_id: "0000000".to_string(),
+            author_name: "Ann".to_string(),
+            author_email: "ann@example.com".to_string(),
+            committer_name: "Ann".to_string(),
+            committer_email: "ann@example.com".to_string(),
+            timestamp: Local::now(),
+            message: full_message.lines().next().unwrap_or("").to_string(),
+            full_message: full_message.to_string(),
+            parent_oids: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn co_author_trailers_are_parsed_leniently() {
+        let commit = commit_with_message(
+            "Pair on the parser\n\n\
+             Body text: not a trailer\n\n\
+             Co-authored-by: Bob Builder <bob@example.com>\n\
+             co-authored-by :carol@example.com\n\
+             CO-AUTHORED-BY:   Dave  \n\
+             Co-authored-by: Bobby <BOB@example.com>\n\
+             Co-authored-by: Ann <ann@example.com>\n\
+             Co-authored-by:\n",
+        );
+        let names: Vec<_> = commit
+            .co_authors()
+            .into_iter()
+            .map(|c| (c.name, c.email))
+            .collect();
+        assert_eq!(
+            names,
+            [
+                ("Bob Builder".to_string(), "bob@example.com".to_string()),
+                (
+                    "carol@example.com".to_string(),
+                    "carol@example.com".to_string()
+                ),
+                ("Dave".to_string(), String::new()),
+            ]
+        );
+    }
 }
@@ -0,0 +1,257 @@
+//! Side-by-side comparison of two branches: each side lists its branch's
+//! first-parent history down to a little past the merge base, on a shared
+//! timeline so commits on both sides line up
+
+use std::collections::HashSet;
+
+use anyhow::Result;
+use git2::{Oid, Repository};
+
+use super::CommitInfo;
+
+/// Commits listed per side at most
+const MAX_COMPARE_COMMITS: usize = 500;
+/// Shared commits shown below the merge base
+const CONTEXT_BELOW_BASE: usize = 3;
+
+/// One branch being compared
+#[derive(Debug, Clone)]
+pub struct CompareSide {
+    pub name: String,
+    pub tip: Oid,
+}
+
+/// One row of the shared timeline
+#[derive(Debug, Clone)]
+pub struct CompareRow {
+    pub left: Option<CommitInfo>,
+    pub right: Option<CommitInfo>,
+}
+
+impl CompareRow {
+    /// Whether both branches have this commit
+    pub fn is_shared(&self) -> bool {
+        matches!((&self.left, &self.right), (Some(l), Some(r)) if l.oid == r.oid)
+    }
+
+    pub fn commit(&self, right: bool) -> Option<&CommitInfo> {
+        if right {
+            self.right.as_ref()
+        } else {
+            self.left.as_ref()
+        }
+    }
+}
+
+/// State of the branch comparison view
+#[derive(Debug, Clone)]
+pub struct BranchCompare {
+    pub left: CompareSide,
+    pub right: CompareSide,
+    pub rows: Vec<CompareRow>,
+    /// Merge base of the two tips
+    pub merge_base: Option<Oid>,
+    pub selected: usize,
+    /// First listed row, kept in view of the selection by the renderer
+    pub scroll: usize,
+    /// Whether the cursor is in the right pane
+    pub focus_right: bool,
+    /// Merge base of the selected commit and the other branch's tip
+    pub highlight: Option<Oid>,
+}
+
+impl BranchCompare {
+    pub fn open(repo: &Repository, left: CompareSide, right: CompareSide) -> Result<Self> {
+        let merge_base = repo.merge_base(left.tip, right.tip).ok();
+        let left_commits = first_parent_history(repo, left.tip, merge_base)?;
+        let right_commits = first_parent_history(repo, right.tip, merge_base)?;
+        let mut compare = Self {
+            left,
+            right,
+            rows: align(left_commits, right_commits),
+            merge_base,
+            selected: 0,
+            scroll: 0,
+            focus_right: false,
+            highlight: None,
+        };
+        compare.update_highlight(repo);
+        Ok(compare)
+    }
+
+    /// The commit under the cursor in the focused pane
+    pub fn selected_commit(&self) -> Option<&CommitInfo> {
+        self.rows.get(self.selected)?.commit(self.focus_right)
+    }
+
+    pub fn move_selection(&mut self, repo: &Repository, delta: isize) {
+        let max = self.rows.len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(delta).min(max);
+        self.update_highlight(repo);
+    }
+
+    pub fn focus(&mut self, repo: &Repository, right: bool) {
+        self.focus_right = right;
+        self.update_highlight(repo);
+    }
+
+    /// Recompute where the selected commit's history meets the other branch
+    pub fn update_highlight(&mut self, repo: &Repository) {
+        let other = if self.focus_right {
+            self.left.tip
+        } else {
+            self.right.tip
+        };
+        self.highlight = self
+            .selected_commit()
+            .and_then(|commit| repo.merge_base(commit.oid, other).ok());
+    }
+}
+
+/// First-parent history of `tip`, newest first, ending a few commits past
+/// `base` (or at the cap when the walk never reaches it)
+fn first_parent_history(repo: &Repository, tip: Oid, base: Option<Oid>) -> Result<Vec<CommitInfo>> {
+    let mut commits = Vec::new();
+    let mut below_base = None;
+    let mut next = Some(repo.find_commit(tip)?);
+    while let Some(commit) = next {
+        if commits.len() >= MAX_COMPARE_COMMITS || below_base == Some(0) {
+            break;
+        }
+        if base == Some(commit.id()) {
+            below_base = Some(CONTEXT_BELOW_BASE + 1);
+        }
+        below_base = below_base.map(|n: usize| n - 1);
+        commits.push(CommitInfo::from_git2_commit(&commit));
+        next = commit.parent(0).ok();
+    }
+    Ok(commits)
+}
+
+/// Merge two newest-first histories into one timeline. Commits on both
+/// sides share a row; the others are ordered by commit time, and a commit
+/// the other side also has waits until that side catches up
+fn align(left: Vec<CommitInfo>, right: Vec<CommitInfo>) -> Vec<CompareRow> {
+    let left_oids: HashSet<Oid> = left.iter().map(|c| c.oid).collect();
+    let right_oids: HashSet<Oid> = right.iter().map(|c| c.oid).collect();
+    let mut left = left.into_iter().peekable();
+    let mut right = right.into_iter().peekable();
+    let mut rows = Vec::new();
+    loop {
+        let take_left = match (left.peek(), right.peek()) {
+            (None, None) => break,
+            (Some(l), Some(r)) if l.oid == r.oid => {
+                rows.push(CompareRow {
+                    left: left.next(),
+                    right: right.next(),
+                });
+                continue;
+            }
+            (Some(l), Some(r)) => {
+                let l_waits = right_oids.contains(&l.oid);
+                let r_waits = left_oids.contains(&r.oid);
+                match (l_waits, r_waits) {
+                    (false, true) => true,
+                    (true, false) => false,
+                    _ => l.timestamp >= r.timestamp,
+                }
+            }
+            (Some(_), None) => true,
+            (None, Some(_)) => false,
+        };
+        rows.push(if take_left {
+            CompareRow {
+                left: left.next(),
+                right: None,
+            }
+        } else {
+            CompareRow {
+                left: None,
+                right: right.next(),
+            }
+        });
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use git2::{Signature, Time};
+
+    use super::*;
+
+    fn commit_at(repo: &Repository, parent: Option<Oid>, message: &str, time: i64) -> Oid {
+        let sig = Signature::new("Test", "test@example.com", &Time::new(time, 0)).unwrap();
+        let tree = repo
+            .find_tree(repo.treebuilder(None).unwrap().write().unwrap())
+            .unwrap();
+        let parents: Vec<git2::Commit> = parent
+            .into_iter()
+            .map(|oid| repo.find_commit(oid).unwrap())
+            .collect();
+        let parents: Vec<&git2::Commit> = parents.iter().collect();
+        repo.commit(None, &sig, &sig, message, &tree, &parents)
+            .unwrap()
+    }
+
+    fn side(name: &str, tip: Oid) -> CompareSide {
+        CompareSide {
+            name: name.to_string(),
+            tip,
+        }
+    }
+
+    #[test]
+    fn diverged_commits_interleave_and_shared_history_lines_up() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(tempdir.path()).unwrap();
+        let mut parent = None;
+        let mut shared = Vec::new();
+        for i in 0..6 {
+            let oid = commit_at(&repo, parent, &format!("shared {i}"), 100 + i);
+            shared.push(oid);
+            parent = Some(oid);
+        }
+        let base = parent.unwrap();
+        let a1 = commit_at(&repo, Some(base), "a1", 200);
+        let a2 = commit_at(&repo, Some(a1), "a2", 400);
+        let b1 = commit_at(&repo, Some(base), "b1", 300);
+
+        let mut compare = BranchCompare::open(&repo, side("a", a2), side("b", b1)).unwrap();
+
+        let summary: Vec<(Option<&str>, Option<&str>)> = compare
+            .rows
+            .iter()
+            .map(|row| {
+                (
+                    row.left.as_ref().map(|c| c.message.as_str()),
+                    row.right.as_ref().map(|c| c.message.as_str()),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (Some("a2"), None),
+                (None, Some("b1")),
+                (Some("a1"), None),
+                (Some("shared 5"), Some("shared 5")),
+                (Some("shared 4"), Some("shared 4")),
+                (Some("shared 3"), Some("shared 3")),
+                (Some("shared 2"), Some("shared 2")),
+            ]
+        );
+        assert_eq!(compare.merge_base, Some(base));
+        assert!(compare.rows[3].is_shared());
+
+        // a2's history meets b at the merge base; shared commits meet
+        // themselves
+        assert_eq!(compare.highlight, Some(base));
+        compare.move_selection(&repo, 5);
+        assert_eq!(compare.highlight, Some(shared[3]));
+        compare.move_selection(&repo, -4);
+        compare.focus(&repo, true);
+        assert_eq!(compare.selected_commit().unwrap().oid, b1);
+        assert_eq!(compare.highlight, Some(base));
+    }
+}
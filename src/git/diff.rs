@@ -7,12 +7,13 @@ use std::path::{Path, PathBuf};
 
 use anyhow::Result;
 use git2::{
-    AttrCheckFlags, AttrValue, Delta, Diff, DiffDelta, DiffLineType, DiffOptions, ErrorCode, Oid,
-    Patch, Repository, Status, StatusOptions, Tree,
+    AttrCheckFlags, AttrValue, Delta, Diff, DiffDelta, DiffFindOptions, DiffLineType, DiffOptions,
+    ErrorCode, Oid, Patch, Repository, Status, StatusOptions, Tree,
 };
+use serde::Deserialize;
 
-/// Maximum number of files to display
-const MAX_FILES_TO_DISPLAY: usize = 50;
+/// Files listed per diff unless `diff.max_files` says otherwise
+pub const DEFAULT_MAX_FILES: usize = 50;
 
 /// Maximum file size (bytes) to read for line counting; larger files are treated as binary
 const MAX_TEXT_FILE_SIZE: u64 = 10 * 1024 * 1024;
@@ -27,6 +28,46 @@ pub enum FileChangeKind {
     Copied,
 }
 
+/// Whitespace handling for diffs, like `git diff` with no flag,
+/// `--ignore-space-at-eol`, `-b` and `-w`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WhitespaceMode {
+    #[default]
+    Show,
+    IgnoreEol,
+    IgnoreChange,
+    IgnoreAll,
+}
+
+impl WhitespaceMode {
+    /// The next mode in the cycle
+    pub fn next(self) -> Self {
+        match self {
+            WhitespaceMode::Show => WhitespaceMode::IgnoreEol,
+            WhitespaceMode::IgnoreEol => WhitespaceMode::IgnoreChange,
+            WhitespaceMode::IgnoreChange => WhitespaceMode::IgnoreAll,
+            WhitespaceMode::IgnoreAll => WhitespaceMode::Show,
+        }
+    }
+
+    /// What is being ignored, for titles and messages (None when nothing is)
+    pub fn label(self) -> Option<&'static str> {
+        match self {
+            WhitespaceMode::Show => None,
+            WhitespaceMode::IgnoreEol => Some("ignoring whitespace at EOL"),
+            WhitespaceMode::IgnoreChange => Some("ignoring whitespace changes"),
+            WhitespaceMode::IgnoreAll => Some("ignoring all whitespace"),
+        }
+    }
+
+    fn apply(self, opts: &mut DiffOptions) {
+        opts.ignore_whitespace_eol(self == WhitespaceMode::IgnoreEol);
+        opts.ignore_whitespace_change(self == WhitespaceMode::IgnoreChange);
+        opts.ignore_whitespace(self == WhitespaceMode::IgnoreAll);
+    }
+}
+
 /// Per-file diff info
 #[derive(Debug, Clone)]
 pub struct FileDiffInfo {
@@ -45,7 +86,7 @@ pub struct FileDiffInfo {
 /// Commit diff info
 #[derive(Debug, Clone, Default)]
 pub struct CommitDiffInfo {
-    /// Changed files list (up to MAX_FILES_TO_DISPLAY)
+    /// Changed files list (up to the `max_files` it was built with)
     pub files: Vec<FileDiffInfo>,
     /// Total insertions
     pub total_insertions: usize,
@@ -63,19 +104,24 @@ struct DiffScan {
     files: Vec<FileDiffInfo>,
     all_paths: HashSet<PathBuf>,
     deferred_paths: HashSet<PathBuf>,
+    /// Insertions and deletions over every changed file, including the ones
+    /// whose per-file counts were skipped
+    line_totals: (usize, usize),
 }
 
-impl DiffScan {
-    fn line_totals(&self) -> (usize, usize) {
-        let insertions = self.files.iter().map(|file| file.insertions).sum();
-        let deletions = self.files.iter().map(|file| file.deletions).sum();
-        (insertions, deletions)
+impl CommitDiffInfo {
+    /// Get diff info for working tree (staged + unstaged + untracked changes),
+    /// listing at most `max_files` files
+    pub fn from_working_tree(repo: &Repository, max_files: usize) -> Result<Self> {
+        Self::from_working_tree_with_whitespace(repo, WhitespaceMode::Show, max_files)
     }
-}
 
-impl CommitDiffInfo {
-    /// Get diff info for working tree (staged + unstaged + untracked changes)
-    pub fn from_working_tree(repo: &Repository) -> Result<Self> {
+    /// [`Self::from_working_tree`] with whitespace changes ignored per `whitespace`
+    pub fn from_working_tree_with_whitespace(
+        repo: &Repository,
+        whitespace: WhitespaceMode,
+        max_files: usize,
+    ) -> Result<Self> {
         let head_tree = match repo.head() {
             Ok(head) => Some(head.peel_to_tree()?),
             Err(err)
@@ -89,6 +135,7 @@ impl CommitDiffInfo {
         let mut opts = DiffOptions::new();
         opts.ignore_submodules(true);
         opts.context_lines(0);
+        whitespace.apply(&mut opts);
 
         // Staged changes: HEAD -> index
         let staged_diff = repo.diff_tree_to_index(head_tree.as_ref(), None, Some(&mut opts))?;
@@ -96,15 +143,14 @@ impl CommitDiffInfo {
         // Unstaged tracked changes: index -> workdir
         let unstaged_diff = repo.diff_index_to_workdir(None, Some(&mut opts))?;
         let workdir = repo.workdir().unwrap_or_else(|| repo.path());
-        let staged_result = Self::scan_diff(&staged_diff)?;
-        let unstaged_result = Self::scan_diff(&unstaged_diff)?;
+        let staged_result = Self::scan_diff(&staged_diff, usize::MAX)?;
+        let unstaged_result = Self::scan_diff(&unstaged_diff, usize::MAX)?;
         let refresh_paths: HashSet<PathBuf> = staged_result
             .all_paths
             .intersection(&unstaged_result.all_paths)
             .cloned()
             .collect();
-        let untracked_display_limit = MAX_FILES_TO_DISPLAY;
-        let untracked_result = Self::scan_untracked_worktree(repo, untracked_display_limit)?;
+        let untracked_result = Self::scan_untracked_worktree(repo, max_files)?;
         let mut worktree_refresh_paths = HashSet::new();
         let mut scan = Self::merge_scans(
             [staged_result, unstaged_result, untracked_result],
@@ -115,19 +161,31 @@ impl CommitDiffInfo {
             repo,
             head_tree.as_ref(),
             &mut scan,
-            workdir,
             &refresh_paths,
             &worktree_refresh_paths,
             &staged_diff,
+            whitespace,
         )?;
-        Self::build_info(scan, Some((total_insertions, total_deletions)))
+        Self::build_info(scan, Some((total_insertions, total_deletions)), max_files)
     }
 
     /// Get diff info for a commit
     /// - Normal commit: diff vs parent
     /// - Merge commit: diff vs first parent
     /// - Initial commit: diff vs empty tree
-    pub fn from_commit(repo: &Repository, commit_oid: Oid) -> Result<Self> {
+    ///
+    /// Lists at most `max_files` files; the totals still cover every file
+    pub fn from_commit(repo: &Repository, commit_oid: Oid, max_files: usize) -> Result<Self> {
+        Self::from_commit_with_whitespace(repo, commit_oid, WhitespaceMode::Show, max_files)
+    }
+
+    /// [`Self::from_commit`] with whitespace changes ignored per `whitespace`
+    pub fn from_commit_with_whitespace(
+        repo: &Repository,
+        commit_oid: Oid,
+        whitespace: WhitespaceMode,
+        max_files: usize,
+    ) -> Result<Self> {
         let commit = repo.find_commit(commit_oid)?;
         let new_tree = commit.tree()?;
 
@@ -143,14 +201,46 @@ impl CommitDiffInfo {
         opts.minimal(false); // Skip minimal diff calculation
         opts.ignore_submodules(true); // Skip submodules
         opts.context_lines(0); // Set context lines to 0
+        whitespace.apply(&mut opts);
 
         let diff = repo.diff_tree_to_tree(old_tree.as_ref(), Some(&new_tree), Some(&mut opts))?;
 
-        Self::build_info(Self::scan_diff(&diff)?, None)
+        Self::build_info(Self::scan_diff(&diff, max_files)?, None, max_files)
+    }
+
+    /// Get the cumulative diff between two commits (`git diff base head`)
+    pub fn from_range(repo: &Repository, base: Oid, head: Oid, max_files: usize) -> Result<Self> {
+        Self::from_range_with_whitespace(repo, base, head, WhitespaceMode::Show, max_files)
     }
 
-    fn scan_diff(diff: &Diff) -> Result<DiffScan> {
-        let _ = diff.stats()?;
+    /// [`Self::from_range`] with whitespace changes ignored per `whitespace`
+    pub fn from_range_with_whitespace(
+        repo: &Repository,
+        base: Oid,
+        head: Oid,
+        whitespace: WhitespaceMode,
+        max_files: usize,
+    ) -> Result<Self> {
+        let old_tree = repo.find_commit(base)?.tree()?;
+        let new_tree = repo.find_commit(head)?.tree()?;
+
+        let mut opts = DiffOptions::new();
+        opts.minimal(false);
+        opts.ignore_submodules(true);
+        opts.context_lines(0);
+        whitespace.apply(&mut opts);
+
+        let diff = repo.diff_tree_to_tree(Some(&old_tree), Some(&new_tree), Some(&mut opts))?;
+
+        Self::build_info(Self::scan_diff(&diff, max_files)?, None, max_files)
+    }
+
+    /// Scan the deltas of `diff`. Only the first `counted_files` files get
+    /// per-file line counts, each of which regenerates the file's patch;
+    /// the totals come from one `diff.stats()` pass over the whole diff
+    fn scan_diff(diff: &Diff, counted_files: usize) -> Result<DiffScan> {
+        // Also loads the blobs, which sets the binary flag on each delta
+        let stats = diff.stats()?;
         let mut files = Vec::with_capacity(diff.deltas().len());
         let mut all_paths = HashSet::new();
 
@@ -162,7 +252,7 @@ impl CommitDiffInfo {
             let path_buf = path.to_path_buf();
             all_paths.insert(path_buf.clone());
 
-            let (insertions, deletions) = if is_binary {
+            let (insertions, deletions) = if is_binary || files.len() >= counted_files {
                 (0, 0)
             } else {
                 Self::line_stats_for_delta(diff, delta_idx)?
@@ -180,6 +270,7 @@ impl CommitDiffInfo {
             files,
             all_paths,
             deferred_paths: HashSet::new(),
+            line_totals: (stats.insertions(), stats.deletions()),
         })
     }
 
@@ -226,6 +317,7 @@ impl CommitDiffInfo {
             files,
             all_paths,
             deferred_paths,
+            line_totals: (0, 0),
         })
     }
 
@@ -308,10 +400,14 @@ impl CommitDiffInfo {
             }
         }
 
+        let line_totals = files.iter().fold((0, 0), |(ins, del), file| {
+            (ins + file.insertions, del + file.deletions)
+        });
         Ok(DiffScan {
             files,
             all_paths,
             deferred_paths,
+            line_totals,
         })
     }
 
@@ -319,14 +415,16 @@ impl CommitDiffInfo {
         repo: &Repository,
         head_tree: Option<&Tree<'_>>,
         scan: &mut DiffScan,
-        workdir: &Path,
         refresh_paths: &HashSet<PathBuf>,
         worktree_refresh_paths: &HashSet<PathBuf>,
         staged_diff: &Diff,
+        whitespace: WhitespaceMode,
     ) -> Result<(usize, usize)> {
+        let workdir = repo.workdir().unwrap_or_else(|| repo.path());
         let mut worktree_opts = DiffOptions::new();
         worktree_opts.ignore_submodules(true);
         worktree_opts.context_lines(0);
+        whitespace.apply(&mut worktree_opts);
         worktree_opts.include_untracked(true);
         worktree_opts.recurse_untracked_dirs(true);
         worktree_opts.show_untracked_content(true);
@@ -404,11 +502,15 @@ impl CommitDiffInfo {
         Ok((total_insertions, total_deletions))
     }
 
-    fn build_info(scan: DiffScan, totals: Option<(usize, usize)>) -> Result<Self> {
+    fn build_info(
+        scan: DiffScan,
+        totals: Option<(usize, usize)>,
+        max_files: usize,
+    ) -> Result<Self> {
         let total_files = scan.all_paths.len();
-        let (total_insertions, total_deletions) = totals.unwrap_or_else(|| scan.line_totals());
-        let truncated = total_files > MAX_FILES_TO_DISPLAY;
-        let files = scan.files.into_iter().take(MAX_FILES_TO_DISPLAY).collect();
+        let (total_insertions, total_deletions) = totals.unwrap_or(scan.line_totals);
+        let truncated = total_files > max_files;
+        let files = scan.files.into_iter().take(max_files).collect();
 
         Ok(Self {
             files,
@@ -665,6 +767,8 @@ pub struct DiffHunkContent {
 #[derive(Debug, Clone)]
 pub struct FileDiffContent {
     pub path: PathBuf,
+    /// Path on the old side; differs from `path` for a rename
+    pub old_path: PathBuf,
     pub kind: FileChangeKind,
     pub is_binary: bool,
     pub hunks: Vec<DiffHunkContent>,
@@ -674,7 +778,12 @@ pub struct FileDiffContent {
 
 impl FileDiffContent {
     /// Get full diff content for a single file in a commit
-    pub fn from_commit(repo: &Repository, commit_oid: Oid, file_path: &Path) -> Result<Self> {
+    pub fn from_commit(
+        repo: &Repository,
+        commit_oid: Oid,
+        file_path: &Path,
+        whitespace: WhitespaceMode,
+    ) -> Result<Self> {
         let commit = repo.find_commit(commit_oid)?;
         let new_tree = commit.tree()?;
 
@@ -684,19 +793,65 @@ impl FileDiffContent {
             None
         };
 
-        let mut opts = DiffOptions::new();
-        opts.ignore_submodules(true);
-        opts.context_lines(3);
+        Self::from_trees(repo, old_tree.as_ref(), &new_tree, file_path, whitespace)
+    }
+
+    /// Get full diff content for a single file between two commits
+    pub fn from_range(
+        repo: &Repository,
+        base: Oid,
+        head: Oid,
+        file_path: &Path,
+        whitespace: WhitespaceMode,
+    ) -> Result<Self> {
+        let old_tree = repo.find_commit(base)?.tree()?;
+        let new_tree = repo.find_commit(head)?.tree()?;
+
+        Self::from_trees(repo, Some(&old_tree), &new_tree, file_path, whitespace)
+    }
+
+    /// Diff of `file_path` between two trees. A file that looks added may
+    /// be a rename, so the whole diff is searched for its old name; the old
+    /// side then shows (and blames) the file under that name.
+    fn from_trees(
+        repo: &Repository,
+        old_tree: Option<&Tree>,
+        new_tree: &Tree,
+        file_path: &Path,
+        whitespace: WhitespaceMode,
+    ) -> Result<Self> {
+        let mut opts = Self::diff_options(whitespace);
         opts.pathspec(file_path);
         opts.disable_pathspec_match(true);
 
-        let diff = repo.diff_tree_to_tree(old_tree.as_ref(), Some(&new_tree), Some(&mut opts))?;
-
-        Self::from_diff(&diff, file_path)
+        let diff = repo.diff_tree_to_tree(old_tree, Some(new_tree), Some(&mut opts))?;
+        let added = diff
+            .deltas()
+            .next()
+            .is_some_and(|delta| delta.status() == Delta::Added);
+        if added && old_tree.is_some() {
+            let mut full = repo.diff_tree_to_tree(
+                old_tree,
+                Some(new_tree),
+                Some(&mut Self::diff_options(whitespace)),
+            )?;
+            full.find_similar(Some(DiffFindOptions::new().renames(true)))?;
+            let renamed = full.deltas().position(|delta| {
+                delta.status() == Delta::Renamed && delta.new_file().path() == Some(file_path)
+            });
+            if let Some(idx) = renamed {
+                return Self::from_diff(&full, idx, file_path);
+            }
+        }
+        Self::from_diff(&diff, 0, file_path)
     }
 
     /// Get full diff content for a single file in the working tree (HEAD+index → workdir)
-    pub fn from_working_tree(repo: &Repository, file_path: &Path) -> Result<Self> {
+    pub fn from_working_tree(
+        repo: &Repository,
+        file_path: &Path,
+        whitespace: WhitespaceMode,
+    ) -> Result<Self> {
         let head_tree = match repo.head() {
             Ok(head) => Some(head.peel_to_tree()?),
             Err(err)
@@ -707,9 +862,7 @@ impl FileDiffContent {
             Err(err) => return Err(err.into()),
         };
 
-        let mut opts = DiffOptions::new();
-        opts.ignore_submodules(true);
-        opts.context_lines(3);
+        let mut opts = Self::diff_options(whitespace);
         opts.pathspec(file_path);
         opts.disable_pathspec_match(true);
         opts.include_untracked(true);
@@ -718,12 +871,24 @@ impl FileDiffContent {
 
         let diff = repo.diff_tree_to_workdir_with_index(head_tree.as_ref(), Some(&mut opts))?;
 
-        Self::from_diff(&diff, file_path)
+        Self::from_diff(&diff, 0, file_path)
+    }
+
+    fn diff_options(whitespace: WhitespaceMode) -> DiffOptions {
+        let mut opts = DiffOptions::new();
+        opts.ignore_submodules(true);
+        opts.context_lines(3);
+        whitespace.apply(&mut opts);
+        opts
     }
 
-    fn from_diff(diff: &Diff, file_path: &Path) -> Result<Self> {
-        // Determine file kind and binary status from the first delta
-        let (kind, is_binary) = if let Some(delta) = diff.deltas().next() {
+    fn from_diff(diff: &Diff, idx: usize, file_path: &Path) -> Result<Self> {
+        let old_path = diff
+            .get_delta(idx)
+            .and_then(|delta| delta.old_file().path().map(Path::to_path_buf))
+            .unwrap_or_else(|| file_path.to_path_buf());
+        // Determine file kind and binary status from the delta
+        let (kind, is_binary) = if let Some(delta) = diff.get_delta(idx) {
             let kind = match delta.status() {
                 Delta::Added | Delta::Untracked => FileChangeKind::Added,
                 Delta::Deleted => FileChangeKind::Deleted,
@@ -742,6 +907,7 @@ impl FileDiffContent {
         if diff.deltas().len() == 0 || is_binary {
             return Ok(Self {
                 path: file_path.to_path_buf(),
+                old_path,
                 kind,
                 is_binary,
                 hunks: Vec::new(),
@@ -750,9 +916,10 @@ impl FileDiffContent {
             });
         }
 
-        let Some(patch) = Patch::from_diff(diff, 0)? else {
+        let Some(patch) = Patch::from_diff(diff, idx)? else {
             return Ok(Self {
                 path: file_path.to_path_buf(),
+                old_path,
                 kind,
                 is_binary: false,
                 hunks: Vec::new(),
@@ -804,6 +971,7 @@ impl FileDiffContent {
 
         Ok(Self {
             path: file_path.to_path_buf(),
+            old_path,
             kind,
             is_binary,
             hunks,
@@ -0,0 +1,93 @@
+//! Recently viewed commit diffs, so moving back to a commit doesn't compute
+//! its diff again
+
+use std::collections::VecDeque;
+
+use git2::Oid;
+
+use super::CommitDiffInfo;
+
+/// Least recently used cache of commit diffs. A commit's diff never
+/// changes, so entries only go stale when the diff options do.
+#[derive(Debug)]
+pub struct DiffCache {
+    capacity: usize,
+    /// Most recently used first; `None` records a failed load so it isn't
+    /// retried right away
+    entries: VecDeque<(Oid, Option<CommitDiffInfo>)>,
+}
+
+impl DiffCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            entries: VecDeque::new(),
+        }
+    }
+
+    /// Whether a result (diff or failure) is cached for `oid`
+    pub fn contains(&self, oid: Oid) -> bool {
+        self.entries.iter().any(|(entry, _)| *entry == oid)
+    }
+
+    /// Cached diff of `oid`; None when it isn't cached or failed to load
+    pub fn get(&self, oid: Oid) -> Option<&CommitDiffInfo> {
+        self.entries
+            .iter()
+            .find(|(entry, _)| *entry == oid)
+            .and_then(|(_, diff)| diff.as_ref())
+    }
+
+    /// Mark `oid` as the most recently used entry
+    pub fn touch(&mut self, oid: Oid) {
+        if let Some(pos) = self.entries.iter().position(|(entry, _)| *entry == oid) {
+            if pos > 0 {
+                let entry = self.entries.remove(pos).unwrap();
+                self.entries.push_front(entry);
+            }
+        }
+    }
+
+    /// Store the result for `oid`, dropping the least recently used entry
+    /// when full
+    pub fn insert(&mut self, oid: Oid, diff: Option<CommitDiffInfo>) {
+        self.entries.retain(|(entry, _)| *entry != oid);
+        self.entries.push_front((oid, diff));
+        self.entries.truncate(self.capacity);
+    }
+
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity.max(1);
+        self.entries.truncate(self.capacity);
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn oid(n: u8) -> Oid {
+        Oid::from_bytes(&[n; 20]).unwrap()
+    }
+
+    #[test]
+    fn least_recently_used_diff_is_dropped_first() {
+        let mut cache = DiffCache::new(2);
+        cache.insert(oid(1), Some(CommitDiffInfo::default()));
+        cache.insert(oid(2), None);
+        cache.touch(oid(1));
+        cache.insert(oid(3), Some(CommitDiffInfo::default()));
+
+        assert!(cache.get(oid(1)).is_some());
+        assert!(!cache.contains(oid(2)));
+        assert!(cache.contains(oid(3)));
+
+        cache.set_capacity(1);
+        assert!(cache.contains(oid(3)));
+        assert!(!cache.contains(oid(1)));
+    }
+}
@@ -5,9 +5,11 @@ use std::collections::HashMap;
 use git2::Oid;
 
 use super::{BranchInfo, CommitInfo};
-use crate::graph::colors::{ColorAssigner, UNCOMMITTED_COLOR_INDEX};
+use crate::graph::colors::{ColorAssigner, ColorMode, UNCOMMITTED_COLOR_INDEX};
 
-/// Graph node
+/// Graph node: one row of the graph. A row is a commit, the uncommitted
+/// changes pseudo-commit, or a connector-only row (fork junctions and, with
+/// `graph.connector_rows`, parent links) that has no commit of its own.
 #[derive(Debug, Clone)]
 pub struct GraphNode {
     /// Commit info (None means connector row only or uncommitted changes row)
@@ -16,10 +18,16 @@ pub struct GraphNode {
     pub lane: usize,
     /// Color index for this node
     pub color_index: usize,
-    /// Branch names pointing to this commit
-    pub branch_names: Vec<String>,
+    /// Branches pointing to this commit
+    pub labels: Vec<RefLabel>,
+    /// Tags pointing to this commit, annotated ones peeled to it
+    pub tag_names: Vec<String>,
     /// Whether HEAD points to this commit
     pub is_head: bool,
+    /// Whether this is the tip of the current branch: the one HEAD is on,
+    /// or with a detached HEAD the one it was detached from. Differs from
+    /// `is_head` only when HEAD is detached.
+    pub is_current_tip: bool,
     /// Whether this is an uncommitted changes node
     pub is_uncommitted: bool,
     /// Number of uncommitted files (None when count is inaccurate, e.g.
@@ -29,6 +37,22 @@ pub struct GraphNode {
     pub cells: Vec<CellType>,
 }
 
+impl GraphNode {
+    /// Whether this row only carries lane art (no commit, not the
+    /// uncommitted changes row)
+    pub fn is_connector(&self) -> bool {
+        self.commit.is_none() && !self.is_uncommitted
+    }
+}
+
+/// A branch pointing at a node, with what the renderer needs to style it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RefLabel {
+    pub name: String,
+    pub is_remote: bool,
+    pub is_head: bool,
+}
+
 /// Cell types
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CellType {
@@ -56,6 +80,29 @@ pub enum CellType {
     TeeLeft(usize),
     /// Upward T junction (fork point) ┴
     TeeUp(usize),
+    /// Lanes folded together by `graph.max_lanes` ┆
+    Overflow(usize),
+}
+
+impl CellType {
+    /// Color index of the glyph (the vertical line's color at a crossing)
+    fn color(self) -> Option<usize> {
+        match self {
+            CellType::Empty => None,
+            CellType::HorizontalPipe(_, color)
+            | CellType::Pipe(color)
+            | CellType::Commit(color)
+            | CellType::BranchRight(color)
+            | CellType::BranchLeft(color)
+            | CellType::MergeRight(color)
+            | CellType::MergeLeft(color)
+            | CellType::Horizontal(color)
+            | CellType::TeeRight(color)
+            | CellType::TeeLeft(color)
+            | CellType::TeeUp(color)
+            | CellType::Overflow(color) => Some(color),
+        }
+    }
 }
 
 /// Graph layout
@@ -65,17 +112,145 @@ pub struct GraphLayout {
     pub max_lane: usize,
 }
 
+impl GraphLayout {
+    /// Every row sits on a single lane, so no row draws anything beside it
+    pub fn is_linear(&self) -> bool {
+        self.max_lane == 0
+    }
+
+    /// Redo the branch labels, `is_head` and `is_current_tip` of the commit
+    /// nodes in place. Lanes and colors don't depend on branches, so this
+    /// matches a rebuild as long as the same commits are shown.
+    pub fn relabel(&mut self, branches: &[BranchInfo], head_commit_oid: Option<Oid>) {
+        let (mut oid_to_branches, current_tip, head_oid) = branch_labels(branches, head_commit_oid);
+        for node in &mut self.nodes {
+            let Some(oid) = node.commit.as_ref().map(|commit| commit.oid) else {
+                continue;
+            };
+            node.labels = oid_to_branches.remove(&oid).unwrap_or_default();
+            node.is_head = head_oid == Some(oid);
+            node.is_current_tip = current_tip == Some(oid);
+        }
+    }
+
+    /// Set each commit node's `tag_names` from tag names by the commit they
+    /// point to, as returned by `GitRepository::tags_by_commit`
+    pub fn attach_tags(&mut self, tags: &HashMap<Oid, Vec<String>>) {
+        for node in &mut self.nodes {
+            node.tag_names = node
+                .commit
+                .as_ref()
+                .and_then(|commit| tags.get(&commit.oid))
+                .cloned()
+                .unwrap_or_default();
+        }
+    }
+
+    /// Remove the rows that only carry lane art, for a list drawn without
+    /// the graph where they would be blank
+    pub fn drop_connectors(&mut self) {
+        self.nodes.retain(|node| !node.is_connector());
+    }
+
+    /// Bound the graph to `max_lanes` lanes (0 = unlimited): the last one
+    /// becomes a shared overflow lane that every lane from there rightward
+    /// folds into. Commits on folded lanes keep their node glyph; other
+    /// rows only show an overflow marker, so which folded branch continues
+    /// where is lost.
+    pub fn cap_lanes(&mut self, max_lanes: usize) {
+        if max_lanes == 0 || self.max_lane < max_lanes {
+            return;
+        }
+        let overflow_lane = max_lanes - 1;
+        let start = overflow_lane * 2;
+        for node in &mut self.nodes {
+            if node.cells.len() > start {
+                let folded = &node.cells[start..];
+                let cell = folded
+                    .iter()
+                    .find(|cell| matches!(cell, CellType::Commit(_)))
+                    .copied()
+                    .or_else(|| {
+                        folded
+                            .iter()
+                            .find_map(|cell| cell.color())
+                            .map(CellType::Overflow)
+                    })
+                    .unwrap_or(CellType::Empty);
+                node.cells.truncate(start);
+                node.cells.extend([cell, CellType::Empty]);
+            }
+            node.lane = node.lane.min(overflow_lane);
+        }
+        self.max_lane = overflow_lane;
+    }
+}
+
+/// How [`build_graph`] lays out and colors the lanes, mirroring `[graph]`
+/// in the config
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GraphOptions {
+    /// How new lanes pick their color
+    pub color_mode: ColorMode,
+    /// Seed for [`ColorMode::Deterministic`]; ignored otherwise
+    pub color_seed: u64,
+    /// Draw a commit's connections to parents on other lanes on a row of
+    /// their own below it instead of on the commit row
+    pub connector_rows: bool,
+    /// Fold lanes past this many into one overflow lane (0 = unlimited);
+    /// see [`GraphLayout::cap_lanes`]
+    pub max_lanes: usize,
+}
+
+/// Branch labels by the commit they point to, the current branch's tip and
+/// the HEAD commit (falling back to the HEAD branch's tip)
+fn branch_labels(
+    branches: &[BranchInfo],
+    head_commit_oid: Option<Oid>,
+) -> (HashMap<Oid, Vec<RefLabel>>, Option<Oid>, Option<Oid>) {
+    let mut oid_to_branches: HashMap<Oid, Vec<RefLabel>> = HashMap::new();
+    let mut current_tip: Option<Oid> = None;
+    let mut head_oid = head_commit_oid;
+    for branch in branches {
+        oid_to_branches
+            .entry(branch.tip_oid)
+            .or_default()
+            .push(RefLabel {
+                name: branch.name.clone(),
+                is_remote: branch.is_remote,
+                is_head: branch.is_head,
+            });
+        if branch.is_current {
+            current_tip = Some(branch.tip_oid);
+        }
+        if branch.is_head {
+            head_oid = head_oid.or(Some(branch.tip_oid));
+        }
+    }
+    (oid_to_branches, current_tip, head_oid)
+}
+
 /// Build a graph from commit list
 /// uncommitted_count: None if no uncommitted changes, Some(count) if there
 /// are uncommitted changes.  The inner Option is None when the exact file
 /// count is unavailable (e.g. collapsed untracked directories).
-/// head_commit_oid: The OID of the commit that HEAD points to (for uncommitted changes)
+/// head_commit_oid: The OID of the commit that HEAD points to (marks the HEAD
+/// node and anchors uncommitted changes; falls back to the HEAD branch's tip)
+/// options: how lanes are colored, drawn and bounded
 pub fn build_graph(
     commits: &[CommitInfo],
     branches: &[BranchInfo],
     uncommitted_count: Option<Option<usize>>,
     head_commit_oid: Option<Oid>,
+    options: &GraphOptions,
 ) -> GraphLayout {
+    let GraphOptions {
+        color_mode,
+        color_seed,
+        connector_rows,
+        max_lanes,
+    } = *options;
+
     if commits.is_empty() {
         if let Some(count) = uncommitted_count {
             return GraphLayout {
@@ -83,8 +258,10 @@ pub fn build_graph(
                     commit: None,
                     lane: 0,
                     color_index: UNCOMMITTED_COLOR_INDEX,
-                    branch_names: Vec::new(),
+                    labels: Vec::new(),
+                    tag_names: Vec::new(),
                     is_head: false,
+                    is_current_tip: false,
                     is_uncommitted: true,
                     uncommitted_count: count,
                     cells: vec![CellType::Commit(UNCOMMITTED_COLOR_INDEX)],
@@ -99,18 +276,8 @@ pub fn build_graph(
         };
     }
 
-    // OID -> branch name mapping
-    let mut oid_to_branches: HashMap<Oid, Vec<String>> = HashMap::new();
-    let mut head_oid: Option<Oid> = None;
-    for branch in branches {
-        oid_to_branches
-            .entry(branch.tip_oid)
-            .or_default()
-            .push(branch.name.clone());
-        if branch.is_head {
-            head_oid = Some(branch.tip_oid);
-        }
-    }
+    // OID -> branch label mapping
+    let (oid_to_branches, current_tip, head_oid) = branch_labels(branches, head_commit_oid);
 
     // OID -> row index mapping
     let oid_to_row: HashMap<Oid, usize> = commits
@@ -147,7 +314,7 @@ pub fn build_graph(
     let mut max_lane: usize = 0;
 
     // Color management
-    let mut color_assigner = ColorAssigner::new();
+    let mut color_assigner = ColorAssigner::with_mode(color_mode, color_seed);
     // OID -> color index mapping
     let mut oid_color_index: HashMap<Oid, usize> = HashMap::new();
     // Lane -> color index mapping (keep colors during forks)
@@ -226,8 +393,10 @@ pub fn build_graph(
                 commit: None,
                 lane: main_lane,
                 color_index: main_color,
-                branch_names: Vec::new(),
+                labels: Vec::new(),
+                tag_names: Vec::new(),
                 is_head: false,
+                is_current_tip: false,
                 is_uncommitted: false,
                 uncommitted_count: None,
                 cells: fork_connector_cells,
@@ -246,13 +415,13 @@ pub fn build_graph(
         // Determine color index
         let commit_color_index = if commit_lane_opt.is_some() {
             // Continue existing branch
-            color_assigner.continue_lane(lane)
+            color_assigner.continue_lane(lane, commit.oid)
         } else if nodes.is_empty() {
             // First commit (main branch) - reserve color so others cannot use it
             color_assigner.assign_main_color(lane)
         } else {
             // New branch start - assign a new color (exclude reserved)
-            color_assigner.assign_color(lane)
+            color_assigner.assign_color(lane, commit.oid)
         };
         oid_color_index.insert(commit.oid, commit_color_index);
         // Record lane color (to preserve colors during forks)
@@ -334,7 +503,7 @@ pub fn build_graph(
                     lanes.len() - 1
                 };
                 lanes[new_lane] = Some(*parent_oid);
-                let new_color = color_assigner.assign_fork_sibling_color(new_lane);
+                let new_color = color_assigner.assign_fork_sibling_color(new_lane, *parent_oid);
                 oid_color_index.insert(*parent_oid, new_color);
                 lane_color_index.insert(new_lane, new_color);
                 (new_lane, false, new_color)
@@ -382,25 +551,53 @@ pub fn build_graph(
             max_lane,
         );
 
-        // Get branch names
-        let branch_names = oid_to_branches
+        // Get branch labels
+        let labels = oid_to_branches
             .get(&commit.oid)
             .cloned()
             .unwrap_or_default();
 
-        let is_head = head_oid.map(|h| h == commit.oid).unwrap_or(false);
+        let is_head = head_oid == Some(commit.oid);
+        let is_current_tip = current_tip == Some(commit.oid);
+
+        // Optionally move the parent connections to a row of their own
+        let continues_down = parent_lanes.iter().any(|&(_, pl, _, _, _)| pl == lane);
+        let (cells, connector) = if connector_rows {
+            match split_connector_row(&cells, lane, final_color_index, continues_down) {
+                Some((commit_cells, connector_cells)) => (commit_cells, Some(connector_cells)),
+                None => (cells, None),
+            }
+        } else {
+            (cells, None)
+        };
 
         // Add commit row
         nodes.push(GraphNode {
             commit: Some(commit.clone()),
             lane,
             color_index: final_color_index,
-            branch_names,
+            labels,
+            tag_names: Vec::new(),
             is_head,
+            is_current_tip,
             is_uncommitted: false,
             uncommitted_count: None,
             cells,
         });
+        if let Some(cells) = connector {
+            nodes.push(GraphNode {
+                commit: None,
+                lane,
+                color_index: final_color_index,
+                labels: Vec::new(),
+                tag_names: Vec::new(),
+                is_head: false,
+                is_current_tip: false,
+                is_uncommitted: false,
+                uncommitted_count: None,
+                cells,
+            });
+        }
 
         // Handle lane merging: when a parent is already tracked on a different lane
         if let Some((parent_lane, _)) = lane_merge {
@@ -557,8 +754,10 @@ pub fn build_graph(
                     commit: None,
                     lane: uncommitted_lane,
                     color_index: UNCOMMITTED_COLOR_INDEX,
-                    branch_names: Vec::new(),
+                    labels: Vec::new(),
+                    tag_names: Vec::new(),
                     is_head: false,
+                    is_current_tip: false,
                     is_uncommitted: true,
                     uncommitted_count: count,
                     cells,
@@ -567,7 +766,25 @@ pub fn build_graph(
         }
     }
 
-    GraphLayout { nodes, max_lane }
+    let mut layout = GraphLayout { nodes, max_lane };
+    layout.cap_lanes(max_lanes);
+    layout
+}
+
+/// [`build_graph`] with the default [`GraphOptions`]
+pub fn build_graph_default(
+    commits: &[CommitInfo],
+    branches: &[BranchInfo],
+    uncommitted_count: Option<Option<usize>>,
+    head_commit_oid: Option<Oid>,
+) -> GraphLayout {
+    build_graph(
+        commits,
+        branches,
+        uncommitted_count,
+        head_commit_oid,
+        &GraphOptions::default(),
+    )
 }
 
 /// Build cells for one row - color index version
@@ -678,6 +895,55 @@ fn build_row_cells_with_colors(
     cells
 }
 
+/// Split a commit row into the commit alone and a connector row below it
+/// carrying the connections to other lanes, e.g. `●─╮` becomes `●` over
+/// `├─╮`. None when the commit links to neither side or to both (there is
+/// no cell for a four-way junction).
+fn split_connector_row(
+    cells: &[CellType],
+    commit_lane: usize,
+    commit_color: usize,
+    continues_down: bool,
+) -> Option<(Vec<CellType>, Vec<CellType>)> {
+    let is_link = |idx: Option<usize>| {
+        idx.and_then(|i| cells.get(i)).is_some_and(|cell| {
+            matches!(cell, CellType::Horizontal(_) | CellType::HorizontalPipe(..))
+        })
+    };
+    let commit_idx = commit_lane * 2;
+    let links_right = is_link(Some(commit_idx + 1));
+    let links_left = is_link(commit_idx.checked_sub(1));
+    if links_right == links_left {
+        return None;
+    }
+
+    // The commit row keeps the lanes passing through: lanes that end or
+    // continue at a connection still reach down to the connector row
+    let commit_cells = cells
+        .iter()
+        .map(|cell| match *cell {
+            CellType::Horizontal(_) | CellType::BranchLeft(_) | CellType::BranchRight(_) => {
+                CellType::Empty
+            }
+            CellType::HorizontalPipe(_, pipe) => CellType::Pipe(pipe),
+            CellType::MergeLeft(color)
+            | CellType::MergeRight(color)
+            | CellType::TeeLeft(color)
+            | CellType::TeeRight(color) => CellType::Pipe(color),
+            other => other,
+        })
+        .collect();
+
+    let mut connector_cells = cells.to_vec();
+    connector_cells[commit_idx] = match (links_right, continues_down) {
+        (true, true) => CellType::TeeRight(commit_color),
+        (true, false) => CellType::MergeRight(commit_color),
+        (false, true) => CellType::TeeLeft(commit_color),
+        (false, false) => CellType::MergeLeft(commit_color),
+    };
+    Some((commit_cells, connector_cells))
+}
+
 /// Build fork connector row cells (multiple branches from the same parent)
 /// Example: ├─┴─╯ (main lane connecting to multiple branch lanes)
 fn build_fork_connector_cells(
@@ -1,19 +1,26 @@
 //! Git layer
 
 pub mod branch;
+pub mod capabilities;
 pub mod commit;
+pub mod compare;
 pub mod diff;
+pub mod diff_cache;
 pub mod extensions;
 pub mod graph;
 pub mod operations;
+pub mod path_scope;
 pub mod repository;
+pub mod signing;
+pub mod tree;
 
 pub use branch::BranchInfo;
 pub use commit::CommitInfo;
 pub use diff::{
     CommitDiffInfo, DiffHunkContent, DiffLineContent, DiffLineOrigin, FileChangeKind,
-    FileDiffContent, FileDiffInfo,
+    FileDiffContent, FileDiffInfo, WhitespaceMode,
 };
 pub use extensions::configure_git_extensions;
-pub use graph::build_graph;
-pub use repository::{GitRepository, StageState, WorkingTreeStatus};
+pub use graph::{build_graph, build_graph_default, GraphOptions};
+pub use repository::{GitRepository, RepoSummary, StageState, StashInfo, WorkingTreeStatus};
+pub use tree::TreeBrowser;
@@ -1,10 +1,64 @@
 //! Git operations (checkout, merge, rebase, branch operations)
 
+use std::fmt;
+use std::io::Read;
 use std::path::Path;
-use std::process::Command;
+use std::process::{Command, Stdio};
 
 use anyhow::{bail, Context, Result};
-use git2::{BranchType, IndexAddOption, Oid, Repository};
+use git2::{
+    build::CheckoutBuilder, AnnotatedCommit, BranchType, Commit, ConfigLevel, ErrorCode,
+    IndexAddOption, Oid, Repository, RevertOptions, Signature, StashFlags,
+};
+
+use super::signing::{commit_to_head, WrittenCommit};
+
+/// Who commits, tags and stashes in place of the repository's
+/// `user.name` / `user.email` (`--author`, for the session only)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Identity {
+    pub name: String,
+    pub email: String,
+}
+
+impl Identity {
+    /// Parse `Name <email>`, the form of `git commit --author`
+    pub fn parse(text: &str) -> Result<Self> {
+        let invalid = || anyhow::anyhow!("Expected \"Name <email>\", got \"{}\"", text.trim());
+        let (name, rest) = text.trim().split_once('<').ok_or_else(invalid)?;
+        let email = rest.strip_suffix('>').ok_or_else(invalid)?;
+        let name = name.trim();
+        let email = email.trim();
+        if name.is_empty() || email.is_empty() || name.contains('>') || email.contains(['<', '>']) {
+            return Err(invalid());
+        }
+        Ok(Self {
+            name: name.to_string(),
+            email: email.to_string(),
+        })
+    }
+}
+
+impl fmt::Display for Identity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} <{}>", self.name, self.email)
+    }
+}
+
+/// Signature for a new object: `identity` when given, otherwise the
+/// repository's `user.name` / `user.email`. `role` names it in the error.
+fn signature(
+    repo: &Repository,
+    identity: Option<&Identity>,
+    role: &str,
+) -> Result<Signature<'static>> {
+    match identity {
+        Some(identity) => Ok(Signature::now(&identity.name, &identity.email)?),
+        None => repo
+            .signature()
+            .with_context(|| format!("Cannot determine {role} (set user.name and user.email)")),
+    }
+}
 
 /// Checkout a branch
 pub fn checkout_branch(repo: &Repository, branch_name: &str) -> Result<()> {
@@ -33,12 +87,67 @@ pub fn checkout_commit(repo: &Repository, oid: Oid) -> Result<()> {
     Ok(())
 }
 
+/// Checkout the previously checked-out branch or commit (`git checkout -`).
+/// Returns the branch name, or the short hash for a detached HEAD.
+pub fn checkout_previous(repo: &Repository) -> Result<String> {
+    let (object, reference) = repo
+        .revparse_ext("@{-1}")
+        .context("No previously checked-out branch")?;
+
+    if let Some(name) = reference
+        .as_ref()
+        .filter(|r| r.is_branch())
+        .and_then(|r| r.shorthand())
+    {
+        let name = name.to_string();
+        checkout_branch(repo, &name)?;
+        return Ok(name);
+    }
+
+    let oid = object.peel_to_commit()?.id();
+    checkout_commit(repo, oid)?;
+    Ok(oid.to_string()[..7].to_string())
+}
+
+/// Split a remote-tracking branch name like `upstream/feature/x` into the
+/// remote and the branch (`upstream`, `feature/x`). Remote names may contain
+/// `/`, so the longest configured remote that prefixes the name wins
+pub fn split_remote_branch<'a>(
+    repo: &Repository,
+    remote_branch: &'a str,
+) -> Option<(&'a str, &'a str)> {
+    let remotes = repo.remotes().ok()?;
+    let remotes: Vec<&str> = remotes.iter().flatten().collect();
+    split_remote_name(&remotes, remote_branch)
+}
+
+/// `split_remote_branch` against an already known list of remote names
+pub fn split_remote_name<'a>(
+    remotes: &[impl AsRef<str>],
+    remote_branch: &'a str,
+) -> Option<(&'a str, &'a str)> {
+    let remote_len = remotes
+        .iter()
+        .map(AsRef::as_ref)
+        .filter(|remote| {
+            remote_branch
+                .strip_prefix(remote)
+                .is_some_and(|rest| rest.len() > 1 && rest.starts_with('/'))
+        })
+        .map(str::len)
+        .max()?;
+    Some((
+        &remote_branch[..remote_len],
+        &remote_branch[remote_len + 1..],
+    ))
+}
+
 /// Checkout a remote branch (create and track a local branch)
 pub fn checkout_remote_branch(repo: &Repository, remote_branch: &str) -> Result<()> {
-    // Extract "branch-name" from "origin/branch-name"
-    let local_name = remote_branch
-        .strip_prefix("origin/")
-        .context("Invalid remote branch format")?;
+    // Extract "branch-name" from "<remote>/branch-name"
+    let (_, local_name) = split_remote_branch(repo, remote_branch).with_context(|| {
+        format!("'{remote_branch}' does not start with a configured remote name")
+    })?;
 
     // Look up the remote branch
     let remote_ref = repo
@@ -59,7 +168,7 @@ pub fn checkout_remote_branch(repo: &Repository, remote_branch: &str) -> Result<
             return checkout_branch(repo, local_name);
         } else {
             // Pointing to different commits -> update local branch and checkout
-            // Equivalent to: git checkout -B local_name origin/xxx
+            // Equivalent to: git checkout -B local_name <remote>/xxx
             let is_current_branch = local_branch.is_head();
             drop(local_branch); // Release the branch reference
 
@@ -117,85 +226,385 @@ pub fn delete_branch(repo: &Repository, branch_name: &str) -> Result<()> {
     Ok(())
 }
 
-/// Perform a merge
-pub fn merge_branch(repo: &Repository, branch_name: &str) -> Result<()> {
+/// Fill `{name}` placeholders in a commit message template. Unknown
+/// placeholders are left as-is
+pub fn expand_message(template: &str, vars: &[(&str, &str)]) -> String {
+    vars.iter()
+        .fold(template.to_string(), |message, (name, value)| {
+            message.replace(&format!("{{{name}}}"), value)
+        })
+}
+
+/// Perform a merge; a merge commit's message comes from `message_template`
+/// (`{branch}`, `{head}`, `{oid}`, `{short}`, `{subject}` of the merged tip).
+/// Returns why the merge commit is unsigned when signing was requested
+pub fn merge_branch(
+    repo: &Repository,
+    branch_name: &str,
+    message_template: &str,
+    identity: Option<&Identity>,
+) -> Result<Option<String>> {
     let branch = repo
         .find_branch(branch_name, BranchType::Local)
         .context(format!("Branch '{}' not found", branch_name))?;
+    let annotated_commit = repo.reference_to_annotated_commit(branch.get())?;
+
+    match merge_annotated(
+        repo,
+        &annotated_commit,
+        branch_name,
+        message_template,
+        identity,
+    )? {
+        PullOutcome::Merged { unsigned_reason } => Ok(unsigned_reason),
+        _ => Ok(None),
+    }
+}
 
-    let reference = branch.get();
-    let annotated_commit = repo.reference_to_annotated_commit(reference)?;
+/// How `pull_current_branch` brought the branch up to date
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PullOutcome {
+    UpToDate,
+    FastForward,
+    /// A merge commit was written; carries why it is unsigned when signing
+    /// was requested
+    Merged {
+        unsigned_reason: Option<String>,
+    },
+    Rebased,
+}
 
-    let (analysis, _) = repo.merge_analysis(&[&annotated_commit])?;
+/// Move HEAD's branch to `target` without a merge commit
+fn fast_forward(repo: &Repository, target: &AnnotatedCommit, name: &str) -> Result<()> {
+    let target_commit = repo.find_commit(target.id())?;
+    let tree = target_commit.tree()?;
 
-    if analysis.is_up_to_date() {
-        return Ok(());
+    repo.checkout_tree(tree.as_object(), None)?;
+
+    let mut head_ref = repo.head()?;
+    head_ref.set_target(target.id(), &format!("Fast-forward merge: {}", name))?;
+    Ok(())
+}
+
+/// Refuse to start `operation` over staged changes: its result is written
+/// from the index, and undoing a conflict resets the paths it touched
+fn ensure_nothing_staged(repo: &Repository, head_commit: &Commit, operation: &str) -> Result<()> {
+    let diff = repo.diff_tree_to_index(Some(&head_commit.tree()?), None, None)?;
+    if diff.deltas().len() > 0 {
+        bail!("Cannot {operation}: commit or stash the staged changes first");
     }
+    Ok(())
+}
 
-    if analysis.is_fast_forward() {
-        // Fast-forward merge
-        let target_oid = reference.target().unwrap();
-        let target_commit = repo.find_commit(target_oid)?;
-        let tree = target_commit.tree()?;
+/// Undo a merge, cherry-pick or revert that stopped on conflicts: put the
+/// paths it touched back to HEAD and drop MERGE_HEAD and the like. Other
+/// local changes stay; the operation refused to start over them. Returns
+/// the conflicting paths
+fn abort_conflicted(repo: &Repository, head_commit: &Commit) -> Result<Vec<String>> {
+    let index = repo.index()?;
+    let mut conflicted = Vec::new();
+    for conflict in index.conflicts()? {
+        let conflict = conflict?;
+        if let Some(entry) = conflict.our.or(conflict.their).or(conflict.ancestor) {
+            conflicted.push(String::from_utf8_lossy(&entry.path).into_owned());
+        }
+    }
+    let mut touched = conflicted.clone();
+    let diff = repo.diff_tree_to_index(Some(&head_commit.tree()?), Some(&index), None)?;
+    for delta in diff.deltas() {
+        for file in [delta.old_file(), delta.new_file()] {
+            if let Some(path) = file.path() {
+                touched.push(path.to_string_lossy().into_owned());
+            }
+        }
+    }
+    touched.sort();
+    touched.dedup();
+
+    if !touched.is_empty() {
+        repo.reset_default(Some(head_commit.as_object()), &touched)?;
+        let mut checkout = CheckoutBuilder::new();
+        checkout.force().remove_untracked(true);
+        for path in &touched {
+            checkout.path(path);
+        }
+        repo.checkout_head(Some(&mut checkout))?;
+    }
+    repo.cleanup_state()?;
+    conflicted.sort();
+    conflicted.dedup();
+    Ok(conflicted)
+}
 
-        repo.checkout_tree(tree.as_object(), None)?;
+/// Merge `annotated_commit` (named `name` in messages) into HEAD,
+/// fast-forwarding when possible
+fn merge_annotated(
+    repo: &Repository,
+    annotated_commit: &AnnotatedCommit,
+    name: &str,
+    message_template: &str,
+    identity: Option<&Identity>,
+) -> Result<PullOutcome> {
+    let (analysis, _) = repo.merge_analysis(&[annotated_commit])?;
 
-        let mut head_ref = repo.head()?;
-        head_ref.set_target(target_oid, &format!("Fast-forward merge: {}", branch_name))?;
+    if analysis.is_up_to_date() {
+        return Ok(PullOutcome::UpToDate);
+    }
 
-        return Ok(());
+    if analysis.is_fast_forward() {
+        fast_forward(repo, annotated_commit, name)?;
+        return Ok(PullOutcome::FastForward);
     }
 
     if analysis.is_normal() {
-        // Normal merge
-        repo.merge(&[&annotated_commit], None, None)?;
+        // Normal merge; check the identity before touching the work tree
+        let signature = signature(repo, identity, "author")?;
+        let head = repo.head()?;
+        let head_commit = head.peel_to_commit()?;
+        ensure_nothing_staged(repo, &head_commit, "merge")?;
+        repo.merge(&[annotated_commit], None, None)?;
 
         if repo.index()?.has_conflicts() {
-            bail!("Merge conflict occurred. Please resolve manually.");
+            let conflicted = abort_conflicted(repo, &head_commit)?;
+            bail!(
+                "Merging {name} conflicts in {}; the merge was undone and '{}' is unchanged",
+                conflicted.join(", "),
+                head.shorthand().unwrap_or("HEAD")
+            );
         }
 
         // Create a merge commit
-        let signature = repo.signature()?;
-        let head = repo.head()?;
-        let head_commit = head.peel_to_commit()?;
         let merge_commit = repo.find_commit(annotated_commit.id())?;
         let tree_oid = repo.index()?.write_tree()?;
         let tree = repo.find_tree(tree_oid)?;
 
-        repo.commit(
-            Some("HEAD"),
+        let oid = merge_commit.id().to_string();
+        let message = expand_message(
+            message_template,
+            &[
+                ("branch", name),
+                ("head", head.shorthand().unwrap_or("HEAD")),
+                ("oid", &oid),
+                ("short", &oid[..7]),
+                ("subject", merge_commit.summary().unwrap_or_default()),
+            ],
+        );
+
+        let written = commit_to_head(
+            repo,
             &signature,
             &signature,
-            &format!("Merge branch '{}'", branch_name),
+            &message,
             &tree,
             &[&head_commit, &merge_commit],
         )?;
 
         repo.cleanup_state()?;
+        return Ok(PullOutcome::Merged {
+            unsigned_reason: written.unsigned_reason,
+        });
     }
 
-    Ok(())
+    Ok(PullOutcome::UpToDate)
 }
 
 /// Perform a rebase (simple implementation)
-pub fn rebase_branch(repo: &Repository, onto_branch: &str) -> Result<()> {
+pub fn rebase_branch(
+    repo: &Repository,
+    onto_branch: &str,
+    identity: Option<&Identity>,
+) -> Result<()> {
     let onto = repo
         .find_branch(onto_branch, BranchType::Local)
         .context(format!("Branch '{}' not found", onto_branch))?;
 
     let onto_annotated = repo.reference_to_annotated_commit(onto.get())?;
+    rebase_onto(repo, &onto_annotated, identity)
+}
+
+/// Replay HEAD's commits onto `onto`. A step that fails (usually a
+/// conflict) aborts the whole rebase, leaving the branch where it was
+fn rebase_onto(
+    repo: &Repository,
+    onto: &AnnotatedCommit,
+    identity: Option<&Identity>,
+) -> Result<()> {
+    let signature = signature(repo, identity, "committer")?;
+    let mut rebase = repo.rebase(None, Some(onto), None, None)?;
+
+    let mut replay = || -> Result<()> {
+        while let Some(op) = rebase.next() {
+            let op = op?;
+            if repo.index()?.has_conflicts() {
+                bail!("{} conflicts with the new base", &op.id().to_string()[..7]);
+            }
+            match rebase.commit(None, &signature, None) {
+                // Already upstream; git drops such commits too
+                Err(e) if e.code() == ErrorCode::Applied => {}
+                result => {
+                    result?;
+                }
+            }
+        }
+        rebase.finish(None)?;
+        Ok(())
+    };
+    if let Err(e) = replay() {
+        rebase
+            .abort()
+            .context("Rebase failed and could not be aborted; run `git rebase --abort`")?;
+        return Err(e.context("Rebase was aborted; the branch is unchanged"));
+    }
+
+    Ok(())
+}
 
-    let mut rebase = repo.rebase(None, Some(&onto_annotated), None, None)?;
+/// Bring HEAD's branch up to date with its (already fetched) upstream:
+/// fast-forward when possible, otherwise merge, or replay the local
+/// commits onto the upstream with `rebase`. Merge commits use
+/// `message_template` like `merge_branch`, with the upstream as `{branch}`
+pub fn pull_current_branch(
+    repo: &Repository,
+    rebase: bool,
+    message_template: &str,
+    identity: Option<&Identity>,
+) -> Result<PullOutcome> {
+    let head = repo.head()?;
+    if !head.is_branch() {
+        bail!("Cannot pull: detached HEAD");
+    }
+    let name = head.shorthand().unwrap_or("HEAD").to_string();
+    let upstream = repo
+        .find_branch(&name, BranchType::Local)?
+        .upstream()
+        .with_context(|| format!("'{name}' has no upstream"))?;
+    let upstream_name = upstream.name()?.unwrap_or("upstream").to_string();
+    let annotated_commit = repo.reference_to_annotated_commit(upstream.get())?;
+
+    if !rebase {
+        return merge_annotated(
+            repo,
+            &annotated_commit,
+            &upstream_name,
+            message_template,
+            identity,
+        );
+    }
+    let (analysis, _) = repo.merge_analysis(&[&annotated_commit])?;
+    if analysis.is_up_to_date() {
+        Ok(PullOutcome::UpToDate)
+    } else if analysis.is_fast_forward() {
+        fast_forward(repo, &annotated_commit, &upstream_name)?;
+        Ok(PullOutcome::FastForward)
+    } else {
+        rebase_onto(repo, &annotated_commit, identity)?;
+        Ok(PullOutcome::Rebased)
+    }
+}
 
-    while let Some(op) = rebase.next() {
-        let _operation = op?;
-        let signature = repo.signature()?;
-        rebase.commit(None, &signature, None)?;
+/// Apply the changes of a commit onto HEAD, keeping its author and message
+/// (signed per `commit.gpgsign`)
+pub fn cherry_pick_commit(
+    repo: &Repository,
+    oid: Oid,
+    identity: Option<&Identity>,
+) -> Result<WrittenCommit> {
+    let commit = repo.find_commit(oid)?;
+    if commit.parent_count() > 1 {
+        bail!("Cannot cherry-pick a merge commit");
     }
+    let head_commit = repo.head()?.peel_to_commit()?;
+    let committer = signature(repo, identity, "committer")?;
+    ensure_nothing_staged(repo, &head_commit, "cherry-pick")?;
 
-    rebase.finish(None)?;
+    repo.cherrypick(&commit, None)?;
 
-    Ok(())
+    let mut index = repo.index()?;
+    if index.has_conflicts() {
+        let conflicted = abort_conflicted(repo, &head_commit)?;
+        bail!(
+            "Cherry-picking {} conflicts in {}; the cherry-pick was undone",
+            &oid.to_string()[..7],
+            conflicted.join(", ")
+        );
+    }
+    let tree_oid = index.write_tree()?;
+    if tree_oid == head_commit.tree_id() {
+        repo.cleanup_state()?;
+        bail!("Nothing to cherry-pick: the changes are already in HEAD");
+    }
+    let tree = repo.find_tree(tree_oid)?;
+
+    let written = commit_to_head(
+        repo,
+        &commit.author(),
+        &committer,
+        commit.message().unwrap_or_default(),
+        &tree,
+        &[&head_commit],
+    )?;
+
+    repo.cleanup_state()?;
+    Ok(written)
+}
+
+/// Commit the inverse of a commit on top of HEAD, like `git revert`. Merge
+/// commits are reverted against their first parent (`-m 1`). The message
+/// comes from `message_template` (`{oid}`, `{short}`, `{subject}` of the
+/// reverted commit)
+pub fn revert_commit(
+    repo: &Repository,
+    oid: Oid,
+    message_template: &str,
+    identity: Option<&Identity>,
+) -> Result<WrittenCommit> {
+    let commit = repo.find_commit(oid)?;
+    let head_commit = repo.head()?.peel_to_commit()?;
+    let signature = signature(repo, identity, "author")?;
+    ensure_nothing_staged(repo, &head_commit, "revert")?;
+
+    let mut opts = RevertOptions::new();
+    if commit.parent_count() > 1 {
+        opts.mainline(1);
+    }
+    repo.revert(&commit, Some(&mut opts))?;
+
+    let mut index = repo.index()?;
+    if index.has_conflicts() {
+        let conflicted = abort_conflicted(repo, &head_commit)?;
+        bail!(
+            "Reverting {} conflicts in {}; the revert was undone",
+            &oid.to_string()[..7],
+            conflicted.join(", ")
+        );
+    }
+    let tree_oid = index.write_tree()?;
+    if tree_oid == head_commit.tree_id() {
+        repo.cleanup_state()?;
+        bail!("Nothing to revert: the changes are already undone in HEAD");
+    }
+    let tree = repo.find_tree(tree_oid)?;
+
+    let oid = commit.id().to_string();
+    let message = expand_message(
+        message_template,
+        &[
+            ("oid", &oid),
+            ("short", &oid[..7]),
+            ("subject", commit.summary().unwrap_or_default()),
+        ],
+    );
+    let written = commit_to_head(
+        repo,
+        &signature,
+        &signature,
+        &message,
+        &tree,
+        &[&head_commit],
+    )?;
+
+    repo.cleanup_state()?;
+    Ok(written)
 }
 
 /// Stage a single path (add to the index, or remove for deleted files)
@@ -255,11 +664,80 @@ pub fn unstage_all(repo: &Repository) -> Result<()> {
     Ok(())
 }
 
-/// Create a commit from the current index
-pub fn create_commit(repo: &Repository, message: &str) -> Result<Oid> {
-    let signature = repo
-        .signature()
-        .context("Cannot determine author (set user.name and user.email)")?;
+/// Stash the working tree and index changes, like `git stash` (untracked
+/// files stay). Returns the stash commit.
+pub fn stash_save(
+    repo: &mut Repository,
+    message: Option<&str>,
+    identity: Option<&Identity>,
+) -> Result<Oid> {
+    let stasher = signature(repo, identity, "author")?;
+    match repo.stash_save2(&stasher, message, Some(StashFlags::DEFAULT)) {
+        Ok(oid) => Ok(oid),
+        Err(e) if e.code() == ErrorCode::NotFound => bail!("No local changes to stash"),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Apply `stash@{index}` to the working tree, keeping the entry
+pub fn stash_apply(repo: &mut Repository, index: usize) -> Result<()> {
+    repo.stash_apply(index, None)
+        .map_err(|e| stash_error(e, index, "apply"))
+}
+
+/// Apply `stash@{index}` and drop it. Nothing is dropped when applying fails.
+pub fn stash_pop(repo: &mut Repository, index: usize) -> Result<()> {
+    repo.stash_pop(index, None)
+        .map_err(|e| stash_error(e, index, "pop"))
+}
+
+fn stash_error(e: git2::Error, index: usize, verb: &str) -> anyhow::Error {
+    match e.code() {
+        ErrorCode::NotFound => anyhow::anyhow!("No stash entry stash@{{{index}}}"),
+        ErrorCode::Conflict | ErrorCode::MergeConflict => anyhow::anyhow!(
+            "Cannot {verb} stash@{{{index}}}: it conflicts with local changes. Commit or stash them first."
+        ),
+        _ => anyhow::Error::new(e).context(format!("Cannot {verb} stash@{{{index}}}")),
+    }
+}
+
+/// Create a tag on a commit: lightweight without a message, annotated
+/// (tagger from `identity` or the repository config) with one
+pub fn create_tag(
+    repo: &Repository,
+    name: &str,
+    oid: Oid,
+    message: Option<&str>,
+    identity: Option<&Identity>,
+) -> Result<()> {
+    let target = repo.find_object(oid, None)?;
+    match message {
+        Some(message) => {
+            let tagger = signature(repo, identity, "tagger")?;
+            repo.tag(name, &target, &tagger, message, false)
+        }
+        None => repo.tag_lightweight(name, &target, false),
+    }
+    .with_context(|| format!("Cannot create tag '{name}'"))?;
+    Ok(())
+}
+
+/// Set `user.name` and `user.email` in the repository's own config
+/// (`.git/config`), like `git config user.name` without `--global`
+pub fn set_identity(repo: &Repository, name: &str, email: &str) -> Result<()> {
+    let mut config = repo.config()?.open_level(ConfigLevel::Local)?;
+    config.set_str("user.name", name)?;
+    config.set_str("user.email", email)?;
+    Ok(())
+}
+
+/// Create a commit from the current index (signed per `commit.gpgsign`)
+pub fn create_commit(
+    repo: &Repository,
+    message: &str,
+    identity: Option<&Identity>,
+) -> Result<WrittenCommit> {
+    let signature = signature(repo, identity, "author")?;
 
     let mut index = repo.index()?;
     let tree_oid = index.write_tree()?;
@@ -275,47 +753,132 @@ pub fn create_commit(repo: &Repository, message: &str) -> Result<Oid> {
     }
 
     let parents: Vec<&git2::Commit> = parent.iter().collect();
-    let oid = repo.commit(
-        Some("HEAD"),
-        &signature,
-        &signature,
-        message,
-        &tree,
-        &parents,
-    )?;
-    Ok(oid)
+    commit_to_head(repo, &signature, &signature, message, &tree, &parents)
+}
+
+/// How `push_branch` treats a remote branch that has diverged
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PushMode {
+    /// Fast-forward only, like plain `git push`
+    Normal,
+    /// `--force-with-lease`: overwrite the remote branch only if it still
+    /// points at `expected` (None: only if it doesn't exist)
+    ForceWithLease { expected: Option<Oid> },
+}
+
+/// A progress line from `git fetch/push --progress`, e.g.
+/// `Receiving objects:  45% (450/1000)`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransferProgress {
+    /// `Receiving objects`, `remote: Compressing objects`, ...
+    pub phase: String,
+    pub percent: u8,
 }
 
-/// Push the given branch to origin using git command (sets upstream)
-pub fn push_branch(repo_path: &str, branch: &str) -> Result<()> {
-    let output = Command::new("git")
-        .args(["push", "--set-upstream", "origin", branch])
-        .current_dir(repo_path)
-        .output()
-        .context("Failed to execute git push")?;
+/// Parse a progress line; None for other output (errors, ref updates).
+/// Lines relayed from the server keep their `remote:` prefix in the phase
+pub fn parse_progress(line: &str) -> Option<TransferProgress> {
+    let (sideband, line) = match line.strip_prefix("remote: ") {
+        Some(rest) => ("remote: ", rest),
+        None => ("", line),
+    };
+    let (phase, rest) = line.split_once(": ")?;
+    let (percent, _) = rest.trim_start().split_once('%')?;
+    let percent: u8 = percent.parse().ok()?;
+    Some(TransferProgress {
+        phase: format!("{sideband}{phase}"),
+        percent: percent.min(100),
+    })
+}
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        bail!("git push failed: {}", stderr.trim());
+/// Run a git network command with `--progress`, reporting its progress
+/// lines as they arrive. git redraws progress with `\r`, so stderr is split
+/// on both `\r` and `\n`; everything else ends up in the error message
+fn run_with_progress(
+    mut command: Command,
+    what: &str,
+    mut progress: impl FnMut(TransferProgress),
+) -> Result<()> {
+    // The terminal is in raw mode under the TUI: git must not prompt on it,
+    // so missing credentials fail right away instead
+    let mut child = command
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to execute git {what}"))?;
+    let mut stderr = child.stderr.take().context("git stderr not captured")?;
+
+    let mut messages: Vec<String> = Vec::new();
+    let mut handle_line = |line: &[u8]| {
+        let text = String::from_utf8_lossy(line);
+        match parse_progress(&text) {
+            Some(update) => progress(update),
+            None if !text.trim().is_empty() => messages.push(text.trim().to_string()),
+            None => {}
+        }
+    };
+    let mut line = Vec::new();
+    let mut buf = [0u8; 4096];
+    loop {
+        let read = match stderr.read(&mut buf) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => {
+                // Don't leave git running (or a zombie) behind
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(e).with_context(|| format!("Failed to read git {what} output"));
+            }
+        };
+        for &byte in &buf[..read] {
+            if byte == b'\r' || byte == b'\n' {
+                handle_line(&line);
+                line.clear();
+            } else {
+                line.push(byte);
+            }
+        }
     }
+    handle_line(&line);
 
+    if !child.wait()?.success() {
+        bail!("git {what} failed: {}", messages.join("\n"));
+    }
     Ok(())
 }
 
-/// Fetch from origin remote using git command
-pub fn fetch_origin(repo_path: &str) -> Result<()> {
-    let output = Command::new("git")
-        .args(["fetch", "origin"])
-        .current_dir(repo_path)
-        .output()
-        .context("Failed to execute git fetch")?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        bail!("git fetch failed: {}", stderr.trim());
+/// Push the given branch to `remote` using git command (sets upstream)
+pub fn push_branch(
+    repo_path: &str,
+    remote: &str,
+    branch: &str,
+    mode: PushMode,
+    progress: impl FnMut(TransferProgress),
+) -> Result<()> {
+    let mut command = Command::new("git");
+    command.args(["push", "--progress", "--set-upstream"]);
+    if let PushMode::ForceWithLease { expected } = mode {
+        let expected = expected.map(|oid| oid.to_string()).unwrap_or_default();
+        command.arg(format!("--force-with-lease=refs/heads/{branch}:{expected}"));
     }
+    command.args([remote, branch]).current_dir(repo_path);
+    run_with_progress(command, "push", progress)
+}
 
-    Ok(())
+/// Fetch from `remote` using git command
+pub fn fetch_remote(
+    repo_path: &str,
+    remote: &str,
+    progress: impl FnMut(TransferProgress),
+) -> Result<()> {
+    let mut command = Command::new("git");
+    command
+        .args(["fetch", "--progress", remote])
+        .current_dir(repo_path);
+    run_with_progress(command, "fetch", progress)
 }
 
 #[cfg(test)]
@@ -326,11 +889,17 @@ mod tests {
     use tempfile::TempDir;
 
     use super::*;
+    use crate::config::MessagesConfig;
     use crate::git::{GitRepository, StageState};
 
     fn init_repo_with_commit() -> (TempDir, Repository) {
         let tempdir = tempfile::tempdir().unwrap();
         let repo = Repository::init(tempdir.path()).unwrap();
+        // Keep a global commit.gpgsign from reaching for a real signer
+        repo.config()
+            .unwrap()
+            .set_bool("commit.gpgsign", false)
+            .unwrap();
         fs::write(tempdir.path().join("base.txt"), "base\n").unwrap();
         let mut index = repo.index().unwrap();
         index.add_path(Path::new("base.txt")).unwrap();
@@ -349,6 +918,428 @@ mod tests {
         repo.stage_states().unwrap().get(Path::new(path)).copied()
     }
 
+    #[test]
+    fn cherry_pick_keeps_author_and_message() {
+        let (tempdir, repo) = init_repo_with_commit();
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "Test").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+        let base = repo.head().unwrap().peel_to_commit().unwrap();
+
+        let author = Signature::now("Other", "other@example.com").unwrap();
+        let mut builder = repo.treebuilder(Some(&base.tree().unwrap())).unwrap();
+        builder
+            .insert("picked.txt", repo.blob(b"picked\n").unwrap(), 0o100644)
+            .unwrap();
+        let tree = repo.find_tree(builder.write().unwrap()).unwrap();
+        let picked = repo
+            .commit(
+                Some("refs/heads/feature"),
+                &author,
+                &author,
+                "Add picked file\n\nWith a body.\n",
+                &tree,
+                &[&base],
+            )
+            .unwrap();
+
+        let written = cherry_pick_commit(&repo, picked, None).unwrap();
+
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.id(), written.oid);
+        assert_ne!(head.id(), picked);
+        assert_eq!(head.parent_id(0).unwrap(), base.id());
+        assert_eq!(head.author().name(), Some("Other"));
+        assert_eq!(head.committer().name(), Some("Test"));
+        assert_eq!(head.message(), Some("Add picked file\n\nWith a body.\n"));
+        assert!(tempdir.path().join("picked.txt").exists());
+        assert_eq!(repo.state(), git2::RepositoryState::Clean);
+
+        // Picking it again finds nothing left to apply
+        assert!(cherry_pick_commit(&repo, picked, None).is_err());
+        assert_eq!(repo.state(), git2::RepositoryState::Clean);
+    }
+
+    #[test]
+    fn revert_undoes_the_commit_on_top_of_head() {
+        let (tempdir, repo) = init_repo_with_commit();
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "Test").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+
+        fs::write(tempdir.path().join("base.txt"), "changed\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("base.txt")).unwrap();
+        index.write().unwrap();
+        let change = create_commit(&repo, "Change base", None).unwrap().oid;
+
+        let template = MessagesConfig::default().revert;
+        let written = revert_commit(&repo, change, &template, None).unwrap();
+
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.id(), written.oid);
+        assert_eq!(head.parent_id(0).unwrap(), change);
+        assert_eq!(
+            head.message(),
+            Some(format!("Revert \"Change base\"\n\nThis reverts commit {change}.\n").as_str())
+        );
+        assert_eq!(
+            fs::read_to_string(tempdir.path().join("base.txt")).unwrap(),
+            "base\n"
+        );
+        assert_eq!(repo.state(), git2::RepositoryState::Clean);
+
+        // A configured template names the reverted commit its own way
+        let reverted = written.oid;
+        revert_commit(&repo, reverted, "Undo {short}: {subject}", None).unwrap();
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(
+            head.message(),
+            Some(
+                format!(
+                    "Undo {}: Revert \"Change base\"",
+                    &reverted.to_string()[..7]
+                )
+                .as_str()
+            )
+        );
+    }
+
+    #[test]
+    fn tags_are_lightweight_without_a_message() {
+        let (_tempdir, repo) = init_repo_with_commit();
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "Test").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+        let head = repo.head().unwrap().peel_to_commit().unwrap().id();
+
+        create_tag(&repo, "light", head, None, None).unwrap();
+        create_tag(&repo, "annotated", head, Some("Release\n"), None).unwrap();
+
+        let light = repo.find_reference("refs/tags/light").unwrap();
+        assert_eq!(light.target(), Some(head));
+        let annotated = repo
+            .find_reference("refs/tags/annotated")
+            .unwrap()
+            .peel_to_tag()
+            .unwrap();
+        assert_eq!(annotated.target_id(), head);
+        assert_eq!(annotated.message(), Some("Release\n"));
+        assert_eq!(annotated.tagger().unwrap().name(), Some("Test"));
+
+        // Existing tags are not overwritten
+        assert!(create_tag(&repo, "light", head, None, None).is_err());
+    }
+
+    #[test]
+    fn merge_without_identity_fails_before_merging() {
+        let (_tempdir, repo) = init_repo_with_commit();
+        let base = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch("feature", &base, false).unwrap();
+        let sig = Signature::now("Test", "test@example.com").unwrap();
+        let commit_on = |refname: &str, path: &str| {
+            let mut builder = repo.treebuilder(Some(&base.tree().unwrap())).unwrap();
+            builder
+                .insert(path, repo.blob(b"x\n").unwrap(), 0o100644)
+                .unwrap();
+            let tree = repo.find_tree(builder.write().unwrap()).unwrap();
+            repo.commit(Some(refname), &sig, &sig, path, &tree, &[&base])
+                .unwrap()
+        };
+        commit_on("refs/heads/feature", "feature.txt");
+        commit_on("HEAD", "main.txt");
+        // An empty name makes repo.signature() fail whatever the global
+        // config says
+        repo.config().unwrap().set_str("user.name", "").unwrap();
+
+        let err = merge_branch(&repo, "feature", "Merge", None).unwrap_err();
+
+        assert!(format!("{err:#}").contains("set user.name and user.email"));
+        assert_eq!(repo.state(), git2::RepositoryState::Clean);
+    }
+
+    #[test]
+    fn merge_commit_message_follows_template() {
+        let (_tempdir, repo) = init_repo_with_commit();
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "Test").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+        let head = repo.head().unwrap().shorthand().unwrap().to_string();
+        let base = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch("feature", &base, false).unwrap();
+
+        let sig = Signature::now("Test", "test@example.com").unwrap();
+        let commit_on = |refname: &str, path: &str, message: &str| {
+            let mut builder = repo.treebuilder(Some(&base.tree().unwrap())).unwrap();
+            let blob = repo.blob(b"x\n").unwrap();
+            builder.insert(path, blob, 0o100644).unwrap();
+            let tree = repo.find_tree(builder.write().unwrap()).unwrap();
+            repo.commit(Some(refname), &sig, &sig, message, &tree, &[&base])
+                .unwrap()
+        };
+        let feature = commit_on("refs/heads/feature", "feature.txt", "Add feature");
+        commit_on("HEAD", "main.txt", "Main work");
+        repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force()))
+            .unwrap();
+
+        merge_branch(
+            &repo,
+            "feature",
+            "Merge {branch} ({short}) into {head}: {subject}",
+            None,
+        )
+        .unwrap();
+
+        let merge = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(merge.parent_count(), 2);
+        assert_eq!(
+            merge.message(),
+            Some(
+                format!(
+                    "Merge feature ({}) into {head}: Add feature",
+                    &feature.to_string()[..7]
+                )
+                .as_str()
+            )
+        );
+    }
+
+    /// Commit `base.txt` with `content` on `refname`, on top of `parent`
+    fn commit_base(repo: &Repository, refname: &str, parent: &Commit, content: &str) -> Oid {
+        let sig = Signature::now("Test", "test@example.com").unwrap();
+        let mut builder = repo.treebuilder(Some(&parent.tree().unwrap())).unwrap();
+        let blob = repo.blob(content.as_bytes()).unwrap();
+        builder.insert("base.txt", blob, 0o100644).unwrap();
+        let tree = repo.find_tree(builder.write().unwrap()).unwrap();
+        repo.commit(Some(refname), &sig, &sig, content.trim(), &tree, &[parent])
+            .unwrap()
+    }
+
+    #[test]
+    fn conflicting_merge_is_undone() {
+        let (tempdir, repo) = init_repo_with_commit();
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "Test").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+        let base = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch("feature", &base, false).unwrap();
+        commit_base(&repo, "refs/heads/feature", &base, "theirs\n");
+        let head = commit_base(&repo, "HEAD", &base, "ours\n");
+        repo.checkout_head(Some(CheckoutBuilder::new().force()))
+            .unwrap();
+        fs::write(tempdir.path().join("notes.txt"), "mine\n").unwrap();
+
+        let err = merge_branch(&repo, "feature", "Merge", None).unwrap_err();
+
+        assert!(
+            format!("{err:#}").contains("conflicts in base.txt"),
+            "{err:#}"
+        );
+        assert_eq!(repo.state(), git2::RepositoryState::Clean);
+        assert!(!repo.index().unwrap().has_conflicts());
+        assert_eq!(repo.head().unwrap().target(), Some(head));
+        let read = |path| fs::read_to_string(tempdir.path().join(path)).unwrap();
+        assert_eq!(read("base.txt"), "ours\n");
+        assert_eq!(read("notes.txt"), "mine\n");
+    }
+
+    #[test]
+    fn conflicting_cherry_pick_and_revert_are_undone() {
+        let (tempdir, repo) = init_repo_with_commit();
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "Test").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+        let base = repo.head().unwrap().peel_to_commit().unwrap();
+        let theirs = commit_base(&repo, "refs/heads/feature", &base, "theirs\n");
+        let first = commit_base(&repo, "HEAD", &base, "ours\n");
+        let first = repo.find_commit(first).unwrap();
+        let head = commit_base(&repo, "HEAD", &first, "ours again\n");
+        repo.checkout_head(Some(CheckoutBuilder::new().force()))
+            .unwrap();
+        fs::write(tempdir.path().join("notes.txt"), "mine\n").unwrap();
+        let assert_undone = |err: anyhow::Error, undone: &str| {
+            assert!(
+                format!("{err:#}").contains(&format!("conflicts in base.txt; the {undone}")),
+                "{err:#}"
+            );
+            assert_eq!(repo.state(), git2::RepositoryState::Clean);
+            assert!(!repo.index().unwrap().has_conflicts());
+            assert_eq!(repo.head().unwrap().target(), Some(head));
+            let read = |path| fs::read_to_string(tempdir.path().join(path)).unwrap();
+            assert_eq!(read("base.txt"), "ours again\n");
+            assert_eq!(read("notes.txt"), "mine\n");
+        };
+
+        let err = cherry_pick_commit(&repo, theirs, None).unwrap_err();
+        assert_undone(err, "cherry-pick was undone");
+        let err = revert_commit(&repo, first.id(), "Revert", None).unwrap_err();
+        assert_undone(err, "revert was undone");
+
+        // Staged changes would end up in the commit, so neither starts
+        fs::write(tempdir.path().join("base.txt"), "staged\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("base.txt")).unwrap();
+        index.write().unwrap();
+        let err = cherry_pick_commit(&repo, theirs, None).unwrap_err();
+        assert!(err.to_string().contains("staged changes"), "{err}");
+    }
+
+    #[test]
+    fn conflicting_rebase_is_aborted() {
+        let (tempdir, repo) = init_repo_with_commit();
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "Test").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+        let base = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch("onto", &base, false).unwrap();
+        commit_base(&repo, "refs/heads/onto", &base, "theirs\n");
+        let head = commit_base(&repo, "HEAD", &base, "ours\n");
+        repo.checkout_head(Some(CheckoutBuilder::new().force()))
+            .unwrap();
+
+        let err = rebase_branch(&repo, "onto", None).unwrap_err();
+
+        assert!(format!("{err:#}").contains("Rebase was aborted"), "{err:#}");
+        assert_eq!(repo.state(), git2::RepositoryState::Clean);
+        assert!(repo.head().unwrap().is_branch());
+        assert_eq!(repo.head().unwrap().target(), Some(head));
+        assert_eq!(
+            fs::read_to_string(tempdir.path().join("base.txt")).unwrap(),
+            "ours\n"
+        );
+    }
+
+    #[test]
+    fn conflicting_pull_leaves_the_branch_unchanged() {
+        let (tempdir, repo) = init_repo_with_commit();
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "Test").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+        let head = repo.head().unwrap().shorthand().unwrap().to_string();
+        let upstream_ref = format!("refs/remotes/origin/{head}");
+        repo.remote("origin", "https://example.com/repo.git")
+            .unwrap();
+        config
+            .set_str(&format!("branch.{head}.remote"), "origin")
+            .unwrap();
+        config
+            .set_str(
+                &format!("branch.{head}.merge"),
+                &format!("refs/heads/{head}"),
+            )
+            .unwrap();
+        let base = repo.head().unwrap().peel_to_commit().unwrap();
+        commit_base(&repo, &upstream_ref, &base, "theirs\n");
+        let local = commit_base(&repo, "HEAD", &base, "ours\n");
+        repo.checkout_head(Some(CheckoutBuilder::new().force()))
+            .unwrap();
+
+        for rebase in [false, true] {
+            let err = pull_current_branch(&repo, rebase, "Merge {branch}", None).unwrap_err();
+            let err = format!("{err:#}");
+            if rebase {
+                assert!(err.contains("Rebase was aborted"), "{err}");
+            } else {
+                assert!(err.contains("conflicts in base.txt"), "{err}");
+            }
+            assert_eq!(repo.state(), git2::RepositoryState::Clean);
+            assert_eq!(repo.head().unwrap().target(), Some(local));
+            assert_eq!(
+                fs::read_to_string(tempdir.path().join("base.txt")).unwrap(),
+                "ours\n"
+            );
+        }
+
+        // Without keifu's own setting, git's decides
+        let git_repo = GitRepository::open(tempdir.path()).unwrap();
+        config.set_bool("pull.rebase", false).unwrap();
+        assert!(!git_repo.pull_rebases(&head));
+        config.set_str("pull.rebase", "merges").unwrap();
+        assert!(git_repo.pull_rebases(&head));
+        config
+            .set_bool(&format!("branch.{head}.rebase"), false)
+            .unwrap();
+        assert!(!git_repo.pull_rebases(&head));
+    }
+
+    #[test]
+    fn pull_fast_forwards_merges_or_rebases_onto_the_upstream() {
+        let (_tempdir, repo) = init_repo_with_commit();
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "Test").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+        let head = repo.head().unwrap().shorthand().unwrap().to_string();
+        let upstream_ref = format!("refs/remotes/origin/{head}");
+        repo.remote("origin", "https://example.com/repo.git")
+            .unwrap();
+        config
+            .set_str(&format!("branch.{head}.remote"), "origin")
+            .unwrap();
+        config
+            .set_str(
+                &format!("branch.{head}.merge"),
+                &format!("refs/heads/{head}"),
+            )
+            .unwrap();
+
+        let sig = Signature::now("Test", "test@example.com").unwrap();
+        let commit_on = |refname: &str, parent: Oid, path: &str| {
+            let parent = repo.find_commit(parent).unwrap();
+            let mut builder = repo.treebuilder(Some(&parent.tree().unwrap())).unwrap();
+            let blob = repo.blob(path.as_bytes()).unwrap();
+            builder.insert(path, blob, 0o100644).unwrap();
+            let tree = repo.find_tree(builder.write().unwrap()).unwrap();
+            let oid = repo
+                .commit(Some(refname), &sig, &sig, path, &tree, &[&parent])
+                .unwrap();
+            repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force()))
+                .unwrap();
+            oid
+        };
+        let head_oid = || repo.head().unwrap().target().unwrap();
+        let template = "Merge {branch}";
+
+        let base = head_oid();
+        let upstream = commit_on(&upstream_ref, base, "one.txt");
+        assert_eq!(
+            pull_current_branch(&repo, false, template, None).unwrap(),
+            PullOutcome::FastForward
+        );
+        assert_eq!(head_oid(), upstream);
+        assert_eq!(
+            pull_current_branch(&repo, false, template, None).unwrap(),
+            PullOutcome::UpToDate
+        );
+
+        // Diverged: merge the upstream in
+        commit_on("HEAD", upstream, "local.txt");
+        let upstream = commit_on(&upstream_ref, upstream, "two.txt");
+        assert_eq!(
+            pull_current_branch(&repo, false, template, None).unwrap(),
+            PullOutcome::Merged {
+                unsigned_reason: None
+            }
+        );
+        let merge = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(merge.parent_count(), 2);
+        assert_eq!(
+            merge.message(),
+            Some(format!("Merge origin/{head}").as_str())
+        );
+
+        // Diverged again: replay the local commit onto the upstream
+        commit_on("HEAD", merge.id(), "more.txt");
+        let upstream = commit_on(&upstream_ref, upstream, "three.txt");
+        assert_eq!(
+            pull_current_branch(&repo, true, template, None).unwrap(),
+            PullOutcome::Rebased
+        );
+        let rebased = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(rebased.parent_count(), 1);
+        assert!(repo.graph_descendant_of(rebased.id(), upstream).unwrap());
+        assert_eq!(rebased.summary(), Some("more.txt"));
+    }
+
     #[test]
     fn stage_and_unstage_untracked_file() {
         let (tempdir, repo) = init_repo_with_commit();
@@ -410,7 +1401,7 @@ mod tests {
         fs::write(tempdir.path().join("unstaged.txt"), "u\n").unwrap();
         stage_path(&repo, Path::new("staged.txt")).unwrap();
 
-        let oid = create_commit(&repo, "add staged.txt").unwrap();
+        let oid = create_commit(&repo, "add staged.txt", None).unwrap().oid;
         let commit = repo.find_commit(oid).unwrap();
         assert_eq!(commit.message(), Some("add staged.txt"));
         let tree = commit.tree().unwrap();
@@ -424,6 +1415,58 @@ mod tests {
         );
     }
 
+    #[test]
+    fn checkout_previous_bounces_between_branches() {
+        let (_tempdir, repo) = init_repo_with_commit();
+        let original = repo.head().unwrap().shorthand().unwrap().to_string();
+        let head = repo.head().unwrap().peel_to_commit().unwrap().id();
+        create_branch(&repo, "feature", head).unwrap();
+
+        assert!(checkout_previous(&repo).is_err());
+
+        checkout_branch(&repo, "feature").unwrap();
+        assert_eq!(checkout_previous(&repo).unwrap(), original);
+        assert_eq!(repo.head().unwrap().shorthand(), Some(original.as_str()));
+
+        assert_eq!(checkout_previous(&repo).unwrap(), "feature");
+        assert_eq!(repo.head().unwrap().shorthand(), Some("feature"));
+    }
+
+    #[test]
+    fn identity_parses_git_author_form() {
+        let identity = Identity::parse("  Ann Example <ann@example.com> ").unwrap();
+        assert_eq!(identity.name, "Ann Example");
+        assert_eq!(identity.email, "ann@example.com");
+        assert_eq!(identity.to_string(), "Ann Example <ann@example.com>");
+
+        for bad in [
+            "Ann",
+            "<ann@example.com>",
+            "Ann <>",
+            "Ann <ann@example.com",
+            "A>n <a@b>",
+        ] {
+            assert!(Identity::parse(bad).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn create_tag_uses_the_given_identity() {
+        let (_tempdir, repo) = init_repo_with_commit();
+        let head = repo.head().unwrap().peel_to_commit().unwrap().id();
+        let identity = Identity::parse("Ann <ann@example.com>").unwrap();
+
+        create_tag(&repo, "v1", head, Some("Release\n"), Some(&identity)).unwrap();
+        let tag = repo
+            .find_reference("refs/tags/v1")
+            .unwrap()
+            .peel_to_tag()
+            .unwrap();
+        let tagger = tag.tagger().unwrap();
+        assert_eq!(tagger.name(), Some("Ann"));
+        assert_eq!(tagger.email(), Some("ann@example.com"));
+    }
+
     #[test]
     fn create_commit_rejects_empty_index() {
         let (_tempdir, repo) = init_repo_with_commit();
@@ -433,7 +1476,155 @@ mod tests {
             .set_str("user.email", "test@example.com")
             .unwrap();
 
-        let err = create_commit(&repo, "empty").unwrap_err();
+        let err = create_commit(&repo, "empty", None).unwrap_err();
         assert!(err.to_string().contains("No staged changes"));
     }
+
+    #[test]
+    fn stash_save_then_pop_restores_changes() {
+        let (tempdir, mut repo) = init_repo_with_commit();
+        repo.config().unwrap().set_str("user.name", "Test").unwrap();
+        repo.config()
+            .unwrap()
+            .set_str("user.email", "test@example.com")
+            .unwrap();
+        let base = tempdir.path().join("base.txt");
+
+        let err = stash_save(&mut repo, None, None).unwrap_err();
+        assert!(err.to_string().contains("No local changes"), "{err}");
+
+        fs::write(&base, "first\n").unwrap();
+        stash_save(&mut repo, Some("first"), None).unwrap();
+        fs::write(&base, "second\n").unwrap();
+        stash_save(&mut repo, None, None).unwrap();
+        assert_eq!(fs::read_to_string(&base).unwrap(), "base\n");
+
+        let stashes = GitRepository::open(tempdir.path())
+            .unwrap()
+            .get_stashes()
+            .unwrap();
+        assert_eq!(stashes.len(), 2);
+        assert_eq!(stashes[0].index, 0);
+        assert!(stashes[0].message.starts_with("WIP on "), "{stashes:?}");
+        assert!(stashes[1].message.ends_with(": first"), "{stashes:?}");
+
+        // Applying over conflicting local changes keeps the entry
+        fs::write(&base, "local\n").unwrap();
+        let err = stash_pop(&mut repo, 1).unwrap_err();
+        assert!(err.to_string().contains("conflicts"), "{err}");
+        fs::write(&base, "base\n").unwrap();
+
+        stash_apply(&mut repo, 1).unwrap();
+        assert_eq!(fs::read_to_string(&base).unwrap(), "first\n");
+        fs::write(&base, "base\n").unwrap();
+        stash_pop(&mut repo, 0).unwrap();
+        assert_eq!(fs::read_to_string(&base).unwrap(), "second\n");
+        let stashes = GitRepository::open(tempdir.path())
+            .unwrap()
+            .get_stashes()
+            .unwrap();
+        assert_eq!(stashes.len(), 1);
+        assert!(stashes[0].message.ends_with(": first"));
+        assert!(stash_pop(&mut repo, 3).is_err());
+    }
+
+    #[test]
+    fn force_push_is_refused_when_the_lease_is_stale() {
+        let (tempdir, repo) = init_repo_with_commit();
+        let remote_dir = tempfile::tempdir().unwrap();
+        Repository::init_bare(remote_dir.path()).unwrap();
+        repo.remote("origin", remote_dir.path().to_str().unwrap())
+            .unwrap();
+        let repo_path = tempdir.path().to_str().unwrap();
+        let branch = repo.head().unwrap().shorthand().unwrap().to_string();
+        let mut updates = Vec::new();
+        push_branch(repo_path, "origin", &branch, PushMode::Normal, |update| {
+            updates.push(update)
+        })
+        .unwrap();
+        assert!(updates.iter().any(|update| update.percent == 100));
+        let pushed = repo.head().unwrap().target().unwrap();
+
+        // Rewrite the pushed commit so only a force-push can land it
+        let sig = Signature::now("Test", "test@example.com").unwrap();
+        let tree = repo.find_commit(pushed).unwrap().tree().unwrap();
+        let rewritten = repo
+            .commit(None, &sig, &sig, "rewritten", &tree, &[])
+            .unwrap();
+        repo.reference(&format!("refs/heads/{branch}"), rewritten, true, "rewrite")
+            .unwrap();
+        assert!(push_branch(repo_path, "origin", &branch, PushMode::Normal, |_| {}).is_err());
+
+        let stale = PushMode::ForceWithLease {
+            expected: Some(rewritten),
+        };
+        assert!(push_branch(repo_path, "origin", &branch, stale, |_| {}).is_err());
+
+        let lease = PushMode::ForceWithLease {
+            expected: Some(pushed),
+        };
+        push_branch(repo_path, "origin", &branch, lease, |_| {}).unwrap();
+        let remote = Repository::open_bare(remote_dir.path()).unwrap();
+        let tip = remote
+            .find_reference(&format!("refs/heads/{branch}"))
+            .unwrap()
+            .target();
+        assert_eq!(tip, Some(rewritten));
+    }
+
+    #[test]
+    fn checkout_remote_branch_tracks_a_non_origin_remote() {
+        let (_tempdir, repo) = init_repo_with_commit();
+        let head = repo.head().unwrap().peel_to_commit().unwrap().id();
+        repo.remote("upstream", "https://example.com/upstream.git")
+            .unwrap();
+        repo.remote("team/fork", "https://example.com/fork.git")
+            .unwrap();
+        repo.reference("refs/remotes/upstream/feature/x", head, true, "remote")
+            .unwrap();
+        repo.reference("refs/remotes/team/fork/main", head, true, "remote")
+            .unwrap();
+
+        assert_eq!(
+            split_remote_branch(&repo, "upstream/feature/x"),
+            Some(("upstream", "feature/x"))
+        );
+        assert_eq!(
+            split_remote_branch(&repo, "team/fork/main"),
+            Some(("team/fork", "main"))
+        );
+        assert_eq!(split_remote_branch(&repo, "elsewhere/main"), None);
+
+        checkout_remote_branch(&repo, "upstream/feature/x").unwrap();
+        assert_eq!(repo.head().unwrap().shorthand(), Some("feature/x"));
+        let local = repo.find_branch("feature/x", BranchType::Local).unwrap();
+        assert_eq!(
+            local.upstream().unwrap().name().unwrap(),
+            Some("upstream/feature/x")
+        );
+        assert!(checkout_remote_branch(&repo, "elsewhere/main").is_err());
+    }
+
+    #[test]
+    fn progress_lines_are_parsed_with_their_phase() {
+        assert_eq!(
+            parse_progress("Receiving objects:  45% (450/1000), 1.20 MiB | 2.00 MiB/s"),
+            Some(TransferProgress {
+                phase: "Receiving objects".to_string(),
+                percent: 45,
+            })
+        );
+        assert_eq!(
+            parse_progress("remote: Compressing objects: 100% (3/3), done."),
+            Some(TransferProgress {
+                phase: "remote: Compressing objects".to_string(),
+                percent: 100,
+            })
+        );
+        assert_eq!(
+            parse_progress("remote: Enumerating objects: 5, done."),
+            None
+        );
+        assert_eq!(parse_progress("fatal: repository not found"), None);
+    }
 }
@@ -0,0 +1,214 @@
+//! Limit the graph to commits touching a set of paths (`git log -- <path>`)
+//!
+//! Whether a commit touches the pathspec needs a tree diff per commit, so
+//! results are computed on a background thread and cached by OID. Until a
+//! commit has been checked it stays visible.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+use git2::Oid;
+
+use super::{CommitInfo, GitRepository};
+
+#[derive(Default)]
+pub struct PathScope {
+    /// Pathspecs relative to the repository root (empty = no scope)
+    paths: Vec<String>,
+    /// OID -> whether the commit touches the pathspec
+    matches: HashMap<Oid, bool>,
+    receiver: Option<Receiver<(Oid, bool)>>,
+    /// Number of commits handed to the running check
+    pending: usize,
+    /// Results received from the running check
+    received: usize,
+}
+
+impl PathScope {
+    /// Replace the pathspec (clears cached results)
+    pub fn set_paths(&mut self, paths: Vec<String>) {
+        *self = Self {
+            paths,
+            ..Self::default()
+        };
+    }
+
+    pub fn paths(&self) -> &[String] {
+        &self.paths
+    }
+
+    pub fn is_active(&self) -> bool {
+        !self.paths.is_empty()
+    }
+
+    /// Check loaded commits that aren't cached yet in the background
+    pub fn start(&mut self, repo_path: &str, commits: &[CommitInfo]) {
+        if !self.is_active() || self.receiver.is_some() {
+            return;
+        }
+        let unchecked: Vec<Oid> = commits
+            .iter()
+            .map(|c| c.oid)
+            .filter(|oid| !self.matches.contains_key(oid))
+            .collect();
+        if unchecked.is_empty() {
+            return;
+        }
+
+        let (tx, rx) = mpsc::channel();
+        let repo_path = repo_path.to_string();
+        let paths = self.paths.clone();
+        self.pending = unchecked.len();
+        self.received = 0;
+        thread::spawn(move || {
+            let repo = GitRepository::open(&repo_path).ok();
+            for oid in unchecked {
+                // Keep commits we can't diff visible rather than hiding them
+                let touches = repo
+                    .as_ref()
+                    .and_then(|repo| repo.commit_touches_paths(oid, &paths).ok())
+                    .unwrap_or(true);
+                if tx.send((oid, touches)).is_err() {
+                    return;
+                }
+            }
+        });
+        self.receiver = Some(rx);
+    }
+
+    /// Collect finished results; true when the running check just completed
+    pub fn poll(&mut self) -> bool {
+        let Some(rx) = &self.receiver else {
+            return false;
+        };
+        loop {
+            match rx.try_recv() {
+                Ok((oid, touches)) => {
+                    self.matches.insert(oid, touches);
+                    self.received += 1;
+                }
+                Err(mpsc::TryRecvError::Empty) => return false,
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    self.receiver = None;
+                    return true;
+                }
+            }
+        }
+    }
+
+    /// (checked, total) while the background check is running
+    pub fn progress(&self) -> Option<(usize, usize)> {
+        self.receiver
+            .as_ref()
+            .map(|_| (self.received, self.pending))
+    }
+
+    /// Commits to display: matching (or not yet checked) ones, with parents
+    /// rewritten to their nearest displayed ancestors so the graph stays
+    /// connected
+    pub fn apply(&self, commits: &[CommitInfo]) -> Vec<CommitInfo> {
+        if !self.is_active() {
+            return commits.to_vec();
+        }
+        let visible = |oid: &Oid| self.matches.get(oid).copied().unwrap_or(true);
+
+        // Commits are ordered children first, so walk from the oldest to
+        // resolve each hidden commit to its displayed ancestors
+        let loaded: HashSet<Oid> = commits.iter().map(|c| c.oid).collect();
+        let mut resolved: HashMap<Oid, Vec<Oid>> = HashMap::new();
+        let mut shown = Vec::new();
+        for commit in commits.iter().rev() {
+            let mut parents: Vec<Oid> = Vec::new();
+            for parent in commit.parent_oids.iter().filter(|p| loaded.contains(p)) {
+                let targets = if visible(parent) {
+                    vec![*parent]
+                } else {
+                    resolved.get(parent).cloned().unwrap_or_default()
+                };
+                for target in targets {
+                    if !parents.contains(&target) {
+                        parents.push(target);
+                    }
+                }
+            }
+            if visible(&commit.oid) {
+                let mut commit = commit.clone();
+                commit.parent_oids = parents;
+                shown.push(commit);
+            } else {
+                resolved.insert(commit.oid, parents);
+            }
+        }
+        shown.reverse();
+        shown
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Local;
+
+    use super::*;
+
+    fn oid(n: u8) -> Oid {
+        Oid::from_bytes(&[n; 20]).unwrap()
+    }
+
+    fn commit(n: u8, parents: &[u8]) -> CommitInfo {
+        CommitInfo {
+            oid: oid(n),
+            short_id: n.to_string(),
+            author_name: "Test User".to_string(),
+            author_email: "test@example.com".to_string(),
+            committer_name: "Test User".to_string(),
+            committer_email: "test@example.com".to_string(),
+            timestamp: Local::now(),
+            message: n.to_string(),
+            full_message: n.to_string(),
+            parent_oids: parents.iter().map(|p| oid(*p)).collect(),
+        }
+    }
+
+    #[test]
+    fn hidden_commits_are_skipped_and_parents_rewritten() {
+        // 5 merges 4 and 3; 4 -> 2; 3 -> 2; 2 -> 1
+        let commits = vec![
+            commit(5, &[4, 3]),
+            commit(4, &[2]),
+            commit(3, &[2]),
+            commit(2, &[1]),
+            commit(1, &[]),
+        ];
+        let mut scope = PathScope::default();
+        scope.set_paths(vec!["src".to_string()]);
+        scope.matches = HashMap::from([
+            (oid(5), true),
+            (oid(4), false),
+            (oid(3), false),
+            (oid(2), false),
+            (oid(1), true),
+        ]);
+
+        let shown = scope.apply(&commits);
+
+        assert_eq!(shown.len(), 2);
+        assert_eq!(shown[0].oid, oid(5));
+        assert_eq!(shown[0].parent_oids, [oid(1)]);
+        assert_eq!(shown[1].oid, oid(1));
+    }
+
+    #[test]
+    fn unchecked_commits_stay_visible() {
+        let commits = vec![commit(2, &[1]), commit(1, &[])];
+        let mut scope = PathScope::default();
+        scope.set_paths(vec!["src".to_string()]);
+        scope.matches.insert(oid(1), false);
+
+        let shown = scope.apply(&commits);
+
+        assert_eq!(shown.len(), 1);
+        assert_eq!(shown[0].oid, oid(2));
+        assert!(shown[0].parent_oids.is_empty());
+    }
+}
@@ -1,11 +1,11 @@
 //! Repository operation wrapper
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 
 use anyhow::{Context, Result};
-use git2::{BranchType, Repository, Status};
+use git2::{Branch, BranchType, Repository, RepositoryState, Status};
 
 use git2::Oid;
 
@@ -59,6 +59,29 @@ impl GitRepository {
 
     /// Get commit history (newest first)
     pub fn get_commits(&self, max_count: usize, include_remotes: bool) -> Result<Vec<CommitInfo>> {
+        let oids = self
+            .history_walk(include_remotes)?
+            .take(max_count)
+            .collect::<Result<Vec<_>, _>>()?;
+        self.read_commits(&oids)
+    }
+
+    /// Order of the whole history without reading any commit, so an already
+    /// loaded history can be extended batch by batch with `read_commits`
+    pub fn commit_order(&self, include_remotes: bool) -> Result<Vec<Oid>> {
+        Ok(self
+            .history_walk(include_remotes)?
+            .collect::<Result<Vec<_>, _>>()?)
+    }
+
+    /// Read the given commits, keeping their order
+    pub fn read_commits(&self, oids: &[Oid]) -> Result<Vec<CommitInfo>> {
+        oids.iter()
+            .map(|&oid| Ok(CommitInfo::from_git2_commit(&self.repo.find_commit(oid)?)))
+            .collect()
+    }
+
+    fn history_walk(&self, include_remotes: bool) -> Result<git2::Revwalk<'_>> {
         let mut revwalk = self.repo.revwalk()?;
         revwalk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::TIME)?;
 
@@ -68,21 +91,171 @@ impl GitRepository {
             Some(BranchType::Local)
         };
 
+        let mut pushed = false;
         for branch_result in self.repo.branches(branch_filter)? {
             let (branch, _) = branch_result?;
             if let Some(oid) = branch.get().target() {
                 revwalk.push(oid)?;
+                pushed = true;
             }
         }
+        // Without branches (only a detached HEAD left) the history still
+        // starts at HEAD; an unborn HEAD has nothing to walk
+        if !pushed {
+            if let Some(oid) = self.repo.head().ok().and_then(|head| head.target()) {
+                revwalk.push(oid)?;
+            }
+        }
+        Ok(revwalk)
+    }
 
-        let mut commits = Vec::new();
-        for oid_result in revwalk.take(max_count) {
-            let oid = oid_result?;
-            let commit = self.repo.find_commit(oid)?;
-            commits.push(CommitInfo::from_git2_commit(&commit));
+    /// Whether the commit changes anything matching `pathspec` (relative to
+    /// the repository root). A merge only counts when it differs from every
+    /// parent, matching `git log -- <path>` history simplification.
+    pub fn commit_touches_paths(&self, oid: Oid, pathspec: &[String]) -> Result<bool> {
+        let commit = self.repo.find_commit(oid)?;
+        let tree = commit.tree()?;
+        let changed = |parent_tree: Option<&git2::Tree>| -> Result<bool> {
+            let mut opts = git2::DiffOptions::new();
+            for path in pathspec {
+                opts.pathspec(path);
+            }
+            let diff = self
+                .repo
+                .diff_tree_to_tree(parent_tree, Some(&tree), Some(&mut opts))?;
+            Ok(diff.deltas().len() > 0)
+        };
+
+        if commit.parent_count() == 0 {
+            return changed(None);
+        }
+        for parent in commit.parents() {
+            if !changed(Some(&parent.tree()?))? {
+                return Ok(false);
+            }
         }
+        Ok(true)
+    }
 
-        Ok(commits)
+    /// Commits whose ancestry is rewritten by `refs/replace/*` or
+    /// `info/grafts`, so the displayed history may differ from the
+    /// stored objects
+    pub fn replaced_commits(&self) -> Result<HashSet<Oid>> {
+        let mut replaced = HashSet::new();
+        for reference in self.repo.references_glob("refs/replace/*")? {
+            let reference = reference?;
+            if let Some(oid) = reference
+                .name()
+                .and_then(|name| name.strip_prefix("refs/replace/"))
+                .and_then(|hex| Oid::from_str(hex).ok())
+            {
+                replaced.insert(oid);
+            }
+        }
+
+        // Each graft line is "<commit> [<parent>...]"
+        if let Ok(grafts) = std::fs::read_to_string(self.repo.path().join("info/grafts")) {
+            replaced.extend(
+                grafts
+                    .lines()
+                    .filter_map(|line| line.split_whitespace().next())
+                    .filter_map(|hex| Oid::from_str(hex).ok()),
+            );
+        }
+        Ok(replaced)
+    }
+
+    /// Tag names by the commit they point to (annotated tags are peeled)
+    pub fn tags_by_commit(&self) -> Result<HashMap<Oid, Vec<String>>> {
+        let mut tags: HashMap<Oid, Vec<String>> = HashMap::new();
+        for reference in self.repo.references_glob("refs/tags/*")? {
+            let reference = reference?;
+            let (Some(name), Ok(commit)) = (reference.shorthand(), reference.peel_to_commit())
+            else {
+                continue;
+            };
+            tags.entry(commit.id()).or_default().push(name.to_string());
+        }
+        for names in tags.values_mut() {
+            names.sort();
+        }
+        Ok(tags)
+    }
+
+    /// Local branches checked out in other worktrees of this repository, with
+    /// the worktree's path. git refuses to check them out here as well
+    pub fn worktree_branches(&self) -> Result<HashMap<String, PathBuf>> {
+        let mut checkouts: Vec<Repository> = Vec::new();
+        if self.repo.is_worktree() {
+            // The main worktree isn't among the linked ones; a linked
+            // worktree's git dir is `<common dir>/worktrees/<name>`
+            let common_dir = self.repo.path().parent().and_then(Path::parent);
+            if let Some(main) = common_dir.and_then(|dir| Repository::open(dir).ok()) {
+                checkouts.push(main);
+            }
+        }
+        for name in self.repo.worktrees()?.iter().flatten() {
+            if let Ok(repo) = self
+                .repo
+                .find_worktree(name)
+                .and_then(|worktree| Repository::open_from_worktree(&worktree))
+            {
+                checkouts.push(repo);
+            }
+        }
+
+        let own_workdir = self.repo.workdir().and_then(|dir| dir.canonicalize().ok());
+        let mut branches = HashMap::new();
+        for repo in checkouts {
+            let Some(workdir) = repo.workdir().and_then(|dir| dir.canonicalize().ok()) else {
+                continue;
+            };
+            if Some(&workdir) == own_workdir.as_ref() {
+                continue;
+            }
+            if let Ok(head) = repo.head() {
+                if let (true, Some(name)) = (head.is_branch(), head.shorthand()) {
+                    branches.insert(name.to_string(), workdir);
+                }
+            }
+        }
+        Ok(branches)
+    }
+
+    /// Resolve a revision (hash, branch, `HEAD~3`, ...) to a commit OID
+    pub fn resolve_commit(&self, rev: &str) -> Result<Oid> {
+        let object = self
+            .repo
+            .revparse_single(rev)
+            .with_context(|| format!("Unknown revision: {rev}"))?;
+        let commit = object
+            .peel_to_commit()
+            .with_context(|| format!("{rev} does not point to a commit"))?;
+        Ok(commit.id())
+    }
+
+    /// Commit that last changed `line` (1-based) of `path` as of `at`,
+    /// like `git blame -L line,line at -- path`
+    pub fn blame_line(&self, at: Oid, path: &Path, line: u32) -> Result<Oid> {
+        let line = line as usize;
+        let mut opts = git2::BlameOptions::new();
+        opts.newest_commit(at).min_line(line).max_line(line);
+        let blame = self
+            .repo
+            .blame_file(path, Some(&mut opts))
+            .with_context(|| format!("Cannot blame {}", path.display()))?;
+        let hunk = blame
+            .get_line(line)
+            .with_context(|| format!("{}:{line} not found", path.display()))?;
+        Ok(hunk.final_commit_id())
+    }
+
+    /// Raw commit object as stored by git (`git cat-file -p` output),
+    /// including the signature header if present
+    pub fn raw_commit_object(&self, oid: Oid) -> Result<String> {
+        let odb = self.repo.odb()?;
+        let object = odb.read(oid)?;
+        Ok(String::from_utf8_lossy(object.data()).into_owned())
     }
 
     /// Get branch list
@@ -107,6 +280,206 @@ impl GitRepository {
             .map(|c| c.id())
     }
 
+    /// Upstream divergence, stash count and in-progress operation
+    pub fn summary(&self) -> RepoSummary {
+        RepoSummary {
+            ahead_behind: self.head_ahead_behind(),
+            stash_count: self
+                .repo
+                .reflog("refs/stash")
+                .map(|reflog| reflog.len())
+                .unwrap_or(0),
+            state: match self.repo.state() {
+                RepositoryState::Clean => None,
+                RepositoryState::Merge => Some("MERGING"),
+                RepositoryState::Revert | RepositoryState::RevertSequence => Some("REVERTING"),
+                RepositoryState::CherryPick | RepositoryState::CherryPickSequence => {
+                    Some("CHERRY-PICKING")
+                }
+                RepositoryState::Bisect => Some("BISECTING"),
+                RepositoryState::Rebase
+                | RepositoryState::RebaseInteractive
+                | RepositoryState::RebaseMerge => Some("REBASING"),
+                RepositoryState::ApplyMailbox | RepositoryState::ApplyMailboxOrRebase => Some("AM"),
+            },
+        }
+    }
+
+    /// HEAD branch's upstream: (upstream name, upstream tip, HEAD tip)
+    pub fn head_upstream(&self) -> Result<(String, Oid, Oid)> {
+        let head = self
+            .repo
+            .head()
+            .context("HEAD does not point to a commit yet")?;
+        if !head.is_branch() {
+            anyhow::bail!("HEAD is detached; there is no upstream to compare with");
+        }
+        let name = head.shorthand().unwrap_or("HEAD").to_string();
+        let local = head
+            .target()
+            .context("HEAD does not point to a commit yet")?;
+        let upstream = Branch::wrap(head).upstream().map_err(|_| {
+            anyhow::anyhow!("'{name}' has no upstream branch (set one with git push -u)")
+        })?;
+        let upstream_name = upstream.name()?.unwrap_or("upstream").to_string();
+        let upstream_oid = upstream
+            .get()
+            .target()
+            .with_context(|| format!("{upstream_name} does not point to a commit"))?;
+        Ok((upstream_name, upstream_oid, local))
+    }
+
+    /// Commits that rewriting HEAD's history down to `base` (rebase onto
+    /// it, amend, reset) would replace although they're already on the
+    /// upstream: (upstream name, count). None when nothing pushed is touched
+    pub fn pushed_commits_since(&self, base: Oid) -> Option<(String, usize)> {
+        let (upstream_name, upstream, head) = self.head_upstream().ok()?;
+        // Newest commit on HEAD's line that the upstream already has
+        let pushed_tip = self.repo.merge_base(upstream, head).ok()?;
+        let mut walk = self.repo.revwalk().ok()?;
+        walk.push(pushed_tip).ok()?;
+        walk.hide(base).ok()?;
+        let count = walk.count();
+        (count > 0).then_some((upstream_name, count))
+    }
+
+    /// Commits rebasing HEAD onto `onto` replays, oldest first: the ones
+    /// `onto` doesn't have, without merges (the rebase drops them)
+    pub fn rebase_commits(&self, onto: Oid) -> Result<Vec<CommitInfo>> {
+        let mut walk = self.repo.revwalk()?;
+        walk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::REVERSE)?;
+        walk.push_head()?;
+        walk.hide(onto)?;
+        let mut commits = Vec::new();
+        for oid in walk {
+            let commit = self.repo.find_commit(oid?)?;
+            if commit.parent_count() <= 1 {
+                commits.push(CommitInfo::from_git2_commit(&commit));
+            }
+        }
+        Ok(commits)
+    }
+
+    /// (ahead, behind) of the HEAD branch relative to its upstream
+    fn head_ahead_behind(&self) -> Option<(usize, usize)> {
+        let head = self.repo.head().ok()?;
+        if !head.is_branch() {
+            return None;
+        }
+        let local = head.target()?;
+        let upstream = Branch::wrap(head).upstream().ok()?.get().target()?;
+        self.repo.graph_ahead_behind(local, upstream).ok()
+    }
+
+    /// Configured remotes, in config order
+    pub fn remote_names(&self) -> Vec<String> {
+        self.repo
+            .remotes()
+            .map(|names| names.iter().flatten().map(String::from).collect())
+            .unwrap_or_default()
+    }
+
+    /// Remote a plain `git fetch` would use: the HEAD branch's remote, then
+    /// `origin`, then the only remote
+    pub fn default_remote(&self) -> Option<String> {
+        let remotes = self.remote_names();
+        let head_remote = self
+            .repo
+            .head()
+            .ok()
+            .filter(|head| head.is_branch())
+            .and_then(|head| head.shorthand().map(String::from))
+            .and_then(|branch| self.branch_config(&branch, "remote"));
+        head_remote
+            .filter(|name| remotes.contains(name))
+            .or_else(|| remotes.iter().find(|name| *name == "origin").cloned())
+            .or_else(|| (remotes.len() == 1).then(|| remotes[0].clone()))
+    }
+
+    /// Remote configured for pushing `branch` (`branch.<name>.pushRemote`,
+    /// `remote.pushDefault`, then the branch's upstream remote). None when
+    /// nothing is configured, so the caller has to pick one
+    pub fn push_remote(&self, branch: &str) -> Option<String> {
+        let remotes = self.remote_names();
+        let push_default = self
+            .repo
+            .config()
+            .ok()
+            .and_then(|config| config.get_string("remote.pushDefault").ok());
+        self.branch_config(branch, "pushRemote")
+            .or(push_default)
+            .or_else(|| self.branch_config(branch, "remote"))
+            .filter(|name| remotes.contains(name))
+    }
+
+    /// Stash entries, newest (`stash@{0}`) first. Read from the
+    /// `refs/stash` reflog, which is what `stash_foreach` walks too but
+    /// without needing the repository mutably.
+    pub fn get_stashes(&self) -> Result<Vec<StashInfo>> {
+        let reflog = match self.repo.reflog("refs/stash") {
+            Ok(reflog) => reflog,
+            Err(e) if e.code() == git2::ErrorCode::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(reflog
+            .iter()
+            .enumerate()
+            .map(|(index, entry)| StashInfo {
+                index,
+                message: entry.message().unwrap_or_default().to_string(),
+                oid: entry.id_new(),
+            })
+            .collect())
+    }
+
+    /// Effective values of the config keys keifu's operations depend on,
+    /// None when unset
+    pub fn operation_config(&self) -> Vec<(&'static str, Option<String>)> {
+        let config = self.repo.config().ok();
+        ["user.name", "user.email", "pull.rebase", "commit.gpgsign"]
+            .into_iter()
+            .map(|key| {
+                let value = config.as_ref().and_then(|c| c.get_string(key).ok());
+                (key, value)
+            })
+            .collect()
+    }
+
+    /// Remote holding the upstream of `branch` (`branch.<name>.remote`)
+    pub fn upstream_remote(&self, branch: &str) -> Option<String> {
+        self.branch_config(branch, "remote")
+    }
+
+    /// Whether `git pull` would rebase `branch` (`branch.<name>.rebase`,
+    /// then `pull.rebase`). The `merges` and `interactive` modes count as
+    /// rebasing
+    pub fn pull_rebases(&self, branch: &str) -> bool {
+        let Ok(config) = self.repo.config() else {
+            return false;
+        };
+        [format!("branch.{branch}.rebase"), "pull.rebase".to_string()]
+            .iter()
+            .find_map(|key| {
+                config.get_bool(key).ok().or_else(|| {
+                    let value = config.get_string(key).ok()?;
+                    Some(matches!(
+                        value.as_str(),
+                        "merges" | "interactive" | "i" | "m"
+                    ))
+                })
+            })
+            .unwrap_or(false)
+    }
+
+    /// `branch.<branch>.<key>` from the repository config
+    fn branch_config(&self, branch: &str, key: &str) -> Option<String> {
+        self.repo
+            .config()
+            .ok()?
+            .get_string(&format!("branch.{branch}.{key}"))
+            .ok()
+    }
+
     /// Get working tree status (staged + unstaged + untracked changes)
     /// Returns None if there are no changes
     pub fn get_working_tree_status(&self) -> Result<Option<WorkingTreeStatus>> {
@@ -208,6 +581,28 @@ impl GitRepository {
     }
 }
 
+/// Repository-wide indicators for the status bar
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RepoSummary {
+    /// Commits ahead/behind the upstream of the HEAD branch
+    pub ahead_behind: Option<(usize, usize)>,
+    /// Number of stash entries
+    pub stash_count: usize,
+    /// In-progress operation (merge, rebase, ...), None when clean
+    pub state: Option<&'static str>,
+}
+
+/// A stash entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StashInfo {
+    /// Position in the stash list (`stash@{index}`)
+    pub index: usize,
+    /// e.g. "WIP on main: 1a2b3c4 Fix typo"
+    pub message: String,
+    /// The stash commit
+    pub oid: Oid,
+}
+
 /// Stage state of a file in the working tree
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum StageState {
@@ -0,0 +1,334 @@
+//! Signing for commits keifu writes itself (commit dialog, merges)
+//!
+//! Follows the repository's git config like `git commit` does:
+//! `commit.gpgsign` turns signing on, `gpg.format` picks openpgp / x509 /
+//! ssh, `user.signingkey` selects the key and `gpg.<format>.program`
+//! overrides the signer. libgit2 can't sign, so the commit buffer is
+//! signed by the external program and written with `commit_signed`.
+
+use std::collections::hash_map::RandomState;
+use std::fs::{File, OpenOptions};
+use std::hash::{BuildHasher, Hasher};
+use std::io::{ErrorKind, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::time::SystemTime;
+
+use anyhow::{bail, Context, Result};
+use git2::{Commit, Oid, Repository, Signature, Tree};
+
+/// A commit written by keifu
+#[derive(Debug)]
+pub struct WrittenCommit {
+    pub oid: Oid,
+    /// Why the commit is unsigned although `commit.gpgsign` is set
+    pub unsigned_reason: Option<String>,
+}
+
+/// How the repository asks for commits to be signed
+enum Signer {
+    /// gpg / gpgsm: detached armored signature of stdin
+    Gpg { program: String, key: String },
+    /// ssh-keygen -Y sign with a key file (or a literal public key)
+    Ssh { program: String, key: String },
+}
+
+/// Create a commit and move HEAD to it, signing it when the repository
+/// config asks for it. `author` differs from `signature` (the committer)
+/// only when replaying someone else's commit
+pub fn commit_to_head(
+    repo: &Repository,
+    author: &Signature,
+    signature: &Signature,
+    message: &str,
+    tree: &Tree,
+    parents: &[&Commit],
+) -> Result<WrittenCommit> {
+    let signer = match signer(repo, signature) {
+        Ok(None) => {
+            let oid = repo.commit(Some("HEAD"), author, signature, message, tree, parents)?;
+            return Ok(WrittenCommit {
+                oid,
+                unsigned_reason: None,
+            });
+        }
+        Ok(Some(signer)) => signer,
+        Err(reason) => {
+            tracing::warn!(%reason, "committing unsigned");
+            let oid = repo.commit(Some("HEAD"), author, signature, message, tree, parents)?;
+            return Ok(WrittenCommit {
+                oid,
+                unsigned_reason: Some(reason),
+            });
+        }
+    };
+
+    let buffer = repo.commit_create_buffer(author, signature, message, tree, parents)?;
+    let buffer = buffer
+        .as_str()
+        .context("Commit buffer is not valid UTF-8")?;
+    let signed = sign(&signer, buffer)?;
+    let oid = repo.commit_signed(buffer, &signed, None)?;
+
+    // commit_signed doesn't touch refs; update HEAD like repo.commit does
+    let summary = message.lines().next().unwrap_or_default();
+    let reflog = match parents.len() {
+        0 => format!("commit (initial): {summary}"),
+        1 => format!("commit: {summary}"),
+        _ => format!("commit (merge): {summary}"),
+    };
+    let head = repo.find_reference("HEAD")?;
+    match head.symbolic_target() {
+        Some(target) => {
+            repo.reference(target, oid, true, &reflog)?;
+        }
+        None => repo.set_head_detached(oid)?,
+    }
+    Ok(WrittenCommit {
+        oid,
+        unsigned_reason: None,
+    })
+}
+
+/// The signer configured for the repository. `Ok(None)` when signing is
+/// off; `Err` explains why signing is on but can't be done
+fn signer(repo: &Repository, signature: &Signature) -> Result<Option<Signer>, String> {
+    let config = repo.config().map_err(|e| e.to_string())?;
+    if !config.get_bool("commit.gpgsign").unwrap_or(false) {
+        return Ok(None);
+    }
+    let format = config
+        .get_string("gpg.format")
+        .unwrap_or_else(|_| "openpgp".to_string());
+    let key = config.get_string("user.signingkey").ok();
+    let program = |format: &str, default: &str| {
+        config
+            .get_string(&format!("gpg.{format}.program"))
+            .or_else(|_| {
+                // gpg.program predates gpg.format and only applies to openpgp
+                if format == "openpgp" {
+                    config.get_string("gpg.program")
+                } else {
+                    Err(git2::Error::from_str("unset"))
+                }
+            })
+            .unwrap_or_else(|_| default.to_string())
+    };
+
+    match format.as_str() {
+        "openpgp" | "x509" => {
+            let default = if format == "x509" { "gpgsm" } else { "gpg" };
+            // Like git, fall back to the committer identity as the key id
+            let key = key.unwrap_or_else(|| {
+                format!(
+                    "{} <{}>",
+                    signature.name().unwrap_or_default(),
+                    signature.email().unwrap_or_default()
+                )
+            });
+            Ok(Some(Signer::Gpg {
+                program: program(&format, default),
+                key,
+            }))
+        }
+        "ssh" => match key {
+            Some(key) => Ok(Some(Signer::Ssh {
+                program: program("ssh", "ssh-keygen"),
+                key,
+            })),
+            None => Err(
+                "gpg.format is ssh but user.signingkey is not set; committed unsigned".to_string(),
+            ),
+        },
+        other => Err(format!(
+            "Unsupported gpg.format '{other}'; committed unsigned"
+        )),
+    }
+}
+
+fn sign(signer: &Signer, buffer: &str) -> Result<String> {
+    let (program, mut command, key_file) = match signer {
+        Signer::Gpg { program, key } => {
+            let mut command = Command::new(program);
+            command.args(["--status-fd=2", "-bsau", key]);
+            (program, command, None)
+        }
+        Signer::Ssh { program, key } => {
+            let (path, temporary) = ssh_key_file(key)?;
+            let mut command = Command::new(program);
+            command.args(["-Y", "sign", "-n", "git", "-f"]).arg(&path);
+            (program, command, temporary.then_some(path))
+        }
+    };
+
+    let result = run_signer(program, &mut command, buffer);
+    if let Some(path) = key_file {
+        let _ = std::fs::remove_file(path);
+    }
+    result
+}
+
+fn run_signer(program: &str, command: &mut Command, buffer: &str) -> Result<String> {
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to run {program} to sign the commit"))?;
+    child
+        .stdin
+        .take()
+        .context("Signer stdin unavailable")?
+        .write_all(buffer.as_bytes())?;
+    let output = child.wait_with_output()?;
+    let signature = String::from_utf8_lossy(&output.stdout).into_owned();
+    if !output.status.success() || signature.trim().is_empty() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let detail = stderr
+            .lines()
+            .filter(|line| !line.starts_with("[GNUPG:]"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        bail!("Signing with {program} failed: {}", detail.trim());
+    }
+    Ok(signature)
+}
+
+/// Path of the ssh signing key and whether it's a temporary file holding
+/// a literal `ssh-...` / `key::...` key from the config
+fn ssh_key_file(key: &str) -> Result<(PathBuf, bool)> {
+    let literal = key
+        .strip_prefix("key::")
+        .or_else(|| key.starts_with("ssh-").then_some(key));
+    if let Some(literal) = literal {
+        let (path, mut file) = create_temp_key_file()?;
+        if let Err(e) = file.write_all(format!("{literal}\n").as_bytes()) {
+            drop(file);
+            let _ = std::fs::remove_file(&path);
+            return Err(e).context("Failed to write the signing key to a temporary file");
+        }
+        return Ok((path, true));
+    }
+    let path = match key.strip_prefix("~/") {
+        Some(rest) => dirs::home_dir().context("No home directory")?.join(rest),
+        None => PathBuf::from(key),
+    };
+    Ok((path, false))
+}
+
+/// Fresh file in the temp directory, readable only by the user. The name
+/// is random and the file must not exist yet, so a file or symlink planted
+/// in the shared directory is never written through
+fn create_temp_key_file() -> Result<(PathBuf, File)> {
+    const ATTEMPTS: usize = 16;
+    for _ in 0..ATTEMPTS {
+        let mut hasher = RandomState::new().build_hasher();
+        if let Ok(elapsed) = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH) {
+            hasher.write_u128(elapsed.as_nanos());
+        }
+        let path = std::env::temp_dir().join(format!(
+            "keifu-signing-{}-{:016x}.pub",
+            std::process::id(),
+            hasher.finish()
+        ));
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        match options.open(&path) {
+            Ok(file) => return Ok((path, file)),
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+            Err(e) => {
+                return Err(e).context("Failed to create a temporary file for the signing key")
+            }
+        }
+    }
+    bail!("Failed to create a temporary file for the signing key: names kept colliding")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn init_repo() -> (tempfile::TempDir, Repository) {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(tempdir.path()).unwrap();
+        (tempdir, repo)
+    }
+
+    fn commit_empty(repo: &Repository) -> WrittenCommit {
+        let sig = Signature::now("Test", "test@example.com").unwrap();
+        let tree = repo
+            .find_tree(repo.treebuilder(None).unwrap().write().unwrap())
+            .unwrap();
+        commit_to_head(repo, &sig, &sig, "init", &tree, &[]).unwrap()
+    }
+
+    #[test]
+    fn literal_ssh_keys_go_to_fresh_private_files() {
+        let (first, temporary) = ssh_key_file("key::ssh-ed25519 AAAA test").unwrap();
+        let (second, _) = ssh_key_file("ssh-ed25519 AAAA test").unwrap();
+        assert!(temporary);
+        assert_ne!(first, second);
+        assert_eq!(
+            std::fs::read_to_string(&first).unwrap(),
+            "ssh-ed25519 AAAA test\n"
+        );
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&first).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        std::fs::remove_file(first).unwrap();
+        std::fs::remove_file(second).unwrap();
+
+        let (path, temporary) = ssh_key_file("/keys/id_ed25519.pub").unwrap();
+        assert!(!temporary);
+        assert_eq!(path, PathBuf::from("/keys/id_ed25519.pub"));
+    }
+
+    #[test]
+    fn ssh_signing_without_key_falls_back_to_unsigned() {
+        let (_tempdir, repo) = init_repo();
+        let mut config = repo.config().unwrap();
+        config.set_bool("commit.gpgsign", true).unwrap();
+        config.set_str("gpg.format", "ssh").unwrap();
+
+        let written = commit_empty(&repo);
+
+        assert!(written.unsigned_reason.unwrap().contains("user.signingkey"));
+        assert_eq!(repo.head().unwrap().target(), Some(written.oid));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn configured_program_signs_the_commit() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let (tempdir, repo) = init_repo();
+        let program = tempdir.path().join("fake-gpg");
+        std::fs::write(
+            &program,
+            "#!/bin/sh\ncat >/dev/null\necho '-----BEGIN PGP SIGNATURE-----'\necho 'fake'\necho '-----END PGP SIGNATURE-----'\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&program, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let mut config = repo.config().unwrap();
+        config.set_bool("commit.gpgsign", true).unwrap();
+        config
+            .set_str("gpg.program", program.to_str().unwrap())
+            .unwrap();
+
+        let written = commit_empty(&repo);
+
+        assert!(written.unsigned_reason.is_none());
+        assert_eq!(repo.head().unwrap().target(), Some(written.oid));
+        let commit = repo.find_commit(written.oid).unwrap();
+        let gpgsig = commit.header_field_bytes("gpgsig").unwrap();
+        assert!(String::from_utf8_lossy(&gpgsig).contains("fake"));
+    }
+}
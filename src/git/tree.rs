@@ -0,0 +1,273 @@
+//! Browsing a commit's full tree: an expandable directory listing that
+//! loads each directory only when it is first opened
+
+use anyhow::{Context, Result};
+use git2::{ObjectType, Oid, Repository};
+
+/// Largest blob shown in the file view; bigger files only report their size
+const MAX_BLOB_SIZE: usize = 2 * 1024 * 1024;
+
+/// Kind of a tree entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryKind {
+    Dir,
+    File,
+    Symlink,
+    Submodule,
+}
+
+/// One visible row of the tree listing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeRow {
+    pub name: String,
+    /// Path from the repository root
+    pub path: String,
+    pub depth: usize,
+    pub kind: EntryKind,
+    pub oid: Oid,
+    /// Whether the directory's children are listed below it
+    pub expanded: bool,
+}
+
+/// A file's contents at the browsed commit
+#[derive(Debug, Clone)]
+pub struct FileView {
+    pub path: String,
+    /// Text lines (empty for binary or oversized blobs)
+    pub lines: Vec<String>,
+    /// Why the contents aren't shown ("binary, 12 KiB", ...)
+    pub placeholder: Option<String>,
+    pub scroll: usize,
+}
+
+/// State of the tree browser overlay
+#[derive(Debug, Clone)]
+pub struct TreeBrowser {
+    pub commit: Oid,
+    pub rows: Vec<TreeRow>,
+    pub selected: usize,
+    /// First listed row, kept in view of the selection by the renderer
+    pub scroll: usize,
+    /// Open file, shown instead of the listing
+    pub file: Option<FileView>,
+}
+
+impl TreeBrowser {
+    /// List the top level of `commit`'s tree
+    pub fn open(repo: &Repository, commit: Oid) -> Result<Self> {
+        let tree = repo.find_commit(commit)?.tree()?;
+        Ok(Self {
+            commit,
+            rows: list_dir(repo, tree.id(), "", 0)?,
+            selected: 0,
+            scroll: 0,
+            file: None,
+        })
+    }
+
+    pub fn selected_row(&self) -> Option<&TreeRow> {
+        self.rows.get(self.selected)
+    }
+
+    pub fn move_selection(&mut self, delta: isize) {
+        let max = self.rows.len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(delta).min(max);
+    }
+
+    /// Expand the selected directory (listing it on first use) or open the
+    /// selected file
+    pub fn expand(&mut self, repo: &Repository) -> Result<()> {
+        let Some(row) = self.rows.get(self.selected).cloned() else {
+            return Ok(());
+        };
+        match row.kind {
+            EntryKind::Dir if row.expanded => {}
+            EntryKind::Dir => {
+                let children = list_dir(repo, row.oid, &row.path, row.depth + 1)?;
+                self.rows[self.selected].expanded = true;
+                let at = self.selected + 1;
+                self.rows.splice(at..at, children);
+            }
+            EntryKind::File | EntryKind::Symlink => {
+                self.file = Some(read_file(repo, row.oid, &row.path)?);
+            }
+            EntryKind::Submodule => {}
+        }
+        Ok(())
+    }
+
+    /// Collapse the selected directory, or move to the parent directory
+    pub fn collapse(&mut self) {
+        let Some(row) = self.rows.get(self.selected) else {
+            return;
+        };
+        if row.kind == EntryKind::Dir && row.expanded {
+            let depth = row.depth;
+            let end = self.rows[self.selected + 1..]
+                .iter()
+                .position(|child| child.depth <= depth)
+                .map_or(self.rows.len(), |offset| self.selected + 1 + offset);
+            self.rows.drain(self.selected + 1..end);
+            self.rows[self.selected].expanded = false;
+        } else if row.depth > 0 {
+            let depth = row.depth;
+            if let Some(parent) = self.rows[..self.selected]
+                .iter()
+                .rposition(|candidate| candidate.depth < depth)
+            {
+                self.selected = parent;
+            }
+        }
+    }
+}
+
+/// Entries of one directory: directories first, then by name
+fn list_dir(repo: &Repository, tree: Oid, prefix: &str, depth: usize) -> Result<Vec<TreeRow>> {
+    let tree = repo.find_tree(tree)?;
+    let mut rows: Vec<TreeRow> = tree
+        .iter()
+        .map(|entry| {
+            let name = String::from_utf8_lossy(entry.name_bytes()).into_owned();
+            let kind = match (entry.kind(), entry.filemode()) {
+                (Some(ObjectType::Tree), _) => EntryKind::Dir,
+                (Some(ObjectType::Commit), _) => EntryKind::Submodule,
+                (_, 0o120000) => EntryKind::Symlink,
+                _ => EntryKind::File,
+            };
+            let path = if prefix.is_empty() {
+                name.clone()
+            } else {
+                format!("{prefix}/{name}")
+            };
+            TreeRow {
+                name,
+                path,
+                depth,
+                kind,
+                oid: entry.id(),
+                expanded: false,
+            }
+        })
+        .collect();
+    rows.sort_by(|a, b| {
+        (b.kind == EntryKind::Dir)
+            .cmp(&(a.kind == EntryKind::Dir))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    Ok(rows)
+}
+
+fn read_file(repo: &Repository, oid: Oid, path: &str) -> Result<FileView> {
+    let blob = repo
+        .find_blob(oid)
+        .with_context(|| format!("Cannot read {path}"))?;
+    let size = blob.size();
+    let placeholder = if blob.is_binary() {
+        Some(format!("binary, {}", format_size(size)))
+    } else if size > MAX_BLOB_SIZE {
+        Some(format!("too large to show, {}", format_size(size)))
+    } else {
+        None
+    };
+    let lines = if placeholder.is_some() {
+        Vec::new()
+    } else {
+        String::from_utf8_lossy(blob.content())
+            .lines()
+            .map(|line| line.replace('\t', "    "))
+            .collect()
+    };
+    Ok(FileView {
+        path: path.to_string(),
+        lines,
+        placeholder,
+        scroll: 0,
+    })
+}
+
+fn format_size(bytes: usize) -> String {
+    if bytes < 1024 {
+        format!("{bytes} B")
+    } else if bytes < 1024 * 1024 {
+        format!("{} KiB", bytes / 1024)
+    } else {
+        format!("{} MiB", bytes / (1024 * 1024))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use git2::Signature;
+
+    use super::*;
+
+    fn commit_tree(repo: &Repository, files: &[(&str, &[u8])]) -> Oid {
+        let mut index = repo.index().unwrap();
+        for (path, contents) in files {
+            let entry = git2::IndexEntry {
+                ctime: git2::IndexTime::new(0, 0),
+                mtime: git2::IndexTime::new(0, 0),
+                dev: 0,
+                ino: 0,
+                mode: 0o100644,
+                uid: 0,
+                gid: 0,
+                file_size: contents.len() as u32,
+                id: Oid::zero(),
+                flags: 0,
+                flags_extended: 0,
+                path: path.as_bytes().to_vec(),
+            };
+            index.add_frombuffer(&entry, contents).unwrap();
+        }
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = Signature::now("Test", "test@example.com").unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "tree", &tree, &[])
+            .unwrap()
+    }
+
+    #[test]
+    fn directories_expand_lazily_and_collapse() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(tempdir.path()).unwrap();
+        let commit = commit_tree(
+            &repo,
+            &[
+                ("README.md", b"hi\n"),
+                ("src/main.rs", b"fn main() {}\n"),
+                ("src/ui/mod.rs", b"\tmod x;\n"),
+            ],
+        );
+
+        let mut browser = TreeBrowser::open(&repo, commit).unwrap();
+        let names = |b: &TreeBrowser| -> Vec<String> {
+            b.rows
+                .iter()
+                .map(|r| format!("{}{}", "  ".repeat(r.depth), r.name))
+                .collect()
+        };
+        assert_eq!(names(&browser), ["src", "README.md"]);
+
+        browser.expand(&repo).unwrap();
+        assert_eq!(names(&browser), ["src", "  ui", "  main.rs", "README.md"]);
+        browser.move_selection(1);
+        browser.expand(&repo).unwrap();
+        assert_eq!(
+            names(&browser),
+            ["src", "  ui", "    mod.rs", "  main.rs", "README.md"]
+        );
+
+        browser.move_selection(1);
+        browser.expand(&repo).unwrap();
+        let file = browser.file.take().unwrap();
+        assert_eq!(file.path, "src/ui/mod.rs");
+        assert_eq!(file.lines, ["    mod x;"]);
+
+        // h on a file goes to its directory, then collapses it
+        browser.collapse();
+        assert_eq!(browser.selected_row().unwrap().path, "src/ui");
+        browser.move_selection(-1);
+        browser.collapse();
+        assert_eq!(names(&browser), ["src", "README.md"]);
+    }
+}
@@ -0,0 +1,84 @@
+//! Commit node glyphs
+//!
+//! Single source of truth for commit glyphs: `ui::graph_view` is the only
+//! graph renderer and draws every commit node through [`GraphChars`].
+//! By default `◉` means "HEAD points here", `◎` marks the current branch's
+//! tip while HEAD is detached elsewhere, and `●` is any other commit;
+//! selection is shown by the row highlight, not by a glyph change.
+
+use serde::Deserialize;
+
+/// Commit node glyph for each role, configurable via `[glyphs]`.
+///
+/// Precedence when several roles apply: `selected` > `head` > `tip` > `merge` > `commit`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct GraphChars {
+    /// Commit that HEAD points to
+    pub head: char,
+    /// Tip of the current branch when HEAD is detached at another commit
+    pub tip: char,
+    /// Regular commit
+    pub commit: char,
+    /// Commit with more than one parent
+    pub merge: char,
+    /// Selected commit (None keeps the role glyph; the row is highlighted anyway)
+    pub selected: Option<char>,
+}
+
+impl Default for GraphChars {
+    fn default() -> Self {
+        Self {
+            head: '◉',
+            tip: '◎',
+            commit: '●',
+            merge: '●',
+            selected: None,
+        }
+    }
+}
+
+impl GraphChars {
+    /// Glyph for a commit node
+    pub fn commit_glyph(
+        &self,
+        is_head: bool,
+        is_tip: bool,
+        is_merge: bool,
+        is_selected: bool,
+    ) -> char {
+        match self.selected {
+            Some(ch) if is_selected => ch,
+            _ if is_head => self.head,
+            _ if is_tip => self.tip,
+            _ if is_merge => self.merge,
+            _ => self.commit,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_glyphs_keep_head_distinct() {
+        let chars = GraphChars::default();
+        assert_eq!(chars.commit_glyph(true, false, false, false), '◉');
+        assert_eq!(chars.commit_glyph(true, false, false, true), '◉');
+        assert_eq!(chars.commit_glyph(false, false, true, false), '●');
+        assert_eq!(chars.commit_glyph(false, false, false, true), '●');
+        assert_eq!(chars.commit_glyph(true, true, false, false), '◉');
+        assert_eq!(chars.commit_glyph(false, true, true, false), '◎');
+    }
+
+    #[test]
+    fn configured_glyphs_follow_precedence() {
+        let chars: GraphChars = toml::from_str("selected = '▶'\nmerge = '◆'").unwrap();
+        assert_eq!(chars.head, '◉');
+        assert_eq!(chars.commit_glyph(true, false, true, true), '▶');
+        assert_eq!(chars.commit_glyph(true, false, true, false), '◉');
+        assert_eq!(chars.commit_glyph(false, false, true, false), '◆');
+        assert_eq!(chars.commit_glyph(false, false, false, false), '●');
+    }
+}
@@ -1,6 +1,8 @@
 //! Branch color management
 
+use git2::Oid;
 use ratatui::style::Color;
+use serde::Deserialize;
 use std::collections::{HashSet, VecDeque};
 
 /// Per-lane color palette (11-color rotation)
@@ -32,6 +34,29 @@ pub fn get_color_by_index(color_index: usize) -> Color {
 /// Main branch color (light blue)
 pub const MAIN_BRANCH_COLOR: usize = 9; // Color::LightBlue
 
+/// How new lanes pick their color
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorMode {
+    /// Penalty-based: avoid neighboring and recently used colors. Depends on
+    /// traversal order, so a shifted history can recolor branches.
+    #[default]
+    Adaptive,
+    /// Hash of the commit that starts the lane (plus a seed). Stable for a
+    /// given commit set; intended for screenshots and snapshot tests.
+    Deterministic,
+}
+
+/// FNV-1a over the seed and OID bytes (stable across runs and platforms)
+fn stable_hash(seed: u64, oid: Oid) -> u64 {
+    seed.to_le_bytes()
+        .iter()
+        .chain(oid.as_bytes())
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ *byte as u64).wrapping_mul(0x0100_0000_01b3)
+        })
+}
+
 /// Color assignment to vary colors when lanes are reused
 #[derive(Debug)]
 pub struct ColorAssigner {
@@ -55,10 +80,16 @@ pub struct ColorAssigner {
     color_usage_count: [usize; 11],
     /// Lane of the main branch (fixed color)
     main_lane: Option<usize>,
+    mode: ColorMode,
+    seed: u64,
 }
 
 impl ColorAssigner {
     pub fn new() -> Self {
+        Self::with_mode(ColorMode::default(), 0)
+    }
+
+    pub fn with_mode(mode: ColorMode, seed: u64) -> Self {
         Self {
             lane_colors: Vec::new(),
             lane_last_color: Vec::new(),
@@ -70,6 +101,8 @@ impl ColorAssigner {
             current_fork_colors: HashSet::new(),
             color_usage_count: [0; 11],
             main_lane: None,
+            mode,
+            seed,
         }
     }
 
@@ -112,17 +145,43 @@ impl ColorAssigner {
         self.current_fork_colors.clear();
     }
 
-    /// Assign a color to a new branch (penalty-based algorithm)
+    /// Deterministic mode: hash the lane's first commit, then probe forward
+    /// past reserved colors and colors already taken by fork siblings
+    fn hashed_color(&self, key: Oid, is_fork_sibling: bool) -> usize {
+        let start = (stable_hash(self.seed, key) % LANE_COLORS.len() as u64) as usize;
+        let taken = |color: &usize| {
+            self.reserved_colors.contains(color)
+                || (is_fork_sibling && self.current_fork_colors.contains(color))
+        };
+        (0..LANE_COLORS.len())
+            .map(|offset| (start + offset) % LANE_COLORS.len())
+            .find(|color| !taken(color))
+            .unwrap_or(start)
+    }
+
+    /// Assign a color to a new branch (penalty-based algorithm, or hashed
+    /// from `key` in deterministic mode)
     /// is_fork_sibling: true treats it as a fork sibling to avoid duplicate colors within a fork
     /// use_reserved: true allows reserved colors (for the main branch)
     fn assign_color_advanced(
         &mut self,
         lane: usize,
+        key: Oid,
         is_fork_sibling: bool,
         use_reserved: bool,
     ) -> usize {
         self.ensure_capacity(lane);
 
+        if self.mode == ColorMode::Deterministic {
+            let color = self.hashed_color(key, is_fork_sibling);
+            self.lane_colors[lane] = Some(color);
+            self.lane_last_color[lane] = color;
+            if is_fork_sibling {
+                self.current_fork_colors.insert(color);
+            }
+            return color;
+        }
+
         // Compute penalties for each color
         let mut color_penalties: [f64; 11] = [0.0; 11];
 
@@ -208,14 +267,14 @@ impl ColorAssigner {
         best_color
     }
 
-    /// Assign a color to a new branch (do not use reserved colors)
-    pub fn assign_color(&mut self, lane: usize) -> usize {
-        self.assign_color_advanced(lane, false, false)
+    /// Assign a color to a new branch starting at `key` (do not use reserved colors)
+    pub fn assign_color(&mut self, lane: usize, key: Oid) -> usize {
+        self.assign_color_advanced(lane, key, false, false)
     }
 
     /// Assign a color as a fork sibling (avoid duplicates within a fork)
-    pub fn assign_fork_sibling_color(&mut self, lane: usize) -> usize {
-        self.assign_color_advanced(lane, true, false)
+    pub fn assign_fork_sibling_color(&mut self, lane: usize, key: Oid) -> usize {
+        self.assign_color_advanced(lane, key, true, false)
     }
 
     /// Assign a color to the main branch (fixed blue, reserve it)
@@ -232,12 +291,12 @@ impl ColorAssigner {
 
     /// Continue using an existing lane
     /// Always return blue for the main lane
-    pub fn continue_lane(&mut self, lane: usize) -> usize {
+    pub fn continue_lane(&mut self, lane: usize, key: Oid) -> usize {
         if self.main_lane == Some(lane) {
             return MAIN_BRANCH_COLOR;
         }
         self.ensure_capacity(lane);
-        self.lane_colors[lane].unwrap_or_else(|| self.assign_color(lane))
+        self.lane_colors[lane].unwrap_or_else(|| self.assign_color(lane, key))
     }
 
     /// Release a lane (when a branch ends)
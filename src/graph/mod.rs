@@ -1,3 +1,4 @@
 //! Graph rendering layer
 
+pub mod chars;
 pub mod colors;
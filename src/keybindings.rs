@@ -7,6 +7,559 @@ use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use crate::action::Action;
 use crate::app::AppMode;
 
+/// Help popup section a normal-mode binding is listed under
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HelpSection {
+    Navigation,
+    Git,
+    Search,
+    Diff,
+    Other,
+}
+
+/// A key a binding answers to. `None` modifiers match any, for symbols whose
+/// Shift state depends on the keyboard layout
+type Key = (Option<KeyModifiers>, KeyCode);
+
+const fn plain(c: char) -> Key {
+    (Some(KeyModifiers::NONE), KeyCode::Char(c))
+}
+
+const fn shift(c: char) -> Key {
+    (Some(KeyModifiers::SHIFT), KeyCode::Char(c))
+}
+
+const fn ctrl(c: char) -> Key {
+    (Some(KeyModifiers::CONTROL), KeyCode::Char(c))
+}
+
+const fn any(c: char) -> Key {
+    (None, KeyCode::Char(c))
+}
+
+const fn special(code: KeyCode) -> Key {
+    (Some(KeyModifiers::NONE), code)
+}
+
+/// A normal-mode action: the keys that trigger it, and how the help popup
+/// and the command palette (`Ctrl+x`) list it
+pub struct Binding {
+    pub action: Action,
+    keys: &'static [Key],
+    /// Key shown next to the name ("" when the action has no key)
+    pub key: &'static str,
+    pub name: &'static str,
+    pub section: HelpSection,
+    /// Offered by the command palette (plain movement isn't)
+    pub in_palette: bool,
+}
+
+impl Binding {
+    fn matches(&self, key: &KeyEvent) -> bool {
+        self.keys.iter().any(|(modifiers, code)| {
+            *code == key.code && modifiers.is_none_or(|m| m == key.modifiers)
+        })
+    }
+}
+
+const fn bind(
+    section: HelpSection,
+    action: Action,
+    keys: &'static [Key],
+    key: &'static str,
+    name: &'static str,
+) -> Binding {
+    Binding {
+        action,
+        keys,
+        key,
+        name,
+        section,
+        in_palette: true,
+    }
+}
+
+/// A binding the command palette leaves out
+const fn key_only(binding: Binding) -> Binding {
+    Binding {
+        in_palette: false,
+        ..binding
+    }
+}
+
+use HelpSection::{Diff, Git, Navigation, Other, Search};
+
+/// Every normal-mode binding, in help and palette order. `map_normal_mode`,
+/// the help popup and the command palette all read this table
+pub const BINDINGS: &[Binding] = &[
+    // Navigation
+    key_only(bind(
+        Navigation,
+        Action::MoveDown,
+        &[plain('j'), special(KeyCode::Down)],
+        "j / ↓",
+        "Move down",
+    )),
+    key_only(bind(
+        Navigation,
+        Action::MoveUp,
+        &[plain('k'), special(KeyCode::Up)],
+        "k / ↑",
+        "Move up",
+    )),
+    key_only(bind(
+        Navigation,
+        Action::FocusNext,
+        &[
+            special(KeyCode::Tab),
+            (Some(KeyModifiers::SHIFT), KeyCode::BackTab),
+        ],
+        "Tab",
+        "Switch pane focus (graph / detail)",
+    )),
+    key_only(bind(
+        Navigation,
+        Action::NextBranch,
+        &[any(']')],
+        "]",
+        "Select next branch",
+    )),
+    key_only(bind(
+        Navigation,
+        Action::PrevBranch,
+        &[any('[')],
+        "[",
+        "Select previous branch",
+    )),
+    bind(
+        Navigation,
+        Action::NextMerge,
+        &[any('}')],
+        "}",
+        "Next merge commit",
+    ),
+    bind(
+        Navigation,
+        Action::PrevMerge,
+        &[any('{')],
+        "{",
+        "Previous merge commit",
+    ),
+    bind(
+        Navigation,
+        Action::NextTag,
+        &[plain('t')],
+        "t",
+        "Next tagged commit",
+    ),
+    bind(
+        Navigation,
+        Action::PrevTag,
+        &[shift('T')],
+        "T",
+        "Previous tagged commit",
+    ),
+    key_only(bind(
+        Navigation,
+        Action::BranchLeft,
+        &[plain('h'), special(KeyCode::Left)],
+        "h / ←",
+        "Select left branch (same commit)",
+    )),
+    key_only(bind(
+        Navigation,
+        Action::BranchRight,
+        &[plain('l'), special(KeyCode::Right)],
+        "l / →",
+        "Select right branch (same commit)",
+    )),
+    key_only(bind(
+        Navigation,
+        Action::PageDown,
+        &[ctrl('d')],
+        "Ctrl+d",
+        "Page down",
+    )),
+    key_only(bind(
+        Navigation,
+        Action::PageUp,
+        &[ctrl('u')],
+        "Ctrl+u",
+        "Page up",
+    )),
+    bind(
+        Navigation,
+        Action::GoToTop,
+        &[plain('g'), special(KeyCode::Home)],
+        "g / Home",
+        "Go to top",
+    ),
+    bind(
+        Navigation,
+        Action::GoToBottom,
+        &[shift('G'), special(KeyCode::End)],
+        "G / End",
+        "Go to bottom",
+    ),
+    bind(
+        Navigation,
+        Action::JumpToHead,
+        &[any('@')],
+        "@",
+        "Jump to HEAD (current branch)",
+    ),
+    bind(
+        Navigation,
+        Action::GotoBranch,
+        &[shift('B')],
+        "B",
+        "Go to a branch tip (no checkout)",
+    ),
+    bind(
+        Navigation,
+        Action::GotoRevision,
+        &[any(':')],
+        ":",
+        "Go to revision (HEAD~3, v1.0^)",
+    ),
+    bind(
+        Navigation,
+        Action::ToggleRemoteBranches,
+        &[plain('o')],
+        "o",
+        "Toggle remote branches",
+    ),
+    bind(
+        Navigation,
+        Action::PathScope,
+        &[shift('P')],
+        "P",
+        "Limit graph to paths",
+    ),
+    bind(
+        Navigation,
+        Action::ToggleMinimap,
+        &[shift('M')],
+        "M",
+        "Toggle graph minimap",
+    ),
+    bind(
+        Navigation,
+        Action::ToggleFocusMode,
+        &[plain('z')],
+        "z",
+        "Focus mode: only the bare graph",
+    ),
+    bind(
+        Navigation,
+        Action::ToggleDetail,
+        &[plain('i')],
+        "i",
+        "Toggle detail pane",
+    ),
+    bind(
+        Navigation,
+        Action::ToggleOrientation,
+        &[shift('H')],
+        "H",
+        "Toggle horizontal graph (time left to right)",
+    ),
+    bind(
+        Navigation,
+        Action::ToggleGraph,
+        &[any('|')],
+        "|",
+        "Toggle graph lanes (plain commit list)",
+    ),
+    bind(
+        Navigation,
+        Action::ToggleRawCommit,
+        &[plain('v')],
+        "v",
+        "Toggle raw commit object",
+    ),
+    bind(
+        Navigation,
+        Action::ToggleFullMessage,
+        &[],
+        "",
+        "Toggle full commit message",
+    ),
+    bind(
+        Navigation,
+        Action::ToggleRewrittenMarker,
+        &[any('~')],
+        "~",
+        "Toggle committer-differs markers",
+    ),
+    bind(
+        Navigation,
+        Action::ToggleParentBadge,
+        &[any('#')],
+        "#",
+        "Toggle merge parent count badges",
+    ),
+    bind(
+        Navigation,
+        Action::ToggleDateHeaders,
+        &[],
+        "",
+        "Toggle date group headers",
+    ),
+    bind(
+        Navigation,
+        Action::ToggleAgeHeat,
+        &[],
+        "",
+        "Toggle commit age heat colors",
+    ),
+    bind(
+        Navigation,
+        Action::ToggleHashLength,
+        &[],
+        "",
+        "Toggle full commit hashes",
+    ),
+    // Git operations
+    bind(
+        Git,
+        Action::Checkout,
+        &[special(KeyCode::Enter)],
+        "Enter",
+        "Checkout selected branch/commit",
+    ),
+    bind(
+        Git,
+        Action::CheckoutPrevious,
+        &[any('-')],
+        "-",
+        "Checkout previous branch",
+    ),
+    bind(
+        Git,
+        Action::CreateBranch,
+        &[plain('b')],
+        "b",
+        "Create branch at selected commit (Tab: also switch)",
+    ),
+    bind(
+        Git,
+        Action::DeleteBranch,
+        &[plain('d')],
+        "d",
+        "Delete selected branch",
+    ),
+    bind(
+        Git,
+        Action::CleanupBranches,
+        &[shift('D')],
+        "D",
+        "Delete branches merged into main",
+    ),
+    bind(
+        Git,
+        Action::CreateTag,
+        &[],
+        "",
+        "Create tag on selected commit",
+    ),
+    // No keys yet for merge and rebase; the palette runs them
+    bind(
+        Git,
+        Action::Merge,
+        &[],
+        "",
+        "Merge selected branch into current branch",
+    ),
+    bind(
+        Git,
+        Action::Rebase,
+        &[],
+        "",
+        "Rebase current branch onto selected branch",
+    ),
+    bind(
+        Git,
+        Action::CherryPick,
+        &[shift('C')],
+        "C",
+        "Cherry-pick selected commit onto HEAD",
+    ),
+    bind(Git, Action::Revert, &[], "", "Revert selected commit"),
+    bind(
+        Git,
+        Action::GitIdentity,
+        &[],
+        "",
+        "Show git config / set identity",
+    ),
+    bind(
+        Git,
+        Action::SessionAuthor,
+        &[],
+        "",
+        "Commit as another author (this session)",
+    ),
+    bind(
+        Git,
+        Action::StashSave,
+        &[shift('S')],
+        "S",
+        "Stash local changes",
+    ),
+    bind(
+        Git,
+        Action::StashPop,
+        &[shift('O')],
+        "O",
+        "Pop latest stash",
+    ),
+    bind(
+        Git,
+        Action::StashList,
+        &[],
+        "",
+        "Browse stashes (apply / pop)",
+    ),
+    bind(
+        Git,
+        Action::Fetch,
+        &[plain('f')],
+        "f",
+        "Fetch (asks which remote if none is configured)",
+    ),
+    bind(
+        Git,
+        Action::Pull,
+        &[shift('F')],
+        "F",
+        "Pull current branch from its upstream",
+    ),
+    bind(
+        Git,
+        Action::CommitDialog,
+        &[plain('c')],
+        "c",
+        "Commit staged changes",
+    ),
+    bind(
+        Git,
+        Action::Push,
+        &[plain('p')],
+        "p",
+        "Push current branch (to its push remote)",
+    ),
+    bind(
+        Git,
+        Action::ForcePush,
+        &[ctrl('p')],
+        "Ctrl+p",
+        "Force-push current branch (with lease)",
+    ),
+    bind(
+        Git,
+        Action::UpstreamDiff,
+        &[shift('U')],
+        "U",
+        "Review changes to push (upstream..HEAD)",
+    ),
+    // Search
+    bind(Search, Action::Search, &[any('/')], "/", "Search branches"),
+    // Diff
+    bind(
+        Diff,
+        Action::EnterFileSelect,
+        &[special(KeyCode::Char(' '))],
+        "Space",
+        "View file diffs",
+    ),
+    bind(
+        Diff,
+        Action::OpenTree,
+        &[plain('e')],
+        "e",
+        "Browse the commit's file tree (l/h: open/close)",
+    ),
+    bind(
+        Diff,
+        Action::MarkRange,
+        &[plain('x')],
+        "x",
+        "Mark a range end; again on the other end for its diffstat",
+    ),
+    bind(
+        Diff,
+        Action::CompareBranches,
+        &[],
+        "",
+        "Compare selected branch with current branch",
+    ),
+    // Other
+    bind(
+        Other,
+        Action::CopyHash,
+        &[plain('y')],
+        "y",
+        "Copy commit hash to clipboard",
+    ),
+    bind(
+        Other,
+        Action::CopyBranch,
+        &[shift('Y')],
+        "Y",
+        "Copy branch name to clipboard",
+    ),
+    bind(
+        Other,
+        Action::Refresh,
+        &[shift('R')],
+        "R",
+        "Refresh repository data",
+    ),
+    bind(
+        Other,
+        Action::ReloadConfig,
+        &[ctrl('r')],
+        "Ctrl+r",
+        "Reload config file",
+    ),
+    bind(
+        Other,
+        Action::ToggleTimers,
+        &[],
+        "",
+        "Toggle refresh timers",
+    ),
+    bind(
+        Other,
+        Action::ToggleGraphDebug,
+        &[ctrl('g')],
+        "Ctrl+g",
+        "Toggle graph debug column",
+    ),
+    key_only(bind(
+        Other,
+        Action::CommandPalette,
+        &[ctrl('x')],
+        "Ctrl+x",
+        "Command palette (run any action by name)",
+    )),
+    bind(
+        Other,
+        Action::ToggleHelp,
+        &[any('?')],
+        "?",
+        "Toggle this help",
+    ),
+    bind(
+        Other,
+        Action::Quit,
+        &[plain('q'), special(KeyCode::Esc)],
+        "q / Esc",
+        "Quit",
+    ),
+];
+
 pub fn map_key_to_action(key: KeyEvent, mode: &AppMode) -> Option<Action> {
     #[cfg(windows)]
     if key.kind != KeyEventKind::Press {
@@ -16,7 +569,7 @@ pub fn map_key_to_action(key: KeyEvent, mode: &AppMode) -> Option<Action> {
         AppMode::Normal => map_normal_mode(key),
         AppMode::Help => map_help_mode(key),
         AppMode::Input { action, .. } => {
-            if *action == crate::app::InputAction::Search {
+            if action.is_picker() {
                 map_search_mode(key)
             } else {
                 map_input_mode(key)
@@ -26,80 +579,17 @@ pub fn map_key_to_action(key: KeyEvent, mode: &AppMode) -> Option<Action> {
         AppMode::Error { .. } => map_error_mode(key),
         AppMode::FileSelect { .. } => map_file_select_mode(key),
         AppMode::FileDiff { .. } => map_file_diff_mode(key),
+        AppMode::TreeBrowser { .. } => map_tree_mode(key),
+        AppMode::BranchCompare { .. } => map_compare_mode(key),
+        AppMode::StashList { .. } => map_stash_mode(key),
     }
 }
 
 fn map_normal_mode(key: KeyEvent) -> Option<Action> {
-    match (key.modifiers, key.code) {
-        // Movement
-        (KeyModifiers::NONE, KeyCode::Char('j')) | (KeyModifiers::NONE, KeyCode::Down) => {
-            Some(Action::MoveDown)
-        }
-        (KeyModifiers::NONE, KeyCode::Char('k')) | (KeyModifiers::NONE, KeyCode::Up) => {
-            Some(Action::MoveUp)
-        }
-
-        // Page scroll
-        (KeyModifiers::CONTROL, KeyCode::Char('d')) => Some(Action::PageDown),
-        (KeyModifiers::CONTROL, KeyCode::Char('u')) => Some(Action::PageUp),
-
-        // Top/bottom
-        (KeyModifiers::NONE, KeyCode::Char('g')) | (KeyModifiers::NONE, KeyCode::Home) => {
-            Some(Action::GoToTop)
-        }
-        (KeyModifiers::SHIFT, KeyCode::Char('G')) | (KeyModifiers::NONE, KeyCode::End) => {
-            Some(Action::GoToBottom)
-        }
-
-        // Jump to HEAD (@ works with or without Shift depending on keyboard layout)
-        (_, KeyCode::Char('@')) => Some(Action::JumpToHead),
-
-        // Pane focus
-        (KeyModifiers::NONE, KeyCode::Tab) | (KeyModifiers::SHIFT, KeyCode::BackTab) => {
-            Some(Action::FocusNext)
-        }
-
-        // Branch jump
-        (_, KeyCode::Char(']')) => Some(Action::NextBranch),
-        (_, KeyCode::Char('[')) => Some(Action::PrevBranch),
-
-        // Branch selection within same commit
-        (KeyModifiers::NONE, KeyCode::Char('h')) | (KeyModifiers::NONE, KeyCode::Left) => {
-            Some(Action::BranchLeft)
-        }
-        (KeyModifiers::NONE, KeyCode::Char('l')) | (KeyModifiers::NONE, KeyCode::Right) => {
-            Some(Action::BranchRight)
-        }
-
-        // Git operations
-        (KeyModifiers::NONE, KeyCode::Enter) => Some(Action::Checkout),
-        (KeyModifiers::NONE, KeyCode::Char('b')) => Some(Action::CreateBranch),
-        (KeyModifiers::NONE, KeyCode::Char('d')) => Some(Action::DeleteBranch),
-        (KeyModifiers::NONE, KeyCode::Char('f')) => Some(Action::Fetch),
-        (KeyModifiers::NONE, KeyCode::Char('c')) => Some(Action::CommitDialog),
-        (KeyModifiers::NONE, KeyCode::Char('p')) => Some(Action::Push),
-
-        // Clipboard
-        (KeyModifiers::NONE, KeyCode::Char('y')) => Some(Action::CopyHash),
-        (KeyModifiers::SHIFT, KeyCode::Char('Y')) => Some(Action::CopyBranch),
-        // TODO: merge and rebase will be implemented in the future
-        // (KeyModifiers::NONE, KeyCode::Char('m')) => Some(Action::Merge),
-        // (KeyModifiers::NONE, KeyCode::Char('r')) => Some(Action::Rebase),
-
-        // File diff
-        (KeyModifiers::NONE, KeyCode::Char(' ')) => Some(Action::EnterFileSelect),
-
-        // UI
-        (_, KeyCode::Char('/')) => Some(Action::Search),
-        (KeyModifiers::SHIFT, KeyCode::Char('R')) => Some(Action::Refresh),
-        (KeyModifiers::NONE, KeyCode::Char('o')) => Some(Action::ToggleRemoteBranches),
-        (_, KeyCode::Char('?')) => Some(Action::ToggleHelp),
-        (KeyModifiers::NONE, KeyCode::Char('q')) | (KeyModifiers::NONE, KeyCode::Esc) => {
-            Some(Action::Quit)
-        }
-
-        _ => None,
-    }
+    BINDINGS
+        .iter()
+        .find(|binding| binding.matches(&key))
+        .map(|binding| binding.action.clone())
 }
 
 fn map_help_mode(key: KeyEvent) -> Option<Action> {
@@ -121,6 +611,9 @@ fn map_help_mode(key: KeyEvent) -> Option<Action> {
         (KeyModifiers::SHIFT, KeyCode::Char('G')) | (KeyModifiers::NONE, KeyCode::End) => {
             Some(Action::ScrollToBottom)
         }
+        (KeyModifiers::NONE, KeyCode::Char('/')) => Some(Action::Search),
+        (KeyModifiers::NONE, KeyCode::Char('n')) => Some(Action::NextMatch),
+        (KeyModifiers::SHIFT, KeyCode::Char('N')) => Some(Action::PrevMatch),
         _ => None,
     }
 }
@@ -130,6 +623,7 @@ fn map_input_mode(key: KeyEvent) -> Option<Action> {
         KeyCode::Enter => Some(Action::Confirm),
         KeyCode::Esc => Some(Action::Cancel),
         KeyCode::Backspace => Some(Action::InputBackspace),
+        KeyCode::Tab => Some(Action::InputToggle),
         KeyCode::Char(c) => Some(Action::InputChar(c)),
         _ => None,
     }
@@ -154,9 +648,14 @@ fn map_search_mode(key: KeyEvent) -> Option<Action> {
 }
 
 fn map_confirm_mode(key: KeyEvent) -> Option<Action> {
-    match key.code {
-        KeyCode::Char('y') | KeyCode::Enter => Some(Action::Confirm),
-        KeyCode::Char('n') | KeyCode::Esc => Some(Action::Cancel),
+    match (key.modifiers, key.code) {
+        (_, KeyCode::Char('y') | KeyCode::Enter) => Some(Action::Confirm),
+        (_, KeyCode::Char('n') | KeyCode::Esc) => Some(Action::Cancel),
+        // Long messages (e.g. the commits a rebase replays) scroll
+        (KeyModifiers::NONE, KeyCode::Char('j') | KeyCode::Down) => Some(Action::ScrollDown),
+        (KeyModifiers::NONE, KeyCode::Char('k') | KeyCode::Up) => Some(Action::ScrollUp),
+        (KeyModifiers::CONTROL, KeyCode::Char('d')) => Some(Action::ScrollPageDown),
+        (KeyModifiers::CONTROL, KeyCode::Char('u')) => Some(Action::ScrollPageUp),
         _ => None,
     }
 }
@@ -182,6 +681,100 @@ fn map_file_select_mode(key: KeyEvent) -> Option<Action> {
         (KeyModifiers::NONE, KeyCode::Char('a')) => Some(Action::StageAll),
         (KeyModifiers::NONE, KeyCode::Char('u')) => Some(Action::UnstageAll),
         (KeyModifiers::NONE, KeyCode::Char('c')) => Some(Action::CommitDialog),
+        (KeyModifiers::SHIFT, KeyCode::Char('W')) => Some(Action::CycleWhitespace),
+        (KeyModifiers::NONE, KeyCode::Esc) | (KeyModifiers::NONE, KeyCode::Char('q')) => {
+            Some(Action::Cancel)
+        }
+        _ => None,
+    }
+}
+
+fn map_compare_mode(key: KeyEvent) -> Option<Action> {
+    match (key.modifiers, key.code) {
+        (KeyModifiers::NONE, KeyCode::Char('j')) | (KeyModifiers::NONE, KeyCode::Down) => {
+            Some(Action::MoveDown)
+        }
+        (KeyModifiers::NONE, KeyCode::Char('k')) | (KeyModifiers::NONE, KeyCode::Up) => {
+            Some(Action::MoveUp)
+        }
+        (KeyModifiers::CONTROL, KeyCode::Char('d')) | (KeyModifiers::NONE, KeyCode::PageDown) => {
+            Some(Action::PageDown)
+        }
+        (KeyModifiers::CONTROL, KeyCode::Char('u')) | (KeyModifiers::NONE, KeyCode::PageUp) => {
+            Some(Action::PageUp)
+        }
+        (KeyModifiers::NONE, KeyCode::Char('g')) | (KeyModifiers::NONE, KeyCode::Home) => {
+            Some(Action::GoToTop)
+        }
+        (KeyModifiers::SHIFT, KeyCode::Char('G')) | (KeyModifiers::NONE, KeyCode::End) => {
+            Some(Action::GoToBottom)
+        }
+        // Switch pane
+        (KeyModifiers::NONE, KeyCode::Char('h')) | (KeyModifiers::NONE, KeyCode::Left) => {
+            Some(Action::BranchLeft)
+        }
+        (KeyModifiers::NONE, KeyCode::Char('l')) | (KeyModifiers::NONE, KeyCode::Right) => {
+            Some(Action::BranchRight)
+        }
+        (KeyModifiers::NONE, KeyCode::Tab) => Some(Action::FocusNext),
+        (KeyModifiers::NONE, KeyCode::Enter) => Some(Action::Confirm),
+        (KeyModifiers::NONE, KeyCode::Esc) | (KeyModifiers::NONE, KeyCode::Char('q')) => {
+            Some(Action::Cancel)
+        }
+        _ => None,
+    }
+}
+
+fn map_stash_mode(key: KeyEvent) -> Option<Action> {
+    match (key.modifiers, key.code) {
+        (KeyModifiers::NONE, KeyCode::Char('j')) | (KeyModifiers::NONE, KeyCode::Down) => {
+            Some(Action::MoveDown)
+        }
+        (KeyModifiers::NONE, KeyCode::Char('k')) | (KeyModifiers::NONE, KeyCode::Up) => {
+            Some(Action::MoveUp)
+        }
+        (KeyModifiers::NONE, KeyCode::Char('g')) | (KeyModifiers::NONE, KeyCode::Home) => {
+            Some(Action::GoToTop)
+        }
+        (KeyModifiers::SHIFT, KeyCode::Char('G')) | (KeyModifiers::NONE, KeyCode::End) => {
+            Some(Action::GoToBottom)
+        }
+        (KeyModifiers::NONE, KeyCode::Enter) => Some(Action::Confirm),
+        (KeyModifiers::NONE, KeyCode::Char('p')) => Some(Action::StashPop),
+        (KeyModifiers::NONE, KeyCode::Esc) | (KeyModifiers::NONE, KeyCode::Char('q')) => {
+            Some(Action::Cancel)
+        }
+        _ => None,
+    }
+}
+
+fn map_tree_mode(key: KeyEvent) -> Option<Action> {
+    match (key.modifiers, key.code) {
+        (KeyModifiers::NONE, KeyCode::Char('j')) | (KeyModifiers::NONE, KeyCode::Down) => {
+            Some(Action::MoveDown)
+        }
+        (KeyModifiers::NONE, KeyCode::Char('k')) | (KeyModifiers::NONE, KeyCode::Up) => {
+            Some(Action::MoveUp)
+        }
+        (KeyModifiers::CONTROL, KeyCode::Char('d')) | (KeyModifiers::NONE, KeyCode::PageDown) => {
+            Some(Action::PageDown)
+        }
+        (KeyModifiers::CONTROL, KeyCode::Char('u')) | (KeyModifiers::NONE, KeyCode::PageUp) => {
+            Some(Action::PageUp)
+        }
+        (KeyModifiers::NONE, KeyCode::Char('g')) | (KeyModifiers::NONE, KeyCode::Home) => {
+            Some(Action::GoToTop)
+        }
+        (KeyModifiers::SHIFT, KeyCode::Char('G')) | (KeyModifiers::NONE, KeyCode::End) => {
+            Some(Action::GoToBottom)
+        }
+        // Expand directory / open file, collapse / go to parent
+        (KeyModifiers::NONE, KeyCode::Char('l'))
+        | (KeyModifiers::NONE, KeyCode::Right)
+        | (KeyModifiers::NONE, KeyCode::Enter) => Some(Action::TreeExpand),
+        (KeyModifiers::NONE, KeyCode::Char('h')) | (KeyModifiers::NONE, KeyCode::Left) => {
+            Some(Action::TreeCollapse)
+        }
         (KeyModifiers::NONE, KeyCode::Esc) | (KeyModifiers::NONE, KeyCode::Char('q')) => {
             Some(Action::Cancel)
         }
@@ -229,6 +822,11 @@ fn map_file_diff_mode(key: KeyEvent) -> Option<Action> {
         // File navigation
         (KeyModifiers::NONE, KeyCode::Char('n')) => Some(Action::NextFile),
         (KeyModifiers::SHIFT, KeyCode::Char('N')) => Some(Action::PrevFile),
+        // Word-level emphasis
+        (KeyModifiers::NONE, KeyCode::Char('w')) => Some(Action::ToggleWordDiff),
+        (KeyModifiers::SHIFT, KeyCode::Char('W')) => Some(Action::CycleWhitespace),
+        // Commit that last changed the line at the top of the view
+        (KeyModifiers::NONE, KeyCode::Char('b')) => Some(Action::BlameLine),
         // Exit
         (KeyModifiers::NONE, KeyCode::Esc) | (KeyModifiers::NONE, KeyCode::Char('q')) => {
             Some(Action::Cancel)
@@ -236,3 +834,26 @@ fn map_file_diff_mode(key: KeyEvent) -> Option<Action> {
         _ => None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn each_key_triggers_one_binding() {
+        let keys: Vec<&Key> = BINDINGS.iter().flat_map(|b| b.keys).collect();
+        for (i, key) in keys.iter().enumerate() {
+            assert!(!keys[i + 1..].contains(key), "{key:?} is bound twice");
+        }
+        let press = |modifiers, code| map_normal_mode(KeyEvent::new(code, modifiers));
+        assert_eq!(
+            press(KeyModifiers::SHIFT, KeyCode::Char('@')),
+            Some(Action::JumpToHead)
+        );
+        assert_eq!(
+            press(KeyModifiers::CONTROL, KeyCode::Char('x')),
+            Some(Action::CommandPalette)
+        );
+        assert_eq!(press(KeyModifiers::NONE, KeyCode::Char('m')), None);
+    }
+}
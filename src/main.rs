@@ -1,23 +1,54 @@
 //! keifu: a TUI tool that shows Git commit graphs
 
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::Parser;
 use crossterm::event::Event;
 
 use keifu::{
-    app::App, debug_server, event::poll_events, git::configure_git_extensions,
-    keybindings::map_key_to_action, logging, mouse, tui, ui,
+    app::App,
+    debug_server,
+    event::poll_events,
+    git::{
+        capabilities::{self, GitCapabilities},
+        configure_git_extensions,
+        operations::Identity,
+        GitRepository,
+    },
+    keybindings::map_key_to_action,
+    logging, mouse, status, tui, ui,
 };
 
 #[derive(Parser)]
 #[command(name = "keifu")]
 #[command(
-    version,
+    disable_version_flag = true,
     about = "A TUI tool to visualize Git commit graphs with branch genealogy"
 )]
 struct Cli {
+    /// Print version and libgit2 build information
+    #[arg(short = 'V', long)]
+    version: bool,
+
+    /// Print a short repository summary (branch, upstream, changes,
+    /// stashes, recent commits) and exit without starting the TUI
+    #[arg(long)]
+    status: bool,
+
+    /// With --status, append each commit's diffstat (files changed,
+    /// insertions, deletions); slower, since every diff is computed
+    #[arg(long, requires = "status")]
+    stat: bool,
+
+    /// Run as if keifu was started in this directory
+    #[arg(short = 'C', value_name = "PATH")]
+    directory: Option<PathBuf>,
+
+    /// Select this revision (hash, branch, HEAD~3, ...) on startup
+    #[arg(long, value_name = "REV")]
+    select: Option<String>,
+
     /// Append debug logs and a perf summary on exit to this file
     /// (level via KEIFU_LOG, default "debug")
     #[arg(long, value_name = "PATH")]
@@ -26,11 +57,63 @@ struct Cli {
     /// Listen for debug commands (NDJSON over TCP, e.g. 127.0.0.1:7167)
     #[arg(long, value_name = "ADDR")]
     debug_listen: Option<String>,
+
+    /// Only navigate and inspect: refuse checkout, branch, merge, stash,
+    /// commit and remote operations
+    #[arg(long)]
+    read_only: bool,
+
+    /// Commit, tag and stash as this identity for the session instead of
+    /// user.name / user.email, like `git commit --author`
+    #[arg(long, value_name = "NAME <EMAIL>", value_parser = Identity::parse)]
+    author: Option<Identity>,
+
+    /// Only show commits touching these paths (like `git log -- <path>`)
+    #[arg(last = true, value_name = "PATH")]
+    paths: Vec<String>,
+}
+
+/// Turn paths given relative to the current directory into pathspecs
+/// relative to the repository root
+fn repo_relative_paths(paths: &[String], repo_root: &str) -> Result<Vec<String>> {
+    let cwd = std::env::current_dir()?.canonicalize()?;
+    let root = Path::new(repo_root).canonicalize()?;
+    let prefix = cwd.strip_prefix(&root).unwrap_or(Path::new(""));
+
+    let mut pathspecs = Vec::new();
+    for path in paths {
+        let mut parts: Vec<String> = Vec::new();
+        for component in prefix.join(path).components() {
+            match component {
+                Component::Normal(part) => parts.push(part.to_string_lossy().into_owned()),
+                Component::ParentDir => {
+                    if parts.pop().is_none() {
+                        anyhow::bail!("{path} is outside the repository");
+                    }
+                }
+                Component::CurDir => {}
+                Component::RootDir | Component::Prefix(_) => {
+                    anyhow::bail!("{path}: use a path relative to the current directory");
+                }
+            }
+        }
+        // An empty result means the whole repository; no point scoping
+        if parts.is_empty() {
+            return Ok(Vec::new());
+        }
+        pathspecs.push(parts.join("/"));
+    }
+    Ok(pathspecs)
 }
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
 
+    if cli.version {
+        println!("{}", capabilities::build_info());
+        return Ok(());
+    }
+
     if let Some(path) = &cli.log_file {
         logging::init(path)?;
     }
@@ -48,8 +131,34 @@ fn main() -> Result<()> {
 
     configure_git_extensions()?;
 
+    if let Some(dir) = &cli.directory {
+        std::env::set_current_dir(dir)
+            .with_context(|| format!("Cannot change directory to {}", dir.display()))?;
+    }
+
+    if cli.status {
+        let repo = GitRepository::discover()?;
+        print!(
+            "{}",
+            status::render(&repo, status::STATUS_COMMITS, cli.stat)?
+        );
+        return Ok(());
+    }
+
     // Initialize application
-    let mut app = App::new()?;
+    let mut app = App::new(cli.select.as_deref(), GitCapabilities::detect())?;
+    if cli.read_only {
+        app.set_read_only();
+    }
+    if cli.author.is_some() {
+        app.set_session_identity(cli.author);
+    }
+    if !cli.paths.is_empty() {
+        let paths = repo_relative_paths(&cli.paths, &app.repo_path)?;
+        if !paths.is_empty() {
+            app.set_path_scope(paths)?;
+        }
+    }
 
     // Initialize terminal
     let mut terminal = tui::init()?;
@@ -66,6 +175,8 @@ fn main() -> Result<()> {
         // Check if async fetch/push has completed
         app.update_fetch_status();
         app.update_push_status();
+        app.update_path_scope_status();
+        app.load_ahead();
 
         // Auto-refresh check
         app.check_auto_refresh();
@@ -92,7 +203,10 @@ fn main() -> Result<()> {
                     Event::Mouse(mouse_event) => {
                         mouse::handle_mouse(&mut app, mouse_event);
                     }
-                    // Resize events trigger redraw automatically
+                    Event::FocusGained => app.on_focus_gained(),
+                    // The next draw picks up the new size; this only
+                    // schedules the optional refresh_on_resize reload
+                    Event::Resize(..) => app.on_resize(),
                     _ => {}
                 }
                 if app.should_quit {
@@ -10,7 +10,8 @@ use ratatui::layout::{Position, Rect};
 
 use crate::{
     action::Action,
-    app::{App, AppMode, FocusedPane},
+    app::{App, AppMode, FocusedPane, GraphOrientation},
+    ui::{graph_view::horizontal_node_at, minimap},
 };
 
 /// Max delay between two clicks on the same cell to count as a double-click
@@ -42,6 +43,30 @@ fn inner_row(rect: Rect, y: u16) -> Option<u16> {
     (y >= top && y < bottom).then(|| y - top)
 }
 
+/// Graph node drawn at a screen cell inside the graph pane
+fn graph_node_at(app: &App, x: u16, y: u16) -> Option<usize> {
+    let graph = app.layout.graph;
+    // Focus mode draws the graph without a border
+    let border = if app.focus_mode() { 0 } else { 1 };
+    if app.graph_orientation() == GraphOrientation::Horizontal {
+        let inner_width = graph.width.saturating_sub(2 * border) as usize;
+        let column = x.checked_sub(graph.x + border)? as usize;
+        return horizontal_node_at(
+            app.graph_layout.nodes.len(),
+            app.graph_list_state.selected(),
+            inner_width,
+            column,
+        );
+    }
+    let row = if border == 0 {
+        Some(y - graph.y)
+    } else {
+        inner_row(graph, y)
+    }?;
+    app.date_headers()
+        .node_on_screen(app.graph_list_state.offset(), row as usize)
+}
+
 fn handle_scroll(app: &mut App, delta: i32, x: u16, y: u16) {
     match &app.mode {
         AppMode::FileDiff { .. } => {
@@ -63,6 +88,14 @@ fn handle_scroll(app: &mut App, delta: i32, x: u16, y: u16) {
             };
             dispatch(app, action);
         }
+        AppMode::TreeBrowser { .. } | AppMode::BranchCompare { .. } | AppMode::StashList { .. } => {
+            let action = if delta > 0 {
+                Action::MoveDown
+            } else {
+                Action::MoveUp
+            };
+            dispatch(app, action);
+        }
         AppMode::Normal | AppMode::FileSelect { .. } => {
             let layout = app.layout;
             if contains(layout.commit_detail, x, y) {
@@ -76,7 +109,9 @@ fn handle_scroll(app: &mut App, delta: i32, x: u16, y: u16) {
                     };
                     dispatch(app, action);
                 }
-            } else if contains(layout.graph, x, y) && matches!(app.mode, AppMode::Normal) {
+            } else if (contains(layout.graph, x, y) || contains(layout.minimap, x, y))
+                && matches!(app.mode, AppMode::Normal)
+            {
                 app.move_selection(delta);
             }
         }
@@ -112,10 +147,9 @@ fn handle_click(app: &mut App, x: u16, y: u16) {
         AppMode::Normal | AppMode::FileSelect { .. } => {
             let layout = app.layout;
             if contains(layout.graph, x, y) {
-                let Some(row) = inner_row(layout.graph, y) else {
+                let Some(idx) = graph_node_at(app, x, y) else {
                     return;
                 };
-                let idx = app.graph_list_state.offset() + row as usize;
                 if idx >= app.graph_layout.nodes.len() {
                     return;
                 }
@@ -127,6 +161,20 @@ fn handle_click(app: &mut App, x: u16, y: u16) {
                 if is_double {
                     dispatch(app, Action::EnterFileSelect);
                 }
+            } else if contains(layout.minimap, x, y) {
+                let row = (y - layout.minimap.y) as usize;
+                let Some(idx) = minimap::node_index_for_row(
+                    app.graph_layout.nodes.len(),
+                    layout.minimap.height as usize,
+                    row,
+                ) else {
+                    return;
+                };
+                if matches!(app.mode, AppMode::FileSelect { .. }) {
+                    dispatch(app, Action::Cancel);
+                }
+                app.focused_pane = FocusedPane::Graph;
+                app.select_node(idx);
             } else if contains(layout.commit_detail, x, y) {
                 if matches!(app.mode, AppMode::Normal) {
                     app.focused_pane = FocusedPane::Detail;
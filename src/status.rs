@@ -0,0 +1,135 @@
+//! Non-interactive repository digest for `keifu --status`
+
+use std::fmt::Write;
+
+use anyhow::Result;
+
+use crate::git::{diff::DEFAULT_MAX_FILES, CommitDiffInfo, GitRepository};
+
+/// Number of commits listed under the summary
+pub const STATUS_COMMITS: usize = 5;
+
+/// Compact repo-state summary: branch, upstream divergence, dirty files,
+/// stashes, in-progress operation and the newest commits. Tagged commits get
+/// a `(tag: v1.0)` suffix like `git log --decorate`. With `stat`, each
+/// commit line ends with its diffstat, like `git log --oneline --shortstat`.
+pub fn render(repo: &GitRepository, commit_count: usize, stat: bool) -> Result<String> {
+    let mut out = String::new();
+    let summary = repo.summary();
+
+    let branch = match (repo.head_name(), repo.head_oid()) {
+        (Some(name), Some(oid)) if name == "HEAD" => {
+            format!("(detached at {})", &oid.to_string()[..7])
+        }
+        (Some(name), _) => name,
+        (None, _) => "(no commits yet)".to_string(),
+    };
+    writeln!(out, "branch    {branch}")?;
+
+    match repo.head_upstream() {
+        Ok((upstream, _, _)) => {
+            let (ahead, behind) = summary.ahead_behind.unwrap_or_default();
+            writeln!(out, "upstream  {upstream} ↑{ahead} ↓{behind}")?;
+        }
+        Err(_) => writeln!(out, "upstream  (none)")?,
+    }
+
+    let changed = repo
+        .get_working_tree_status()?
+        .map(|status| status.file_count())
+        .unwrap_or(0);
+    writeln!(out, "changes   {changed} file(s)")?;
+    writeln!(out, "stashes   {}", summary.stash_count)?;
+    if let Some(state) = summary.state {
+        writeln!(out, "state     {state}")?;
+    }
+
+    let commits = repo.get_commits(commit_count, false)?;
+    if !commits.is_empty() {
+        writeln!(out)?;
+    }
+    let tags = repo.tags_by_commit()?;
+    for commit in commits {
+        write!(out, "{} {}", commit.short_id, commit.message)?;
+        if let Some(names) = tags.get(&commit.oid) {
+            let names: Vec<String> = names.iter().map(|name| format!("tag: {name}")).collect();
+            write!(out, " ({})", names.join(", "))?;
+        }
+        write!(
+            out,
+            " ({}, {})",
+            commit.author_name,
+            commit.timestamp.format("%Y-%m-%d")
+        )?;
+        if stat {
+            let diff = CommitDiffInfo::from_commit(&repo.repo, commit.oid, DEFAULT_MAX_FILES)?;
+            write!(out, "  {}", short_stat(&diff))?;
+        }
+        writeln!(out)?;
+    }
+    Ok(out)
+}
+
+/// `3 files, +10 -2`. Totals cover every file, including those past the
+/// file list's display limit.
+fn short_stat(diff: &CommitDiffInfo) -> String {
+    let files = if diff.total_files == 1 {
+        "1 file".to_string()
+    } else {
+        format!("{} files", diff.total_files)
+    };
+    format!(
+        "{files}, +{} -{}",
+        diff.total_insertions, diff.total_deletions
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+
+    use git2::{Repository, Signature};
+
+    use super::*;
+
+    #[test]
+    fn summary_lists_branch_changes_and_commits() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(tempdir.path()).unwrap();
+        fs::write(tempdir.path().join("a.txt"), "a\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("a.txt")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = Signature::now("Test", "test@example.com").unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "first commit", &tree, &[])
+            .unwrap();
+        fs::write(tempdir.path().join("b.txt"), "b\n").unwrap();
+
+        let git_repo = GitRepository::open(tempdir.path()).unwrap();
+        let head = git_repo.head_name().unwrap();
+        let report = render(&git_repo, STATUS_COMMITS, false).unwrap();
+
+        assert!(report.starts_with(&format!("branch    {head}\n")));
+        assert!(report.contains("upstream  (none)\n"));
+        assert!(report.contains("changes   1 file(s)\n"));
+        assert!(report.contains("stashes   0\n"));
+        assert!(report.contains(" first commit (Test, "));
+        assert!(!report.contains("+1 -0"));
+
+        let report = render(&git_repo, STATUS_COMMITS, true).unwrap();
+        assert!(report.contains("  1 file, +1 -0\n"), "{report}");
+
+        // Annotated tags decorate the commit they point to
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.tag_lightweight("v1", head.as_object(), false).unwrap();
+        repo.tag("v1-notes", head.as_object(), &sig, "notes", false)
+            .unwrap();
+        let report = render(&git_repo, STATUS_COMMITS, false).unwrap();
+        assert!(
+            report.contains(" first commit (tag: v1, tag: v1-notes) (Test, "),
+            "{report}"
+        );
+    }
+}
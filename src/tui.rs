@@ -4,7 +4,7 @@ use std::io::{self, Stdout};
 
 use anyhow::Result;
 use crossterm::{
-    event::{DisableMouseCapture, EnableMouseCapture},
+    event::{DisableFocusChange, DisableMouseCapture, EnableFocusChange, EnableMouseCapture},
     execute,
     style::Print,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
@@ -28,7 +28,8 @@ pub fn init() -> Result<Tui> {
         stdout,
         EnterAlternateScreen,
         EnableMouseCapture,
-        Print("\x1b[?1003l\x1b[?1002h")
+        Print("\x1b[?1003l\x1b[?1002h"),
+        EnableFocusChange
     )?;
     let backend = CrosstermBackend::new(stdout);
     let terminal = Terminal::new(backend)?;
@@ -38,7 +39,12 @@ pub fn init() -> Result<Tui> {
 /// Restore the terminal
 pub fn restore() -> Result<()> {
     disable_raw_mode()?;
-    execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture)?;
+    execute!(
+        io::stdout(),
+        LeaveAlternateScreen,
+        DisableMouseCapture,
+        DisableFocusChange
+    )?;
     Ok(())
 }
 
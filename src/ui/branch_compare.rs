@@ -0,0 +1,136 @@
+//! Two branches side by side on a shared timeline
+
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Widget},
+};
+
+use crate::git::compare::{BranchCompare, CompareSide};
+
+use super::graph_view::truncate_to_width;
+
+/// Background of the cursor row in the pane without focus
+const OTHER_SIDE_BG: Color = Color::Rgb(40, 44, 62);
+
+pub struct BranchCompareWidget<'a> {
+    compare: &'a BranchCompare,
+}
+
+impl<'a> BranchCompareWidget<'a> {
+    pub fn new(compare: &'a BranchCompare) -> Self {
+        Self { compare }
+    }
+}
+
+/// Keep the selected row inside a viewport of `height` rows
+pub fn clamp_scroll(compare: &mut BranchCompare, height: usize) {
+    let height = height.max(1);
+    if compare.selected < compare.scroll {
+        compare.scroll = compare.selected;
+    } else if compare.selected >= compare.scroll + height {
+        compare.scroll = compare.selected + 1 - height;
+    }
+}
+
+impl Widget for BranchCompareWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+        let [left, right] =
+            Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .areas(area);
+        render_side(self.compare, false, left, buf);
+        render_side(self.compare, true, right, buf);
+    }
+}
+
+fn render_side(compare: &BranchCompare, right: bool, area: Rect, buf: &mut Buffer) {
+    let (side, color) = if right {
+        (&compare.right, Color::Blue)
+    } else {
+        (&compare.left, Color::Green)
+    };
+    let focused = compare.focus_right == right;
+    let block = Block::default()
+        .title(title(side, compare, right))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(if focused {
+            Color::Cyan
+        } else {
+            Color::DarkGray
+        }))
+        .border_type(BorderType::Rounded)
+        .style(Style::default().bg(Color::Black));
+    let height = area.height.saturating_sub(2) as usize;
+    let width = area.width.saturating_sub(2) as usize;
+
+    // The lane keeps going on rows where only the other side has a commit
+    let last_row = compare
+        .rows
+        .iter()
+        .rposition(|row| row.commit(right).is_some());
+    let lines: Vec<Line> = compare
+        .rows
+        .iter()
+        .enumerate()
+        .skip(compare.scroll)
+        .take(height)
+        .map(|(idx, row)| {
+            let mut line = match row.commit(right) {
+                Some(commit) => {
+                    let (glyph, style) = if compare.highlight == Some(commit.oid) {
+                        (
+                            "◆",
+                            Style::default()
+                                .fg(Color::Magenta)
+                                .add_modifier(Modifier::BOLD),
+                        )
+                    } else if row.is_shared() {
+                        ("●", Style::default().fg(Color::DarkGray))
+                    } else {
+                        ("●", Style::default().fg(color))
+                    };
+                    let text = format!(" {} {}", commit.short_id, commit.message);
+                    Line::from(vec![
+                        Span::styled(format!(" {glyph}"), style),
+                        Span::styled(
+                            truncate_to_width(&text, width.saturating_sub(2)),
+                            if row.is_shared() {
+                                Style::default().fg(Color::DarkGray)
+                            } else {
+                                Style::default()
+                            },
+                        ),
+                    ])
+                }
+                None if last_row.is_some_and(|last| idx < last) => {
+                    Line::from(Span::styled(" │", Style::default().fg(color)))
+                }
+                None => Line::from(""),
+            };
+            if idx == compare.selected {
+                let bg = if focused {
+                    Color::DarkGray
+                } else {
+                    OTHER_SIDE_BG
+                };
+                line = line.style(Style::default().bg(bg));
+            }
+            line
+        })
+        .collect();
+
+    Paragraph::new(lines).block(block).render(area, buf);
+}
+
+/// Branch name and how many commits it has that the other side doesn't
+fn title(side: &CompareSide, compare: &BranchCompare, right: bool) -> String {
+    let unique = compare
+        .rows
+        .iter()
+        .filter(|row| !row.is_shared() && row.commit(right).is_some())
+        .count();
+    format!(" {} (+{unique}) ", side.name)
+}
@@ -14,6 +14,56 @@ use crate::git::{FileChangeKind, StageState};
 
 use super::{render_placeholder_block, MIN_WIDGET_HEIGHT, MIN_WIDGET_WIDTH};
 
+/// Message characters shown before the rest is cut, so a huge message
+/// can't wrap into thousands of rows
+const MAX_MESSAGE_CHARS: usize = 4000;
+/// Message lines shown before the rest is cut
+const MAX_MESSAGE_LINES: usize = 300;
+
+/// Commit message lines, cut at [`MAX_MESSAGE_CHARS`] or
+/// [`MAX_MESSAGE_LINES`] with a note unless `full`
+fn message_lines(message: &str, full: bool) -> Vec<Line<'static>> {
+    let (shown, hidden) = if full {
+        (message, 0)
+    } else {
+        cap_message(message)
+    };
+    let mut lines: Vec<Line<'static>> = shown
+        .lines()
+        .map(|line| Line::from(Span::raw(format!(" {}", line))))
+        .collect();
+    if hidden > 0 {
+        lines.push(truncation_note(hidden));
+    }
+    lines
+}
+
+/// The start of `message` within the caps, and how many characters are
+/// left out
+fn cap_message(message: &str) -> (&str, usize) {
+    let by_lines = message
+        .match_indices('\n')
+        .nth(MAX_MESSAGE_LINES - 1)
+        .map(|(i, _)| i);
+    let by_chars = message
+        .char_indices()
+        .nth(MAX_MESSAGE_CHARS)
+        .map(|(i, _)| i);
+    match by_lines.into_iter().chain(by_chars).min() {
+        Some(cut) => (&message[..cut], message[cut..].trim_end().chars().count()),
+        None => (message, 0),
+    }
+}
+
+fn truncation_note(hidden: usize) -> Line<'static> {
+    Line::from(Span::styled(
+        format!(
+            " (message truncated, {hidden} more characters; \"Toggle full commit message\" in Ctrl+x)"
+        ),
+        Style::default().fg(Color::DarkGray),
+    ))
+}
+
 /// Human-friendly relative time like "3 days ago"
 fn relative_time(ts: chrono::DateTime<chrono::Local>) -> String {
     let secs = chrono::Local::now().signed_duration_since(ts).num_seconds();
@@ -37,6 +87,7 @@ pub struct CommitDetailWidget {
     commit_lines: Vec<Line<'static>>,
     scroll: u16,
     focused: bool,
+    raw: bool,
 }
 
 impl CommitDetailWidget {
@@ -45,6 +96,7 @@ impl CommitDetailWidget {
             commit_lines: Self::build_commit_lines(app),
             scroll: app.detail_scroll,
             focused: matches!(app.mode, AppMode::Normal) && app.focused_pane == FocusedPane::Detail,
+            raw: app.show_raw_commit(),
         }
     }
 
@@ -71,7 +123,103 @@ impl CommitDetailWidget {
         )
     }
 
+    /// Raw commit object lines: header fields dimmed by key, the signature
+    /// block dimmed entirely, and the message as-is.
+    fn raw_commit_lines(app: &App, oid: git2::Oid) -> Vec<Line<'static>> {
+        let raw = match app.repo.raw_commit_object(oid) {
+            Ok(raw) => raw,
+            Err(e) => {
+                return vec![Line::from(Span::styled(
+                    format!(" Failed to read commit object: {}", e),
+                    Style::default().fg(Color::Red),
+                ))];
+            }
+        };
+
+        let (header, message) = raw.split_once("\n\n").unwrap_or((raw.as_str(), ""));
+        let mut lines = Vec::new();
+        let mut in_signature = false;
+        for line in header.lines() {
+            // Multi-line header values (gpgsig, mergetag) continue with a leading space
+            if let Some(rest) = line.strip_prefix(' ') {
+                let style = if in_signature {
+                    Style::default().fg(Color::DarkGray)
+                } else {
+                    Style::default()
+                };
+                lines.push(Line::from(Span::styled(format!("  {}", rest), style)));
+                continue;
+            }
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+            in_signature = key.starts_with("gpgsig");
+            let value_style = match key {
+                "tree" | "parent" => Style::default().fg(Color::Yellow),
+                "author" | "committer" => Style::default().fg(Color::Blue),
+                _ if in_signature => Style::default().fg(Color::DarkGray),
+                _ => Style::default(),
+            };
+            lines.push(Line::from(vec![
+                Span::styled(format!(" {} ", key), Style::default().fg(Color::DarkGray)),
+                Span::styled(value.to_string(), value_style),
+            ]));
+        }
+        lines.push(Line::from(""));
+        lines.extend(message_lines(message, app.show_full_message()));
+        lines
+    }
+
     fn build_commit_lines(app: &App) -> Vec<Line<'static>> {
+        if let Some(range) = app.range_diff() {
+            let plural = if range.ahead == 1 { "" } else { "s" };
+            let (title, note) = if !range.for_push {
+                (" Marked range", " Net diff between the two marked commits")
+            } else if range.behind > 0 {
+                (
+                    " Changes to push",
+                    " Cumulative diff from the merge base with the upstream to HEAD",
+                )
+            } else {
+                (
+                    " Changes to push",
+                    " Cumulative diff from the upstream tip to HEAD",
+                )
+            };
+            // `...` like git: the range starts at the merge base
+            let dots = if range.behind > 0 { "..." } else { ".." };
+            let mut lines = vec![
+                Line::from(Span::styled(
+                    title,
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                )),
+                Line::from(""),
+                Line::from(vec![
+                    Self::metadata_label("Range"),
+                    Span::styled(
+                        format!("{}{dots}{}", range.base_label, range.head_label),
+                        Style::default().fg(Color::Cyan),
+                    ),
+                ]),
+                Line::from(vec![
+                    Self::metadata_label("Ahead"),
+                    Span::raw(format!("{} commit{}", range.ahead, plural)),
+                ]),
+                Line::from(""),
+                Line::from(Span::styled(note, Style::default().fg(Color::DarkGray))),
+            ];
+            if range.behind > 0 {
+                lines.push(Line::from(Span::styled(
+                    format!(
+                        " ⚠ Diverged: {} upstream commit(s) are not in HEAD; pull before pushing",
+                        range.behind
+                    ),
+                    Style::default().fg(Color::Yellow),
+                )));
+            }
+            return lines;
+        }
+
         let Some(selected) = app.graph_list_state.selected() else {
             return vec![Line::from(Span::styled(
                 "Select a commit",
@@ -116,6 +264,10 @@ impl CommitDetailWidget {
             ))];
         };
 
+        if app.show_raw_commit() {
+            return Self::raw_commit_lines(app, commit.oid);
+        }
+
         // Hash line: short hash emphasized, remainder dimmed
         let full_hash = commit.oid.to_string();
         let (short, rest) = full_hash.split_at(7.min(full_hash.len()));
@@ -137,22 +289,49 @@ impl CommitDetailWidget {
                     Style::default().fg(Color::Blue),
                 ),
             ]),
-            Line::from(vec![
-                Self::metadata_label("Date"),
-                Span::raw(commit.timestamp.format("%Y-%m-%d %H:%M:%S").to_string()),
+        ];
+        for co_author in commit.co_authors() {
+            let who = if co_author.email.is_empty() || co_author.email == co_author.name {
+                co_author.name
+            } else {
+                format!("{} <{}>", co_author.name, co_author.email)
+            };
+            lines.push(Line::from(vec![
+                Self::metadata_label("Coauthor"),
+                Span::styled(who, Style::default().fg(Color::Blue)),
+            ]));
+        }
+        if commit.committer_differs() {
+            lines.push(Line::from(vec![
+                Self::metadata_label("Commit"),
                 Span::styled(
-                    format!(" ({})", relative_time(commit.timestamp)),
-                    Style::default().fg(Color::DarkGray),
+                    format!("{} <{}>", commit.committer_name, commit.committer_email),
+                    Style::default().fg(Color::Yellow),
                 ),
-            ]),
-        ];
+            ]));
+        }
+        lines.push(Line::from(vec![
+            Self::metadata_label("Date"),
+            Span::raw(commit.timestamp.format("%Y-%m-%d %H:%M:%S").to_string()),
+            Span::styled(
+                format!(" ({})", relative_time(commit.timestamp)),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ]));
 
         // Parent commits
         if !commit.parent_oids.is_empty() {
             let parents: Vec<String> = commit
                 .parent_oids
                 .iter()
-                .map(|oid| oid.to_string()[..7].to_string())
+                .map(|oid| {
+                    let hash = oid.to_string();
+                    if app.full_hashes() {
+                        hash
+                    } else {
+                        hash[..7].to_string()
+                    }
+                })
                 .collect();
             lines.push(Line::from(vec![
                 Self::metadata_label("Parent"),
@@ -161,20 +340,55 @@ impl CommitDetailWidget {
         }
 
         // Branches pointing at this commit
-        if !node.branch_names.is_empty() {
+        if !node.labels.is_empty() {
             let mut spans = vec![Self::metadata_label("Branch")];
-            for (i, name) in node.branch_names.iter().enumerate() {
+            for (i, name) in node.labels.iter().map(|l| &l.name).enumerate() {
                 if i > 0 {
                     spans.push(Span::raw(" "));
                 }
+                let color = if app.merged_branches().contains(name) {
+                    Color::DarkGray
+                } else {
+                    Color::Cyan
+                };
                 spans.push(Span::styled(
                     format!("[{}]", name),
-                    Style::default()
-                        .fg(Color::Cyan)
-                        .add_modifier(Modifier::BOLD),
+                    Style::default().fg(color).add_modifier(Modifier::BOLD),
                 ));
             }
             lines.push(Line::from(spans));
+
+            let merged: Vec<&str> = node
+                .labels
+                .iter()
+                .map(|l| l.name.as_str())
+                .filter(|name| app.merged_branches().contains(*name))
+                .collect();
+            if let (false, Some(main)) = (merged.is_empty(), app.main_branch()) {
+                lines.push(Line::from(Span::styled(
+                    format!(" ✓ {} merged into {main}", merged.join(", ")),
+                    Style::default().fg(Color::DarkGray),
+                )));
+            }
+            for label in node.labels.iter().filter(|l| !l.is_remote) {
+                if let Some(path) = app.worktree_branches().get(&label.name) {
+                    lines.push(Line::from(Span::styled(
+                        format!(
+                            " ⌂ {} checked out in worktree {}",
+                            label.name,
+                            path.display()
+                        ),
+                        Style::default().fg(Color::DarkGray),
+                    )));
+                }
+            }
+        }
+
+        if app.replaced_oids.contains(&commit.oid) {
+            lines.push(Line::from(Span::styled(
+                " ⇄ Replaced via refs/replace or grafts; ancestry may differ from git log",
+                Style::default().fg(Color::Magenta),
+            )));
         }
 
         lines.push(Line::from(Span::styled(
@@ -183,9 +397,7 @@ impl CommitDetailWidget {
         )));
 
         // Message
-        for line in commit.full_message.lines() {
-            lines.push(Line::from(Span::raw(format!(" {}", line))));
-        }
+        lines.extend(message_lines(&commit.full_message, app.show_full_message()));
 
         lines
     }
@@ -198,7 +410,12 @@ impl Widget for CommitDetailWidget {
             return;
         }
 
-        let block = super::pane_block("Commit Detail", self.focused);
+        let title = if self.raw {
+            "Commit Detail (raw)"
+        } else {
+            "Commit Detail"
+        };
+        let block = super::pane_block(title, self.focused);
 
         let max_scroll = self
             .estimated_height(area.width.saturating_sub(2))
@@ -247,6 +464,7 @@ pub struct FileListWidget {
     content: FileListContent,
     file_scroll: u16,
     focused: bool,
+    whitespace: Option<&'static str>,
 }
 
 impl FileListWidget {
@@ -259,6 +477,7 @@ impl FileListWidget {
             content: Self::build_content(app),
             file_scroll,
             focused: matches!(app.mode, AppMode::FileSelect { .. }),
+            whitespace: app.whitespace().label(),
         }
     }
 
@@ -525,9 +744,10 @@ impl Widget for FileListWidget {
             return;
         }
 
-        let title = match self.file_count() {
-            Some(count) => format!("Changed Files ({})", count),
-            None => "Changed Files".to_string(),
+        let title = match (self.file_count(), self.whitespace) {
+            (Some(count), Some(whitespace)) => format!("Changed Files ({count}, {whitespace})"),
+            (Some(count), None) => format!("Changed Files ({})", count),
+            (None, _) => "Changed Files".to_string(),
         };
         let block = super::pane_block(&title, self.focused);
 
@@ -542,3 +762,37 @@ impl Widget for FileListWidget {
         Widget::render(paragraph, area, buf);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn long_single_line_message_is_capped() {
+        let message = "x".repeat(10_000);
+        let lines = message_lines(&message, false);
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].width(), MAX_MESSAGE_CHARS + 1);
+        assert!(lines[1]
+            .to_string()
+            .contains("message truncated, 6000 more characters"));
+
+        let lines = message_lines(&message, true);
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].width(), 10_001);
+    }
+
+    #[test]
+    fn many_short_lines_are_capped_by_line_count() {
+        let message = "line\n".repeat(MAX_MESSAGE_LINES + 5);
+        let lines = message_lines(&message, false);
+        assert_eq!(lines.len(), MAX_MESSAGE_LINES + 1);
+        assert!(lines[MAX_MESSAGE_LINES]
+            .to_string()
+            .contains("message truncated"));
+
+        // A message right at the cap, trailing newline included, isn't cut
+        let message = "line\n".repeat(MAX_MESSAGE_LINES);
+        assert_eq!(message_lines(&message, false).len(), MAX_MESSAGE_LINES);
+    }
+}
@@ -1,5 +1,7 @@
 //! Input and confirmation dialog widgets
 
+use std::ops::Range;
+
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
@@ -61,14 +63,48 @@ impl<'a> Widget for InputDialog<'a> {
     }
 }
 
+/// Lines of a confirmation message that scroll: the message's list, so the
+/// question and any warnings around it stay visible. Falls back to the whole
+/// message when there is no list or the fixed lines alone don't fit.
+fn scroll_region(
+    line_count: usize,
+    list: Option<Range<usize>>,
+    viewport_height: usize,
+) -> Range<usize> {
+    match list {
+        Some(list) if list.end <= line_count && line_count - list.len() < viewport_height => list,
+        _ => 0..line_count,
+    }
+}
+
+/// Largest useful scroll offset for a confirmation message
+pub fn max_confirm_scroll(
+    message: &str,
+    list: Option<Range<usize>>,
+    viewport_height: usize,
+) -> u16 {
+    let line_count = message.lines().count();
+    let region = scroll_region(line_count, list, viewport_height);
+    let room = viewport_height.saturating_sub(line_count - region.len());
+    region.len().saturating_sub(room) as u16
+}
+
 /// Confirmation dialog
 pub struct ConfirmDialog<'a> {
     message: &'a str,
+    /// Message lines that scroll, see [`scroll_region`]
+    list: Option<Range<usize>>,
+    /// First message line shown when the message is taller than the dialog
+    scroll: u16,
 }
 
 impl<'a> ConfirmDialog<'a> {
-    pub fn new(message: &'a str) -> Self {
-        Self { message }
+    pub fn new(message: &'a str, list: Option<Range<usize>>, scroll: u16) -> Self {
+        Self {
+            message,
+            list,
+            scroll,
+        }
     }
 }
 
@@ -83,12 +119,32 @@ impl<'a> Widget for ConfirmDialog<'a> {
             .border_type(BorderType::Rounded)
             .style(Style::default().bg(Color::Black));
 
-        let lines = vec![
-            Line::from(""),
-            Line::from(Span::styled(
-                format!("  {}", self.message),
-                Style::default().fg(Color::White),
-            )),
+        // Room for the message between the blank top line and the hint
+        let viewport_height = area.height.saturating_sub(5) as usize;
+        let message: Vec<&str> = self.message.lines().collect();
+        let scrollable = message.len() > viewport_height;
+        let region = scroll_region(message.len(), self.list, viewport_height);
+        let room = viewport_height.saturating_sub(message.len() - region.len());
+        let mut lines = vec![Line::from("")];
+        let shown = message[..region.start]
+            .iter()
+            .chain(
+                message[region.clone()]
+                    .iter()
+                    .skip(self.scroll as usize)
+                    .take(room),
+            )
+            .chain(&message[region.end..]);
+        lines.extend(shown.map(|line| {
+            // Warnings (e.g. rewriting pushed commits) must not be missed
+            let style = if line.starts_with('⚠') {
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            Line::from(Span::styled(format!("  {}", line), style))
+        }));
+        lines.extend([
             Line::from(""),
             Line::from(vec![
                 Span::styled(
@@ -103,8 +159,12 @@ impl<'a> Widget for ConfirmDialog<'a> {
                     Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
                 ),
                 Span::raw(": No"),
+                Span::styled(
+                    if scrollable { "  j/k: scroll" } else { "" },
+                    Style::default().fg(Color::DarkGray),
+                ),
             ]),
-        ];
+        ]);
 
         let paragraph = Paragraph::new(lines).block(block);
         Widget::render(paragraph, area, buf);
@@ -169,3 +229,53 @@ impl<'a> Widget for BranchInfoPopup<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MESSAGE: &str = "Rebase onto 'main'?\nReplays 4 commit(s):\n  a\n  b\n  c\n  d\n⚠ pushed";
+
+    #[test]
+    fn only_the_list_scrolls_when_the_rest_fits() {
+        assert_eq!(scroll_region(7, Some(2..6), 5), 2..6);
+        // Three fixed lines leave two rows for the four list lines
+        assert_eq!(max_confirm_scroll(MESSAGE, Some(2..6), 5), 2);
+        assert_eq!(max_confirm_scroll(MESSAGE, Some(2..6), 7), 0);
+
+        // Without a list, or when the fixed lines alone overflow, the whole
+        // message scrolls
+        assert_eq!(scroll_region(7, None, 5), 0..7);
+        assert_eq!(max_confirm_scroll(MESSAGE, None, 5), 2);
+        assert_eq!(scroll_region(7, Some(2..6), 3), 0..7);
+        assert_eq!(max_confirm_scroll(MESSAGE, Some(2..6), 3), 4);
+        // A range past the message is ignored rather than trusted
+        assert_eq!(scroll_region(7, Some(5..9), 5), 0..7);
+    }
+
+    #[test]
+    fn scrolled_dialog_keeps_the_question_and_warning() {
+        let area = Rect::new(0, 0, 30, 10);
+        let mut buf = Buffer::empty(area);
+        ConfirmDialog::new(MESSAGE, Some(2..6), 2).render(area, &mut buf);
+        let rows: Vec<String> = (2..7)
+            .map(|y| {
+                (1..29)
+                    .map(|x| buf[(x, y)].symbol())
+                    .collect::<String>()
+                    .trim_end()
+                    .to_string()
+            })
+            .collect();
+        assert_eq!(
+            rows,
+            [
+                "  Rebase onto 'main'?",
+                "  Replays 4 commit(s):",
+                "    c",
+                "    d",
+                "  ⚠ pushed"
+            ]
+        );
+    }
+}
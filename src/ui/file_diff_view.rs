@@ -371,7 +371,14 @@ fn determine_syntax(path: &std::path::Path) -> &'static SyntaxReference {
 /// Build pre-computed highlighted lines and hunk header positions.
 /// Returns `(rendered_lines, hunk_positions)` so that hunk navigation
 /// positions are always in sync with the actual rendered output.
-pub fn build_highlighted_lines(content: &FileDiffContent) -> (Vec<Line<'static>>, Vec<usize>) {
+/// `word_diff` enables word-level emphasis within changed line pairs.
+/// Without `syntax_highlight` every file is treated as plain text, leaving
+/// only the +/- backgrounds.
+pub fn build_highlighted_lines(
+    content: &FileDiffContent,
+    word_diff: bool,
+    syntax_highlight: bool,
+) -> (Vec<Line<'static>>, Vec<usize>) {
     if content.is_binary {
         return (
             vec![Line::from(Span::styled(
@@ -392,7 +399,11 @@ pub fn build_highlighted_lines(content: &FileDiffContent) -> (Vec<Line<'static>>
         );
     }
 
-    let syntax = determine_syntax(&content.path);
+    let syntax = if syntax_highlight {
+        determine_syntax(&content.path)
+    } else {
+        SYNTAX_SET.find_syntax_plain_text()
+    };
     let theme = THEME_SET
         .themes
         .get(THEME_NAME)
@@ -434,11 +445,13 @@ pub fn build_highlighted_lines(content: &FileDiffContent) -> (Vec<Line<'static>>
                     deletions,
                     additions,
                 } => {
-                    let emp = compute_word_emphasis(deletions, additions);
+                    let emp = word_diff.then(|| compute_word_emphasis(deletions, additions));
 
                     for (i, dl) in deletions.iter().enumerate() {
                         let syn = highlight_line_owned(&mut old_hl, &dl.content);
-                        let spans = if let Some(emp_spans) = emp.old_spans.get(i) {
+                        let spans = if let Some(emp_spans) =
+                            emp.as_ref().and_then(|e| e.old_spans.get(i))
+                        {
                             merge_syntax_and_emphasis(&syn, emp_spans, BG_DEL, BG_DEL_EMPH)
                         } else {
                             syntax_to_ratatui(&syn, Some(BG_DEL))
@@ -448,7 +461,9 @@ pub fn build_highlighted_lines(content: &FileDiffContent) -> (Vec<Line<'static>>
 
                     for (i, dl) in additions.iter().enumerate() {
                         let syn = highlight_line_owned(&mut new_hl, &dl.content);
-                        let spans = if let Some(emp_spans) = emp.new_spans.get(i) {
+                        let spans = if let Some(emp_spans) =
+                            emp.as_ref().and_then(|e| e.new_spans.get(i))
+                        {
                             merge_syntax_and_emphasis(&syn, emp_spans, BG_ADD, BG_ADD_EMPH)
                         } else {
                             syntax_to_ratatui(&syn, Some(BG_ADD))
@@ -469,6 +484,41 @@ pub fn build_highlighted_lines(content: &FileDiffContent) -> (Vec<Line<'static>>
     (lines, hunk_positions)
 }
 
+/// Diff line drawn on each rendered row (None for spacers, hunk headers and
+/// markers), in the order `build_highlighted_lines` lays them out
+fn row_lines(content: &FileDiffContent) -> Vec<Option<&DiffLineContent>> {
+    let mut rows = Vec::new();
+    if content.is_binary {
+        return rows;
+    }
+    for hunk in &content.hunks {
+        rows.extend([None, None]);
+        for group in group_diff_lines(&hunk.lines) {
+            match group {
+                LineGroup::Context(dl) => rows.push(Some(dl)),
+                LineGroup::Change {
+                    deletions,
+                    additions,
+                } => rows.extend(deletions.into_iter().chain(additions).map(Some)),
+                LineGroup::NoNewline => rows.push(None),
+            }
+        }
+    }
+    rows
+}
+
+/// Old-side line number of the first context or removed line at or below
+/// `row`: the line a blame jump starts from. Added lines are skipped since
+/// the diff's own commit introduced them.
+pub fn blame_line_from(content: &FileDiffContent, row: usize) -> Option<u32> {
+    row_lines(content)
+        .into_iter()
+        .skip(row)
+        .flatten()
+        .find(|dl| dl.origin != DiffLineOrigin::Addition)
+        .and_then(|dl| dl.old_lineno)
+}
+
 // --- Widget (renders pre-computed lines) ---
 
 pub struct FileDiffViewWidget<'a> {
@@ -477,6 +527,8 @@ pub struct FileDiffViewWidget<'a> {
     scroll_offset: usize,
     horizontal_offset: usize,
     file_position: String,
+    /// Active whitespace-ignore mode, shown so the counts aren't misread
+    whitespace: Option<&'static str>,
 }
 
 impl<'a> FileDiffViewWidget<'a> {
@@ -487,6 +539,7 @@ impl<'a> FileDiffViewWidget<'a> {
         horizontal_offset: usize,
         file_index: usize,
         file_count: usize,
+        whitespace: Option<&'static str>,
     ) -> Self {
         Self {
             content,
@@ -494,6 +547,7 @@ impl<'a> FileDiffViewWidget<'a> {
             scroll_offset,
             horizontal_offset,
             file_position: format!("[{}/{}]", file_index + 1, file_count),
+            whitespace,
         }
     }
 }
@@ -523,10 +577,13 @@ impl<'a> Widget for FileDiffViewWidget<'a> {
             )
         };
 
-        let title = format!(
+        let mut title = format!(
             " [{}] {}  {}  {} ",
             indicator, path_str, stats, self.file_position
         );
+        if let Some(whitespace) = self.whitespace {
+            title.push_str(&format!("({whitespace}) "));
+        }
 
         let block = Block::default()
             .title(title)
@@ -556,3 +613,53 @@ impl<'a> Widget for FileDiffViewWidget<'a> {
         Widget::render(paragraph, area, buf);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::git::{DiffHunkContent, FileChangeKind};
+
+    fn rust_addition() -> FileDiffContent {
+        FileDiffContent {
+            path: PathBuf::from("src/main.rs"),
+            old_path: PathBuf::from("src/main.rs"),
+            kind: FileChangeKind::Modified,
+            is_binary: false,
+            hunks: vec![DiffHunkContent {
+                header: "@@ -0,0 +1 @@".to_string(),
+                lines: vec![DiffLineContent {
+                    origin: DiffLineOrigin::Addition,
+                    old_lineno: None,
+                    new_lineno: Some(1),
+                    content: "fn main() { let answer = 42; }\n".to_string(),
+                }],
+            }],
+            total_additions: 1,
+            total_deletions: 0,
+        }
+    }
+
+    #[test]
+    fn syntax_highlight_can_be_turned_off() {
+        let content = rust_addition();
+        let colors = |highlight: bool| {
+            let (lines, _) = build_highlighted_lines(&content, true, highlight);
+            let added = lines.last().unwrap();
+            assert!(added.to_string().contains("let answer = 42;"));
+            // Past the line numbers and the +/- marker
+            added
+                .spans
+                .iter()
+                .skip(5)
+                .filter(|span| span.content.contains(|c: char| c.is_alphanumeric()))
+                .filter_map(|span| span.style.fg)
+                .collect::<HashSet<_>>()
+                .len()
+        };
+        assert!(colors(true) > 1);
+        assert_eq!(colors(false), 1);
+    }
+}
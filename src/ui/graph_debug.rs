@@ -0,0 +1,90 @@
+//! Graph layout debug overlay
+//!
+//! Annotates each graph row with the data the renderer works from: row
+//! number, commit lane, which lanes are active, and the connector cells.
+//! A development aid for lane swaps and broken connectors; enabled with
+//! `[graph] debug_overlay` or `Ctrl+g`.
+
+use crate::git::graph::{CellType, GraphNode};
+
+/// Width of the overlay column, including the trailing separator
+pub const DEBUG_COLUMN_WIDTH: usize = 36;
+
+/// `row lane bitmap connections`, e.g. `  12 L1 ██· ╭2 ─3 ╯4`.
+/// Bitmap has one glyph per lane (`█` active, `·` empty); connections are
+/// non-pipe cells with their cell column (two columns per lane).
+pub fn annotate(row: usize, node: &GraphNode) -> String {
+    let bitmap: String = node
+        .cells
+        .iter()
+        .step_by(2)
+        .map(|cell| {
+            if *cell == CellType::Empty {
+                '·'
+            } else {
+                '█'
+            }
+        })
+        .collect();
+
+    let connections: Vec<String> = node
+        .cells
+        .iter()
+        .enumerate()
+        .filter_map(|(col, cell)| connector_glyph(cell).map(|ch| format!("{ch}{col}")))
+        .collect();
+
+    let lane = if node.is_connector() {
+        "L-".to_string()
+    } else {
+        format!("L{}", node.lane)
+    };
+
+    format!("{row:>4} {lane} {bitmap} {}", connections.join(" "))
+}
+
+fn connector_glyph(cell: &CellType) -> Option<char> {
+    match cell {
+        CellType::Empty | CellType::Pipe(_) | CellType::Commit(_) => None,
+        CellType::BranchRight(_) => Some('╭'),
+        CellType::BranchLeft(_) => Some('╮'),
+        CellType::MergeRight(_) => Some('╰'),
+        CellType::MergeLeft(_) => Some('╯'),
+        CellType::Horizontal(_) => Some('─'),
+        CellType::HorizontalPipe(..) => Some('┼'),
+        CellType::TeeRight(_) => Some('├'),
+        CellType::TeeLeft(_) => Some('┤'),
+        CellType::TeeUp(_) => Some('┴'),
+        CellType::Overflow(_) => Some('┆'),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn annotates_lanes_and_connectors() {
+        let node = GraphNode {
+            commit: None,
+            lane: 0,
+            color_index: 0,
+            labels: Vec::new(),
+            tag_names: Vec::new(),
+            is_head: false,
+            is_current_tip: false,
+            is_uncommitted: true,
+            uncommitted_count: Some(1),
+            cells: vec![
+                CellType::Commit(0),
+                CellType::Horizontal(1),
+                CellType::MergeLeft(1),
+                CellType::Empty,
+                CellType::Empty,
+                CellType::Empty,
+                CellType::Pipe(2),
+            ],
+        };
+        assert_eq!(annotate(7, &node), "   7 L0 ██·█ ─1 ╯2");
+    }
+}
@@ -1,23 +1,40 @@
 //! Graph view widget
 
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+use chrono::{Datelike, NaiveDate};
+
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{List, ListItem, ListState, StatefulWidget},
+    widgets::{List, ListItem, ListState, Paragraph, StatefulWidget, Widget},
 };
 use unicode_width::UnicodeWidthChar;
 
 use crate::{
     app::App,
-    git::graph::{CellType, GraphNode},
-    graph::colors::get_color_by_index,
+    config::Column,
+    git::{
+        branch::MergedBranches,
+        graph::{CellType, GraphLayout, GraphNode, RefLabel},
+        operations::split_remote_name,
+        CommitInfo,
+    },
+    graph::{chars::GraphChars, colors::get_color_by_index},
 };
 
-use super::{render_placeholder_block, MIN_WIDGET_HEIGHT, MIN_WIDGET_WIDTH};
+use super::{
+    graph_debug::{annotate, DEBUG_COLUMN_WIDTH},
+    render_placeholder_block, MIN_WIDGET_HEIGHT, MIN_WIDGET_WIDTH,
+};
 
 const ROW_SELECTION_BG: Color = Color::Rgb(40, 44, 62);
+/// Date color of the newest and the oldest loaded commit with age heat on
+const HEAT_NEW: (u8, u8, u8) = (255, 158, 100);
+const HEAT_OLD: (u8, u8, u8) = (65, 72, 104);
 
 fn with_row_selection(style: Style, is_row_selected: bool) -> Style {
     if is_row_selected {
@@ -80,35 +97,155 @@ pub struct GraphViewWidget<'a> {
     focused: bool,
     /// (selected position, total rows) for the pane title
     position: (usize, usize),
+    /// Full author/message of the selected row when the row cuts them short
+    full_fields: Option<String>,
+    /// Focus mode: no border, title or footer
+    bare: bool,
+    headers: DateHeaders,
 }
 
 impl<'a> GraphViewWidget<'a> {
     pub fn new(app: &App, width: u16) -> Self {
-        let max_lane = app.graph_layout.max_lane;
-        // Actual width minus borders
-        let inner_width = width.saturating_sub(2) as usize;
+        // Actual width minus borders (and the debug column, when shown)
+        let debug_width = if app.show_graph_debug() {
+            DEBUG_COLUMN_WIDTH
+        } else {
+            0
+        };
+        let border_width = if app.focus_mode() { 0 } else { 2 };
+        let inner_width = (width.saturating_sub(border_width) as usize).saturating_sub(debug_width);
+        // Without the graph, rows are just the other columns
+        let columns: Vec<Column> = app
+            .config()
+            .display
+            .columns
+            .iter()
+            .copied()
+            .filter(|&column| column != Column::Graph || app.show_graph())
+            .collect();
+        let graph_width = if app.show_graph() {
+            clamp_graph_width(&app.graph_layout, app.config().graph.max_width, inner_width)
+        } else {
+            0
+        };
 
         // Get the currently selected branch name
-        let selected_branch_name = app.selected_branch_name();
+        let labels = LabelState {
+            selected: app.selected_branch_name(),
+            merged: (app.config().branches.merged == MergedBranches::Dim)
+                .then(|| app.merged_branches()),
+            worktrees: app.worktree_branches(),
+            remotes: app.remote_names(),
+        };
 
-        let items: Vec<ListItem> = app
+        let format = RowFormat {
+            graph_width,
+            total_width: inner_width,
+            columns: &columns,
+            widths: FieldWidths {
+                author: app.config().display.author_width,
+                hash: if app.full_hashes() {
+                    FULL_HASH_WIDTH
+                } else {
+                    SHORT_HASH_WIDTH
+                },
+            },
+            age_heat: app.age_heat(),
+        };
+
+        let mut full_fields = None;
+        let mut items: Vec<ListItem> = app
             .graph_layout
             .nodes
             .iter()
             .enumerate()
             .map(|(idx, node)| {
                 let is_selected = app.graph_list_state.selected() == Some(idx);
-                let line = render_graph_line(
+                let markers = RowMarkers {
+                    replaced: node
+                        .commit
+                        .as_ref()
+                        .is_some_and(|c| app.replaced_oids.contains(&c.oid)),
+                    rewritten: app.show_rewritten_marker()
+                        && node.commit.as_ref().is_some_and(|c| c.committer_differs()),
+                    parents: node
+                        .commit
+                        .as_ref()
+                        .map(|c| c.parent_oids.len())
+                        .filter(|&n| app.show_parent_badge() && n > 1)
+                        .unwrap_or(0),
+                    range_mark: node
+                        .commit
+                        .as_ref()
+                        .is_some_and(|c| app.range_mark() == Some(c.oid)),
+                };
+                let (mut line, truncated) = render_graph_line(
                     node,
-                    max_lane,
                     is_selected,
-                    inner_width,
-                    selected_branch_name,
+                    format,
+                    labels,
+                    markers,
+                    app.graph_chars(),
                 );
+                if is_selected {
+                    full_fields = node
+                        .commit
+                        .as_ref()
+                        .and_then(|commit| full_fields_text(commit, truncated));
+                }
+                if debug_width > 0 {
+                    let text = truncate_to_width(&annotate(idx, node), debug_width - 1);
+                    let padding = debug_width - display_width(&text);
+                    line.spans.insert(
+                        0,
+                        Span::styled(
+                            format!("{text}{}", " ".repeat(padding)),
+                            Style::default().fg(Color::DarkGray),
+                        ),
+                    );
+                }
                 ListItem::new(line)
             })
             .collect();
 
+        // Date group separators, inserted back to front so indices hold
+        let headers = app.date_headers().clone();
+        for (&node, label) in headers.starts.iter().zip(&headers.labels).rev() {
+            let text = format!(" ── {label} ──");
+            items.insert(
+                node,
+                ListItem::new(Line::from(Span::styled(
+                    text,
+                    Style::default()
+                        .fg(Color::DarkGray)
+                        .add_modifier(Modifier::BOLD),
+                ))),
+            );
+        }
+
+        // Explicit boundary when the commit limit cut off the history
+        if app.history_truncated() {
+            let is_selected = app.is_load_more_selected();
+            let marker = if is_selected {
+                Span::styled(
+                    "▌",
+                    with_row_selection(Style::default().fg(Color::Cyan), true),
+                )
+            } else {
+                Span::raw(" ")
+            };
+            let text = format!(" ── load more ({}) ── ", app.config().commits.batch_size);
+            let padding = inner_width.saturating_sub(display_width(&text) + 1);
+            items.push(ListItem::new(Line::from(vec![
+                marker,
+                Span::styled(
+                    text,
+                    with_row_selection(Style::default().fg(Color::DarkGray), is_selected),
+                ),
+                row_space(padding, is_selected),
+            ])));
+        }
+
         let focused = matches!(app.mode, crate::app::AppMode::Normal)
             && app.focused_pane == crate::app::FocusedPane::Graph;
 
@@ -121,25 +258,162 @@ impl<'a> GraphViewWidget<'a> {
             items,
             focused,
             position,
+            full_fields,
+            bare: app.focus_mode(),
+            headers,
+        }
+    }
+}
+
+/// Date group header rows (`── Today ──`), each above the first commit of
+/// its group. The list state keeps node indices; these map them to rows.
+/// Built with the graph (`App::date_headers`); empty when headers are off.
+#[derive(Debug, Clone, Default)]
+pub struct DateHeaders {
+    /// Node index each header sits above, ascending
+    starts: Vec<usize>,
+    labels: Vec<String>,
+}
+
+impl DateHeaders {
+    /// Headers for `nodes`, grouped relative to `today`
+    pub fn new(nodes: &[GraphNode], today: NaiveDate) -> Self {
+        let mut headers = Self::default();
+        for (idx, node) in nodes.iter().enumerate() {
+            let Some(commit) = &node.commit else {
+                continue;
+            };
+            // Topological order can step back in time; a group may recur
+            let label = date_group(commit.timestamp.date_naive(), today);
+            if headers.labels.last() != Some(&label) {
+                headers.starts.push(idx);
+                headers.labels.push(label);
+            }
+        }
+        headers
+    }
+
+    /// List row of `node`
+    pub fn row_of(&self, node: usize) -> usize {
+        node + self.starts.partition_point(|&start| start <= node)
+    }
+
+    /// First visible row when the list is scrolled to node `offset`; a
+    /// header above that node stays in view
+    pub fn top_row(&self, offset: usize) -> usize {
+        self.row_of(offset) - usize::from(self.starts.binary_search(&offset).is_ok())
+    }
+
+    /// Node shown on `row`, or the node under the header on `row`
+    fn node_below(&self, row: usize) -> usize {
+        row - self.headers_before(row)
+    }
+
+    /// Node shown on `row`; None for header rows
+    pub fn node_at(&self, row: usize) -> Option<usize> {
+        let before = self.headers_before(row);
+        let is_header = self
+            .starts
+            .get(before)
+            .is_some_and(|&start| start + before == row);
+        (!is_header).then_some(row - before)
+    }
+
+    /// Node drawn `screen_row` rows below the top of a list scrolled to
+    /// node `offset`; None on a header row
+    pub fn node_on_screen(&self, offset: usize, screen_row: usize) -> Option<usize> {
+        self.node_at(self.top_row(offset) + screen_row)
+    }
+
+    /// Number of header rows above `row`
+    fn headers_before(&self, row: usize) -> usize {
+        let mut k = 0;
+        while self.starts.get(k).is_some_and(|&start| start + k < row) {
+            k += 1;
         }
+        k
+    }
+}
+
+/// Relative bucket of a commit date, like the date groups of git GUIs
+fn date_group(date: NaiveDate, today: NaiveDate) -> String {
+    let days = (today - date).num_days();
+    let last_week = today - chrono::Duration::weeks(1);
+    if days <= 0 {
+        "Today".to_string()
+    } else if days == 1 {
+        "Yesterday".to_string()
+    } else if date.iso_week() == today.iso_week() {
+        "This week".to_string()
+    } else if date.iso_week() == last_week.iso_week() {
+        "Last week".to_string()
+    } else if (date.year(), date.month()) == (today.year(), today.month()) {
+        "Earlier this month".to_string()
+    } else {
+        date.format("%B %Y").to_string()
     }
 }
 
+/// `author · message` with whichever fields the selected row truncated
+fn full_fields_text(commit: &CommitInfo, truncated: Truncated) -> Option<String> {
+    let fields: Vec<&str> = [
+        truncated.author.then_some(commit.author_name.as_str()),
+        truncated.message.then_some(commit.message.as_str()),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    (!fields.is_empty()).then(|| fields.join(" · "))
+}
+
+/// Width of the lane art in cells (2 per lane). Deeply branched histories
+/// are clamped to `max_width` columns (or half the pane when 0) so the
+/// commit info stays visible; rows with lanes beyond it show `…`. A linear
+/// history never draws the gap after its only lane, so the message gets it.
+fn clamp_graph_width(layout: &GraphLayout, max_width: usize, inner_width: usize) -> usize {
+    if layout.is_linear() {
+        return 1;
+    }
+    let full = (layout.max_lane + 1) * 2;
+    let limit = if max_width > 0 {
+        max_width
+    } else {
+        inner_width / 2
+    };
+    // Whole lanes only, and always room for at least one lane
+    full.min(limit / 2 * 2).max(2)
+}
+
+/// Branch label state shared by every row
+#[derive(Clone, Copy)]
+struct LabelState<'b> {
+    /// Branch selected with Tab
+    selected: Option<&'b str>,
+    /// Branches merged into the main branch, when they're dimmed
+    merged: Option<&'b HashSet<String>>,
+    /// Branches checked out in other worktrees
+    worktrees: &'b HashMap<String, PathBuf>,
+    /// Configured remotes, to split `<remote>/<branch>` labels
+    remotes: &'b [String],
+}
+
 /// Optimize branch name display
-/// - If a local branch matches its origin/xxx, show "xxx <-> origin"
+/// - If a local branch matches a remote's `<remote>/xxx`, show "xxx ↔ <remote>"
 /// - Otherwise, show each name separately
 /// - Render in bold with the graph color, wrapped in brackets
 /// - Selected branch is shown with inverted colors
+/// - Remote-only labels are red; branches merged into the main branch are
+///   grayed out
+/// - Branches checked out in another worktree are marked with "wt"
 fn optimize_branch_display(
-    branch_names: &[String],
+    refs: &[RefLabel],
     is_head: bool,
     color_index: usize,
-    selected_branch_name: Option<&str>,
+    labels: LabelState,
     is_row_selected: bool,
 ) -> Vec<(String, Style)> {
-    use std::collections::HashSet;
-
-    if branch_names.is_empty() {
+    let selected_branch_name = labels.selected;
+    if refs.is_empty() {
         return Vec::new();
     }
 
@@ -147,15 +421,18 @@ fn optimize_branch_display(
     const MAX_LABEL_WIDTH: usize = 40;
 
     // Split local and remote branches (HashSet for O(1) lookup)
-    let local_branches: HashSet<&str> = branch_names
+    let local_branches: HashSet<&str> = refs
         .iter()
-        .filter(|n| !n.starts_with("origin/"))
-        .map(|s| s.as_str())
+        .filter(|r| !r.is_remote)
+        .map(|r| r.name.as_str())
         .collect();
-    let remote_branches: HashSet<&str> = branch_names
+    // Remote of each remote label, by branch name; the first listed wins
+    let remote_branches: HashMap<&str, &str> = refs
         .iter()
-        .filter(|n| n.starts_with("origin/"))
-        .map(|s| s.as_str())
+        .rev()
+        .filter(|r| r.is_remote)
+        .filter_map(|r| split_remote_name(labels.remotes, &r.name))
+        .map(|(remote, branch)| (branch, remote))
         .collect();
 
     // Determine base color: main branch stays blue; other HEADs are green
@@ -167,10 +444,21 @@ fn optimize_branch_display(
     };
 
     // Helper to create style based on selection state
-    let make_style = |branch_name: &str| -> Style {
-        let style = Style::default().fg(base_color).add_modifier(Modifier::BOLD);
+    let make_style = |label: &RefLabel| -> Style {
+        let branch_name = label.name.as_str();
+        let color = if labels
+            .merged
+            .is_some_and(|merged| merged.contains(branch_name))
+        {
+            Color::DarkGray
+        } else if label.is_remote {
+            Color::Red
+        } else {
+            base_color
+        };
+        let style = Style::default().fg(color).add_modifier(Modifier::BOLD);
         if selected_branch_name == Some(branch_name) {
-            style.fg(Color::Black).bg(base_color)
+            style.fg(Color::Black).bg(color)
         } else if is_row_selected {
             with_row_selection(style, true)
         } else {
@@ -200,33 +488,38 @@ fn optimize_branch_display(
 
     // Process branches in original order (matches tab order from filter_remote_duplicates)
     let mut result: Vec<(String, Style)> = Vec::new();
-    for name in branch_names {
-        if let Some(local_name) = name.strip_prefix("origin/") {
+    for label in refs {
+        let name = &label.name;
+        if label.is_remote {
             // Remote branch: skip if matching local exists
-            if local_branches.contains(local_name) {
+            if split_remote_name(labels.remotes, name)
+                .is_some_and(|(_, local_name)| local_branches.contains(local_name))
+            {
                 continue;
             }
-            result.push((make_label(name, None), make_style(name)));
+            result.push((make_label(name, None), make_style(label)));
         } else {
             // Local branch: check for matching remote
-            let remote_name = format!("origin/{}", name);
-            let suffix = if remote_branches.contains(remote_name.as_str()) {
-                Some("↔ origin")
-            } else {
-                None
+            let suffix = match (
+                remote_branches.get(name.as_str()),
+                labels.worktrees.contains_key(name),
+            ) {
+                (Some(remote), true) => Some(format!("↔ {remote} wt")),
+                (Some(remote), false) => Some(format!("↔ {remote}")),
+                (None, true) => Some("wt".to_string()),
+                (None, false) => None,
             };
-            result.push((make_label(name, suffix), make_style(name)));
+            result.push((make_label(name, suffix.as_deref()), make_style(label)));
         }
     }
 
     // Collapse multiple branches to single + count
     if result.len() > 1 {
-        // Find selected index directly from branch_names, clamped to result bounds
+        // Find selected index directly from the labels, clamped to result bounds
         let selected_idx = selected_branch_name
             .and_then(|sel| {
-                branch_names
-                    .iter()
-                    .position(|n| n == sel || n.ends_with(&format!("/{}", sel)))
+                refs.iter()
+                    .position(|r| r.name == sel || r.name.ends_with(&format!("/{}", sel)))
             })
             .unwrap_or(0)
             .min(result.len().saturating_sub(1));
@@ -247,7 +540,7 @@ fn optimize_branch_display(
 
 /// Truncate a string to the specified display width.
 /// Handles VS16 which changes preceding character to emoji presentation (width 2).
-fn truncate_to_width(s: &str, max_width: usize) -> String {
+pub(crate) fn truncate_to_width(s: &str, max_width: usize) -> String {
     let chars: Vec<char> = s.chars().collect();
     let mut result = String::new();
     let mut current_width = 0;
@@ -271,30 +564,6 @@ fn truncate_to_width(s: &str, max_width: usize) -> String {
     result
 }
 
-/// Determine which right-side elements (date, author, hash) to display based on available width.
-/// Returns (show_date, show_author, show_hash, total_right_width).
-/// Priority: author > date > hash (hash disappears first, then date, then author)
-fn compute_right_side_visibility(remaining_for_content: usize) -> (bool, bool, bool, usize) {
-    // Widths for each display level (right-aligned block)
-    const WIDTH_DATE_AUTHOR_HASH: usize = 31; // " YYYY-MM-DD  author    hash   "
-    const WIDTH_DATE_AUTHOR: usize = 22; // " YYYY-MM-DD  author   "
-    const WIDTH_AUTHOR_ONLY: usize = 11; // "  author   "
-
-    // Ensure minimum space for branch + commit message before showing right-side info
-    const CONTENT_MIN_WIDTH: usize = 50;
-    let available = remaining_for_content.saturating_sub(CONTENT_MIN_WIDTH);
-
-    if available >= WIDTH_DATE_AUTHOR_HASH {
-        (true, true, true, WIDTH_DATE_AUTHOR_HASH)
-    } else if available >= WIDTH_DATE_AUTHOR {
-        (true, true, false, WIDTH_DATE_AUTHOR)
-    } else if available >= WIDTH_AUTHOR_ONLY {
-        (false, true, false, WIDTH_AUTHOR_ONLY)
-    } else {
-        (false, false, false, 0)
-    }
-}
-
 /// Abbreviate branch name to max_width, showing "+N" if more branches exist
 /// Uses format: prefix/head...tail (preserving last 5 chars)
 fn abbreviate_branch_label(name: &str, max_width: usize, extra_count: usize) -> String {
@@ -350,13 +619,186 @@ fn abbreviate_branch_label(name: &str, max_width: usize, extra_count: usize) ->
     format!("[{}{}{}{}]{}", prefix, head, ELLIPSIS, tail, suffix)
 }
 
+/// Per-commit markers drawn between the branch labels and the message
+#[derive(Debug, Clone, Copy, Default)]
+struct RowMarkers {
+    /// Ancestry rewritten by replace refs / grafts (`⇄`)
+    replaced: bool,
+    /// Committer differs from the author (`~`)
+    rewritten: bool,
+    /// Parent count badge for merges (`×2`; 0 when not shown)
+    parents: usize,
+    /// One end of a range marked with `x` (drawn as a magenta accent bar)
+    range_mark: bool,
+}
+
+/// Commit fields cut short on a rendered row
+#[derive(Debug, Clone, Copy, Default)]
+struct Truncated {
+    /// Author clipped to its column, or the column hidden
+    author: bool,
+    message: bool,
+}
+
+impl Column {
+    /// Width of the fixed-width fields (0 for the graph and subject)
+    fn width(self, widths: FieldWidths) -> usize {
+        match self {
+            Column::Hash => widths.hash,
+            Column::Author => widths.author,
+            Column::Date => 10,
+            Column::Graph | Column::Subject => 0,
+        }
+    }
+
+    fn is_fixed(self) -> bool {
+        !matches!(self, Column::Graph | Column::Subject)
+    }
+
+    /// Narrow rows drop fixed fields lowest priority first:
+    /// hash disappears first, then date, then author
+    fn priority(self) -> u8 {
+        match self {
+            Column::Author => 2,
+            Column::Date => 1,
+            _ => 0,
+        }
+    }
+}
+
+/// Ensure minimum space for branch + commit message before showing the fixed
+/// fields
+const CONTENT_MIN_WIDTH: usize = 50;
+
+const SHORT_HASH_WIDTH: usize = 7;
+const FULL_HASH_WIDTH: usize = 40;
+
+/// Widths of the fixed fields that depend on settings
+#[derive(Debug, Clone, Copy)]
+struct FieldWidths {
+    /// Display columns of the author field
+    author: usize,
+    /// `SHORT_HASH_WIDTH`, or `FULL_HASH_WIDTH` with full hashes on
+    hash: usize,
+}
+
+/// Row width taken by everything but the subject: the start marker, the
+/// columns themselves and the gaps between them (two spaces between fixed
+/// fields, one next to the graph or subject, one after a trailing fixed field)
+fn fixed_row_width(columns: &[Column], graph_width: usize, widths: FieldWidths) -> usize {
+    let mut width = 1;
+    for (i, column) in columns.iter().enumerate() {
+        width += match column {
+            Column::Graph => graph_width,
+            Column::Subject => 0,
+            fixed => fixed.width(widths),
+        };
+        if let Some(next) = columns.get(i + 1) {
+            width += if column.is_fixed() && next.is_fixed() {
+                2
+            } else {
+                1
+            };
+        } else if column.is_fixed() {
+            width += 1;
+        }
+    }
+    width
+}
+
+/// Configured columns that fit `total_width`, dropping fixed fields by
+/// priority. The subject keeps at least `CONTENT_MIN_WIDTH` when present.
+fn visible_columns(
+    columns: &[Column],
+    graph_width: usize,
+    widths: FieldWidths,
+    total_width: usize,
+) -> Vec<Column> {
+    let reserved = if columns.contains(&Column::Subject) {
+        CONTENT_MIN_WIDTH
+    } else {
+        0
+    };
+    let mut visible = columns.to_vec();
+    while fixed_row_width(&visible, graph_width, widths) + reserved > total_width {
+        let lowest = visible
+            .iter()
+            .enumerate()
+            .filter(|(_, column)| column.is_fixed())
+            .min_by_key(|(i, column)| (column.priority(), std::cmp::Reverse(*i)))
+            .map(|(i, _)| i);
+        match lowest {
+            Some(i) => {
+                visible.remove(i);
+            }
+            None => break,
+        }
+    }
+    visible
+}
+
+/// Sizes and column order shared by every row of the pane
+#[derive(Debug, Clone, Copy)]
+struct RowFormat<'c> {
+    graph_width: usize,
+    total_width: usize,
+    columns: &'c [Column],
+    widths: FieldWidths,
+    /// Commit time span for age heat colors (None when off)
+    age_heat: Option<AgeRange>,
+}
+
+/// Commit times (Unix seconds) of the newest and oldest loaded commits
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AgeRange {
+    newest: i64,
+    oldest: i64,
+}
+
+impl AgeRange {
+    /// None without commits (an empty graph, or only the uncommitted row)
+    pub fn new(nodes: &[GraphNode]) -> Option<Self> {
+        let mut times = nodes
+            .iter()
+            .filter(|node| !node.is_uncommitted)
+            .filter_map(|node| node.commit.as_ref())
+            .map(|commit| commit.timestamp.timestamp());
+        let first = times.next()?;
+        let (newest, oldest) = times.fold((first, first), |(newest, oldest), time| {
+            (newest.max(time), oldest.min(time))
+        });
+        Some(Self { newest, oldest })
+    }
+
+    /// Linear blend from `HEAT_NEW` at the newest commit to `HEAT_OLD` at
+    /// the oldest
+    fn color(self, time: i64) -> Color {
+        let span = (self.newest - self.oldest).max(1) as f64;
+        let age = ((self.newest - time) as f64 / span).clamp(0.0, 1.0);
+        let blend = |new: u8, old: u8| (new as f64 + (old as f64 - new as f64) * age).round() as u8;
+        Color::Rgb(
+            blend(HEAT_NEW.0, HEAT_OLD.0),
+            blend(HEAT_NEW.1, HEAT_OLD.1),
+            blend(HEAT_NEW.2, HEAT_OLD.2),
+        )
+    }
+}
+
 fn render_graph_line<'a>(
     node: &GraphNode,
-    max_lane: usize,
     is_selected: bool,
-    total_width: usize,
-    selected_branch_name: Option<&str>,
-) -> Line<'a> {
+    format: RowFormat,
+    labels: LabelState,
+    markers: RowMarkers,
+    chars: &GraphChars,
+) -> (Line<'a>, Truncated) {
+    let RowFormat {
+        graph_width,
+        total_width,
+        columns,
+        widths,
+        age_heat,
+    } = format;
     let mut spans: Vec<Span> = Vec::new();
 
     // Graph start marker; accent bar makes the selected row easy to spot
@@ -365,82 +807,47 @@ fn render_graph_line<'a>(
             "▌",
             with_row_selection(Style::default().fg(Color::Cyan), true),
         ));
+    } else if markers.range_mark {
+        spans.push(Span::styled("▌", Style::default().fg(Color::Magenta)));
     } else {
         spans.push(Span::raw(" "));
     }
     let mut left_width: usize = 1;
 
-    // Render cells
-    for cell in &node.cells {
-        let (ch, color) = match cell {
-            CellType::Empty => (' ', Color::Reset),
-            CellType::Pipe(color_idx) => ('│', get_color_by_index(*color_idx)),
-            CellType::Commit(color_idx) => {
-                // HEAD uses a double circle, others use a filled circle
-                let ch = if node.is_head { '◉' } else { '●' };
-                // Main branch (blue) stays blue; other HEADs are green
-                let is_main = *color_idx == crate::graph::colors::MAIN_BRANCH_COLOR;
-                let color = if node.is_head && !is_main {
-                    Color::Green
-                } else {
-                    get_color_by_index(*color_idx)
-                };
-                (ch, color)
-            }
-            CellType::BranchRight(color_idx) => ('╭', get_color_by_index(*color_idx)),
-            CellType::BranchLeft(color_idx) => ('╮', get_color_by_index(*color_idx)),
-            CellType::MergeRight(color_idx) => ('╰', get_color_by_index(*color_idx)),
-            CellType::MergeLeft(color_idx) => ('╯', get_color_by_index(*color_idx)),
-            CellType::Horizontal(color_idx) => ('─', get_color_by_index(*color_idx)),
-            CellType::HorizontalPipe(_h_color_idx, p_color_idx) => {
-                // Vertical and horizontal lines cross (use pipe color)
-                ('┼', get_color_by_index(*p_color_idx))
-            }
-            CellType::TeeRight(color_idx) => ('├', get_color_by_index(*color_idx)),
-            CellType::TeeLeft(color_idx) => ('┤', get_color_by_index(*color_idx)),
-            CellType::TeeUp(color_idx) => ('┴', get_color_by_index(*color_idx)),
-        };
-
-        // Draw all line glyphs in bold
-        let style = with_row_selection(
-            Style::default().fg(color).add_modifier(Modifier::BOLD),
-            is_selected,
-        );
-
-        let ch_str = ch.to_string();
-        let ch_width = display_width(&ch_str);
-        spans.push(Span::styled(ch_str, style));
-        left_width += ch_width;
-    }
-
-    // Padding to align graph width (display width based)
-    let graph_display_width = (max_lane + 1) * 2;
-    if left_width < graph_display_width + 1 {
-        // +1 accounts for the start marker
-        let padding = graph_display_width + 1 - left_width;
-        spans.push(row_space(padding, is_selected));
-        left_width += padding;
-    }
-
-    // Separator between graph and commit info
-    spans.push(row_space(1, is_selected));
-    left_width += 1;
-
-    // Handle uncommitted changes row
-    if node.is_uncommitted {
-        let text = match node.uncommitted_count {
-            Some(count) => format!("uncommitted changes ({})", count),
-            None => "uncommitted changes".to_string(),
-        };
-        let style = with_row_selection(Style::default().fg(Color::Rgb(192, 202, 245)), is_selected);
-        spans.push(Span::styled(text, style));
-        return Line::from(spans);
-    }
+    let show_graph = columns.contains(&Column::Graph);
+    let graph_spans = if show_graph {
+        graph_cells(node, graph_width, is_selected, chars)
+    } else {
+        Vec::new()
+    };
 
-    // Early return for connector-only rows
+    // Rows without a commit keep the lane art (when shown) at the start
     let commit = match &node.commit {
-        Some(c) => c,
-        None => return Line::from(spans),
+        Some(commit) if !node.is_uncommitted => commit,
+        _ => {
+            if show_graph {
+                spans.extend(graph_spans);
+                spans.push(row_space(1, is_selected));
+                left_width += graph_width + 1;
+            }
+            if node.is_uncommitted {
+                let text = match node.uncommitted_count {
+                    Some(count) => format!("uncommitted changes ({})", count),
+                    None => "uncommitted changes".to_string(),
+                };
+                let style =
+                    with_row_selection(Style::default().fg(Color::Rgb(192, 202, 245)), is_selected);
+                spans.push(Span::styled(text, style));
+            } else if is_selected {
+                // Connector-only rows draw just the lane art; the selection
+                // highlight still spans the whole row like on commit rows
+                spans.push(row_space(
+                    total_width.saturating_sub(left_width),
+                    is_selected,
+                ));
+            }
+            return (Line::from(spans), Truncated::default());
+        }
     };
 
     // Style definitions
@@ -460,14 +867,12 @@ fn render_graph_line<'a>(
         }),
         is_selected,
     );
-    let date_style = with_row_selection(
-        Style::default().fg(if is_selected {
-            Color::Rgb(154, 162, 180)
-        } else {
-            Color::DarkGray
-        }),
-        is_selected,
-    );
+    let date_color = match age_heat {
+        Some(range) => range.color(commit.timestamp.timestamp()),
+        None if is_selected => Color::Rgb(154, 162, 180),
+        None => Color::DarkGray,
+    };
+    let date_style = with_row_selection(Style::default().fg(date_color), is_selected);
     let msg_style = if is_selected {
         with_row_selection(
             Style::default()
@@ -479,23 +884,210 @@ fn render_graph_line<'a>(
         Style::default()
     };
 
-    // === Left-aligned: branch names + message ===
+    // Fixed-width fields, padded to their column by display width so
+    // double-width names line up
+    let date = commit.timestamp.format("%Y-%m-%d").to_string(); // 10 chars
+    let author = truncate_to_width(&commit.author_name, widths.author);
+    let hash = if widths.hash > SHORT_HASH_WIDTH {
+        commit.oid.to_string()
+    } else {
+        commit.short_id.clone()
+    };
+    let hash = truncate_to_width(&hash, widths.hash);
+    let pad = |text: &str, column: Column| {
+        let padding = column.width(widths).saturating_sub(display_width(text));
+        format!("{text}{}", " ".repeat(padding))
+    };
+
+    let visible = visible_columns(columns, graph_width, widths, total_width);
+    let subject_width = total_width.saturating_sub(fixed_row_width(&visible, graph_width, widths));
+    let mut message_width = display_width(&commit.message);
+    let mut graph_spans = Some(graph_spans);
+
+    for (i, column) in visible.iter().enumerate() {
+        match column {
+            Column::Graph => spans.extend(graph_spans.take().unwrap_or_default()),
+            Column::Hash => spans.push(Span::styled(pad(&hash, Column::Hash), hash_style)),
+            Column::Author => spans.push(Span::styled(pad(&author, Column::Author), author_style)),
+            Column::Date => spans.push(Span::styled(date.clone(), date_style)),
+            Column::Subject => {
+                let (subject, width) = render_subject(
+                    node,
+                    commit,
+                    subject_width,
+                    labels,
+                    markers,
+                    is_selected,
+                    msg_style,
+                );
+                spans.extend(subject);
+                message_width = width;
+            }
+        }
+        let gap = match visible.get(i + 1) {
+            Some(next) if column.is_fixed() && next.is_fixed() => 2,
+            Some(_) => 1,
+            None if column.is_fixed() => 1,
+            None => 0,
+        };
+        spans.push(row_space(gap, is_selected));
+    }
+    // Without a subject nothing stretches, so fill the rest of the row
+    if !visible.contains(&Column::Subject) && is_selected {
+        spans.push(row_space(subject_width, is_selected));
+    }
+
+    let truncated = Truncated {
+        author: !visible.contains(&Column::Author) || author != commit.author_name,
+        message: !visible.contains(&Column::Subject)
+            || message_width < display_width(&commit.message),
+    };
+    (Line::from(spans), truncated)
+}
+
+/// Lane art for a row, padded to `graph_width`
+fn graph_cells<'a>(
+    node: &GraphNode,
+    graph_width: usize,
+    is_selected: bool,
+    chars: &GraphChars,
+) -> Vec<Span<'a>> {
+    let mut spans = Vec::new();
+    let mut width = 0;
+
+    // Lanes beyond the clamped width collapse into an overflow marker
+    let overflows = node
+        .cells
+        .iter()
+        .skip(graph_width)
+        .any(|cell| *cell != CellType::Empty);
+    let visible_cells = if overflows {
+        graph_width.saturating_sub(2)
+    } else {
+        graph_width
+    };
+
+    // Render cells
+    for cell in node.cells.iter().take(visible_cells) {
+        let (ch, style) = cell_glyph(*cell, node, is_selected, chars);
+        let ch_str = ch.to_string();
+        let ch_width = display_width(&ch_str);
+        spans.push(Span::styled(ch_str, style));
+        width += ch_width;
+    }
+
+    if overflows {
+        spans.push(Span::styled(
+            "…",
+            with_row_selection(Style::default().fg(Color::DarkGray), is_selected),
+        ));
+        width += 1;
+    }
+
+    // Padding to align graph width (display width based)
+    if width < graph_width {
+        spans.push(row_space(graph_width - width, is_selected));
+    }
+    spans
+}
+
+/// Glyph and style of one lane cell, drawn top to bottom
+fn cell_glyph(
+    cell: CellType,
+    node: &GraphNode,
+    is_selected: bool,
+    chars: &GraphChars,
+) -> (char, Style) {
+    let (ch, color) = match cell {
+        CellType::Empty => (' ', Color::Reset),
+        CellType::Pipe(color_idx) => ('│', get_color_by_index(color_idx)),
+        CellType::Commit(color_idx) => {
+            let is_merge = node
+                .commit
+                .as_ref()
+                .is_some_and(|c| c.parent_oids.len() > 1);
+            let ch = chars.commit_glyph(node.is_head, node.is_current_tip, is_merge, is_selected);
+            // Main branch (blue) stays blue; other HEADs are green
+            let is_main = color_idx == crate::graph::colors::MAIN_BRANCH_COLOR;
+            let color = if node.is_head && !is_main {
+                Color::Green
+            } else {
+                get_color_by_index(color_idx)
+            };
+            (ch, color)
+        }
+        CellType::BranchRight(color_idx) => ('╭', get_color_by_index(color_idx)),
+        CellType::BranchLeft(color_idx) => ('╮', get_color_by_index(color_idx)),
+        CellType::MergeRight(color_idx) => ('╰', get_color_by_index(color_idx)),
+        CellType::MergeLeft(color_idx) => ('╯', get_color_by_index(color_idx)),
+        CellType::Horizontal(color_idx) => ('─', get_color_by_index(color_idx)),
+        CellType::HorizontalPipe(_h_color_idx, p_color_idx) => {
+            // Vertical and horizontal lines cross (use pipe color)
+            ('┼', get_color_by_index(p_color_idx))
+        }
+        CellType::TeeRight(color_idx) => ('├', get_color_by_index(color_idx)),
+        CellType::TeeLeft(color_idx) => ('┤', get_color_by_index(color_idx)),
+        CellType::TeeUp(color_idx) => ('┴', get_color_by_index(color_idx)),
+        CellType::Overflow(color_idx) => ('┆', get_color_by_index(color_idx)),
+    };
+
+    // Draw all line glyphs in bold
+    let style = with_row_selection(
+        Style::default().fg(color).add_modifier(Modifier::BOLD),
+        is_selected,
+    );
+    (ch, style)
+}
+
+/// The same cell with the graph turned on its side, newest commit on the
+/// right: rows become columns, so the line art is mirrored across the
+/// diagonal and then left to right
+fn sideways_glyph(cell: CellType, ch: char) -> char {
+    match cell {
+        CellType::Empty | CellType::Commit(_) => ch,
+        CellType::Pipe(_) => '─',
+        CellType::Horizontal(_) => '│',
+        CellType::HorizontalPipe(..) => '┼',
+        CellType::BranchRight(_) => '╮',
+        CellType::BranchLeft(_) => '╯',
+        CellType::MergeRight(_) => '╭',
+        CellType::MergeLeft(_) => '╰',
+        CellType::TeeRight(_) => '┬',
+        CellType::TeeLeft(_) => '┴',
+        CellType::TeeUp(_) => '├',
+        CellType::Overflow(_) => '┄',
+    }
+}
+
+/// Branch labels, row markers and the message, padded to `width`.
+/// Returns the spans and the displayed message width.
+fn render_subject<'a>(
+    node: &GraphNode,
+    commit: &CommitInfo,
+    width: usize,
+    labels: LabelState,
+    markers: RowMarkers,
+    is_selected: bool,
+    msg_style: Style,
+) -> (Vec<Span<'a>>, usize) {
+    let mut spans = Vec::new();
 
     // Optimize branch names (compact when local matches origin/local)
-    let branch_display = optimize_branch_display(
-        &node.branch_names,
+    let mut branch_display = optimize_branch_display(
+        &node.labels,
         node.is_head,
         node.color_index,
-        selected_branch_name,
+        labels,
         is_selected,
     );
-
-    // === Right-aligned: date author hash (fixed width) ===
-    let date = commit.timestamp.format("%Y-%m-%d").to_string(); // 10 chars
-    let author = truncate_to_width(&commit.author_name, 8);
-    let author_formatted = format!("{:<8}", author); // fixed 8 chars
-    let hash = truncate_to_width(&commit.short_id, 7);
-    let hash_formatted = format!("{:<7}", hash); // fixed 7 chars
+    // Tags get a background so they don't read as another branch
+    let tag_style = Style::default()
+        .fg(Color::Black)
+        .bg(Color::Magenta)
+        .add_modifier(Modifier::BOLD);
+    for tag in &node.tag_names {
+        branch_display.push((format!(" {tag} "), tag_style));
+    }
 
     // Calculate branch width first (before rendering)
     let branch_width: usize = branch_display
@@ -505,63 +1097,68 @@ fn render_graph_line<'a>(
         .sum::<usize>()
         + if !branch_display.is_empty() { 1 } else { 0 };
 
-    // Calculate remaining space for branch + message + right info
-    let graph_width = left_width;
-    let remaining_for_content = total_width.saturating_sub(graph_width);
-
-    // Determine which right-side elements to show based on available space
-    let (show_date, show_author, show_hash, right_width) =
-        compute_right_side_visibility(remaining_for_content);
-
     // Render branch labels
     for (i, (label, style)) in branch_display.iter().enumerate() {
         if i > 0 {
             spans.push(row_space(1, is_selected));
-            left_width += 1;
         }
-        left_width += display_width(label);
         spans.push(Span::styled(label.clone(), *style));
     }
     if !branch_display.is_empty() {
         spans.push(row_space(1, is_selected));
-        left_width += 1;
     }
 
-    // Compute max message width (remaining space after branch and right side)
-    let available_for_message = remaining_for_content
-        .saturating_sub(branch_width)
-        .saturating_sub(right_width);
-    let message = truncate_to_width(&commit.message, available_for_message);
-    let message_width = display_width(&message);
-    spans.push(Span::styled(message, msg_style));
-    left_width += message_width;
-
-    // Padding so the right-aligned block starts at a fixed column
-    let padding = total_width
-        .saturating_sub(left_width)
-        .saturating_sub(right_width);
-    if padding > 0 {
-        spans.push(row_space(padding, is_selected));
-    }
-
-    // === Append right-aligned block (display: date, author, hash) ===
-    if show_date {
-        spans.push(row_space(1, is_selected));
-        spans.push(Span::styled(date, date_style));
-    }
-    if show_author {
-        spans.push(row_space(2, is_selected));
-        spans.push(Span::styled(author_formatted, author_style));
+    // Marker for commits whose ancestry is rewritten (replace refs / grafts)
+    let mut marker_width = 0;
+    if markers.replaced {
+        spans.push(Span::styled(
+            "⇄ ",
+            with_row_selection(Style::default().fg(Color::Magenta), is_selected),
+        ));
+        marker_width += 2;
     }
-    if show_hash {
-        spans.push(row_space(2, is_selected));
-        spans.push(Span::styled(hash_formatted, hash_style));
+    // Marker for commits applied by someone other than the author
+    if markers.rewritten {
+        spans.push(Span::styled(
+            "~ ",
+            with_row_selection(Style::default().fg(Color::Yellow), is_selected),
+        ));
+        marker_width += 2;
     }
-    if show_date || show_author || show_hash {
-        spans.push(row_space(1, is_selected));
+    // Parent count for merges; octopus merges stand out
+    if markers.parents > 0 {
+        let badge = format!("×{} ", markers.parents);
+        let style = if markers.parents > 2 {
+            Style::default()
+                .fg(Color::Magenta)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::DarkGray)
+        };
+        marker_width += display_width(&badge);
+        spans.push(Span::styled(badge, with_row_selection(style, is_selected)));
     }
 
-    Line::from(spans)
+    // Message gets the space left after the labels and markers
+    let available_for_message = width
+        .saturating_sub(branch_width)
+        .saturating_sub(marker_width);
+    // A cut message ends in `…`; the footer shows it in full
+    let message = if display_width(&commit.message) <= available_for_message {
+        commit.message.clone()
+    } else if available_for_message == 0 {
+        String::new()
+    } else {
+        let head = truncate_to_width(&commit.message, available_for_message - 1);
+        format!("{head}…")
+    };
+    let message_width = display_width(&message);
+    spans.push(Span::styled(message, msg_style));
+
+    // Padding so the fields after the subject start at a fixed column
+    let used = branch_width + marker_width + message_width;
+    spans.push(row_space(width.saturating_sub(used), is_selected));
+    (spans, message_width)
 }
 
 impl<'a> StatefulWidget for GraphViewWidget<'a> {
@@ -573,11 +1170,494 @@ impl<'a> StatefulWidget for GraphViewWidget<'a> {
             return;
         }
 
+        // Render in row space, then carry the scroll back to node space
+        let mut rows = ListState::default()
+            .with_offset(self.headers.top_row(state.offset()))
+            .with_selected(state.selected().map(|node| self.headers.row_of(node)));
+        if self.bare {
+            StatefulWidget::render(List::new(self.items), area, buf, &mut rows);
+            *state.offset_mut() = self.headers.node_below(rows.offset());
+            return;
+        }
+
         let title = format!("Commits {}/{}", self.position.0, self.position.1);
-        let block = super::pane_block(&title, self.focused);
+        let mut block = super::pane_block(&title, self.focused);
+        // Unobtrusive footer with what the selected row had to cut
+        if let Some(text) = self.full_fields {
+            let text = truncate_to_width(&text, (area.width as usize).saturating_sub(6));
+            block = block.title_bottom(Span::styled(
+                format!(" {text} "),
+                Style::default().fg(Color::DarkGray),
+            ));
+        }
 
         let list = List::new(self.items).block(block);
 
-        StatefulWidget::render(list, area, buf, state);
+        StatefulWidget::render(list, area, buf, &mut rows);
+        *state.offset_mut() = self.headers.node_below(rows.offset());
+    }
+}
+
+/// First column shown by the horizontal graph: the selected commit stays
+/// centered once the history is wider than the pane
+pub fn horizontal_scroll(total: usize, selected: Option<usize>, width: usize) -> usize {
+    let Some(selected) = selected else {
+        return 0;
+    };
+    let column = total.saturating_sub(selected + 1);
+    column
+        .saturating_sub(width / 2)
+        .min(total.saturating_sub(width))
+}
+
+/// Node drawn in `column` of a horizontal graph `width` columns wide: one
+/// column per node, oldest on the left. None past the last node
+pub fn horizontal_node_at(
+    total: usize,
+    selected: Option<usize>,
+    width: usize,
+    column: usize,
+) -> Option<usize> {
+    if column >= width {
+        return None;
+    }
+    let start = horizontal_scroll(total, selected, width);
+    total.checked_sub(start + column + 1)
+}
+
+/// The graph turned on its side (`GraphOrientation::Horizontal`): one
+/// column per row of the layout, oldest on the left, and lanes as rows.
+/// The selected commit is marked below the lanes and summarized in the footer.
+pub struct HorizontalGraphWidget<'a> {
+    lines: Vec<Line<'a>>,
+    focused: bool,
+    /// (selected position, total rows) for the pane title
+    position: (usize, usize),
+    /// Hash, refs and subject of the selected commit
+    summary: Option<String>,
+    /// Focus mode: no border, title or footer
+    bare: bool,
+}
+
+impl<'a> HorizontalGraphWidget<'a> {
+    pub fn new(app: &App, area: Rect) -> Self {
+        let bare = app.focus_mode();
+        let border = if bare { 0 } else { 2 };
+        let inner_width = area.width.saturating_sub(border) as usize;
+        let inner_height = area.height.saturating_sub(border) as usize;
+
+        let nodes = &app.graph_layout.nodes;
+        let selected = app.graph_list_state.selected();
+        let shown: Vec<usize> = (0..inner_width)
+            .map_while(|column| horizontal_node_at(nodes.len(), selected, inner_width, column))
+            .collect();
+
+        // One line per lane, leaving the last row for the selection marker
+        let lanes = shown
+            .iter()
+            .map(|&idx| nodes[idx].cells.len())
+            .max()
+            .unwrap_or(0)
+            .min(inner_height.saturating_sub(1));
+        let mut lines: Vec<Line> = (0..lanes)
+            .map(|lane| {
+                let spans: Vec<Span> = shown
+                    .iter()
+                    .map(|&idx| {
+                        let node = &nodes[idx];
+                        let cell = node.cells.get(lane).copied().unwrap_or(CellType::Empty);
+                        let is_selected = selected == Some(idx);
+                        let (ch, style) = cell_glyph(cell, node, is_selected, app.graph_chars());
+                        Span::styled(sideways_glyph(cell, ch).to_string(), style)
+                    })
+                    .collect();
+                Line::from(spans)
+            })
+            .collect();
+        if let Some(column) = shown.iter().position(|&idx| selected == Some(idx)) {
+            lines.push(Line::from(vec![
+                Span::raw(" ".repeat(column)),
+                Span::styled("▲", Style::default().fg(Color::Cyan)),
+            ]));
+        }
+
+        let summary = selected.and_then(|idx| nodes.get(idx)).and_then(|node| {
+            let commit = node.commit.as_ref()?;
+            let refs = node
+                .labels
+                .iter()
+                .map(|label| format!("[{}] ", label.name))
+                .chain(node.tag_names.iter().map(|tag| format!("<{tag}> ")))
+                .collect::<String>();
+            Some(format!("{} {refs}{}", commit.short_id, commit.message))
+        });
+
+        let focused = matches!(app.mode, crate::app::AppMode::Normal)
+            && app.focused_pane == crate::app::FocusedPane::Graph;
+
+        Self {
+            lines,
+            focused,
+            position: (selected.map_or(0, |idx| idx + 1), nodes.len()),
+            summary,
+            bare,
+        }
+    }
+}
+
+impl Widget for HorizontalGraphWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.width < MIN_WIDGET_WIDTH || area.height < MIN_WIDGET_HEIGHT {
+            render_placeholder_block(area, buf);
+            return;
+        }
+        if self.bare {
+            Paragraph::new(self.lines).render(area, buf);
+            return;
+        }
+
+        let title = format!("Commits {}/{}", self.position.0, self.position.1);
+        let mut block = super::pane_block(&title, self.focused);
+        if let Some(text) = self.summary {
+            let text = truncate_to_width(&text, (area.width as usize).saturating_sub(6));
+            block = block.title_bottom(Span::styled(
+                format!(" {text} "),
+                Style::default().fg(Color::DarkGray),
+            ));
+        }
+        Paragraph::new(self.lines).block(block).render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Local, TimeZone};
+    use git2::Oid;
+
+    use super::*;
+
+    fn commit_at(timestamp: DateTime<Local>) -> CommitInfo {
+        CommitInfo {
+            oid: Oid::zero(),
+            short_id: "0000000".to_string(),
+            author_name: "Test".to_string(),
+            author_email: "test@example.com".to_string(),
+            committer_name: "Test".to_string(),
+            committer_email: "test@example.com".to_string(),
+            timestamp,
+            message: "subject".to_string(),
+            full_message: "subject\n".to_string(),
+            parent_oids: Vec::new(),
+        }
+    }
+
+    fn node(commit: Option<CommitInfo>) -> GraphNode {
+        GraphNode {
+            commit,
+            lane: 0,
+            color_index: 0,
+            labels: Vec::new(),
+            tag_names: Vec::new(),
+            is_head: false,
+            is_current_tip: false,
+            is_uncommitted: false,
+            uncommitted_count: None,
+            cells: vec![CellType::Commit(0)],
+        }
+    }
+
+    fn on(year: i32, month: u32, day: u32, hour: u32) -> GraphNode {
+        let timestamp = Local
+            .with_ymd_and_hms(year, month, day, hour, 0, 0)
+            .unwrap();
+        node(Some(commit_at(timestamp)))
+    }
+
+    #[test]
+    fn long_subjects_end_in_an_ellipsis_at_the_display_width() {
+        let worktrees = HashMap::new();
+        let labels = LabelState {
+            selected: None,
+            merged: None,
+            worktrees: &worktrees,
+            remotes: &[],
+        };
+        let mut commit = commit_at(Local::now());
+        commit.message = "日本語abc".to_string();
+        let row = node(Some(commit.clone()));
+        let render = |width, markers| {
+            let (spans, message_width) = render_subject(
+                &row,
+                &commit,
+                width,
+                labels,
+                markers,
+                false,
+                Style::default(),
+            );
+            let text: String = spans.iter().map(|span| span.content.as_ref()).collect();
+            (text, message_width)
+        };
+
+        assert_eq!(
+            render(9, RowMarkers::default()),
+            ("日本語abc".to_string(), 9)
+        );
+        // "語" would end past the column left for `…`, so it's dropped whole
+        // and the row is padded to its width
+        assert_eq!(render(6, RowMarkers::default()), ("日本… ".to_string(), 5));
+        assert_eq!(render(0, RowMarkers::default()), (String::new(), 0));
+
+        let rewritten = RowMarkers {
+            rewritten: true,
+            ..RowMarkers::default()
+        };
+        assert_eq!(render(4, rewritten), ("~ … ".to_string(), 1));
+    }
+
+    #[test]
+    fn age_heat_runs_from_newest_to_oldest_commit() {
+        let rgb = |(r, g, b): (u8, u8, u8)| Color::Rgb(r, g, b);
+        let mut uncommitted = on(2030, 1, 1, 0);
+        uncommitted.is_uncommitted = true;
+        let nodes = [
+            uncommitted,
+            on(2024, 3, 10, 12),
+            node(None),
+            on(2024, 3, 8, 12),
+            on(2024, 3, 9, 12),
+        ];
+        let range = AgeRange::new(&nodes).unwrap();
+        let time = |node: &GraphNode| node.commit.as_ref().unwrap().timestamp.timestamp();
+
+        // The uncommitted row doesn't stretch the range
+        assert_eq!(range.color(time(&nodes[1])), rgb(HEAT_NEW));
+        assert_eq!(range.color(time(&nodes[3])), rgb(HEAT_OLD));
+        let middle = |new: u8, old: u8| ((new as f64 + old as f64) / 2.0).round() as u8;
+        assert_eq!(
+            range.color(time(&nodes[4])),
+            Color::Rgb(
+                middle(HEAT_NEW.0, HEAT_OLD.0),
+                middle(HEAT_NEW.1, HEAT_OLD.1),
+                middle(HEAT_NEW.2, HEAT_OLD.2),
+            )
+        );
+        // Times outside the range are clamped
+        assert_eq!(range.color(time(&nodes[0])), rgb(HEAT_NEW));
+
+        // One shared timestamp is all new rather than a division by zero
+        let same = [on(2024, 3, 8, 12), on(2024, 3, 8, 12)];
+        let range = AgeRange::new(&same).unwrap();
+        assert_eq!(range.color(time(&same[1])), rgb(HEAT_NEW));
+
+        assert_eq!(AgeRange::new(&[node(None)]), None);
+    }
+
+    #[test]
+    fn date_headers_start_each_day_group() {
+        // A Friday
+        let today = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+        let nodes = [
+            node(None),
+            on(2026, 10, 16, 11),
+            on(2026, 10, 16, 9),
+            on(2026, 10, 15, 18),
+            on(2026, 10, 14, 12),
+            on(2026, 10, 6, 12),
+            on(2026, 10, 1, 12),
+            on(2026, 8, 3, 12),
+            on(2026, 10, 15, 7),
+        ];
+
+        let headers = DateHeaders::new(&nodes, today);
+
+        // Rows without a commit join the next group; groups may recur
+        assert_eq!(headers.starts, [1, 3, 4, 5, 6, 7, 8]);
+        assert_eq!(
+            headers.labels,
+            [
+                "Today",
+                "Yesterday",
+                "This week",
+                "Last week",
+                "Earlier this month",
+                "August 2026",
+                "Yesterday",
+            ]
+        );
+    }
+
+    #[test]
+    fn date_header_rows_map_to_nodes_and_back() {
+        // Rows: header, 0, 1, header, 2, 3
+        let headers = DateHeaders {
+            starts: vec![0, 2],
+            labels: vec!["Today".to_string(), "Yesterday".to_string()],
+        };
+
+        for node in 0..4 {
+            assert_eq!(headers.node_at(headers.row_of(node)), Some(node));
+        }
+        assert_eq!(headers.node_at(0), None);
+        assert_eq!(headers.node_at(3), None);
+        assert_eq!(headers.node_below(0), 0);
+        assert_eq!(headers.node_below(3), 2);
+        assert_eq!(headers.headers_before(5), 2);
+
+        // A group's header stays in view above its first node
+        assert_eq!(headers.top_row(0), 0);
+        assert_eq!(headers.top_row(1), 2);
+        assert_eq!(headers.top_row(2), 3);
+
+        // Clicking a header selects nothing; the row below it is its node
+        assert_eq!(headers.node_on_screen(0, 0), None);
+        assert_eq!(headers.node_on_screen(0, 1), Some(0));
+        assert_eq!(headers.node_on_screen(1, 1), None);
+        assert_eq!(headers.node_on_screen(1, 2), Some(2));
+
+        let none = DateHeaders::default();
+        assert_eq!(none.row_of(3), 3);
+        assert_eq!(none.node_on_screen(2, 1), Some(3));
+    }
+
+    #[test]
+    fn horizontal_scroll_keeps_the_selection_centered_within_bounds() {
+        // 10 nodes in 4 columns; node 9 is the oldest (leftmost)
+        assert_eq!(horizontal_scroll(10, None, 4), 0);
+        assert_eq!(horizontal_scroll(10, Some(9), 4), 0);
+        assert_eq!(horizontal_scroll(10, Some(5), 4), 2);
+        assert_eq!(horizontal_scroll(10, Some(0), 4), 6);
+        // A history narrower than the pane never scrolls
+        assert_eq!(horizontal_scroll(3, Some(0), 10), 0);
+        assert_eq!(horizontal_scroll(3, Some(7), 10), 0);
+    }
+
+    #[test]
+    fn horizontal_columns_map_to_nodes() {
+        // Scrolled to the newest node: columns 0..4 show nodes 3..=0
+        assert_eq!(horizontal_node_at(10, Some(0), 4, 0), Some(3));
+        assert_eq!(horizontal_node_at(10, Some(0), 4, 3), Some(0));
+        assert_eq!(horizontal_node_at(10, Some(0), 4, 4), None);
+        assert_eq!(horizontal_node_at(10, Some(9), 4, 0), Some(9));
+        // Columns right of the newest node are empty
+        assert_eq!(horizontal_node_at(3, Some(0), 10, 2), Some(0));
+        assert_eq!(horizontal_node_at(3, Some(0), 10, 3), None);
+        assert_eq!(horizontal_node_at(0, None, 10, 0), None);
+    }
+
+    #[test]
+    fn sideways_glyphs_mirror_the_vertical_art() {
+        let cases = [
+            (CellType::Pipe(0), '─'),
+            (CellType::Horizontal(0), '│'),
+            (CellType::HorizontalPipe(0, 1), '┼'),
+            (CellType::BranchRight(0), '╮'),
+            (CellType::BranchLeft(0), '╯'),
+            (CellType::MergeRight(0), '╭'),
+            (CellType::MergeLeft(0), '╰'),
+            (CellType::TeeRight(0), '┬'),
+            (CellType::TeeLeft(0), '┴'),
+            (CellType::TeeUp(0), '├'),
+            (CellType::Overflow(0), '┄'),
+        ];
+        for (cell, expected) in cases {
+            assert_eq!(sideways_glyph(cell, '?'), expected, "{cell:?}");
+        }
+        // Commit dots and blanks keep their glyph
+        assert_eq!(sideways_glyph(CellType::Commit(0), '◉'), '◉');
+        assert_eq!(sideways_glyph(CellType::Empty, ' '), ' ');
+    }
+
+    #[test]
+    fn hidden_columns_give_their_width_to_the_subject() {
+        use Column::{Author, Date, Graph, Subject};
+        let widths = FieldWidths { author: 8, hash: 7 };
+
+        // Marker, graph, gaps, date, author, hash
+        assert_eq!(fixed_row_width(&Column::DEFAULT, 4, widths), 37);
+        assert_eq!(
+            fixed_row_width(&[Graph, Subject, Date, Author], 4, widths),
+            28
+        );
+        assert_eq!(fixed_row_width(&[Graph, Subject, Author], 4, widths), 16);
+        assert_eq!(fixed_row_width(&[Graph, Subject], 4, widths), 6);
+
+        // Columns left out of the config never come back
+        let configured = [Graph, Subject, Author];
+        assert_eq!(visible_columns(&configured, 4, widths, 200), configured);
+
+        // Narrow rows drop hash, then date, then author
+        let visible = |total| visible_columns(&Column::DEFAULT, 4, widths, total);
+        assert_eq!(visible(87), Column::DEFAULT);
+        assert_eq!(visible(86), [Graph, Subject, Date, Author]);
+        assert_eq!(visible(77), [Graph, Subject, Author]);
+        assert_eq!(visible(60), [Graph, Subject]);
+    }
+
+    #[test]
+    fn wide_branch_and_author_names_keep_the_columns_aligned() {
+        let worktrees = HashMap::new();
+        let labels = LabelState {
+            selected: None,
+            merged: None,
+            worktrees: &worktrees,
+            remotes: &[],
+        };
+        let format = RowFormat {
+            graph_width: 2,
+            total_width: 100,
+            columns: &Column::DEFAULT,
+            widths: FieldWidths { author: 8, hash: 7 },
+            age_heat: None,
+        };
+        let row = |branch: &str, author: &str, short_id: &str| {
+            let mut commit = commit_at(Local::now());
+            commit.author_name = author.to_string();
+            commit.short_id = short_id.to_string();
+            let mut node = node(Some(commit));
+            node.labels = vec![RefLabel {
+                name: branch.to_string(),
+                is_remote: false,
+                is_head: false,
+            }];
+            let (line, _) = render_graph_line(
+                &node,
+                false,
+                format,
+                labels,
+                RowMarkers::default(),
+                &GraphChars::default(),
+            );
+            // Display column where each field starts
+            let start = |text: &str| {
+                let idx = line
+                    .spans
+                    .iter()
+                    .position(|span| span.content.contains(text))
+                    .unwrap();
+                line.spans[..idx]
+                    .iter()
+                    .map(|span| span.width())
+                    .sum::<usize>()
+            };
+            let author_head: String = author.chars().take(1).collect();
+            (line.width(), start(&author_head), start(short_id))
+        };
+
+        let ascii = row("feature", "Bob", "aaaaaaa");
+        assert_eq!(ascii.0, 100);
+        assert_eq!(
+            row("機能/検索", "山田太郎", "bbbbbbb"),
+            (100, ascii.1, ascii.2)
+        );
+        assert_eq!(
+            row("🚀-launch", "😀 Emoji", "ccccccc"),
+            (100, ascii.1, ascii.2)
+        );
+        // Cut to the column, not past it
+        assert_eq!(
+            row("main", "長谷川一郎", "ddddddd"),
+            (100, ascii.1, ascii.2)
+        );
     }
 }
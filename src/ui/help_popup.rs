@@ -8,20 +8,97 @@ use ratatui::{
     widgets::{Block, BorderType, Borders, Clear, Paragraph, Widget},
 };
 
-pub struct HelpPopup {
+use crate::keybindings::{HelpSection, BINDINGS};
+
+/// Background of help lines matching the search
+const MATCH_BG: Color = Color::Rgb(40, 44, 62);
+/// Background of the current match
+const CURRENT_MATCH_BG: Color = Color::Rgb(61, 89, 161);
+
+pub struct HelpPopup<'a> {
     scroll: u16,
+    /// Search text; matching lines are highlighted
+    query: &'a str,
+    /// Line of the current match
+    current: Option<usize>,
 }
 
-impl HelpPopup {
-    pub fn new(scroll: u16) -> Self {
-        Self { scroll }
+impl<'a> HelpPopup<'a> {
+    pub fn new(scroll: u16, query: &'a str, current: Option<usize>) -> Self {
+        Self {
+            scroll,
+            query,
+            current,
+        }
     }
 
     pub fn line_count() -> usize {
         help_lines().len()
     }
+
+    /// Lines containing `query` (case-insensitive), in order
+    pub fn matching_lines(query: &str) -> Vec<usize> {
+        let query = query.to_lowercase();
+        if query.is_empty() {
+            return Vec::new();
+        }
+        help_lines()
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| line_text(line).to_lowercase().contains(&query))
+            .map(|(i, _)| i)
+            .collect()
+    }
 }
 
+fn line_text(line: &Line) -> String {
+    line.spans
+        .iter()
+        .map(|span| span.content.as_ref())
+        .collect()
+}
+
+/// Key label and description of a help line
+type HelpEntry = (&'static str, &'static str);
+
+/// Help sections in display order, with keys of other modes listed after
+/// the section's normal-mode bindings
+const SECTIONS: &[(HelpSection, &str, &[HelpEntry])] = &[
+    (HelpSection::Navigation, "Navigation", &[]),
+    (HelpSection::Git, "Git Operations", &[]),
+    (
+        HelpSection::Search,
+        "Search",
+        &[
+            ("↑ / C-k", "Select previous result"),
+            ("↓ / C-j", "Select next result"),
+            ("Enter", "Jump to selected branch"),
+            ("Esc", "Cancel search"),
+        ],
+    ),
+    (
+        HelpSection::Diff,
+        "Diff / Staging",
+        &[
+            ("s", "Stage/unstage selected file (file list)"),
+            ("a / u", "Stage all / unstage all (file list)"),
+            ("w", "Toggle word-level emphasis (diff view)"),
+            ("W", "Cycle whitespace-ignore modes (file list/diff)"),
+            (
+                "b",
+                "Jump to the commit behind the top unchanged line (diff view)",
+            ),
+        ],
+    ),
+    (
+        HelpSection::Other,
+        "Other",
+        &[("/, n / N", "Search this help, next/previous match")],
+    ),
+];
+
+/// Help text built from `BINDINGS`; palette-only actions are left to the
+/// palette
 fn help_lines() -> Vec<Line<'static>> {
     let key_style = Style::default()
         .fg(Color::Cyan)
@@ -31,164 +108,47 @@ fn help_lines() -> Vec<Line<'static>> {
         .fg(Color::Yellow)
         .add_modifier(Modifier::BOLD);
 
-    vec![
-        Line::from(Span::styled("Navigation", header_style)),
-        Line::from(vec![
-            Span::styled("  j / ↓      ", key_style),
-            Span::styled("Move down", desc_style),
-        ]),
-        Line::from(vec![
-            Span::styled("  k / ↑      ", key_style),
-            Span::styled("Move up", desc_style),
-        ]),
-        Line::from(vec![
-            Span::styled("  Tab        ", key_style),
-            Span::styled("Switch pane focus (graph / detail)", desc_style),
-        ]),
-        Line::from(vec![
-            Span::styled("  ]          ", key_style),
-            Span::styled("Select next branch", desc_style),
-        ]),
-        Line::from(vec![
-            Span::styled("  [          ", key_style),
-            Span::styled("Select previous branch", desc_style),
-        ]),
-        Line::from(vec![
-            Span::styled("  h / ←      ", key_style),
-            Span::styled("Select left branch (same commit)", desc_style),
-        ]),
-        Line::from(vec![
-            Span::styled("  l / →      ", key_style),
-            Span::styled("Select right branch (same commit)", desc_style),
-        ]),
-        Line::from(vec![
-            Span::styled("  Ctrl+d     ", key_style),
-            Span::styled("Page down", desc_style),
-        ]),
-        Line::from(vec![
-            Span::styled("  Ctrl+u     ", key_style),
-            Span::styled("Page up", desc_style),
-        ]),
-        Line::from(vec![
-            Span::styled("  g / Home   ", key_style),
-            Span::styled("Go to top", desc_style),
-        ]),
-        Line::from(vec![
-            Span::styled("  G / End    ", key_style),
-            Span::styled("Go to bottom", desc_style),
-        ]),
-        Line::from(vec![
-            Span::styled("  @          ", key_style),
-            Span::styled("Jump to HEAD (current branch)", desc_style),
-        ]),
-        Line::from(vec![
-            Span::styled("  o          ", key_style),
-            Span::styled("Toggle remote branches", desc_style),
-        ]),
-        Line::from(""),
-        Line::from(Span::styled("Git Operations", header_style)),
-        Line::from(vec![
-            Span::styled("  Enter      ", key_style),
-            Span::styled("Checkout selected branch/commit", desc_style),
-        ]),
-        Line::from(vec![
-            Span::styled("  b          ", key_style),
-            Span::styled("Create new branch", desc_style),
-        ]),
-        Line::from(vec![
-            Span::styled("  d          ", key_style),
-            Span::styled("Delete branch", desc_style),
-        ]),
-        Line::from(vec![
-            Span::styled("  f          ", key_style),
-            Span::styled("Fetch from origin", desc_style),
-        ]),
-        Line::from(vec![
-            Span::styled("  c          ", key_style),
-            Span::styled("Commit staged changes", desc_style),
-        ]),
-        Line::from(vec![
-            Span::styled("  p          ", key_style),
-            Span::styled("Push current branch to origin", desc_style),
-        ]),
-        // TODO: merge and rebase will be implemented in the future
-        // Line::from(vec![
-        //     Span::styled("  m          ", key_style),
-        //     Span::styled("Merge branch", desc_style),
-        // ]),
-        // Line::from(vec![
-        //     Span::styled("  r          ", key_style),
-        //     Span::styled("Rebase onto branch", desc_style),
-        // ]),
-        Line::from(""),
-        Line::from(Span::styled("Search", header_style)),
-        Line::from(vec![
-            Span::styled("  /          ", key_style),
-            Span::styled("Search branches", desc_style),
-        ]),
-        Line::from(vec![
-            Span::styled("  ↑ / C-k    ", key_style),
-            Span::styled("Select previous result", desc_style),
-        ]),
-        Line::from(vec![
-            Span::styled("  ↓ / C-j    ", key_style),
-            Span::styled("Select next result", desc_style),
-        ]),
-        Line::from(vec![
-            Span::styled("  Enter      ", key_style),
-            Span::styled("Jump to selected branch", desc_style),
-        ]),
-        Line::from(vec![
-            Span::styled("  Esc        ", key_style),
-            Span::styled("Cancel search", desc_style),
-        ]),
-        Line::from(""),
-        Line::from(Span::styled("Diff / Staging", header_style)),
-        Line::from(vec![
-            Span::styled("  Space      ", key_style),
-            Span::styled("View file diffs", desc_style),
-        ]),
-        Line::from(vec![
-            Span::styled("  s          ", key_style),
-            Span::styled("Stage/unstage selected file (file list)", desc_style),
-        ]),
-        Line::from(vec![
-            Span::styled("  a / u      ", key_style),
-            Span::styled("Stage all / unstage all (file list)", desc_style),
-        ]),
-        Line::from(""),
-        Line::from(Span::styled("Other", header_style)),
-        Line::from(vec![
-            Span::styled("  y          ", key_style),
-            Span::styled("Copy commit hash to clipboard", desc_style),
-        ]),
-        Line::from(vec![
-            Span::styled("  Y          ", key_style),
-            Span::styled("Copy branch name to clipboard", desc_style),
-        ]),
-        Line::from(vec![
-            Span::styled("  R          ", key_style),
-            Span::styled("Refresh", desc_style),
-        ]),
-        Line::from(vec![
-            Span::styled("  ?          ", key_style),
-            Span::styled("Toggle this help", desc_style),
-        ]),
-        Line::from(vec![
-            Span::styled("  q / Esc    ", key_style),
-            Span::styled("Quit", desc_style),
-        ]),
-    ]
+    let mut lines = Vec::new();
+    for (section, title, extra) in SECTIONS {
+        if !lines.is_empty() {
+            lines.push(Line::from(""));
+        }
+        lines.push(Line::from(Span::styled(*title, header_style)));
+        let bindings = BINDINGS
+            .iter()
+            .filter(|binding| binding.section == *section && !binding.key.is_empty())
+            .map(|binding| (binding.key, binding.name));
+        for (key, desc) in bindings.chain(extra.iter().copied()) {
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {key:<11}"), key_style),
+                Span::styled(desc, desc_style),
+            ]));
+        }
+    }
+    lines
 }
 
-impl Widget for HelpPopup {
+impl Widget for HelpPopup<'_> {
     fn render(self, area: Rect, buf: &mut Buffer) {
         // Clear the background
         Clear.render(area, buf);
 
-        let lines = help_lines();
+        let mut lines = help_lines();
+        for i in Self::matching_lines(self.query) {
+            let bg = if self.current == Some(i) {
+                CURRENT_MATCH_BG
+            } else {
+                MATCH_BG
+            };
+            lines[i] = lines[i].clone().patch_style(Style::default().bg(bg));
+        }
+        let title = if self.query.is_empty() {
+            " Help ".to_string()
+        } else {
+            format!(" Help /{} ", self.query)
+        };
         let block = Block::default()
-            .title(" Help ")
+            .title(title)
             .borders(Borders::ALL)
             .border_style(Style::default().fg(Color::Cyan))
             .border_type(BorderType::Rounded)
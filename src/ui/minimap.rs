@@ -0,0 +1,206 @@
+//! Graph minimap widget
+//!
+//! Compresses the whole loaded graph into the pane height: each row
+//! summarizes a bucket of graph nodes (lane density, merges, branch tips),
+//! and the rows covering the visible part of the graph are highlighted.
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    widgets::Widget,
+};
+
+use crate::{
+    app::App,
+    git::graph::{CellType, GraphNode},
+};
+
+/// Width of the minimap column (density glyph + marker glyph)
+pub const MINIMAP_WIDTH: u16 = 2;
+
+const VIEWPORT_BG: Color = Color::Rgb(40, 44, 62);
+
+/// Summary of the graph nodes that share one minimap row
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MinimapRow {
+    /// First node index covered by this row
+    pub start: usize,
+    /// One past the last node index covered by this row
+    pub end: usize,
+    /// Number of merge commits in the bucket
+    pub merges: usize,
+    /// Number of commits with branch labels in the bucket
+    pub branch_tips: usize,
+    /// Widest number of occupied lanes in the bucket
+    pub max_lanes: usize,
+}
+
+/// Node range covered by a minimap row. When there are fewer nodes than
+/// rows, each node gets its own row and trailing rows stay empty.
+fn bucket_range(total: usize, rows: usize, row: usize) -> Option<(usize, usize)> {
+    let used_rows = rows.min(total);
+    if row >= used_rows {
+        return None;
+    }
+    let start = row * total / used_rows;
+    let end = (row + 1) * total / used_rows;
+    Some((start, end))
+}
+
+/// Downsample graph nodes into at most `rows` minimap rows
+pub fn downsample(nodes: &[GraphNode], rows: usize) -> Vec<MinimapRow> {
+    (0..rows)
+        .map_while(|row| bucket_range(nodes.len(), rows, row))
+        .map(|(start, end)| {
+            let bucket = &nodes[start..end];
+            MinimapRow {
+                start,
+                end,
+                merges: bucket
+                    .iter()
+                    .filter(|n| n.commit.as_ref().is_some_and(|c| c.parent_oids.len() > 1))
+                    .count(),
+                branch_tips: bucket.iter().filter(|n| !n.labels.is_empty()).count(),
+                max_lanes: bucket
+                    .iter()
+                    .map(|n| {
+                        n.cells
+                            .iter()
+                            .step_by(2)
+                            .filter(|c| **c != CellType::Empty)
+                            .count()
+                    })
+                    .max()
+                    .unwrap_or(0),
+            }
+        })
+        .collect()
+}
+
+/// Node index to jump to when the given minimap row is clicked
+pub fn node_index_for_row(total: usize, rows: usize, row: usize) -> Option<usize> {
+    bucket_range(total, rows, row).map(|(start, _)| start)
+}
+
+pub struct MinimapWidget {
+    rows: Vec<MinimapRow>,
+    selected: Option<usize>,
+    /// Visible node range of the graph pane
+    viewport: (usize, usize),
+}
+
+impl MinimapWidget {
+    /// `rows` is the inner height of the graph pane, so minimap rows line up
+    /// with graph rows.
+    pub fn new(app: &App, rows: usize) -> Self {
+        let offset = app.graph_list_state.offset();
+        Self {
+            rows: downsample(&app.graph_layout.nodes, rows),
+            selected: app.graph_list_state.selected(),
+            viewport: (offset, offset + rows),
+        }
+    }
+}
+
+impl Widget for MinimapWidget {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        for (i, row) in self.rows.iter().enumerate() {
+            if i as u16 >= area.height {
+                break;
+            }
+            let y = area.y + i as u16;
+            let in_viewport = row.start < self.viewport.1 && row.end > self.viewport.0;
+            let is_selected = self.selected.is_some_and(|s| s >= row.start && s < row.end);
+
+            let base = if in_viewport {
+                Style::default().bg(VIEWPORT_BG)
+            } else {
+                Style::default()
+            };
+
+            let density = match row.max_lanes {
+                0 => ' ',
+                1 => '░',
+                2 => '▒',
+                _ => '▓',
+            };
+            buf.set_string(area.x, y, density.to_string(), base.fg(Color::DarkGray));
+
+            let (marker, color) = if is_selected {
+                ('◀', Color::Cyan)
+            } else if row.merges > 0 {
+                ('◆', Color::Magenta)
+            } else if row.branch_tips > 0 {
+                ('●', Color::Green)
+            } else {
+                (' ', Color::Reset)
+            };
+            if area.width > 1 {
+                buf.set_string(
+                    area.x + 1,
+                    y,
+                    marker.to_string(),
+                    base.fg(color).add_modifier(Modifier::BOLD),
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::graph::RefLabel;
+
+    fn node(lanes: usize, branch: bool) -> GraphNode {
+        GraphNode {
+            commit: None,
+            lane: 0,
+            color_index: 0,
+            labels: if branch {
+                vec![RefLabel {
+                    name: "main".to_string(),
+                    is_remote: false,
+                    is_head: false,
+                }]
+            } else {
+                Vec::new()
+            },
+            tag_names: Vec::new(),
+            is_head: false,
+            is_current_tip: false,
+            is_uncommitted: false,
+            uncommitted_count: None,
+            cells: (0..lanes)
+                .flat_map(|_| [CellType::Pipe(0), CellType::Empty])
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn downsample_covers_every_node_once() {
+        let nodes: Vec<_> = (0..103).map(|i| node(1 + i % 3, i == 50)).collect();
+        let rows = downsample(&nodes, 10);
+
+        assert_eq!(rows.len(), 10);
+        assert_eq!(rows[0].start, 0);
+        assert_eq!(rows[9].end, 103);
+        for pair in rows.windows(2) {
+            assert_eq!(pair[0].end, pair[1].start);
+        }
+        assert_eq!(rows.iter().map(|r| r.branch_tips).sum::<usize>(), 1);
+        assert!(rows.iter().all(|r| r.max_lanes == 3));
+    }
+
+    #[test]
+    fn short_graphs_use_one_row_per_node() {
+        let nodes: Vec<_> = (0..3).map(|_| node(1, false)).collect();
+        let rows = downsample(&nodes, 10);
+
+        assert_eq!(rows.len(), 3);
+        assert_eq!(node_index_for_row(3, 10, 2), Some(2));
+        assert_eq!(node_index_for_row(3, 10, 5), None);
+        assert_eq!(node_index_for_row(1000, 10, 5), Some(500));
+    }
+}
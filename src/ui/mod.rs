@@ -1,12 +1,17 @@
 //! UI components
 
+pub mod branch_compare;
 pub mod commit_detail;
 pub mod dialog;
 pub mod file_diff_view;
+pub mod graph_debug;
 pub mod graph_view;
 pub mod help_popup;
+pub mod minimap;
 pub mod search_dropdown;
+pub mod stash_list;
 pub mod status_bar;
+pub mod tree_browser;
 
 use ratatui::{
     buffer::Buffer,
@@ -19,17 +24,23 @@ use ratatui::{
     },
     Frame,
 };
+use unicode_width::UnicodeWidthStr;
 
-use crate::app::{App, AppMode, InputAction};
+use crate::action::Action;
+use crate::app::{App, AppMode, GraphOrientation};
 
 use self::{
+    branch_compare::BranchCompareWidget,
     commit_detail::{CommitDetailWidget, FileListWidget},
-    dialog::{BranchInfoPopup, ConfirmDialog, InputDialog},
+    dialog::{max_confirm_scroll, BranchInfoPopup, ConfirmDialog, InputDialog},
     file_diff_view::FileDiffViewWidget,
-    graph_view::GraphViewWidget,
+    graph_view::{GraphViewWidget, HorizontalGraphWidget},
     help_popup::HelpPopup,
+    minimap::{MinimapWidget, MINIMAP_WIDTH},
     search_dropdown::{calculate_dropdown_height, SearchDropdown},
+    stash_list::StashListWidget,
     status_bar::StatusBar,
+    tree_browser::TreeBrowserWidget,
 };
 
 /// Minimum terminal width required for rendering
@@ -168,6 +179,7 @@ pub fn draw(frame: &mut Frame, app: &mut App) {
                 *horizontal_offset,
                 *file_index,
                 file_list.len(),
+                app.whitespace().label(),
             ),
             vertical[0],
         );
@@ -183,6 +195,12 @@ pub fn draw(frame: &mut Frame, app: &mut App) {
         let status_bar = StatusBar::new(app);
         app.status_hints = status_bar.hint_regions(vertical[1]);
         frame.render_widget(status_bar, vertical[1]);
+        render_action_indicator(frame, app, area);
+        return;
+    }
+
+    if app.focus_mode() {
+        draw_focus_mode(frame, app, area);
         return;
     }
 
@@ -195,67 +213,102 @@ pub fn draw(frame: &mut Frame, app: &mut App) {
     let main_area = vertical[0];
     let status_area = vertical[1];
 
-    // Split main area vertically: graph (70%) + detail (30%)
-    let content_vertical = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([Constraint::Percentage(70), Constraint::Percentage(30)])
-        .split(main_area);
+    // Split main area vertically: graph (70%) + detail (30%), or the graph
+    // alone with the detail pane hidden
+    let show_detail = app.show_detail();
+    let (graph_pane, commit_area, files_area) = if show_detail {
+        let content_vertical = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(70), Constraint::Percentage(30)])
+            .split(main_area);
+        let (commit_area, files_area) = split_detail_area(content_vertical[1]);
+        (content_vertical[0], commit_area, files_area)
+    } else {
+        (main_area, Rect::default(), Rect::default())
+    };
 
-    let graph_area = content_vertical[0];
-    let detail_area = content_vertical[1];
-    let (commit_area, files_area) = split_detail_area(detail_area);
+    // Minimap column on the right of the graph pane, aligned with its inner
+    // rows (which the horizontal layout doesn't have)
+    let horizontal = app.graph_orientation() == GraphOrientation::Horizontal;
+    let show_minimap = app.show_minimap() && !horizontal;
+    let (graph_area, minimap_area) = if show_minimap {
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(0), Constraint::Length(MINIMAP_WIDTH)])
+            .split(graph_pane);
+        (chunks[0], chunks[1].inner(Margin::new(0, 1)))
+    } else {
+        (graph_pane, Rect::default())
+    };
 
     // Record pane regions for mouse hit-testing
     app.layout = crate::app::LayoutMap {
         graph: graph_area,
+        minimap: minimap_area,
         commit_detail: commit_area,
         files: files_area,
         status_bar: status_area,
     };
 
-    // Update detail viewport size and clamp the scroll before rendering
-    app.detail_viewport_height = commit_area.height.saturating_sub(2);
-    let commit_widget = CommitDetailWidget::new(app);
-    app.detail_content_height = commit_widget.estimated_height(commit_area.width.saturating_sub(2));
-    app.scroll_detail(0);
-    let commit_widget = commit_widget.with_scroll(app.detail_scroll);
-
-    let files_widget = FileListWidget::new(app);
-    app.files_pane_scroll = files_widget.scroll_offset(files_area);
-
     // Render widgets
-    frame.render_stateful_widget(
-        GraphViewWidget::new(app, graph_area.width),
-        graph_area,
-        &mut app.graph_list_state,
-    );
-    frame.render_widget(commit_widget, commit_area);
-    frame.render_widget(files_widget, files_area);
+    render_graph(frame, app, graph_area);
+    if show_detail {
+        // Update detail viewport size and clamp the scroll before rendering
+        app.detail_viewport_height = commit_area.height.saturating_sub(2);
+        let commit_widget = CommitDetailWidget::new(app);
+        app.detail_content_height =
+            commit_widget.estimated_height(commit_area.width.saturating_sub(2));
+        app.scroll_detail(0);
+        let commit_widget = commit_widget.with_scroll(app.detail_scroll);
+
+        let files_widget = FileListWidget::new(app);
+        app.files_pane_scroll = files_widget.scroll_offset(files_area);
+
+        frame.render_widget(commit_widget, commit_area);
+        frame.render_widget(files_widget, files_area);
+        render_scrollbar(
+            frame,
+            commit_area,
+            app.detail_content_height as usize,
+            app.detail_viewport_height as usize,
+            app.detail_scroll as usize,
+        );
+    }
+    if show_minimap {
+        // Rendered after the graph so the list offset is up to date
+        frame.render_widget(
+            MinimapWidget::new(app, minimap_area.height as usize),
+            minimap_area,
+        );
+    }
 
     // Scrollbars
-    render_scrollbar(
-        frame,
-        graph_area,
-        app.graph_layout.nodes.len(),
-        graph_area.height.saturating_sub(2) as usize,
-        app.graph_list_state.offset(),
-    );
-    render_scrollbar(
-        frame,
-        commit_area,
-        app.detail_content_height as usize,
-        app.detail_viewport_height as usize,
-        app.detail_scroll as usize,
-    );
+    if !horizontal {
+        render_scrollbar(
+            frame,
+            graph_area,
+            app.graph_layout.nodes.len(),
+            graph_area.height.saturating_sub(2) as usize,
+            app.graph_list_state.offset(),
+        );
+    }
 
     let status_bar = StatusBar::new(app);
     app.status_hints = status_bar.hint_regions(status_area);
     frame.render_widget(status_bar, status_area);
 
-    // Branch info popup (when multiple branches exist on selected node)
-    render_branch_info_popup(frame, app, graph_area);
+    // Branch info popup (when multiple branches exist on selected node); the
+    // horizontal layout names them in its footer instead
+    if !horizontal {
+        render_branch_info_popup(frame, app, graph_area);
+    }
+
+    render_popups(frame, app, area);
+    render_action_indicator(frame, app, area);
+}
 
-    // Popups
+/// Modal popups drawn over the main view
+fn render_popups(frame: &mut Frame, app: &mut App, area: Rect) {
     match &app.mode {
         AppMode::Help => {
             let popup_area = centered_rect(60, 70, area);
@@ -263,7 +316,10 @@ pub fn draw(frame: &mut Frame, app: &mut App) {
             let line_count = HelpPopup::line_count();
             let max_scroll = line_count.saturating_sub(viewport_height) as u16;
             app.help_scroll = app.help_scroll.min(max_scroll);
-            frame.render_widget(HelpPopup::new(app.help_scroll), popup_area);
+            frame.render_widget(
+                HelpPopup::new(app.help_scroll, app.help_query(), app.help_match()),
+                popup_area,
+            );
             render_scrollbar(
                 frame,
                 popup_area,
@@ -273,19 +329,20 @@ pub fn draw(frame: &mut Frame, app: &mut App) {
             );
         }
         AppMode::Input {
+            title,
             input,
-            action: InputAction::Search,
-            ..
-        } => {
+            action,
+        } if action.is_picker() => {
             // Search dropdown at bottom of screen
             let results = app.search_results();
             let height = calculate_dropdown_height(results.len());
             let popup_area = bottom_rect(60, height, area);
             frame.render_widget(
                 SearchDropdown::new(
+                    title,
                     input,
                     results,
-                    &app.branch_positions,
+                    app.search_candidates(),
                     app.search_selection(),
                 ),
                 popup_area,
@@ -295,12 +352,95 @@ pub fn draw(frame: &mut Frame, app: &mut App) {
             let popup_area = centered_rect(50, 20, area);
             frame.render_widget(InputDialog::new(title, input), popup_area);
         }
-        AppMode::Confirm { message, .. } => {
-            let popup_area = centered_rect(50, 20, area);
-            frame.render_widget(ConfirmDialog::new(message), popup_area);
+        AppMode::Confirm { message, list, .. } => {
+            // Grow past 20% for messages listing several lines
+            let mut popup_area = centered_rect(50, 20, area);
+            let line_count = message.lines().count();
+            let height = (line_count as u16 + 5).min(area.height);
+            if height > popup_area.height {
+                popup_area.y = area.y + (area.height - height) / 2;
+                popup_area.height = height;
+            }
+            // Whatever still doesn't fit scrolls
+            let viewport_height = popup_area.height.saturating_sub(5) as usize;
+            let max_scroll = max_confirm_scroll(message, list.clone(), viewport_height);
+            app.confirm_scroll = app.confirm_scroll.min(max_scroll);
+            frame.render_widget(
+                ConfirmDialog::new(message, list.clone(), app.confirm_scroll),
+                popup_area,
+            );
         }
         _ => {}
     }
+    if let AppMode::TreeBrowser { browser } = &mut app.mode {
+        let popup_area = centered_rect(80, 80, area);
+        tree_browser::clamp_scroll(browser, popup_area.height.saturating_sub(2) as usize);
+        frame.render_widget(TreeBrowserWidget::new(browser), popup_area);
+    }
+    if let AppMode::BranchCompare { compare } = &mut app.mode {
+        let popup_area = centered_rect(90, 90, area);
+        branch_compare::clamp_scroll(compare, popup_area.height.saturating_sub(2) as usize);
+        frame.render_widget(BranchCompareWidget::new(compare), popup_area);
+    }
+    if let AppMode::StashList { stashes, selected } = &app.mode {
+        let popup_area = centered_rect(70, 50, area);
+        frame.render_widget(StashListWidget::new(stashes, *selected), popup_area);
+    }
+}
+
+/// Focus mode: the graph fills the whole frame with no borders, detail pane
+/// or status bar; popups still open on top of it
+fn draw_focus_mode(frame: &mut Frame, app: &mut App, area: Rect) {
+    app.layout = crate::app::LayoutMap {
+        graph: area,
+        ..Default::default()
+    };
+    app.status_hints.clear();
+    render_graph(frame, app, area);
+    render_popups(frame, app, area);
+    render_action_indicator(frame, app, area);
+}
+
+/// The commit graph in the current orientation
+fn render_graph(frame: &mut Frame, app: &mut App, area: Rect) {
+    match app.graph_orientation() {
+        GraphOrientation::Vertical => frame.render_stateful_widget(
+            GraphViewWidget::new(app, area.width),
+            area,
+            &mut app.graph_list_state,
+        ),
+        GraphOrientation::Horizontal => {
+            frame.render_widget(HorizontalGraphWidget::new(app, area), area)
+        }
+    }
+}
+
+/// Flash the last triggered action in the top-right corner (`ui.show_actions`)
+fn render_action_indicator(frame: &mut Frame, app: &App, area: Rect) {
+    let Some(action) = app.action_indicator() else {
+        return;
+    };
+    let glyph = match action {
+        Action::MoveUp | Action::ScrollUp | Action::FileSelectUp => "↑",
+        Action::MoveDown | Action::ScrollDown | Action::FileSelectDown => "↓",
+        Action::PageUp | Action::ScrollPageUp => "⇞",
+        Action::PageDown | Action::ScrollPageDown => "⇟",
+        Action::ScrollLeft | Action::BranchLeft => "←",
+        Action::ScrollRight | Action::BranchRight => "→",
+        _ => "•",
+    };
+    let text = format!(" {glyph} {action:?} ");
+    let width = (text.width() as u16).min(area.width);
+    let indicator_area = Rect::new(area.right().saturating_sub(width + 1), area.y, width, 1);
+    frame.render_widget(
+        Paragraph::new(text).style(
+            Style::default()
+                .fg(Color::Black)
+                .bg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        ),
+        indicator_area,
+    );
 }
 
 /// Render branch info popup when multiple branches exist on selected node
@@ -321,9 +461,10 @@ fn render_branch_info_popup(frame: &mut Frame, app: &App, graph_area: Rect) {
     let popup_width = (max_branch_len + 6).min(50) as u16;
 
     // Calculate selected row's screen position (add 1 for border)
-    let selected_idx = app.graph_list_state.selected().unwrap_or(0);
-    let offset = app.graph_list_state.offset();
-    let selected_screen_y = graph_area.y + 1 + selected_idx.saturating_sub(offset) as u16;
+    let headers = app.date_headers();
+    let selected_row = headers.row_of(app.graph_list_state.selected().unwrap_or(0));
+    let top_row = headers.top_row(app.graph_list_state.offset());
+    let selected_screen_y = graph_area.y + 1 + selected_row.saturating_sub(top_row) as u16;
 
     // Position popup at right side of graph area
     let popup_x = graph_area.x + graph_area.width.saturating_sub(popup_width + 2);
@@ -1,6 +1,7 @@
 //! Search dropdown widget with fuzzy matching
 
 use crate::search::FuzzySearchResult;
+use crate::ui::graph_view::truncate_to_width;
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
@@ -8,11 +9,13 @@ use ratatui::{
     text::{Line, Span},
     widgets::{Block, BorderType, Borders, Clear, Widget},
 };
+use unicode_width::UnicodeWidthChar;
 
 const MAX_VISIBLE_RESULTS: usize = 7;
 
 /// Search dropdown widget showing input field and fuzzy search results
 pub struct SearchDropdown<'a> {
+    title: &'a str,
     input: &'a str,
     results: &'a [FuzzySearchResult],
     branch_names: &'a [(usize, String)],
@@ -21,12 +24,14 @@ pub struct SearchDropdown<'a> {
 
 impl<'a> SearchDropdown<'a> {
     pub fn new(
+        title: &'a str,
         input: &'a str,
         results: &'a [FuzzySearchResult],
         branch_names: &'a [(usize, String)],
         selected_index: Option<usize>,
     ) -> Self {
         Self {
+            title,
             input,
             results,
             branch_names,
@@ -55,9 +60,12 @@ impl<'a> SearchDropdown<'a> {
         let mut spans = Vec::new();
         let mut current_segment = String::new();
         let mut current_is_matched = false;
+        let mut used_width = 0;
 
         for (char_idx, ch) in name.chars().enumerate() {
-            if char_idx >= max_width.saturating_sub(3) {
+            // Budget in display columns so wide characters don't overflow
+            used_width += UnicodeWidthChar::width(ch).unwrap_or(0);
+            if used_width > max_width.saturating_sub(3) {
                 current_segment.push_str("...");
                 break;
             }
@@ -107,7 +115,7 @@ impl<'a> Widget for SearchDropdown<'a> {
 
         // Build block with cyan border (matching InputDialog style)
         let block = Block::default()
-            .title(" Search branches ")
+            .title(format!(" {} ", self.title))
             .borders(Borders::ALL)
             .border_style(Style::default().fg(Color::Cyan))
             .border_type(BorderType::Rounded)
@@ -203,7 +211,7 @@ impl<'a> Widget for SearchDropdown<'a> {
                 if is_selected {
                     // For selected item, use inverted colors without per-char highlighting
                     let name = self.get_branch_name(result);
-                    let display_name: String = name.chars().take(max_name_width).collect();
+                    let display_name = truncate_to_width(name, max_name_width);
                     spans.push(Span::styled(
                         display_name,
                         Style::default()
@@ -266,3 +274,43 @@ pub fn calculate_dropdown_height(result_count: usize) -> u16 {
     };
     (base_height + results_height) as u16
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use unicode_width::UnicodeWidthStr;
+
+    #[test]
+    fn wide_branch_names_are_cut_by_display_width() {
+        let names = vec![(0, "機能-検索".to_string()), (1, "feature".to_string())];
+        let results = [
+            FuzzySearchResult {
+                branch_idx: 0,
+                score: 0,
+                matched_indices: vec![0, 3],
+            },
+            FuzzySearchResult {
+                branch_idx: 1,
+                score: 0,
+                matched_indices: vec![],
+            },
+        ];
+        let dropdown = SearchDropdown::new("Branch", "", &results, &names, Some(1));
+
+        let spans = dropdown.render_highlighted_name(&results[0], 8);
+        let text: String = spans.iter().map(|span| span.content.as_ref()).collect();
+        assert_eq!(text, "機能-...");
+        assert_eq!(text.width(), 8);
+        assert_eq!(spans[0].content, "機");
+        assert_eq!(spans[0].style, highlight_style(true));
+
+        // The selected row is cut without an ellipsis and never overflows the box
+        let area = Rect::new(0, 0, 12, 6);
+        let mut buf = Buffer::empty(area);
+        SearchDropdown::new("Branch", "", &results, &names, Some(0)).render(area, &mut buf);
+        let row: String = (1..11).map(|x| buf[(x, 3)].symbol()).collect();
+        // Wide characters leave a blank continuation cell
+        assert_eq!(row.replace(' ', ""), "▶機能-");
+        assert_eq!(buf[(11, 3)].symbol(), "│");
+    }
+}
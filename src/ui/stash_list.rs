@@ -0,0 +1,64 @@
+//! Stash list overlay
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Widget},
+};
+
+use crate::git::StashInfo;
+
+use super::graph_view::truncate_to_width;
+
+pub struct StashListWidget<'a> {
+    stashes: &'a [StashInfo],
+    selected: usize,
+}
+
+impl<'a> StashListWidget<'a> {
+    pub fn new(stashes: &'a [StashInfo], selected: usize) -> Self {
+        Self { stashes, selected }
+    }
+}
+
+impl Widget for StashListWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let block = Block::default()
+            .title(format!(" Stashes ({}) ", self.stashes.len()))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .border_type(BorderType::Rounded)
+            .style(Style::default().bg(Color::Black));
+        let height = area.height.saturating_sub(2).max(1) as usize;
+        let width = area.width.saturating_sub(2) as usize;
+        // Scroll just enough to keep the selection on screen
+        let scroll = (self.selected + 1).saturating_sub(height);
+
+        let lines: Vec<Line> = self
+            .stashes
+            .iter()
+            .skip(scroll)
+            .take(height)
+            .map(|stash| {
+                let name = format!(" stash@{{{}}} ", stash.index);
+                let short_id = format!("{} ", &stash.oid.to_string()[..7]);
+                let rest = width.saturating_sub(name.len() + short_id.len());
+                let mut line = Line::from(vec![
+                    Span::styled(name, Style::default().fg(Color::Yellow)),
+                    Span::styled(short_id, Style::default().fg(Color::DarkGray)),
+                    Span::raw(truncate_to_width(&stash.message, rest)),
+                ]);
+                if stash.index == self.selected {
+                    line = line.style(Style::default().bg(Color::DarkGray));
+                }
+                line
+            })
+            .collect();
+
+        Paragraph::new(lines).block(block).render(area, buf);
+    }
+}
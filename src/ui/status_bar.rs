@@ -2,6 +2,8 @@
 //!
 //! Key hints are modeled as segments with an optional `Action`, so the same
 //! data drives both rendering and mouse hit regions (`hint_regions`).
+//! Repository indicators on the left are prioritized segments; the least
+//! important ones are dropped first when the terminal is narrow.
 
 use ratatui::{
     buffer::Buffer,
@@ -13,17 +15,29 @@ use ratatui::{
 use unicode_width::UnicodeWidthStr;
 
 use crate::action::Action;
-use crate::app::{App, AppMode, FocusedPane, InputAction};
+use crate::app::{App, AppMode, FocusedPane, GraphOrientation};
 
 struct Hint {
     key: &'static str,
     desc: &'static str,
     action: Option<Action>,
+    enabled: bool,
 }
 
 impl Hint {
     fn new(key: &'static str, desc: &'static str, action: Option<Action>) -> Self {
-        Self { key, desc, action }
+        Self {
+            key,
+            desc,
+            action,
+            enabled: true,
+        }
+    }
+
+    /// Grayed out (still clickable, so the reason can be shown)
+    fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
     }
 
     fn key_text(&self) -> String {
@@ -39,7 +53,52 @@ impl Hint {
     }
 }
 
+/// Repository indicator; lower `priority` is elided first
+struct Segment {
+    text: String,
+    style: Style,
+    priority: u8,
+}
+
+impl Segment {
+    fn new(text: String, style: Style, priority: u8) -> Self {
+        Self {
+            text,
+            style,
+            priority,
+        }
+    }
+
+    /// Width including the trailing separator space
+    fn width(&self) -> u16 {
+        self.text.width() as u16 + 1
+    }
+}
+
+/// Width of the fetch/push progress bar in cells
+const PROGRESS_BAR_WIDTH: usize = 12;
+
+/// `Receiving objects ▕█████░░░░░░░▏ 45%`
+fn progress_bar(phase: &str, percent: u8) -> String {
+    let filled = PROGRESS_BAR_WIDTH * usize::from(percent.min(100)) / 100;
+    format!(
+        "{phase} ▕{}{}▏{percent:>3}%",
+        "█".repeat(filled),
+        "░".repeat(PROGRESS_BAR_WIDTH - filled)
+    )
+}
+
+/// `42s`, or `4m05s` from a minute up
+fn countdown(secs: u64) -> String {
+    if secs < 60 {
+        format!("{secs}s")
+    } else {
+        format!("{}m{:02}s", secs / 60, secs % 60)
+    }
+}
+
 pub struct StatusBar {
+    segments: Vec<Segment>,
     prefix: Vec<Span<'static>>,
     hints: Vec<Hint>,
     mode_label: Option<&'static str>,
@@ -52,6 +111,7 @@ impl StatusBar {
             .bg(Color::Magenta)
             .add_modifier(Modifier::BOLD);
 
+        let mut segments: Vec<Segment> = Vec::new();
         let mut prefix: Vec<Span> = Vec::new();
 
         // Repository name (folder name) on the left
@@ -59,26 +119,136 @@ impl StatusBar {
             .file_name()
             .and_then(|n| n.to_str())
             .unwrap_or(&app.repo_path);
-        prefix.push(Span::styled(format!(" {} ", repo_name), repo_style));
-        prefix.push(Span::raw(" "));
+        segments.push(Segment::new(format!(" {} ", repo_name), repo_style, 1));
 
+        if app.is_read_only() {
+            segments.push(Segment::new(
+                " READ-ONLY ".to_string(),
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+                7,
+            ));
+        }
+        if app.is_bare() {
+            segments.push(Segment::new(
+                " bare repository ".to_string(),
+                Style::default()
+                    .fg(Color::White)
+                    .bg(Color::DarkGray)
+                    .add_modifier(Modifier::BOLD),
+                5,
+            ));
+        }
+        // Commits go out under someone else's name this session
+        if let Some(identity) = app.session_identity() {
+            segments.push(Segment::new(
+                format!(" as {} ", identity.name),
+                Style::default().fg(Color::Black).bg(Color::LightCyan),
+                5,
+            ));
+        }
         // HEAD branch
         if let Some(head) = app.head_name.as_deref() {
-            prefix.push(Span::styled(
+            segments.push(Segment::new(
                 format!(" {} ", head),
                 Style::default().fg(Color::Black).bg(Color::Green),
+                6,
+            ));
+        }
+        // Merge / rebase / ... in progress
+        if let Some(state) = app.repo_summary.state {
+            segments.push(Segment::new(
+                format!(" {} ", state),
+                Style::default()
+                    .fg(Color::White)
+                    .bg(Color::Red)
+                    .add_modifier(Modifier::BOLD),
+                5,
+            ));
+        }
+        if let Some((ahead, behind)) = app.repo_summary.ahead_behind {
+            if ahead > 0 || behind > 0 {
+                let mut text = String::from(" ");
+                if ahead > 0 {
+                    text.push_str(&format!("↑{} ", ahead));
+                }
+                if behind > 0 {
+                    text.push_str(&format!("↓{} ", behind));
+                }
+                segments.push(Segment::new(
+                    text,
+                    Style::default().fg(Color::Black).bg(Color::Blue),
+                    4,
+                ));
+            }
+        }
+        let dirty = app.dirty_file_count();
+        if dirty > 0 {
+            segments.push(Segment::new(
+                format!(" ~{} ", dirty),
+                Style::default().fg(Color::Black).bg(Color::LightYellow),
+                3,
+            ));
+        }
+        let stashes = app.repo_summary.stash_count;
+        if stashes > 0 {
+            segments.push(Segment::new(
+                format!(" stash {} ", stashes),
+                Style::default().fg(Color::Black).bg(Color::LightMagenta),
+                2,
+            ));
+        }
+        if !app.path_scope().is_empty() {
+            let text = match app.path_scope_progress() {
+                Some((done, total)) => {
+                    format!(" -- {} {done}/{total} ", app.path_scope().join(" "))
+                }
+                None => format!(" -- {} ", app.path_scope().join(" ")),
+            };
+            segments.push(Segment::new(
+                text,
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+                5,
+            ));
+        }
+        if app.show_timers() {
+            let (refresh, fetch) = app.timer_countdowns();
+            let mut text = String::new();
+            if let Some(secs) = refresh {
+                text.push_str(&format!(" ⟳ {}", countdown(secs)));
+            }
+            if let Some(secs) = fetch {
+                text.push_str(&format!(" ⇣ {}", countdown(secs)));
+            }
+            if !text.is_empty() {
+                text.push(' ');
+                segments.push(Segment::new(text, Style::default().fg(Color::DarkGray), 0));
+            }
+        }
+        if app.graph_orientation() == GraphOrientation::Horizontal {
+            segments.push(Segment::new(
+                " horizontal ".to_string(),
+                Style::default()
+                    .fg(Color::White)
+                    .bg(Color::DarkGray)
+                    .add_modifier(Modifier::BOLD),
+                0,
             ));
-            prefix.push(Span::raw(" "));
         }
         if !app.show_remote_branches() {
-            prefix.push(Span::styled(
-                " remotes hidden ",
+            segments.push(Segment::new(
+                " remotes hidden ".to_string(),
                 Style::default()
                     .fg(Color::White)
                     .bg(Color::DarkGray)
                     .add_modifier(Modifier::BOLD),
+                0,
             ));
-            prefix.push(Span::raw(" "));
         }
 
         let mut hints: Vec<Hint> = Vec::new();
@@ -100,6 +270,13 @@ impl StatusBar {
                             .bg(bg)
                             .add_modifier(Modifier::BOLD),
                     ));
+                    if let Some(progress) = app.transfer_progress() {
+                        prefix.push(Span::raw(" "));
+                        prefix.push(Span::styled(
+                            progress_bar(&progress.phase, progress.percent),
+                            Style::default().fg(Color::Yellow),
+                        ));
+                    }
                     prefix.push(Span::raw("  "));
                 } else if app.focused_pane == FocusedPane::Detail {
                     hints.push(Hint::new("j/k", "scroll", None));
@@ -108,10 +285,19 @@ impl StatusBar {
                     hints.push(Hint::new("?", "help", Some(Action::ToggleHelp)));
                 } else {
                     hints.push(Hint::new("j/k", "move", None));
-                    hints.push(Hint::new("Enter", "checkout", Some(Action::Checkout)));
+                    hints.push(
+                        Hint::new("Enter", "checkout", Some(Action::Checkout))
+                            .enabled(!app.is_bare()),
+                    );
                     hints.push(Hint::new("Space", "files", Some(Action::EnterFileSelect)));
-                    hints.push(Hint::new("c", "commit", Some(Action::CommitDialog)));
-                    hints.push(Hint::new("p", "push", Some(Action::Push)));
+                    hints.push(
+                        Hint::new("c", "commit", Some(Action::CommitDialog))
+                            .enabled(!app.is_bare()),
+                    );
+                    hints.push(
+                        Hint::new("p", "push", Some(Action::Push))
+                            .enabled(app.remote_unsupported().is_none()),
+                    );
                     hints.push(Hint::new("?", "help", Some(Action::ToggleHelp)));
                     hints.push(Hint::new("q", "quit", Some(Action::Quit)));
                 }
@@ -119,11 +305,15 @@ impl StatusBar {
             AppMode::Help => {
                 mode_label = Some(" HELP ");
                 hints.push(Hint::new("j/k", "scroll", None));
+                hints.push(Hint::new("/", "search", Some(Action::Search)));
+                if !app.help_query().is_empty() {
+                    hints.push(Hint::new("n/N", "match", Some(Action::NextMatch)));
+                }
                 hints.push(Hint::new("Esc/q", "close help", Some(Action::ToggleHelp)));
             }
             AppMode::Input { action, .. } => {
                 mode_label = Some(" INPUT ");
-                if *action == InputAction::Search {
+                if action.is_picker() {
                     let count = app.search_match_count();
                     let info = if count > 0 {
                         format!(" {} matches ", count)
@@ -177,28 +367,81 @@ impl StatusBar {
                 hints.push(Hint::new("]/[", "hunk", Some(Action::NextHunk)));
                 hints.push(Hint::new("j/k", "scroll", None));
                 hints.push(Hint::new("h/l", "pan", None));
+                hints.push(Hint::new("b", "blame", Some(Action::BlameLine)));
+                hints.push(Hint::new("Esc", "back", Some(Action::Cancel)));
+            }
+            AppMode::TreeBrowser { .. } => {
+                mode_label = Some(" TREE ");
+                hints.push(Hint::new("j/k", "select", None));
+                hints.push(Hint::new("l", "open", Some(Action::TreeExpand)));
+                hints.push(Hint::new("h", "close", Some(Action::TreeCollapse)));
+                hints.push(Hint::new("Esc", "back", Some(Action::Cancel)));
+            }
+            AppMode::BranchCompare { .. } => {
+                mode_label = Some(" COMPARE ");
+                hints.push(Hint::new("j/k", "move", None));
+                hints.push(Hint::new("h/l", "side", Some(Action::FocusNext)));
+                hints.push(Hint::new("Enter", "show in graph", Some(Action::Confirm)));
+                hints.push(Hint::new("Esc", "back", Some(Action::Cancel)));
+            }
+            AppMode::StashList { .. } => {
+                mode_label = Some(" STASH ");
+                hints.push(Hint::new("j/k", "move", None));
+                hints.push(Hint::new("Enter", "apply", Some(Action::Confirm)));
+                hints.push(Hint::new("p", "pop", Some(Action::StashPop)));
                 hints.push(Hint::new("Esc", "back", Some(Action::Cancel)));
             }
         }
 
         Self {
+            segments,
             prefix,
             hints,
             mode_label,
         }
     }
 
-    fn prefix_width(&self) -> u16 {
-        self.prefix
-            .iter()
-            .map(|span| span.content.width() as u16)
-            .sum()
+    /// Segments that fit next to the message and mode label, dropping the
+    /// lowest-priority ones first
+    fn visible_segments(&self, width: u16) -> Vec<&Segment> {
+        let mode_width = self.mode_label.map_or(0, |text| text.len() as u16);
+        let budget = width
+            .saturating_sub(mode_width)
+            .saturating_sub(self.spans_width(&self.prefix));
+        let mut visible: Vec<&Segment> = self.segments.iter().collect();
+        while visible.iter().map(|s| s.width()).sum::<u16>() > budget {
+            let Some(idx) = visible
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, s)| s.priority)
+                .map(|(idx, _)| idx)
+            else {
+                break;
+            };
+            visible.remove(idx);
+        }
+        visible
+    }
+
+    fn spans_width(&self, spans: &[Span]) -> u16 {
+        spans.iter().map(|span| span.content.width() as u16).sum()
+    }
+
+    /// Visible segments followed by the mode-specific prefix
+    fn left_spans(&self, width: u16) -> Vec<Span<'static>> {
+        let mut spans = Vec::new();
+        for segment in self.visible_segments(width) {
+            spans.push(Span::styled(segment.text.clone(), segment.style));
+            spans.push(Span::raw(" "));
+        }
+        spans.extend(self.prefix.iter().cloned());
+        spans
     }
 
     /// Clickable regions for the hints, mirroring the render layout
     pub fn hint_regions(&self, area: Rect) -> Vec<(Rect, Action)> {
         let mut regions = Vec::new();
-        let mut x = area.x + self.prefix_width();
+        let mut x = area.x + self.spans_width(&self.left_spans(area.width));
         for hint in &self.hints {
             let width = hint.width();
             if x + width > area.x + area.width {
@@ -225,10 +468,17 @@ impl Widget for StatusBar {
             .bg(Color::Yellow)
             .add_modifier(Modifier::BOLD);
 
-        let mut spans = self.prefix.clone();
+        let mut spans = self.left_spans(area.width);
+        let disabled_key_style = Style::default().fg(Color::Black).bg(Color::DarkGray);
+        let disabled_desc_style = Style::default().fg(Color::DarkGray);
         for hint in &self.hints {
-            spans.push(Span::styled(hint.key_text(), key_style));
-            spans.push(Span::styled(hint.desc_text(), desc_style));
+            let (key, desc) = if hint.enabled {
+                (key_style, desc_style)
+            } else {
+                (disabled_key_style, disabled_desc_style)
+            };
+            spans.push(Span::styled(hint.key_text(), key));
+            spans.push(Span::styled(hint.desc_text(), desc));
         }
 
         let line = Line::from(spans);
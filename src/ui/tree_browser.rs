@@ -0,0 +1,123 @@
+//! Commit tree browser overlay
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Widget},
+};
+
+use crate::git::tree::{EntryKind, TreeBrowser};
+
+pub struct TreeBrowserWidget<'a> {
+    browser: &'a TreeBrowser,
+}
+
+impl<'a> TreeBrowserWidget<'a> {
+    pub fn new(browser: &'a TreeBrowser) -> Self {
+        Self { browser }
+    }
+}
+
+/// Keep the selected row (or the open file's scroll) inside a viewport of
+/// `height` rows
+pub fn clamp_scroll(browser: &mut TreeBrowser, height: usize) {
+    let height = height.max(1);
+    if let Some(file) = &mut browser.file {
+        file.scroll = file.scroll.min(file.lines.len().saturating_sub(height));
+        return;
+    }
+    if browser.selected < browser.scroll {
+        browser.scroll = browser.selected;
+    } else if browser.selected >= browser.scroll + height {
+        browser.scroll = browser.selected + 1 - height;
+    }
+}
+
+impl Widget for TreeBrowserWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let short_id = &self.browser.commit.to_string()[..7];
+        let title = match &self.browser.file {
+            Some(file) => format!(" {} @ {} ", file.path, short_id),
+            None => format!(" Tree @ {short_id} "),
+        };
+        let block = Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .border_type(BorderType::Rounded)
+            .style(Style::default().bg(Color::Black));
+        let height = area.height.saturating_sub(2) as usize;
+
+        let lines: Vec<Line> = match &self.browser.file {
+            Some(file) => match &file.placeholder {
+                Some(note) => vec![Line::from(Span::styled(
+                    format!("  ({note})"),
+                    Style::default().fg(Color::DarkGray),
+                ))],
+                None => {
+                    let number_width = file.lines.len().max(1).to_string().len();
+                    file.lines
+                        .iter()
+                        .enumerate()
+                        .skip(file.scroll)
+                        .take(height)
+                        .map(|(idx, line)| {
+                            Line::from(vec![
+                                Span::styled(
+                                    format!("{:>number_width$} ", idx + 1),
+                                    Style::default().fg(Color::DarkGray),
+                                ),
+                                Span::raw(line.as_str()),
+                            ])
+                        })
+                        .collect()
+                }
+            },
+            None => self
+                .browser
+                .rows
+                .iter()
+                .enumerate()
+                .skip(self.browser.scroll)
+                .take(height)
+                .map(|(idx, row)| {
+                    let (marker, name, style) = match row.kind {
+                        EntryKind::Dir => (
+                            if row.expanded { "▾ " } else { "▸ " },
+                            format!("{}/", row.name),
+                            Style::default()
+                                .fg(Color::Blue)
+                                .add_modifier(Modifier::BOLD),
+                        ),
+                        EntryKind::Submodule => (
+                            "  ",
+                            format!("{} (submodule)", row.name),
+                            Style::default().fg(Color::DarkGray),
+                        ),
+                        EntryKind::Symlink => (
+                            "  ",
+                            format!("{} @", row.name),
+                            Style::default().fg(Color::Cyan),
+                        ),
+                        EntryKind::File => ("  ", row.name.clone(), Style::default()),
+                    };
+                    let mut line = Line::from(vec![
+                        Span::raw("  ".repeat(row.depth + 1)),
+                        Span::raw(marker),
+                        Span::styled(name, style),
+                    ]);
+                    if idx == self.browser.selected {
+                        line = line.style(Style::default().bg(Color::DarkGray));
+                    }
+                    line
+                })
+                .collect(),
+        };
+
+        Paragraph::new(lines).block(block).render(area, buf);
+    }
+}
@@ -2,7 +2,10 @@
 
 use chrono::Local;
 use git2::Oid;
-use keifu::git::{build_graph, graph::CellType, BranchInfo, CommitInfo};
+use keifu::git::{
+    build_graph, build_graph_default, graph::CellType, BranchInfo, CommitInfo, GraphOptions,
+};
+use keifu::graph::colors::ColorMode;
 
 fn make_oid(id: &str) -> Oid {
     // Convert id into a 40-char hex hash
@@ -20,6 +23,8 @@ fn make_commit(id: &str, parents: Vec<&str>) -> CommitInfo {
         short_id: id.to_string(),
         author_name: "test".to_string(),
         author_email: "test@example.com".to_string(),
+        committer_name: "test".to_string(),
+        committer_email: "test@example.com".to_string(),
         timestamp: Local::now(),
         message: format!("Commit {}", id),
         full_message: format!("Commit {}", id),
@@ -32,6 +37,7 @@ fn make_branch(name: &str, tip: &str, is_head: bool) -> BranchInfo {
         name: name.to_string(),
         tip_oid: make_oid(tip),
         is_head,
+        is_current: is_head,
         is_remote: false,
         upstream: None,
     }
@@ -53,6 +59,7 @@ fn render_cells(cells: &[CellType]) -> String {
             CellType::TeeRight(_) => '├',
             CellType::TeeLeft(_) => '┤',
             CellType::TeeUp(_) => '┴',
+            CellType::Overflow(_) => '┆',
         })
         .collect()
 }
@@ -74,7 +81,7 @@ fn test_linear_history() {
     ];
     let branches = vec![make_branch("main", "c3", true)];
 
-    let layout = build_graph(&commits, &branches, None, None);
+    let layout = build_graph_default(&commits, &branches, None, None);
 
     println!("Linear history:");
     for node in &layout.nodes {
@@ -90,7 +97,7 @@ fn test_linear_history() {
 
 #[test]
 fn test_unborn_repo_shows_uncommitted_node() {
-    let layout = build_graph(&[], &[], Some(Some(1)), None);
+    let layout = build_graph_default(&[], &[], Some(Some(1)), None);
 
     assert_eq!(layout.max_lane, 0);
     assert_eq!(layout.nodes.len(), 1);
@@ -120,7 +127,7 @@ fn test_simple_branch_merge() {
         make_branch("feature", "c2", false),
     ];
 
-    let layout = build_graph(&commits, &branches, None, None);
+    let layout = build_graph_default(&commits, &branches, None, None);
 
     println!("\nSimple branch merge:");
     for node in &layout.nodes {
@@ -169,7 +176,7 @@ fn test_multiple_merges() {
         make_branch("develop", "c2", false),
     ];
 
-    let layout = build_graph(&commits, &branches, None, None);
+    let layout = build_graph_default(&commits, &branches, None, None);
 
     println!("\nMultiple merges:");
     for node in &layout.nodes {
@@ -202,7 +209,7 @@ fn test_cell_structure() {
     ];
     let branches = vec![make_branch("main", "m1", true)];
 
-    let layout = build_graph(&commits, &branches, None, None);
+    let layout = build_graph_default(&commits, &branches, None, None);
 
     println!("\nCell structure analysis:");
     for node in &layout.nodes {
@@ -243,7 +250,7 @@ fn test_octopus_merge() {
         make_branch("branch-c", "C", false),
     ];
 
-    let layout = build_graph(&commits, &branches, None, None);
+    let layout = build_graph_default(&commits, &branches, None, None);
 
     println!("\nOctopus merge:");
     for node in &layout.nodes {
@@ -279,7 +286,7 @@ fn test_parallel_branches() {
     ];
     let branches = vec![make_branch("main", "M2", true)];
 
-    let layout = build_graph(&commits, &branches, None, None);
+    let layout = build_graph_default(&commits, &branches, None, None);
 
     println!("\nParallel branches:");
     for node in &layout.nodes {
@@ -318,7 +325,7 @@ fn test_many_active_lanes() {
         make_branch("d", "D", false),
     ];
 
-    let layout = build_graph(&commits, &branches, None, None);
+    let layout = build_graph_default(&commits, &branches, None, None);
 
     println!("\nMany active lanes:");
     for node in &layout.nodes {
@@ -370,7 +377,7 @@ fn test_chained_merges_different_branches() {
         make_branch("develop", "develop-merge", true),
     ];
 
-    let layout = build_graph(&commits, &branches, None, None);
+    let layout = build_graph_default(&commits, &branches, None, None);
 
     println!("\nChained merges (keifu-demo structure):");
     for node in &layout.nodes {
@@ -452,7 +459,7 @@ fn test_hotfix_merged_into_multiple_branches() {
         make_branch("hotfix", "hotfix", false),
     ];
 
-    let layout = build_graph(&commits, &branches, None, None);
+    let layout = build_graph_default(&commits, &branches, None, None);
 
     println!("\nHotfix merged into multiple branches:");
     for node in &layout.nodes {
@@ -517,3 +524,232 @@ fn test_hotfix_merged_into_multiple_branches() {
         "Expected continuous Pipe line from main-merge to hotfix"
     );
 }
+
+#[test]
+fn test_deterministic_colors_ignore_unrelated_branches() {
+    // feature (f1 -> c1) colored the same whether or not another branch
+    // (o1 -> c1) is traversed before it
+    let base = vec![
+        make_commit("c2", vec!["c1"]),
+        make_commit("f1", vec!["c1"]),
+        make_commit("c1", vec![]),
+    ];
+    let mut with_other = base.clone();
+    with_other.insert(1, make_commit("o1", vec!["c1"]));
+    let branches = vec![
+        make_branch("main", "c2", true),
+        make_branch("feature", "f1", false),
+        make_branch("other", "o1", false),
+    ];
+
+    let feature_color = |commits: &[CommitInfo], mode, seed| {
+        let options = GraphOptions {
+            color_mode: mode,
+            color_seed: seed,
+            ..GraphOptions::default()
+        };
+        let layout = build_graph(commits, &branches, None, None, &options);
+        layout
+            .nodes
+            .iter()
+            .find(|n| get_short_id(n) == "f1")
+            .unwrap()
+            .color_index
+    };
+
+    let plain = feature_color(&base, ColorMode::Deterministic, 3);
+    assert_eq!(
+        plain,
+        feature_color(&with_other, ColorMode::Deterministic, 3)
+    );
+    // Repeated builds agree, and the main branch keeps its reserved color
+    assert_eq!(plain, feature_color(&base, ColorMode::Deterministic, 3));
+    let options = GraphOptions {
+        color_mode: ColorMode::Deterministic,
+        color_seed: 3,
+        ..GraphOptions::default()
+    };
+    let layout = build_graph(&base, &branches, None, None, &options);
+    assert_eq!(
+        layout.nodes[0].color_index,
+        keifu::graph::colors::MAIN_BRANCH_COLOR
+    );
+    assert_ne!(plain, keifu::graph::colors::MAIN_BRANCH_COLOR);
+}
+
+#[test]
+fn test_connector_rows_move_links_below_the_commit() {
+    // Same shape as test_simple_branch_merge
+    let commits = vec![
+        make_commit("c4", vec!["c3", "c2"]),
+        make_commit("c3", vec!["c1"]),
+        make_commit("c2", vec!["c1"]),
+        make_commit("c1", vec![]),
+    ];
+    let branches = vec![
+        make_branch("main", "c4", true),
+        make_branch("feature", "c2", false),
+    ];
+
+    let build = |connector_rows| {
+        let options = GraphOptions {
+            connector_rows,
+            ..GraphOptions::default()
+        };
+        build_graph(&commits, &branches, None, None, &options)
+    };
+    let compact = build(false);
+    let spaced = build(true);
+
+    let rows = |layout: &keifu::git::graph::GraphLayout| -> Vec<String> {
+        layout
+            .nodes
+            .iter()
+            .map(|node| {
+                format!("{} {}", get_short_id(node), render_cells(&node.cells))
+                    .trim_end()
+                    .to_string()
+            })
+            .collect()
+    };
+    println!(
+        "\nCompact: {:#?}\nSpaced: {:#?}",
+        rows(&compact),
+        rows(&spaced)
+    );
+
+    assert_eq!(rows(&compact)[0], "c4 ○─╮");
+    assert_eq!(
+        rows(&spaced),
+        vec![
+            "c4 ○",
+            "(connector) ├─╮",
+            "c3 ○ │",
+            "c2 │ ○",
+            "(connector) ├─╯",
+            "c1 ○",
+        ]
+    );
+    // Every commit is still there, in the same order
+    let commit_ids = |layout: &keifu::git::graph::GraphLayout| -> Vec<String> {
+        layout
+            .nodes
+            .iter()
+            .filter(|node| node.commit.is_some())
+            .map(get_short_id)
+            .collect()
+    };
+    assert_eq!(commit_ids(&compact), commit_ids(&spaced));
+}
+
+#[test]
+fn test_connector_rows_carry_only_lane_art() {
+    // Fork junction below c2/c3 plus, with connector_rows, the merge link
+    let commits = vec![
+        make_commit("c4", vec!["c3", "c2"]),
+        make_commit("c3", vec!["c1"]),
+        make_commit("c2", vec!["c1"]),
+        make_commit("c1", vec![]),
+    ];
+    let branches = vec![
+        make_branch("main", "c4", true),
+        make_branch("feature", "c2", false),
+    ];
+
+    for connector_rows in [false, true] {
+        let options = GraphOptions {
+            connector_rows,
+            ..GraphOptions::default()
+        };
+        let layout = build_graph(
+            &commits,
+            &branches,
+            Some(Some(1)),
+            Some(make_oid("c4")),
+            &options,
+        );
+        let connectors: Vec<_> = layout.nodes.iter().filter(|n| n.is_connector()).collect();
+        assert_eq!(connectors.len(), 1 + usize::from(connector_rows));
+        for node in connectors {
+            assert!(node.commit.is_none());
+            assert!(node.labels.is_empty());
+            assert!(!node.is_head);
+            assert_eq!(node.cells.len(), (layout.max_lane + 1) * 2);
+        }
+        assert!(!layout.nodes[0].is_connector());
+        assert!(layout.nodes[0].is_uncommitted);
+    }
+}
+
+#[test]
+fn test_max_lanes_folds_extra_branches_into_an_overflow_lane() {
+    // Five branches off the same base need five lanes side by side
+    let tips = ["t1", "t2", "t3", "t4", "t5"];
+    let mut commits: Vec<_> = tips
+        .iter()
+        .map(|tip| make_commit(tip, vec!["base"]))
+        .collect();
+    commits.push(make_commit("base", vec![]));
+    let branches: Vec<_> = tips
+        .iter()
+        .map(|tip| make_branch(tip, tip, *tip == "t1"))
+        .collect();
+
+    let mut layout = build_graph_default(&commits, &branches, None, None);
+    assert_eq!(layout.max_lane, 4);
+    layout.cap_lanes(3);
+
+    assert_eq!(layout.max_lane, 2);
+    for node in &layout.nodes {
+        assert!(node.cells.len() <= 6);
+        assert!(node.lane <= 2);
+        // Every commit keeps its node glyph, folded or not
+        if node.commit.is_some() {
+            assert_eq!(
+                node.cells[node.lane * 2],
+                CellType::Commit(node.color_index)
+            );
+        }
+    }
+    let fork_row = layout.nodes.iter().find(|n| n.is_connector()).unwrap();
+    assert_eq!(render_cells(&fork_row.cells), "├─┴─┆ ");
+
+    // The max_lanes option folds the same way while building
+    let options = GraphOptions {
+        max_lanes: 3,
+        ..GraphOptions::default()
+    };
+    let built = build_graph(&commits, &branches, None, None, &options);
+    assert_eq!(built.max_lane, 2);
+    let cells = |layout: &keifu::git::graph::GraphLayout| -> Vec<_> {
+        layout.nodes.iter().map(|n| n.cells.clone()).collect()
+    };
+    assert_eq!(cells(&built), cells(&layout));
+
+    // Graphs within the limit are left alone
+    let mut narrow = build_graph_default(&commits[..1], &branches, None, None);
+    let before: Vec<_> = narrow.nodes.iter().map(|n| n.cells.clone()).collect();
+    narrow.cap_lanes(3);
+    let after: Vec<_> = narrow.nodes.iter().map(|n| n.cells.clone()).collect();
+    assert_eq!(before, after);
+}
+
+#[test]
+fn test_is_linear_only_for_single_lane_histories() {
+    let linear = vec![make_commit("c2", vec!["c1"]), make_commit("c1", vec![])];
+    let branches = vec![make_branch("main", "c2", true)];
+    let layout = build_graph_default(&linear, &branches, Some(Some(1)), Some(make_oid("c2")));
+    assert!(layout.is_linear());
+    assert!(layout.nodes.iter().all(|node| node
+        .cells
+        .iter()
+        .skip(1)
+        .all(|c| *c == CellType::Empty)));
+
+    let forked = vec![
+        make_commit("c3", vec!["c1"]),
+        make_commit("c2", vec!["c1"]),
+        make_commit("c1", vec![]),
+    ];
+    assert!(!build_graph_default(&forked, &branches, None, None).is_linear());
+}
@@ -0,0 +1,127 @@
+//! Branch and HEAD detection for repositories whose refs live only in packed-refs
+
+use std::fs;
+use std::path::Path;
+
+use git2::{Oid, Repository, Signature};
+use keifu::git::{build_graph_default, graph::RefLabel, GitRepository};
+use tempfile::TempDir;
+
+fn commit(repo: &Repository, message: &str, parents: &[Oid]) -> Oid {
+    let tree_id = repo.index().unwrap().write_tree().unwrap();
+    let tree = repo.find_tree(tree_id).unwrap();
+    let signature = Signature::now("Test User", "test@example.com").unwrap();
+    let parents: Vec<_> = parents
+        .iter()
+        .map(|oid| repo.find_commit(*oid).unwrap())
+        .collect();
+    let parent_refs: Vec<_> = parents.iter().collect();
+    repo.commit(None, &signature, &signature, message, &tree, &parent_refs)
+        .unwrap()
+}
+
+/// Repository with main, feature and origin/main refs stored only in
+/// packed-refs (as after `git pack-refs --all`), HEAD on main
+fn init_packed_repo() -> (TempDir, Oid, Oid) {
+    let tempdir = tempfile::tempdir().unwrap();
+    let repo = Repository::init(tempdir.path()).unwrap();
+
+    let base = commit(&repo, "base", &[]);
+    let main = commit(&repo, "main work", &[base]);
+    let feature = commit(&repo, "feature work", &[base]);
+
+    let git_dir = repo.path();
+    fs::write(
+        git_dir.join("packed-refs"),
+        format!(
+            "# pack-refs with: peeled fully-peeled sorted \n\
+             {feature} refs/heads/feature\n\
+             {main} refs/heads/main\n\
+             {base} refs/remotes/origin/main\n"
+        ),
+    )
+    .unwrap();
+    fs::write(git_dir.join("HEAD"), "ref: refs/heads/main\n").unwrap();
+
+    assert!(!has_loose_refs(&git_dir.join("refs")));
+    (tempdir, main, feature)
+}
+
+fn has_loose_refs(dir: &Path) -> bool {
+    fs::read_dir(dir).unwrap().any(|entry| {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            has_loose_refs(&path)
+        } else {
+            true
+        }
+    })
+}
+
+#[test]
+fn packed_refs_list_branches_and_head() {
+    let (tempdir, main, feature) = init_packed_repo();
+    let repo = GitRepository::open(tempdir.path()).unwrap();
+
+    let branches = repo.get_branches(true).unwrap();
+    let names: Vec<_> = branches.iter().map(|b| b.name.as_str()).collect();
+    assert_eq!(names, ["main", "feature", "origin/main"]);
+
+    let head: Vec<_> = branches.iter().filter(|b| b.is_head).collect();
+    assert_eq!(head.len(), 1);
+    assert_eq!(head[0].name, "main");
+    assert_eq!(head[0].tip_oid, main);
+    assert!(branches
+        .iter()
+        .any(|b| b.name == "feature" && b.tip_oid == feature));
+    assert!(branches
+        .iter()
+        .any(|b| b.name == "origin/main" && b.is_remote));
+
+    assert_eq!(repo.head_name().as_deref(), Some("main"));
+    assert_eq!(repo.head_oid(), Some(main));
+}
+
+#[test]
+fn packed_refs_render_graph() {
+    let (tempdir, main, feature) = init_packed_repo();
+    let repo = GitRepository::open(tempdir.path()).unwrap();
+
+    let commits = repo.get_commits(500, true).unwrap();
+    let branches = repo.get_branches(true).unwrap();
+    assert_eq!(commits.len(), 3);
+
+    let layout = build_graph_default(&commits, &branches, None, repo.head_oid());
+    let commit_nodes: Vec<_> = layout
+        .nodes
+        .iter()
+        .filter_map(|node| node.commit.as_ref().map(|c| (c.oid, node)))
+        .collect();
+    assert_eq!(commit_nodes.len(), 3);
+
+    let (_, main_node) = commit_nodes.iter().find(|(oid, _)| *oid == main).unwrap();
+    assert!(main_node.is_head);
+    assert_eq!(
+        main_node.labels,
+        [RefLabel {
+            name: "main".to_string(),
+            is_remote: false,
+            is_head: true,
+        }]
+    );
+
+    let (_, feature_node) = commit_nodes
+        .iter()
+        .find(|(oid, _)| *oid == feature)
+        .unwrap();
+    assert!(!feature_node.is_head);
+    assert_eq!(
+        feature_node.labels,
+        [RefLabel {
+            name: "feature".to_string(),
+            is_remote: false,
+            is_head: false,
+        }]
+    );
+    assert_eq!(layout.max_lane, 1);
+}
@@ -6,7 +6,7 @@ use std::thread;
 use std::time::Duration;
 
 use git2::{Repository, Signature};
-use keifu::git::{CommitDiffInfo, FileChangeKind, GitRepository};
+use keifu::git::{diff::DEFAULT_MAX_FILES, CommitDiffInfo, FileChangeKind, GitRepository};
 use tempfile::TempDir;
 
 fn init_repo() -> (TempDir, Repository) {
@@ -42,7 +42,7 @@ fn from_working_tree_lists_root_untracked_file() {
 
     fs::write(tempdir.path().join("new_file.txt"), "hello\n").unwrap();
 
-    let diff = CommitDiffInfo::from_working_tree(&repo).unwrap();
+    let diff = CommitDiffInfo::from_working_tree(&repo, DEFAULT_MAX_FILES).unwrap();
     let file = diff
         .files
         .iter()
@@ -61,7 +61,7 @@ fn from_working_tree_lists_untracked_file_before_initial_commit() {
 
     fs::write(tempdir.path().join("new_file.txt"), "hello\n").unwrap();
 
-    let diff = CommitDiffInfo::from_working_tree(&repo).unwrap();
+    let diff = CommitDiffInfo::from_working_tree(&repo, DEFAULT_MAX_FILES).unwrap();
     let file = diff
         .files
         .iter()
@@ -83,7 +83,7 @@ fn from_working_tree_lists_mixed_tracked_and_untracked() {
     // Create untracked file
     fs::write(tempdir.path().join("untracked.txt"), "new\n").unwrap();
 
-    let diff = CommitDiffInfo::from_working_tree(&repo).unwrap();
+    let diff = CommitDiffInfo::from_working_tree(&repo, DEFAULT_MAX_FILES).unwrap();
 
     assert_eq!(diff.total_files, 2);
 
@@ -125,7 +125,7 @@ fn from_working_tree_lists_nested_untracked_files() {
     fs::create_dir_all(nested_path.parent().unwrap()).unwrap();
     fs::write(&nested_path, "first line\nsecond line\n").unwrap();
 
-    let diff = CommitDiffInfo::from_working_tree(&repo).unwrap();
+    let diff = CommitDiffInfo::from_working_tree(&repo, DEFAULT_MAX_FILES).unwrap();
     let file = diff
         .files
         .iter()
@@ -164,7 +164,7 @@ fn from_working_tree_counts_untracked_symlink_as_link_itself() {
 
     symlink("target.txt", tempdir.path().join("link")).unwrap();
 
-    let diff = CommitDiffInfo::from_working_tree(&repo).unwrap();
+    let diff = CommitDiffInfo::from_working_tree(&repo, DEFAULT_MAX_FILES).unwrap();
     let file = diff
         .files
         .iter()
@@ -205,7 +205,7 @@ fn from_working_tree_includes_untracked_symlink_to_directory() {
 
     symlink("dir", tempdir.path().join("linkdir")).unwrap();
 
-    let diff = CommitDiffInfo::from_working_tree(&repo).unwrap();
+    let diff = CommitDiffInfo::from_working_tree(&repo, DEFAULT_MAX_FILES).unwrap();
     let file = diff
         .files
         .iter()
@@ -263,7 +263,7 @@ fn from_working_tree_merges_staged_and_unstaged_for_same_file() {
     // Further edit the same file (unstaged change)
     fs::write(tempdir.path().join("new.txt"), "line1\nline2\n").unwrap();
 
-    let diff = CommitDiffInfo::from_working_tree(&repo).unwrap();
+    let diff = CommitDiffInfo::from_working_tree(&repo, DEFAULT_MAX_FILES).unwrap();
 
     assert_eq!(diff.total_files, 1);
     assert_eq!(diff.files.len(), 1);
@@ -289,7 +289,7 @@ fn from_working_tree_recomputes_modified_stats_across_staged_and_unstaged() {
 
     fs::write(tempdir.path().join("tracked.txt"), "final change\n").unwrap();
 
-    let diff = CommitDiffInfo::from_working_tree(&repo).unwrap();
+    let diff = CommitDiffInfo::from_working_tree(&repo, DEFAULT_MAX_FILES).unwrap();
     let file = diff
         .files
         .iter()
@@ -319,7 +319,7 @@ fn from_working_tree_staged_binary_then_rewritten_as_text_uses_worktree_classifi
 
     fs::write(tempdir.path().join("new.txt"), "hello\nworld\n").unwrap();
 
-    let diff = CommitDiffInfo::from_working_tree(&repo).unwrap();
+    let diff = CommitDiffInfo::from_working_tree(&repo, DEFAULT_MAX_FILES).unwrap();
     let file = diff
         .files
         .iter()
@@ -357,7 +357,7 @@ fn from_working_tree_staged_text_then_rewritten_as_binary_recomputes_stats() {
     )
     .unwrap();
 
-    let diff = CommitDiffInfo::from_working_tree(&repo).unwrap();
+    let diff = CommitDiffInfo::from_working_tree(&repo, DEFAULT_MAX_FILES).unwrap();
     let file = diff
         .files
         .iter()
@@ -388,7 +388,7 @@ fn from_working_tree_staged_added_file_marked_binary_keeps_binary_classification
     index.add_path(Path::new("new.dat")).unwrap();
     index.write().unwrap();
 
-    let diff = CommitDiffInfo::from_working_tree(&repo).unwrap();
+    let diff = CommitDiffInfo::from_working_tree(&repo, DEFAULT_MAX_FILES).unwrap();
     let file = diff
         .files
         .iter()
@@ -420,7 +420,7 @@ fn from_working_tree_keeps_staged_add_removed_in_worktree() {
     // visible to stay consistent with get_working_tree_status() counts.
     // Stats should reflect only the staged (HEAD→index) change, not the
     // index→workdir deletion that reverts it.
-    let diff = CommitDiffInfo::from_working_tree(&repo).unwrap();
+    let diff = CommitDiffInfo::from_working_tree(&repo, DEFAULT_MAX_FILES).unwrap();
 
     assert_eq!(diff.total_files, 1);
     let file = diff
@@ -451,7 +451,7 @@ fn from_working_tree_keeps_staged_change_reverted_in_worktree() {
     // file count reported by get_working_tree_status().
     // Stats should reflect only the staged (HEAD→index) change, not the
     // sum of both directions.
-    let diff = CommitDiffInfo::from_working_tree(&repo).unwrap();
+    let diff = CommitDiffInfo::from_working_tree(&repo, DEFAULT_MAX_FILES).unwrap();
 
     assert_eq!(diff.total_files, 1);
     let file = diff
@@ -479,7 +479,7 @@ fn from_working_tree_deleted_then_recreated_shows_modified() {
     // Recreate the same file on disk (now untracked)
     fs::write(tempdir.path().join("tracked.txt"), "new content\n").unwrap();
 
-    let diff = CommitDiffInfo::from_working_tree(&repo).unwrap();
+    let diff = CommitDiffInfo::from_working_tree(&repo, DEFAULT_MAX_FILES).unwrap();
 
     let file = diff
         .files
@@ -522,7 +522,7 @@ fn from_working_tree_deleted_then_recreated_recomputes_overlapping_text_stats()
 
     fs::write(tempdir.path().join("tracked.txt"), "a\nc\n").unwrap();
 
-    let diff = CommitDiffInfo::from_working_tree(&repo).unwrap();
+    let diff = CommitDiffInfo::from_working_tree(&repo, DEFAULT_MAX_FILES).unwrap();
     let file = diff
         .files
         .iter()
@@ -574,7 +574,7 @@ fn from_working_tree_deleted_binary_then_recreated_text_uses_text_stats() {
     )
     .unwrap();
 
-    let diff = CommitDiffInfo::from_working_tree(&repo).unwrap();
+    let diff = CommitDiffInfo::from_working_tree(&repo, DEFAULT_MAX_FILES).unwrap();
     let file = diff
         .files
         .iter()
@@ -607,7 +607,7 @@ fn from_working_tree_binary_to_text_modified_recomputes_line_stats() {
     )
     .unwrap();
 
-    let diff = CommitDiffInfo::from_working_tree(&repo).unwrap();
+    let diff = CommitDiffInfo::from_working_tree(&repo, DEFAULT_MAX_FILES).unwrap();
     let file = diff
         .files
         .iter()
@@ -626,7 +626,7 @@ fn from_working_tree_binary_to_text_modified_recomputes_line_stats() {
 fn from_working_tree_total_files_not_capped_by_display_limit() {
     let (tempdir, repo) = init_repo();
 
-    // Create 55 untracked files (exceeds MAX_FILES_TO_DISPLAY of 50)
+    // Create 55 untracked files (exceeds DEFAULT_MAX_FILES of 50)
     for i in 0..55 {
         fs::write(
             tempdir.path().join(format!("untracked_{:03}.txt", i)),
@@ -635,7 +635,7 @@ fn from_working_tree_total_files_not_capped_by_display_limit() {
         .unwrap();
     }
 
-    let diff = CommitDiffInfo::from_working_tree(&repo).unwrap();
+    let diff = CommitDiffInfo::from_working_tree(&repo, DEFAULT_MAX_FILES).unwrap();
 
     assert_eq!(diff.total_files, 55);
     assert_eq!(diff.total_insertions, 55);
@@ -669,7 +669,7 @@ fn from_working_tree_refreshes_totals_beyond_display_limit() {
     )
     .unwrap();
 
-    let diff = CommitDiffInfo::from_working_tree(&repo).unwrap();
+    let diff = CommitDiffInfo::from_working_tree(&repo, DEFAULT_MAX_FILES).unwrap();
 
     assert_eq!(diff.total_files, 56);
     assert_eq!(diff.total_insertions, 57);
@@ -688,7 +688,7 @@ fn from_working_tree_includes_untracked_binary_files_without_line_stats() {
     )
     .unwrap();
 
-    let diff = CommitDiffInfo::from_working_tree(&repo).unwrap();
+    let diff = CommitDiffInfo::from_working_tree(&repo, DEFAULT_MAX_FILES).unwrap();
 
     assert_eq!(diff.total_files, 1);
     assert_eq!(diff.total_insertions, 0);
@@ -712,7 +712,7 @@ fn from_working_tree_marks_untracked_file_binary_from_attributes() {
     .unwrap();
     fs::write(tempdir.path().join("new.dat"), "hello\nworld\n").unwrap();
 
-    let diff = CommitDiffInfo::from_working_tree(&repo).unwrap();
+    let diff = CommitDiffInfo::from_working_tree(&repo, DEFAULT_MAX_FILES).unwrap();
     let file = diff
         .files
         .iter()
@@ -734,7 +734,7 @@ fn from_working_tree_skips_untracked_directories() {
 
     Repository::init(tempdir.path().join("child")).unwrap();
 
-    let diff = CommitDiffInfo::from_working_tree(&repo).unwrap();
+    let diff = CommitDiffInfo::from_working_tree(&repo, DEFAULT_MAX_FILES).unwrap();
 
     assert_eq!(diff.total_files, 0);
     assert_eq!(diff.total_insertions, 0);
@@ -771,7 +771,7 @@ fn from_commit_includes_binary_files_without_line_stats() {
         )
         .unwrap();
 
-    let diff = CommitDiffInfo::from_commit(&repo, oid).unwrap();
+    let diff = CommitDiffInfo::from_commit(&repo, oid, DEFAULT_MAX_FILES).unwrap();
 
     assert_eq!(diff.total_files, 1);
     assert_eq!(diff.total_insertions, 0);
@@ -782,13 +782,61 @@ fn from_commit_includes_binary_files_without_line_stats() {
     assert!(diff.files[0].is_binary);
 }
 
+#[test]
+fn from_commit_totals_cover_files_past_the_display_limit() {
+    let (tempdir, repo) = init_repo();
+
+    let mut index = repo.index().unwrap();
+    for i in 0..60 {
+        let name = format!("file_{:02}.txt", i);
+        fs::write(tempdir.path().join(&name), "one\ntwo\n").unwrap();
+        index.add_path(Path::new(&name)).unwrap();
+    }
+    index.write().unwrap();
+    let tree_id = index.write_tree().unwrap();
+    let tree = repo.find_tree(tree_id).unwrap();
+    let signature = Signature::now("Test User", "test@example.com").unwrap();
+    let parent = repo.head().unwrap().peel_to_commit().unwrap();
+    let oid = repo
+        .commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            "add many",
+            &tree,
+            &[&parent],
+        )
+        .unwrap();
+
+    let diff = CommitDiffInfo::from_commit(&repo, oid, DEFAULT_MAX_FILES).unwrap();
+
+    assert_eq!(diff.total_files, 60);
+    assert!(diff.truncated);
+    assert_eq!(diff.files.len(), 50);
+    assert!(diff.files.iter().all(|f| f.insertions == 2));
+    assert_eq!(diff.total_insertions, 120);
+    assert_eq!(diff.total_deletions, 0);
+
+    // A configured cap replaces the default, in both directions
+    let diff = CommitDiffInfo::from_commit(&repo, oid, 10).unwrap();
+    assert!(diff.truncated);
+    assert_eq!(diff.files.len(), 10);
+    assert_eq!(diff.total_files - diff.files.len(), 50);
+    assert_eq!(diff.total_insertions, 120);
+
+    let diff = CommitDiffInfo::from_commit(&repo, oid, usize::MAX).unwrap();
+    assert!(!diff.truncated);
+    assert_eq!(diff.files.len(), 60);
+    assert!(diff.files.iter().all(|f| f.insertions == 2));
+}
+
 #[test]
 fn from_working_tree_empty_untracked_file_has_zero_insertions() {
     let (tempdir, repo) = init_repo();
 
     fs::write(tempdir.path().join("empty.txt"), "").unwrap();
 
-    let diff = CommitDiffInfo::from_working_tree(&repo).unwrap();
+    let diff = CommitDiffInfo::from_working_tree(&repo, DEFAULT_MAX_FILES).unwrap();
 
     assert_eq!(diff.total_files, 1);
     let file = diff
@@ -811,7 +859,7 @@ fn from_working_tree_no_trailing_newline_counts_correctly() {
     )
     .unwrap();
 
-    let diff = CommitDiffInfo::from_working_tree(&repo).unwrap();
+    let diff = CommitDiffInfo::from_working_tree(&repo, DEFAULT_MAX_FILES).unwrap();
     let file = diff
         .files
         .iter()
@@ -911,7 +959,7 @@ fn from_working_tree_includes_both_staged_reverted_and_untracked() {
         .unwrap();
     }
 
-    let diff = CommitDiffInfo::from_working_tree(&repo).unwrap();
+    let diff = CommitDiffInfo::from_working_tree(&repo, DEFAULT_MAX_FILES).unwrap();
 
     // All 6 files should be present: 3 AD (index-only) + 3 untracked
     assert_eq!(diff.total_files, 6);
@@ -950,7 +998,7 @@ fn from_working_tree_deleted_text_then_recreated_binary_shows_zero_lines() {
     )
     .unwrap();
 
-    let diff = CommitDiffInfo::from_working_tree(&repo).unwrap();
+    let diff = CommitDiffInfo::from_working_tree(&repo, DEFAULT_MAX_FILES).unwrap();
     let file = diff
         .files
         .iter()